@@ -1,6 +1,6 @@
 use egui::{Context, Id};
-use crate::core::{Database, TableInfo, ComputedColumnsProcessor, EnhancedGroupingProcessor};
-use crate::ui::{Sidebar, SidebarAction, QueryWindow, CsvImportDialog, FileConfigDialog, HomeScreen, DuplicateDetectionDialog, DuplicateResultsViewer, TransformationDialog, TransformationManager, TimeBinDialog, ComputedColumnsDialog, EnhancedGroupingDialog};
+use crate::core::{Database, TableInfo, ComputedColumnsProcessor, EnhancedGroupingProcessor, QueryLibrary, TableFormat, CsvIngestOptions};
+use crate::ui::{Sidebar, SidebarAction, QueryWindow, QueryLibraryWindow, CsvImportDialog, FileConfigDialog, HomeScreen, DuplicateDetectionDialog, DuplicateResultsViewer, TransformationDialog, TransformationManager, TimeBinDialog, ComputedColumnsDialog, EnhancedGroupingDialog};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -24,6 +24,7 @@ pub struct LeafApp {
     sidebar: Sidebar,
     home_screen: HomeScreen,
     query_windows: Vec<QueryWindow>,
+    query_library_window: QueryLibraryWindow,
     csv_import_dialog: Option<CsvImportDialog>,
     file_config_dialog: FileConfigDialog,
     duplicate_detection_dialog: DuplicateDetectionDialog,
@@ -50,6 +51,7 @@ impl LeafApp {
             sidebar: Sidebar::new(),
             home_screen: HomeScreen::new(),
             query_windows: Vec::new(),
+            query_library_window: QueryLibraryWindow::new(),
             csv_import_dialog: None,
             file_config_dialog: FileConfigDialog::new(),
             duplicate_detection_dialog: DuplicateDetectionDialog::default(),
@@ -133,6 +135,9 @@ impl LeafApp {
                                 self.enhanced_grouping_dialog.update_available_tables(db);
                             }
                         }
+                        SidebarAction::OpenQueryLibrary => {
+                            self.query_library_window.visible = true;
+                        }
                         SidebarAction::None => {}
                     }
                 });
@@ -177,6 +182,13 @@ impl LeafApp {
             });
         }
         
+        // Query history and saved queries, opened in new query windows
+        if let Some(library) = self.query_library() {
+            if let Some((title, query)) = self.query_library_window.show(ctx, &library) {
+                self.open_query(title, query);
+            }
+        }
+        
         // Show CSV import dialog if active
         if let Some(dialog) = &mut self.csv_import_dialog {
             if !dialog.show(ctx) {
@@ -376,17 +388,23 @@ impl LeafApp {
     }
     
     fn open_query_window(&mut self, table_name: &str) {
+        self.open_query(table_name.to_string(), format!("SELECT * FROM \"{}\"", table_name));
+    }
+    
+    fn open_query(&mut self, title: String, query: String) {
         if let Some(_db) = &self.database {
-            let window = QueryWindow::new(
-                self.next_window_id,
-                table_name.to_string(),
-                format!("SELECT * FROM \"{}\"", table_name),
-            );
+            let window = QueryWindow::new(self.next_window_id, title, query)
+                .with_library(self.query_library());
             self.query_windows.push(window);
             self.next_window_id += 1;
         }
     }
     
+    /// The open project's query history and saved queries
+    fn query_library(&self) -> Option<QueryLibrary> {
+        self.database_path.as_deref().map(QueryLibrary::for_project)
+    }
+    
     fn show_csv_import(&mut self) {
         if self.database_path.is_some() && self.mode == AppMode::Builder {
             self.csv_import_dialog = Some(CsvImportDialog::new(Id::new("csv_import_dialog")));
@@ -397,8 +415,8 @@ impl LeafApp {
 
     // === HYBRID PERSISTENCE METHODS ===
 
-    /// Save all current tables in Arrow IPC format
-    pub fn save_all_tables(&mut self) -> Result<Vec<String>, String> {
+    /// Save all current tables in the given format
+    pub fn save_all_tables(&mut self, format: TableFormat) -> Result<Vec<String>, String> {
         if let Some(db) = &self.database {
             if let Some(path) = &self.database_path {
                 // Use the project folder directly for data storage
@@ -406,7 +424,7 @@ impl LeafApp {
                 
                 // Clone the database for mutable operations
                 let mut db_clone = (**db).clone();
-                match db_clone.save_all_tables(&data_dir) {
+                match db_clone.save_all_tables(&data_dir, format) {
                     Ok(saved_tables) => {
                         println!("[App] Saved {} tables to {:?}", saved_tables.len(), data_dir);
                         // Update the stored database with the modified version
@@ -451,17 +469,17 @@ impl LeafApp {
         }
     }
 
-    /// Save a specific table in both formats
-    pub fn save_table(&mut self, table_name: &str) -> Result<(), String> {
+    /// Save a specific table in the given format
+    pub fn save_table(&mut self, table_name: &str, format: TableFormat) -> Result<(), String> {
         if let Some(db) = &self.database {
             if let Some(path) = &self.database_path {
                 let data_dir = path;
                 
                 // Clone the database for mutable operations
                 let mut db_clone = (**db).clone();
-                match db_clone.save_table_dual(table_name, &data_dir) {
-                    Ok(_) => {
-                        println!("[App] Saved table '{}' in both formats", table_name);
+                match db_clone.save_table_as(table_name, &data_dir, format) {
+                    Ok(path) => {
+                        println!("[App] Saved table '{}' to {:?}", table_name, path);
                         // Update the stored database with the modified version
                         self.database = Some(Arc::new(db_clone));
                         Ok(())
@@ -476,27 +494,25 @@ impl LeafApp {
         }
     }
 
-    /// Import CSV and automatically save in both formats
+    /// Import CSV and automatically save it in the given format
     pub fn import_csv_with_persistence(&mut self, csv_path: &std::path::Path, table_name: &str, 
-                                     delimiter: char, has_header: bool) -> Result<(), String> {
+                                     delimiter: char, has_header: bool, format: TableFormat) -> Result<(), String> {
         if let Some(db) = &self.database {
             // Clone the database for mutable operations
             let mut db_clone = (**db).clone();
-            // Import CSV into DataFusion
-            match db_clone.stream_insert_csv(table_name, csv_path, delimiter, has_header) {
+            let Some(data_dir) = self.database_path.clone() else {
+                return Err("No database path available".to_string());
+            };
+            // Stream the CSV straight into the table file, one batch at a time
+            let options = CsvIngestOptions { delimiter, has_header, ..CsvIngestOptions::default() };
+            match db_clone.import_csv_to_file(table_name, csv_path, &data_dir, format, &options, |_| {}) {
                 Ok(_) => {
-                    // Save in both formats immediately
-                    match db_clone.save_table_dual(table_name, &self.database_path.as_ref().unwrap()) {
-                        Ok(_) => {
-                            println!("[App] Imported and saved table '{}'", table_name);
-                            // Update the stored database with the modified version
-                            self.database = Some(Arc::new(db_clone));
-                            // Refresh tables list
-                            self.load_tables();
-                            Ok(())
-                        }
-                        Err(e) => Err(format!("Failed to save imported table: {}", e))
-                    }
+                    println!("[App] Imported and saved table '{}'", table_name);
+                    // Update the stored database with the modified version
+                    self.database = Some(Arc::new(db_clone));
+                    // Refresh tables list
+                    self.load_tables();
+                    Ok(())
                 }
                 Err(e) => Err(format!("Failed to import CSV: {}", e))
             }
@@ -541,7 +557,7 @@ impl LeafApp {
                             self.load_tables();
                             self.error = Some(format!("Refreshed database: loaded {} new tables", loaded_tables.len()));
                         } else {
-                            self.error = Some("No new Arrow files found in database directory".to_string());
+                            self.error = Some("No new Arrow, Parquet or CSV files found in database directory".to_string());
                         }
                     }
                     Err(e) => {
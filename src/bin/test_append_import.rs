@@ -0,0 +1,115 @@
+use leaf::core::{Database, TableFormat, ParquetCompression, CsvIngestOptions, SchemaReconciliation, CoercionPolicy};
+use leaf::core::csv_ingest;
+use leaf::core::table_format;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use leaf::infer::ColumnType;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing appending CSV files to existing tables");
+    println!("{}", "=".repeat(60));
+
+    // Matching by name: exact first, then ignoring case and spaces
+    let table = Schema::new(vec![
+        Field::new("id", DataType::Int32, true),
+        Field::new("Region", DataType::Utf8, true),
+        Field::new("amount", DataType::Float64, true),
+    ]);
+    let headers: Vec<String> = ["amount", " region ", "note"].iter().map(|s| s.to_string()).collect();
+    let columns = SchemaReconciliation::new(&table, &headers, false);
+    println!("{}", columns.describe());
+    assert_eq!(columns.missing, ["id"]);
+    assert_eq!(columns.extra, ["note"]);
+    assert!(columns.added.is_empty());
+    assert!(!columns.is_exact());
+    assert_eq!(columns.describe(), "Missing (filled with nulls): id; Extra (left out): note");
+    assert_eq!(SchemaReconciliation::new(&table, &headers, true).added, ["note"]);
+    let same: Vec<String> = ["ID", "region", "amount"].iter().map(|s| s.to_string()).collect();
+    assert!(SchemaReconciliation::new(&table, &same, false).is_exact());
+
+    let dir = std::env::temp_dir().join("leaf_append_import_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // January creates the table
+    let january = dir.join("january.csv");
+    let mut csv = String::from("id,region,amount\n");
+    for i in 0..100 {
+        csv.push_str(&format!("{},{},{}.25\n", i, ["north", "south"][i % 2], i));
+    }
+    std::fs::write(&january, csv)?;
+    let mut db = Database::open_writable(&dir)?;
+    let options = CsvIngestOptions { batch_size: 40, ..CsvIngestOptions::default() };
+    db.import_csv_to_file("sales", &january, &dir, TableFormat::Parquet(ParquetCompression::Snappy), &options, |_| {})?;
+
+    // February reorders the columns, drops `region`, writes whole amounts and adds `channel`
+    let february = dir.join("february.csv");
+    let mut csv = String::from("amount,ID,channel\n");
+    for i in 100..150 {
+        let amount = if i == 120 { "lots".to_string() } else { i.to_string() };
+        csv.push_str(&format!("{},{},{}\n", amount, i, ["web", "shop"][i % 2]));
+    }
+    std::fs::write(&february, csv)?;
+
+    // The value check reads the file with the table's types
+    let table_path = table_format::find_table_file(&dir, "sales").expect("the table file");
+    let report = csv_ingest::check_append_coercion(&february, &table_path, &options, false, |_| {})?;
+    assert_eq!(report.total(), 1, "'lots' isn't a Float64");
+
+    let mut progress_calls = 0;
+    let appended = db.append_csv_to_table("sales", &february, &dir, &options, false, |_| progress_calls += 1)?;
+    println!("appended {} rows to {}: {}", appended.ingest.rows, appended.existing_rows, appended.columns.describe());
+    assert_eq!((appended.existing_rows, appended.ingest.rows), (100, 50));
+    assert_eq!(progress_calls, 2);
+    assert_eq!(appended.columns.missing, ["region"]);
+    assert_eq!(appended.columns.extra, ["channel"]);
+    assert_eq!(appended.ingest.coercion.total(), 1);
+
+    // The combined table replaces the Parquet file with a multi-batch Arrow file
+    assert_eq!(appended.ingest.output_path, dir.join("sales.arrow"));
+    assert!(!dir.join("sales.parquet").exists());
+    let (schema, batches) = table_format::read_batches(&appended.ingest.output_path)?;
+    let types: Vec<String> = schema.fields().iter().map(|f| format!("{} {}", f.name(), f.data_type())).collect();
+    println!("schema: {:?}, {} batches", types, batches.len());
+    assert_eq!(types, ["id Int64", "region Utf8", "amount Float64"]);
+    assert!(batches.len() > 2);
+
+    let totals = db.execute_query("SELECT COUNT(*), COUNT(region), COUNT(amount), MAX(id), SUM(amount) FROM sales")?;
+    println!("totals: {:?}", totals[0]);
+    assert_eq!(totals[0], vec!["150", "100", "149", "149", "11080"]);
+
+    // March adds the new column, and the earlier rows get nulls in it
+    let march = dir.join("march.csv");
+    std::fs::write(&march, "id,region,amount,channel\n150,east,1.5,web\n151,west,2.5,\n")?;
+    let fail_options = CsvIngestOptions { coercion_policy: CoercionPolicy::Fail, ..options.clone() };
+    let appended = db.append_csv_to_table("sales", &march, &dir, &fail_options, true, |_| {})?;
+    assert_eq!(appended.columns.added, ["channel"]);
+    let appended = db.append_csv_to_table("sales", &march, &dir, &options, true, |_| {})?;
+    assert!(appended.columns.is_exact(), "the second time the table already has it");
+    assert_eq!(appended.existing_rows, 152);
+    let rows = db.execute_query("SELECT COUNT(*), COUNT(channel), MAX(channel) FROM sales")?;
+    println!("with channel: {:?}", rows[0]);
+    // Only the four March rows have the column; empty text is kept as an empty string
+    assert_eq!(rows[0], vec!["154", "4", "web"]);
+
+    // A failed append leaves the table as it was
+    std::fs::write(&march, "id,amount\nabc,1\n")?;
+    assert!(db.append_csv_to_table("sales", &march, &dir, &fail_options, false, |_| {}).is_err());
+    assert!(!dir.join("sales.arrow.partial").exists());
+    assert_eq!(db.execute_query("SELECT COUNT(*) FROM sales")?[0], vec!["154"]);
+    assert!(db.append_csv_to_table("missing", &march, &dir, &options, false, |_| {}).is_err());
+
+    // Values are only rewritten as times when the table stores the column as one
+    let laps = dir.join("laps.csv");
+    std::fs::write(&laps, "lap,split (s)\n1,00:01:02.5\n2,00:01:03\n")?;
+    let as_text = CsvIngestOptions { column_types: Some(vec![ColumnType::Integer, ColumnType::Text]), ..options.clone() };
+    db.import_csv_to_file("laps", &laps, &dir, TableFormat::Arrow, &as_text, |_| {})?;
+    std::fs::write(&laps, "lap,split (s)\n3,00:01:04\n4,00:01:01.25\n")?;
+    db.append_csv_to_table("laps", &laps, &dir, &options, false, |_| {})?;
+    let splits = db.execute_query("SELECT \"split (s)\" FROM laps WHERE lap > 2 ORDER BY lap")?;
+    assert_eq!(splits, [["00:01:04"], ["00:01:01.25"]], "text columns keep the file's values");
+
+    println!("\n✅ Append import passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
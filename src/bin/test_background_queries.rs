@@ -0,0 +1,73 @@
+use leaf::core::{Database, QueryExecutor, TableFormat, CsvIngestOptions};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing background query execution");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_background_queries_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // A file-backed table, whose scans count the rows they read
+    let rows = 200_000;
+    let mut csv = String::from("id,category,amount\n");
+    for i in 0..rows {
+        csv.push_str(&format!("{},{},{}\n", i, ["a", "b", "c", "d"][i % 4], i % 1000));
+    }
+    let csv_path = dir.join("events.csv");
+    std::fs::write(&csv_path, csv)?;
+    let mut db = Database::open_writable(&dir)?;
+    db.import_csv_to_file("events", &csv_path, &dir, TableFormat::Arrow, &CsvIngestOptions::default(), |_| {})?;
+    let db = Arc::new(db);
+
+    // A query returns at once and reports its result when polled
+    let running = QueryExecutor::spawn(&db, "SELECT category, SUM(amount) AS total FROM events GROUP BY category ORDER BY category")?;
+    let result = wait_for(&running, Duration::from_secs(30)).expect("query should finish")?;
+    assert_eq!(result.num_rows(), 4);
+    assert_eq!(result.row(0), ["a", "24900000"]);
+    assert!(running.rows_scanned().is_some_and(|scanned| scanned >= rows), "scans should count every row");
+
+    // Waiting for a page runs the same way: the whole query is counted and only the page returned
+    let page = QueryExecutor::execute_with_pagination(&db, "SELECT id, amount FROM events WHERE amount < 10 ORDER BY id", 2, 100)?;
+    assert_eq!((page.num_rows(), page.total_rows), (100, Some(2000)));
+    assert_eq!(page.cell(0, 0), "20000");
+
+    // A runaway query can be stopped while other windows keep querying
+    let slow = QueryExecutor::spawn(&db, "SELECT a.id, b.id FROM events a CROSS JOIN events b")?;
+    let quick = QueryExecutor::spawn(&db, "SELECT COUNT(*) FROM events WHERE category = 'b'")?;
+    let counted = wait_for(&quick, Duration::from_secs(60)).expect("a second query runs alongside the first")?;
+    assert_eq!(counted.cell(0, 0), (rows / 4).to_string());
+    while slow.rows_returned() == 0 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(slow.try_result().is_none(), "the cross join should still be running");
+    println!("slow query after {:.1?}: {} rows returned, {:?} rows scanned", slow.elapsed(), slow.rows_returned(), slow.rows_scanned());
+    let cancelled_at = Instant::now();
+    slow.cancel();
+    let outcome = wait_for(&slow, Duration::from_secs(10)).expect("cancelling should end the query");
+    println!("cancelled in {:?}", cancelled_at.elapsed());
+    assert!(outcome.is_err_and(|e| e.to_string().contains("cancelled")));
+
+    // Errors come back through the handle too
+    let broken = QueryExecutor::spawn(&db, "SELECT missing FROM events")?;
+    assert!(wait_for(&broken, Duration::from_secs(30)).expect("query should fail").is_err());
+
+    println!("\n✅ Background queries passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+/// Poll a running query the way the query window does each frame
+fn wait_for(running: &leaf::core::RunningQuery, timeout: Duration) -> Option<leaf::core::error::Result<leaf::core::QueryResult>> {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        if let Some(outcome) = running.try_result() {
+            return Some(outcome);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    None
+}
@@ -0,0 +1,72 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions, CoercionPolicy};
+use leaf::core::csv_ingest;
+use leaf::infer::ColumnType;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing value coercion reports");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_coercion_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // The sample only sees integers in `count`; "12a" and "n/k" turn up further down
+    let csv_path = dir.join("orders.csv");
+    let mut csv = String::from("id,count,price\n");
+    for i in 1..=50 {
+        csv.push_str(&format!("{},{},{}.5\n", i, i * 2, i));
+    }
+    csv.push_str("51,12a,1.0\n52,n/k,oops\n53,,2.0\n");
+    std::fs::write(&csv_path, &csv)?;
+
+    let options = CsvIngestOptions {
+        sample_size: 10,
+        column_types: Some(vec![ColumnType::Integer, ColumnType::Integer, ColumnType::Real]),
+        ..CsvIngestOptions::default()
+    };
+
+    // The check reads every value without writing anything
+    let report = csv_ingest::check_coercion(&csv_path, &options, |_| {})?;
+    for column in &report.columns {
+        println!("{}", column.describe());
+    }
+    assert_eq!(report.total(), 3, "the empty value is a null token, not a bad value");
+    assert_eq!(report.column_indexes(), vec![1, 2]);
+    let count = &report.columns[0];
+    assert_eq!(count.column, "count");
+    assert_eq!(count.count, 2);
+    assert_eq!((count.examples[0].line, count.examples[0].value.as_str()), (52, "12a"));
+    assert_eq!((count.examples[1].line, count.examples[1].value.as_str()), (53, "n/k"));
+    assert!(!dir.join("orders.arrow").exists());
+
+    // Set to null: the import goes ahead and the summary carries the same report
+    let mut db = Database::open_writable(&dir)?;
+    let summary = db.import_csv_to_file("orders", &csv_path, &dir, TableFormat::Arrow, &options, |_| {})?;
+    assert_eq!(summary.coercion.total(), 3);
+    assert_eq!(db.execute_query("SELECT COUNT(*) - COUNT(count) FROM orders")?[0][0], "3");
+
+    // Fail: stops at the first bad value with its line number, leaving no table file behind
+    let failing = CsvIngestOptions { coercion_policy: CoercionPolicy::Fail, ..options.clone() };
+    let fail_dir = dir.join("fail");
+    let error = db.import_csv_to_file("orders", &csv_path, &fail_dir, TableFormat::Arrow, &failing, |_| {})
+        .expect_err("bad values should stop the import");
+    println!("Fail: {}", error);
+    assert!(error.to_string().contains("\"12a\" at line 52"));
+    assert!(!fail_dir.join("orders.arrow").exists());
+
+    // Fall back to Text: only the columns with bad values change type, and nothing is lost
+    let fallback = CsvIngestOptions { coercion_policy: CoercionPolicy::FallBackToText, ..options.clone() };
+    let text_dir = dir.join("text");
+    let summary = db.import_csv_to_file("orders_text", &csv_path, &text_dir, TableFormat::Arrow, &fallback, |_| {})?;
+    assert!(summary.coercion.is_empty());
+    let types: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+    println!("Fallback schema: {:?}", types);
+    assert_eq!(types, vec!["Int64", "Utf8", "Utf8"]);
+    let rows = db.execute_query("SELECT count, price FROM orders_text WHERE id = 52")?;
+    assert_eq!(rows[0], vec!["n/k".to_string(), "oops".to_string()]);
+
+    println!("\n✅ Coercion reports passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use leaf::core::{Database, TableFormat, ParquetCompression, CsvIngestOptions};
+use leaf::infer::{TypeInferrer, ColumnType, NumberFormat, ColumnTokens};
+use leaf::infer::duration_format;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing the column type catalogue");
+    println!("{}", "=".repeat(60));
+
+    // Durations in clock, unit and ISO notation
+    let second = 1_000_000_000i64;
+    let cases: [(&str, Option<i64>); 9] = [
+        ("1:30:00", Some(5_400 * second)),
+        ("36:00:00.5", Some(129_600 * second + second / 2)),
+        ("1h 30m", Some(5_400 * second)),
+        ("2.5s", Some(5 * second / 2)),
+        ("250ms", Some(second / 4)),
+        ("3d4h", Some(76 * 3_600 * second)),
+        ("PT1H30M", Some(5_400 * second)),
+        ("-5 min", Some(-300 * second)),
+        ("90", None),
+    ];
+    for (text, expected) in cases {
+        let parsed = duration_format::parse_duration(text);
+        println!("{:>12} -> {:?}", text, parsed);
+        assert_eq!(parsed, expected, "{}", text);
+    }
+    assert_eq!(duration_format::format_duration(5_400 * second + second / 4), "1h 30m 0.25s");
+    assert_eq!(duration_format::format_duration(second / 4), "250ms");
+
+    // Exact decimals
+    let english = NumberFormat::default();
+    assert_eq!(english.parse_decimal("$1,234.56", 38, 2), Some(123_456));
+    assert_eq!(english.parse_decimal("(0.125)", 38, 2), Some(-13), "extra places round half away from zero");
+    assert_eq!(english.parse_decimal("12.5%", 38, 4), Some(1_250));
+    assert_eq!(english.parse_decimal("12345", 4, 0), None, "too many digits for the precision");
+    assert_eq!(english.parse_u64("18,446,744,073,709,551,615"), Some(u64::MAX));
+    assert_eq!(english.parse_u64("-1"), None);
+
+    // Inference: narrowed integers, money, times of day, durations and low-cardinality text
+    let headers: Vec<String> = ["id", "big", "price", "ratio", "opens", "elapsed", "lap_time", "status", "note"]
+        .iter().map(|s| s.to_string()).collect();
+    let samples: Vec<Vec<String>> = (0..40)
+        .map(|i| vec![
+            i.to_string(),
+            (i as i64 * 10_000_000_000).to_string(),
+            format!("${}.{:02}", i, i),
+            format!("{}.5", i),
+            format!("{:02}:30:00", i % 24),
+            format!("{}h {}m", i, i % 60),
+            format!("00:{:02}:10", i),
+            ["open", "closed", "pending"][i % 3].to_string(),
+            format!("note {}", i),
+        ])
+        .collect();
+    let types: Vec<ColumnType> = TypeInferrer::infer_column_types(&headers, &samples)
+        .into_iter().map(|(_, column_type)| column_type).collect();
+    println!("Inferred: {:?}", types);
+    assert_eq!(types, vec![
+        ColumnType::Integer,
+        ColumnType::Integer,
+        ColumnType::Decimal { precision: 38, scale: 2 },
+        ColumnType::Real,
+        ColumnType::TimeOfDay,
+        ColumnType::Duration,
+        ColumnType::Duration,
+        ColumnType::Dictionary,
+        ColumnType::Text,
+    ]);
+    assert!(TypeInferrer::validate_column_type_change(&samples, 0, &ColumnType::Int8, &english, None, &ColumnTokens::default()).is_ok());
+    assert!(TypeInferrer::validate_column_type_change(&samples, 1, &ColumnType::Int32, &english, None, &ColumnTokens::default()).is_err());
+    assert!(TypeInferrer::validate_column_type_change(&samples, 0, &ColumnType::UInt16, &english, None, &ColumnTokens::default()).is_ok());
+    assert!(ColumnType::SELECTABLE.iter().any(|column_type| column_type.same_kind(&ColumnType::Decimal { precision: 10, scale: 4 })));
+
+    // End to end, including types only picked by hand
+    let dir = std::env::temp_dir().join("leaf_column_types_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    let csv_path = dir.join("shifts.csv");
+    let mut csv = String::from("id,level,reading,wage,starts,shift_length,site\n");
+    for i in 0..30 {
+        csv.push_str(&format!("{},{},{}.25,${}.10,{:02}:15:30.250,{}h 15m,{}\n", i, i % 5, i, i + 10, i % 24, i % 9, ["north", "south"][i % 2]));
+    }
+    csv.push_str("30,300,1.5,$1.00,25:00:00,soon,east\n");
+    std::fs::write(&csv_path, &csv)?;
+
+    let column_types = vec![
+        ColumnType::Int32,
+        ColumnType::UInt8,
+        ColumnType::Float32,
+        ColumnType::Decimal { precision: 10, scale: 2 },
+        ColumnType::TimeOfDay,
+        ColumnType::Duration,
+        ColumnType::Dictionary,
+    ];
+    let options = CsvIngestOptions { column_types: Some(column_types), ..CsvIngestOptions::default() };
+    let mut db = Database::open_writable(&dir)?;
+    for (name, format) in [("shifts", TableFormat::Arrow), ("shifts_parquet", TableFormat::Parquet(ParquetCompression::Snappy))] {
+        let summary = db.import_csv_to_file(name, &csv_path, &dir, format, &options, |_| {})?;
+        let schema: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+        println!("{} schema: {:?}", name, schema);
+        assert_eq!(schema, vec![
+            "Int32",
+            "UInt8",
+            "Float32",
+            "Decimal128(10, 2)",
+            "Time64(Microsecond)",
+            "Duration(Microsecond)",
+            "Dictionary(Int32, Utf8)",
+        ]);
+        // 300 overflows UInt8, 25:00:00 isn't a time of day and "soon" isn't a duration
+        assert_eq!(summary.coercion.total(), 3, "{}", name);
+
+        let rows = db.execute_query(&format!("SELECT level, reading, wage, starts, shift_length, site FROM {} WHERE id = 3", name))?;
+        println!("  {:?}", rows[0]);
+        // Parquet has no duration type and keeps the microsecond count
+        let shift_length = if name == "shifts" { "3h 15m" } else { "11700000000" };
+        assert_eq!(rows[0], vec!["3", "3.25", "13.10", "03:15:30.250", shift_length, "south"]);
+
+        let totals = db.execute_query(&format!("SELECT SUM(wage), COUNT(DISTINCT site), MAX(starts) FROM {}", name))?;
+        println!("  totals {:?}", totals[0]);
+        assert_eq!(totals[0][0], "739.00");
+        assert_eq!(totals[0][1], "3");
+        assert_eq!(totals[0][2], "23:15:30.250");
+        let north = db.execute_query(&format!("SELECT COUNT(*) FROM {} WHERE site = 'north'", name))?;
+        assert_eq!(north[0][0], "15");
+    }
+
+    // Arrow IPC stores dictionary columns as plain strings; Parquet keeps the encoding but stores durations as Int64
+    let arrow_types = db.get_column_types("SELECT shift_length, site FROM shifts")?;
+    let parquet_types = db.get_column_types("SELECT shift_length, site FROM shifts_parquet")?;
+    println!("stored: arrow {:?}, parquet {:?}", arrow_types, parquet_types);
+    assert_eq!(arrow_types[0].to_string(), "Duration(Microsecond)");
+    assert_eq!(arrow_types[1].to_string(), "Utf8");
+    assert_eq!(parquet_types[0].to_string(), "Int64");
+
+    println!("\n✅ Column types passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions, CsvReader};
+use leaf::core::csv_source;
+use std::io::Write;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing compressed CSV inputs");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_compressed_csv_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let rows = 20_000usize;
+    let mut csv = String::from("id,host,latency_ms\n");
+    for i in 0..rows {
+        csv.push_str(&format!("{},web{},{:.2}\n", i, i % 4, i as f64 / 7.0));
+    }
+
+    // gzip, written as two members the way appended daily logs end up
+    let gz_path = dir.join("daily.csv.gz");
+    {
+        let split = csv.find("\n10000,").unwrap() + 1;
+        let mut file = std::fs::File::create(&gz_path)?;
+        for part in [&csv[..split], &csv[split..]] {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(part.as_bytes())?;
+            file.write_all(&encoder.finish()?)?;
+        }
+    }
+
+    // zstd
+    let zst_path = dir.join("hourly.csv.zst");
+    std::fs::write(&zst_path, zstd::encode_all(csv.as_bytes(), 3)?)?;
+
+    // zip with one deflated and one stored member, plus a non-CSV member
+    let zip_path = dir.join("archive.zip");
+    {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path)?);
+        let deflated = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let stored = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("logs/monday.csv", deflated)?;
+        zip.write_all(csv.as_bytes())?;
+        zip.start_file("tuesday.csv", stored)?;
+        zip.write_all(b"id,host,latency_ms\n1,web1,0.5\n2,web2,0.75\n")?;
+        zip.start_file("README.md", deflated)?;
+        zip.write_all(b"not a table")?;
+        zip.finish()?;
+    }
+
+    // Zip members are listed as candidate files
+    let members = csv_source::list_archive_members(&zip_path)?;
+    println!("Archive members: {:?}", members);
+    assert_eq!(members, vec![zip_path.join("logs/monday.csv"), zip_path.join("tuesday.csv")]);
+    assert_eq!(csv_source::table_name_for(&members[0]), "monday");
+    assert_eq!(csv_source::table_name_for(&gz_path), "daily");
+    assert_eq!(csv_source::table_name_for(&zst_path), "hourly");
+
+    let mut db = Database::open_writable(&dir)?;
+    for (path, table) in [(&gz_path, "daily"), (&zst_path, "hourly"), (&members[0], "monday")] {
+        let compressed_size = if path.exists() { std::fs::metadata(path)?.len() } else { 0 };
+        let mut last_bytes = 0;
+        let mut total_bytes = 0;
+        db.import_csv_to_file(table, path, &dir, TableFormat::Arrow, &CsvIngestOptions::default(), |progress| {
+            last_bytes = progress.bytes_read;
+            total_bytes = progress.total_bytes;
+        })?;
+        println!("{}: read {} of {} compressed bytes", table, last_bytes, total_bytes);
+        assert_eq!(last_bytes, total_bytes, "progress should reach the end of the compressed input");
+        if compressed_size > 0 {
+            assert_eq!(total_bytes, compressed_size);
+        }
+
+        let count = db.execute_count_query(&format!("SELECT COUNT(*) FROM {}", table))?;
+        assert_eq!(count as usize, rows, "{} should have every row", table);
+        let last = db.execute_query(&format!("SELECT host FROM {} WHERE id = {}", table, rows - 1))?;
+        assert_eq!(last[0][0], format!("web{}", (rows - 1) % 4));
+    }
+
+    // The in-memory import paths read compressed files too
+    db.stream_insert_csv("tuesday", &members[1], ',', true)?;
+    assert_eq!(db.execute_count_query("SELECT COUNT(*) FROM tuesday")?, 2);
+
+    let mut reader = CsvReader::from_path(&gz_path)?;
+    assert_eq!(reader.headers()?, vec!["id", "host", "latency_ms"]);
+    assert_eq!(reader.records()?.len(), rows + 1);
+
+    println!("\n✅ Compressed CSV inputs passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions, CsvReader};
+use leaf::core::csv_tokenizer::{self, CsvTokenizer};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing RFC 4180 CSV tokenizer");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_csv_tokenizer_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // Quoted delimiters, "" escapes, a note spanning two lines, and significant whitespace
+    let csv_path = dir.join("notes.csv");
+    std::fs::write(&csv_path, concat!(
+        "\u{feff}# exported notes\r\n",
+        "id;note;code\r\n",
+        "1;\"plain; with delimiter\";  A1 \r\n",
+        "2;\"she said \"\"hi\"\"\";B2\r\n",
+        "3;\"first line\r\nsecond line\";C3\r\n",
+        "4;after the multi-line note;D4\r\n",
+    ))?;
+
+    let mut tokenizer = CsvTokenizer::from_path(&csv_path, ';', false, None)?;
+    let mut records = Vec::new();
+    while let Some(record) = tokenizer.next_record()? {
+        println!("line {}: {:?}", record.line, record.fields);
+        records.push(record);
+    }
+    assert_eq!(records.len(), 6, "the multi-line note should be a single record");
+    assert_eq!(records[2].fields, vec!["1", "plain; with delimiter", "  A1 "], "whitespace should be kept");
+    assert_eq!(records[3].fields[1], "she said \"hi\"", "doubled quotes should unescape");
+    assert_eq!(records[4].fields[1], "first line\r\nsecond line", "embedded newline should be kept");
+    assert_eq!(records[5].line, 7, "line numbers should account for the embedded newline");
+
+    let mut trimmed = CsvTokenizer::from_path(&csv_path, ';', true, None)?;
+    let trimmed_rows: Vec<_> = std::iter::from_fn(|| trimmed.next_record().ok().flatten()).collect();
+    assert_eq!(trimmed_rows[2].fields[2], "A1", "trim option should strip surrounding whitespace");
+
+    assert_eq!(csv_tokenizer::infer_delimiter(&csv_path, 1, None)?, ';', "delimiter should come from the header record");
+
+    // Full import keeps every row intact
+    let mut db = Database::open_writable(&dir)?;
+    let options = CsvIngestOptions {
+        header_row: 1,
+        ..CsvIngestOptions::default()
+    };
+    let summary = db.import_csv_to_file("notes", &csv_path, &dir, TableFormat::Arrow, &options, |_| {})?;
+    assert_eq!(summary.delimiter, ';');
+
+    let rows = db.execute_query("SELECT id, note, code FROM notes ORDER BY id")?;
+    for row in &rows {
+        println!("{:?}", row);
+    }
+    assert_eq!(rows.len(), 4, "no row after the multi-line note should be lost");
+    assert_eq!(rows[2][1], "first line\r\nsecond line");
+    assert_eq!(rows[0][2], "  A1 ");
+    assert_eq!(rows[3][1], "after the multi-line note");
+
+    // The simple reader and the in-memory import read records the same way
+    let plain_path = dir.join("plain.csv");
+    std::fs::write(&plain_path, "id,note\r\n1,\"two\r\nlines\"\r\n2,\"a, b\"\r\n")?;
+    let mut reader = CsvReader::from_path(&plain_path)?;
+    assert_eq!(reader.headers()?, ["id", "note"]);
+    assert_eq!(reader.records()?[1], ["1", "two\r\nlines"]);
+    assert_eq!(reader.sample_records(1)?, [["id", "note"]]);
+    assert_eq!(reader.read_record()?.unwrap(), ["id", "note"]);
+    assert_eq!(reader.read_record()?.unwrap()[0], "1");
+    db.stream_insert_csv("plain", &plain_path, ',', true)?;
+    let rows = db.execute_query("SELECT id, note FROM plain ORDER BY id")?;
+    assert_eq!(rows, [["1", "two\r\nlines"], ["2", "a, b"]]);
+
+    println!("\n✅ CSV tokenizer passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use leaf::core::{Database, TableFormat, ParquetCompression, CsvIngestOptions};
+use leaf::infer::{TypeInferrer, ColumnType, DateTimeFormat};
+use leaf::infer::datetime_format;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing datetime patterns and timezones");
+    println!("{}", "=".repeat(60));
+
+    // Offsets and fractions are read, and recorded for the column type
+    let zulu = datetime_format::parse_datetime("2024-03-01T10:00:00Z", None).expect("Z offset");
+    assert_eq!(zulu.offset.map(|offset| offset.local_minus_utc()), Some(0));
+    let plus_two = datetime_format::parse_datetime("2024-03-01 12:00:00.5+0200", None).expect("+0200 offset");
+    assert_eq!(plus_two.to_utc(None), datetime_format::parse_datetime("2024-03-01T10:00:00.5Z", None).unwrap().to_utc(None));
+    assert_eq!(datetime_format::fraction_digits("2024-03-01T10:00:00.123456+02:00"), 6);
+    assert_eq!(datetime_format::fraction_digits("2024-03-01 10:00"), 0);
+    assert!(datetime_format::parse_datetime("01.03.2024 10:00", None).is_none());
+    assert!(datetime_format::parse_datetime("01.03.2024 10:00", Some("%d.%m.%Y %H:%M")).is_some());
+    assert_eq!(datetime_format::pattern_column_type("%d.%m.%Y", ["01.03.2024", ""]), Some(ColumnType::Date));
+
+    let format = DateTimeFormat::infer(None, ["2024-03-01T10:00:00Z", "2024-03-01T10:00:00.25+01:00"]);
+    println!("Inferred: {:?} -> {}", format, format.arrow_type(None));
+    assert_eq!(format.arrow_type(None).to_string(), "Timestamp(Millisecond, Some(\"UTC\"))");
+
+    let headers = vec!["at".to_string()];
+    let samples = vec![vec!["2024-03-01T10:00:00+05:30".to_string()]];
+    assert_eq!(TypeInferrer::infer_column_types(&headers, &samples)[0].1, ColumnType::DateTime);
+
+    // A log with UTC instants, local wall-clock times and a European layout
+    let dir = std::env::temp_dir().join("leaf_datetime_format_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    let csv_path = dir.join("logs.csv");
+    std::fs::write(&csv_path, "id,at,local,custom,day\n\
+        1,2024-03-01T10:00:00Z,2024-03-01 10:00:00.250,01.03.2024 10:00,01.03.2024\n\
+        2,2024-03-01T12:00:00.123456+02:00,2024-03-01 11:00:00.5,02.03.2024 11:30,02.03.2024\n")?;
+
+    let patterns = vec![None, None, None, Some("%d.%m.%Y %H:%M".to_string()), Some("%d.%m.%Y".to_string())];
+    let options = CsvIngestOptions { datetime_patterns: patterns.clone(), ..CsvIngestOptions::default() };
+    let mut db = Database::open_writable(&dir)?;
+    let summary = db.import_csv_to_file("logs", &csv_path, &dir, TableFormat::Arrow, &options, |_| {})?;
+    let types: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+    println!("Schema: {:?}", types);
+    assert_eq!(types, vec![
+        "Int64",
+        "Timestamp(Microsecond, Some(\"UTC\"))",
+        "Timestamp(Millisecond, None)",
+        "Timestamp(Second, None)",
+        "Date32",
+    ]);
+    assert!(summary.coercion.is_empty());
+    let rows = db.execute_query("SELECT at, local, custom, day FROM logs ORDER BY id")?;
+    for row in &rows {
+        println!("  {:?}", row);
+    }
+    assert_eq!(rows[1][0], "2024-03-01 10:00:00.123456+00:00");
+    assert_eq!(rows[0][1], "2024-03-01 10:00:00.250");
+    assert_eq!(rows[1][2], "2024-03-02 11:30:00");
+    assert_eq!(rows[0][3], "2024-03-01");
+
+    // With a target timezone, naive times are read in it and every time column is tagged with it
+    let berlin = CsvIngestOptions {
+        datetime_patterns: patterns,
+        timezone: Some("Europe/Berlin".to_string()),
+        ..CsvIngestOptions::default()
+    };
+    let summary = db.import_csv_to_file("logs_berlin", &csv_path, &dir, TableFormat::Parquet(ParquetCompression::Snappy), &berlin, |_| {})?;
+    let types: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+    println!("Berlin schema: {:?}", types);
+    assert_eq!(types[2], "Timestamp(Millisecond, Some(\"Europe/Berlin\"))");
+    let rows = db.execute_query("SELECT at, local FROM logs_berlin ORDER BY id")?;
+    for row in &rows {
+        println!("  {:?}", row);
+    }
+    assert_eq!(rows[0][0], "2024-03-01 11:00:00+01:00", "UTC instants are shown in Berlin time");
+    assert_eq!(rows[0][1], "2024-03-01 10:00:00.250+01:00", "local times keep their wall-clock value");
+
+    // An unknown timezone is rejected before anything is written
+    let bad = CsvIngestOptions { timezone: Some("Mars/Olympus".to_string()), ..CsvIngestOptions::default() };
+    let error = db.import_csv_to_file("logs_bad", &csv_path, &dir, TableFormat::Arrow, &bad, |_| {})
+        .expect_err("unknown timezones should be rejected");
+    println!("Bad timezone: {}", error);
+    assert!(error.to_string().contains("Unknown timezone 'Mars/Olympus'"));
+    assert!(!dir.join("logs_bad.arrow").exists());
+
+    println!("\n✅ Datetime patterns and timezones passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
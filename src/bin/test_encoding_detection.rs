@@ -0,0 +1,110 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions};
+use leaf::core::csv_tokenizer::CsvTokenizer;
+use leaf::core::text_encoding;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing CSV encoding detection and transcoding");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_encoding_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    let text = "city,note\nMünchen,café crème\nZürich,Straße\n";
+
+    // UTF-16LE with a byte order mark, as exported by Excel's "Unicode Text"
+    let utf16_path = dir.join("utf16.csv");
+    let mut utf16 = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        utf16.extend_from_slice(&unit.to_le_bytes());
+    }
+    std::fs::write(&utf16_path, &utf16)?;
+
+    // UTF-16BE without a byte order mark
+    let utf16be_path = dir.join("utf16be.csv");
+    let utf16be: Vec<u8> = text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+    std::fs::write(&utf16be_path, &utf16be)?;
+
+    // Windows-1252 (single byte Latin-1 accents)
+    let latin1_path = dir.join("latin1.csv");
+    let (latin1, _, _) = encoding_rs::WINDOWS_1252.encode(text);
+    std::fs::write(&latin1_path, &latin1)?;
+
+    // UTF-8 with a byte order mark
+    let utf8_bom_path = dir.join("utf8_bom.csv");
+    let mut utf8_bom = vec![0xEF, 0xBB, 0xBF];
+    utf8_bom.extend_from_slice(text.as_bytes());
+    std::fs::write(&utf8_bom_path, &utf8_bom)?;
+
+    let cases = [
+        (&utf16_path, encoding_rs::UTF_16LE),
+        (&utf16be_path, encoding_rs::UTF_16BE),
+        (&latin1_path, encoding_rs::WINDOWS_1252),
+        (&utf8_bom_path, encoding_rs::UTF_8),
+    ];
+
+    for (path, expected) in cases {
+        let detected = text_encoding::detect_encoding(path)?;
+        println!("{}: detected {}", path.file_name().unwrap().to_string_lossy(), detected.name());
+        assert_eq!(detected, expected);
+
+        let mut tokenizer = CsvTokenizer::from_path(path, ',', false, None)?;
+        let header = tokenizer.next_record()?.expect("header record");
+        assert_eq!(header.fields, vec!["city", "note"], "the byte order mark should be stripped");
+        let first = tokenizer.next_record()?.expect("first data record");
+        assert_eq!(first.fields, vec!["München", "café crème"]);
+    }
+
+    // A multi-byte character cut off at the end of the sample is still UTF-8
+    let truncated = "naïve".as_bytes();
+    assert_eq!(text_encoding::detect_encoding_from_sample(&truncated[..3]), encoding_rs::UTF_8);
+
+    // Decoding with the wrong encoding picked by the user is honoured (no BOM to override it)
+    let mut forced = CsvTokenizer::from_path(&latin1_path, ',', false, Some(encoding_rs::UTF_8))?;
+    forced.next_record()?;
+    let garbled = forced.next_record()?.expect("data record");
+    assert!(garbled.fields[0].contains('\u{FFFD}'), "invalid UTF-8 should be replaced, not rejected");
+
+    // Importing transcodes to UTF-8 in the table file, and progress counts raw file bytes
+    let mut db = Database::open_writable(&dir)?;
+    let mut last_bytes = 0;
+    db.import_csv_to_file("from_utf16", &utf16_path, &dir, TableFormat::Arrow, &CsvIngestOptions::default(), |progress| {
+        last_bytes = progress.bytes_read;
+    })?;
+    assert_eq!(last_bytes, utf16.len() as u64);
+    let rows = db.execute_query("SELECT city, note FROM from_utf16 ORDER BY city")?;
+    assert_eq!(rows[1], vec!["Zürich".to_string(), "Straße".to_string()]);
+
+    // Non-UTF-8 CSV files found in a project folder are transcoded as they are loaded
+    let mut lazy = Database::open_writable(&dir)?;
+    lazy.load_table_csv("from_latin1", &latin1_path, ',', true)?;
+    let rows = lazy.execute_query("SELECT note FROM from_latin1 WHERE city = 'München'")?;
+    assert_eq!(rows[0][0], "café crème");
+
+    // Files without a header row are transcoded too
+    let headerless_path = dir.join("headerless_utf16.csv");
+    let mut headerless = vec![0xFF, 0xFE];
+    for unit in "Zürich,Straße\nMünchen,café crème\n".encode_utf16() {
+        headerless.extend_from_slice(&unit.to_le_bytes());
+    }
+    std::fs::write(&headerless_path, &headerless)?;
+    for (table, path, has_header) in [("headerless_utf16", &headerless_path, false), ("headerless_latin1", &latin1_path, false)] {
+        lazy.load_table_csv(table, path, ',', has_header)?;
+        let rows = lazy.execute_query(&format!("SELECT col_1 FROM {} WHERE col_0 = 'Zürich'", table))?;
+        assert_eq!(rows, [["Straße"]], "{}", table);
+    }
+
+    // ...including when the whole folder is loaded at once
+    let project = dir.join("project");
+    std::fs::create_dir_all(&project)?;
+    std::fs::copy(&latin1_path, project.join("cities.csv"))?;
+    let mut opened = Database::open_writable(&project)?;
+    assert_eq!(opened.load_all_tables_from_directory(&project)?, ["cities"]);
+    let rows = opened.execute_query("SELECT note FROM cities WHERE city = 'München'")?;
+    assert_eq!(rows[0][0], "café crème");
+
+    println!("\n✅ Encoding detection passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
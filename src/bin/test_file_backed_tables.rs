@@ -0,0 +1,113 @@
+use leaf::core::{Database, TableFormat, ParquetCompression};
+use datafusion::arrow::array::{Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing file-backed tables");
+    println!("{}", "=".repeat(60));
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("sensor", DataType::Utf8, false),
+        Field::new("reading", DataType::Float64, true),
+    ]));
+
+    // Several batches so the files have several record batches / row groups
+    let batches: Vec<RecordBatch> = (0..5i64).map(|chunk| {
+        let ids: Vec<i64> = (chunk * 2000..(chunk + 1) * 2000).collect();
+        let sensors: Vec<String> = ids.iter().map(|id| format!("s{}", id % 4)).collect();
+        let readings: Vec<f64> = ids.iter().map(|id| *id as f64 / 10.0).collect();
+        RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(StringArray::from(sensors)),
+            Arc::new(Float64Array::from(readings)),
+        ]).unwrap()
+    }).collect();
+    let expected_rows = 10_000i64;
+
+    let dir = std::env::temp_dir().join("leaf_file_backed_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // Write the same data as Arrow, Parquet and CSV
+    let mut db = Database::open_writable(&dir)?;
+    db.insert_record_batches("readings", schema.clone(), batches)?;
+    let arrow_path = db.save_table_as("readings", &dir, TableFormat::Arrow)?;
+    let parquet_path = db.save_table_as("readings", &dir, TableFormat::Parquet(ParquetCompression::Zstd))?;
+    let csv_path = dir.join("readings_csv.csv");
+    let mut csv_writer = csv::Writer::from_path(&csv_path)?;
+    csv_writer.write_record(["id", "sensor", "reading"])?;
+    for row in db.execute_query("SELECT id, sensor, reading FROM readings")? {
+        csv_writer.write_record(&row)?;
+    }
+    csv_writer.flush()?;
+
+    // Register each file without loading it
+    let mut lazy = Database::open_writable(&dir)?;
+    lazy.load_table_arrow_ipc("from_arrow", &arrow_path)?;
+    lazy.load_table_parquet("from_parquet", &parquet_path)?;
+    lazy.load_table_csv("from_csv", &csv_path, ',', true)?;
+
+    for table in lazy.get_tables()? {
+        println!("{}: {:?} rows, {} columns", table.name, table.row_count, table.columns.len());
+        // CSV files record no row count, and counting one would read the whole file
+        let expected = (table.name != "from_csv").then_some(expected_rows);
+        assert_eq!(table.row_count, expected, "row count should come from the file's metadata");
+    }
+
+    for table in ["from_arrow", "from_parquet", "from_csv"] {
+        // Projection and filter are pushed into the scan
+        let rows = lazy.execute_query(&format!(
+            "SELECT COUNT(*), MAX(reading) FROM {} WHERE sensor = 's1' AND id >= 5000", table
+        ))?;
+        println!("{}: filtered count {}, max {}", table, rows[0][0], rows[0][1]);
+        assert_eq!(rows[0][0], "1250");
+
+        // Plain scans keep the file's row order
+        let batch = lazy.get_table_arrow_batch(table)?;
+        let ids = batch.column(0).as_any().downcast_ref::<Int64Array>().expect("id column");
+        assert!(ids.values().windows(2).all(|pair| pair[0] < pair[1]), "rows should stay in file order");
+    }
+
+    // A cloned database keeps its file-backed tables
+    let cloned = lazy.clone();
+    let count = cloned.execute_count_query("SELECT COUNT(*) FROM from_parquet")?;
+    assert_eq!(count, expected_rows, "cloned database should still see file-backed tables");
+
+    // Replacing one table leaves the others registered
+    lazy.load_table_arrow_ipc("from_csv", &arrow_path)?;
+    let count = lazy.execute_count_query("SELECT COUNT(*) FROM from_parquet")?;
+    assert_eq!(count, expected_rows, "other tables should survive a replacement");
+
+    // Saving a file-backed table streams it into the other format, and saving onto its own file is a no-op
+    let saved = lazy.save_table_as("from_arrow", &dir, TableFormat::Parquet(ParquetCompression::Snappy))?;
+    lazy.load_table_parquet("resaved", &saved)?;
+    assert_eq!(lazy.execute_count_query("SELECT COUNT(*) FROM resaved")?, expected_rows);
+    lazy.load_table_arrow_ipc("readings", &arrow_path)?;
+    lazy.save_table_as("readings", &dir, TableFormat::Arrow)?;
+    assert_eq!(lazy.execute_count_query("SELECT COUNT(*) FROM readings")?, expected_rows, "saving onto the backing file should keep it intact");
+
+    // Names with glob characters are file names, not patterns
+    let bracketed = dir.join("data[1].arrow");
+    std::fs::copy(&arrow_path, &bracketed)?;
+    db.insert_record_batch("data1", &db.get_table_arrow_batch("readings")?.slice(0, 10))?;
+    let lookalike = db.save_table_as("data1", &dir, TableFormat::Arrow)?;
+    lazy.load_table_arrow_ipc("bracketed", &bracketed)?;
+    assert_eq!(lazy.execute_count_query("SELECT COUNT(*) FROM bracketed")?, expected_rows, "only the named file should be read");
+    std::fs::remove_file(&bracketed)?;
+    std::fs::remove_file(&lookalike)?;
+
+    // Directory loading registers every format
+    let mut project = Database::open_writable(&dir)?;
+    let mut loaded = project.load_all_tables_from_directory(&dir)?;
+    loaded.sort();
+    println!("Loaded from directory: {:?}", loaded);
+    assert!(loaded.contains(&"readings".to_string()) && loaded.contains(&"readings_csv".to_string()));
+
+    println!("\n✅ File-backed tables passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions, ImportProfile, ProfileColumn, ProfileMatch};
+use leaf::core::{csv_ingest, fixed_width, table_format};
+use leaf::infer::ColumnType;
+use leaf::ui::FileConfig;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing fixed-width text imports");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_fixed_width_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // A mainframe extract: padded columns, right-aligned amounts, names with spaces and a blank line
+    let names = ["Anna Lee", "Bob", "Müller", "Zoë"];
+    let mut text = "ID   NAME        AMOUNT  BOOKED\n".to_string();
+    for i in 0..30 {
+        let amount = if i == 25 { "12x".to_string() } else { format!("{:.2}", i as f64 * 1.5) };
+        text.push_str(&format!("{:<5}{:<12}{:>6}  2026-01-{:02}\n", i, names[i % 4], amount, i + 1));
+        if i == 10 {
+            text.push_str("   \n");
+        }
+    }
+    let accounts = dir.join("accounts.txt");
+    std::fs::write(&accounts, &text)?;
+
+    // Boundaries are guessed from where text starts after a column that is blank in every line
+    // With too few lines the space inside "Anna Lee" looks like a column gap; Müller's line fills it
+    let lines = fixed_width::sample_lines(&accounts, None, 3)?;
+    assert_eq!(lines.len(), 3);
+    assert_eq!(fixed_width::guess_boundaries(&lines), vec![5, 10, 17, 25]);
+    let lines = fixed_width::sample_lines(&accounts, None, fixed_width::SAMPLE_LINES)?;
+    assert_eq!(lines.len(), 32);
+    assert_eq!(fixed_width::guess_boundaries(&lines), vec![5, 17, 25]);
+    let boundaries = fixed_width::detect(&accounts, None)?.expect("fixed-width columns");
+    println!("boundaries: {}", fixed_width::format_boundaries(&boundaries));
+    assert_eq!(boundaries, vec![5, 17, 25]);
+
+    // A delimited file is left to the CSV reader
+    let csv = dir.join("accounts.csv");
+    std::fs::write(&csv, "id,name\n1,Anna Lee\n")?;
+    assert_eq!(fixed_width::detect(&csv, None)?, None);
+
+    // A single column has no delimiter, but the space inside its values isn't a gap in its header
+    let single = "timestamp\n2024-01-01 10:00:00\n2024-01-01 11:30:00\n";
+    for name in ["readings.txt", "readings"] {
+        std::fs::write(dir.join(name), single)?;
+        assert_eq!(fixed_width::detect(&dir.join(name), None)?, None, "{}", name);
+    }
+    // and a .csv or .tsv file is never fixed-width, however its lines line up
+    for name in ["aligned.csv", "aligned.tsv.gz"] {
+        std::fs::write(dir.join(name), &text)?;
+        assert_eq!(fixed_width::detect(&dir.join(name), None)?, None, "{}", name);
+    }
+    std::fs::write(dir.join("accounts.dat"), &text)?;
+    assert_eq!(fixed_width::detect(&dir.join("accounts.dat"), None)?, Some(vec![5, 17, 25]));
+
+    // Positions count characters, not bytes, and short lines leave the last fields empty
+    assert_eq!(fixed_width::split_line("3    Müller        4.50  2026-01-04", &boundaries), ["3", "Müller", "4.50", "2026-01-04"]);
+    assert_eq!(fixed_width::split_line("7    Zoë", &boundaries), ["7", "Zoë", "", ""]);
+    assert_eq!(fixed_width::parse_boundaries("20, 8,,8 31")?, vec![8, 20, 31]);
+    assert!(fixed_width::parse_boundaries("8, x").is_err());
+    assert!(fixed_width::parse_boundaries("0").is_err());
+
+    // The import dialog switches to fixed width by itself
+    let config = FileConfig::new(accounts.clone());
+    assert_eq!(config.column_boundaries, Some(vec![5, 17, 25]));
+    assert_eq!(FileConfig::new(csv.clone()).column_boundaries, None);
+
+    // The value that isn't a number sits past the sample, so only a full check finds it
+    let options = CsvIngestOptions {
+        column_boundaries: Some(boundaries.clone()),
+        sample_size: 5,
+        ..CsvIngestOptions::default()
+    };
+    let report = csv_ingest::check_coercion(&accounts, &options, |_| {})?;
+    println!("coercion issues: {}", report.total());
+    assert_eq!(report.total(), 1);
+    assert_eq!(report.columns[0].column, "AMOUNT");
+
+    let full = CsvIngestOptions { sample_size: 1000, ..options };
+    let mut db = Database::open_writable(&dir)?;
+    let summary = db.import_csv_to_file("accounts", &accounts, &dir, TableFormat::Arrow, &full, |_| {})?;
+    assert_eq!(summary.rows, 30);
+    let (schema, _) = table_format::read_batches(&dir.join("accounts.arrow"))?;
+    let types: Vec<String> = schema.fields().iter().map(|f| format!("{} {}", f.name(), f.data_type())).collect();
+    println!("types: {:?}", types);
+    assert_eq!(schema.fields().iter().map(|f| f.name().as_str()).collect::<Vec<_>>(), ["ID", "NAME", "AMOUNT", "BOOKED"]);
+    assert!(types[3].ends_with("Date32"));
+    let totals = db.execute_query("SELECT COUNT(*), COUNT(DISTINCT \"NAME\"), MAX(\"BOOKED\") FROM accounts")?;
+    println!("totals: {:?}", totals[0]);
+    assert_eq!(totals[0], vec!["30", "4", "2026-01-30"]);
+    assert_eq!(db.execute_query("SELECT \"NAME\" FROM accounts WHERE \"ID\" = 2")?[0], vec!["Müller"]);
+
+    // The same extract in Windows-1252 reads the same once the encoding is given
+    let latin = dir.join("accounts_latin.txt");
+    let (encoded, _, _) = encoding_rs::WINDOWS_1252.encode(&text);
+    std::fs::write(&latin, &encoded)?;
+    let latin_options = CsvIngestOptions {
+        column_boundaries: Some(boundaries.clone()),
+        encoding: Some(encoding_rs::WINDOWS_1252),
+        ..CsvIngestOptions::default()
+    };
+    db.import_csv_to_file("accounts_latin", &latin, &dir, TableFormat::Arrow, &latin_options, |_| {})?;
+    assert_eq!(db.execute_query("SELECT \"NAME\" FROM accounts_latin WHERE \"ID\" = 3")?[0], vec!["Zoë"]);
+
+    // A profile keeps the boundaries and recognizes the next extract by its headers
+    let mut profile = ImportProfile {
+        name: "Accounts".to_string(),
+        filename_pattern: String::new(),
+        columns: Vec::new(),
+        ..config.to_profile("", "")
+    };
+    assert_eq!(profile.column_boundaries, Some(vec![5, 17, 25]));
+    for name in ["ID", "NAME", "AMOUNT", "BOOKED"] {
+        profile.columns.push(ProfileColumn {
+            name: name.to_string(),
+            data_type: ColumnType::Text,
+            included: true,
+            datetime_pattern: String::new(),
+            null_values: String::new(),
+            true_values: String::new(),
+            false_values: String::new(),
+        });
+    }
+    let profiles = vec![profile];
+    let (matched, how) = ImportProfile::find_for_file(&profiles, &latin).expect("profile for the same columns");
+    assert_eq!((matched.name.as_str(), how), ("Accounts", ProfileMatch::Headers));
+    let delimited = ImportProfile { column_boundaries: None, ..profiles[0].clone() };
+    assert!(ImportProfile::find_for_file(&[delimited], &accounts).is_none());
+
+    println!("\n✅ Fixed-width import passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions, CoercionPolicy, RejectPolicy};
+use leaf::core::csv_union::{self, UnionOptions, SOURCE_COLUMN};
+use leaf::core::table_format;
+use leaf::infer::ColumnType;
+use datafusion::arrow::array::StringArray;
+use std::path::PathBuf;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing importing a folder of CSV files as one table");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_folder_union_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let logs = dir.join("logs");
+    std::fs::create_dir_all(&logs)?;
+    let project = dir.join("project");
+
+    // Hourly shards: later ones reorder the columns, drop `status`, add `latency`
+    // and widen `bytes` from whole numbers to decimals
+    for hour in 0..12 {
+        let mut csv = match hour {
+            0..=5 => String::from("ts,host,status,bytes\n"),
+            6..=9 => String::from("host,ts,bytes,latency\n"),
+            _ => String::from("ts,host,status,bytes,latency\n"),
+        };
+        for i in 0..50 {
+            let ts = format!("2026-01-01 {:02}:{:02}:00", hour, i);
+            let host = ["web1", "web2"][i % 2];
+            match hour {
+                0..=5 => csv.push_str(&format!("{},{},{},{}\n", ts, host, 200, i)),
+                6..=9 => csv.push_str(&format!("{},{},{}.5,{}\n", host, ts, i, i * 3)),
+                // The last shards have no latency values at all
+                _ => csv.push_str(&format!("{},{},{},{},\n", ts, host, 404, i)),
+            }
+        }
+        std::fs::write(logs.join(format!("2026-01-01-{:02}.csv", hour)), csv)?;
+    }
+    std::fs::write(logs.join("2025-12-31-23.csv"), "ts,host\n2025-12-31 23:00:00,old\n")?;
+    std::fs::write(logs.join("notes.md"), "not a csv")?;
+    std::fs::write(logs.join("late_rejects.csv"), "ts,host\n2026-01-02 00:00:00,late\n")?;
+    std::fs::create_dir_all(logs.join("rejects"))?;
+    std::fs::write(logs.join("rejects").join("hourly_rejects.csv"), "line,reason,row\n")?;
+
+    // A folder picks up every CSV file; a pattern only the matching ones, ignoring case
+    let all = csv_union::expand_pattern(&logs.display().to_string())?;
+    assert_eq!(all.len(), 14, "notes.md is left out, a user's own *_rejects.csv is not");
+    assert!(csv_union::expand_pattern(&logs.join("*").join("*.csv").display().to_string()).is_err(), "the importer's rejects are left out");
+    let pattern = logs.join("2026-*.CSV").display().to_string();
+    let files = csv_union::expand_pattern(&pattern)?;
+    println!("{} matches {} files", pattern, files.len());
+    assert_eq!(files.len(), 12);
+    assert!(files.windows(2).all(|pair| pair[0] < pair[1]), "sorted by path");
+    let nested = dir.join("*").join("2026-01-01-0?.csv").display().to_string();
+    assert_eq!(csv_union::expand_pattern(&nested)?.len(), 10);
+    assert!(csv_union::expand_pattern(&logs.join("2027-*.csv").display().to_string()).is_err());
+
+    // One schema across all shards, with a source column, parsed in parallel
+    let mut db = Database::open_writable(&project)?;
+    let options = CsvIngestOptions { batch_size: 20, ..CsvIngestOptions::default() };
+    let union = UnionOptions { csv: options.clone(), threads: 4, ..UnionOptions::default() };
+    let mut last_rows = 0;
+    let summary = db.import_csv_union_to_file("hourly", &files, &project, TableFormat::Arrow, &union, |progress| {
+        assert!(progress.rows >= last_rows);
+        last_rows = progress.rows;
+    })?;
+    println!("imported {} rows from {} files", summary.rows, summary.files.len());
+    assert_eq!(summary.rows, 600);
+    assert_eq!(last_rows, 600);
+    assert_eq!(summary.files.len(), 12);
+    assert_eq!(summary.files[0].path, files[0]);
+    assert_eq!(summary.files[6].missing, ["status"]);
+    assert_eq!(summary.files[0].missing, ["latency"]);
+    assert!(summary.rejects.is_clean());
+
+    let (schema, batches) = table_format::read_batches(&project.join("hourly.arrow"))?;
+    let types: Vec<String> = schema.fields().iter().map(|f| format!("{} {}", f.name(), f.data_type())).collect();
+    println!("schema: {:?}", types);
+    assert_eq!(types[1..], ["host Utf8", "status Int64", "bytes Float64", "latency Int64", "source_file Utf8"]);
+    assert!(types[0].starts_with("ts Timestamp"));
+
+    // Files are parsed in parallel but their rows are written in file order, so every import gives the same table
+    let sources: Vec<String> = batches.iter()
+        .flat_map(|batch| {
+            let column = batch.column(batch.num_columns() - 1).as_any().downcast_ref::<StringArray>().expect("source column");
+            column.iter().map(|source| source.unwrap_or_default().to_string()).collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(sources.len(), 600);
+    assert!(sources.windows(2).all(|pair| pair[0] <= pair[1]), "rows follow the file order");
+    db.import_csv_union_to_file("hourly_again", &files, &project, TableFormat::Arrow, &union, |_| {})?;
+    let (_, again) = table_format::read_batches(&project.join("hourly_again.arrow"))?;
+    assert_eq!(again, batches);
+
+    let totals = db.execute_query(
+        "SELECT COUNT(*), COUNT(status), COUNT(latency), COUNT(DISTINCT source_file), SUM(bytes) FROM hourly"
+    )?;
+    println!("totals: {:?}", totals[0]);
+    assert_eq!(totals[0], vec!["600", "400", "200", "12", "14800"]);
+    let sources = db.execute_query("SELECT source_file, COUNT(*) FROM hourly GROUP BY source_file ORDER BY source_file LIMIT 1")?;
+    assert_eq!(sources[0], vec!["2026-01-01-00.csv", "50"]);
+
+    // One thread keeps the rows in file order; without a source column the table has just the data
+    let sequential = UnionOptions { csv: options.clone(), source_column: None, threads: 1 };
+    let summary = db.import_csv_union_to_file("hourly_seq", &files, &project, TableFormat::Arrow, &sequential, |_| {})?;
+    assert_eq!(summary.rows, 600);
+    let (schema, batches) = table_format::read_batches(&project.join("hourly_seq.arrow"))?;
+    assert!(schema.field_with_name(SOURCE_COLUMN).is_err());
+    let first = db.execute_query("SELECT host FROM hourly_seq LIMIT 1")?;
+    assert_eq!(first[0], vec!["web1"]);
+    assert!(batches.len() >= 12 * 3);
+
+    // A type given for the first file is widened to fit the others
+    let given = CsvIngestOptions {
+        column_types: Some(vec![ColumnType::Text, ColumnType::Text, ColumnType::Integer, ColumnType::Integer]),
+        ..options.clone()
+    };
+    db.import_csv_union_to_file("hourly_given", &files, &project, TableFormat::Arrow, &UnionOptions { csv: given, ..sequential.clone() }, |_| {})?;
+    let (schema, _) = table_format::read_batches(&project.join("hourly_given.arrow"))?;
+    assert_eq!(schema.field_with_name("ts")?.data_type().to_string(), "Utf8");
+    assert_eq!(schema.field_with_name("bytes")?.data_type().to_string(), "Float64");
+
+    // Bad values past the sample and ragged rows are reported per file
+    let bad = dir.join("bad");
+    std::fs::create_dir_all(&bad)?;
+    std::fs::write(bad.join("a.csv"), "id,amount\n1,10\n2,20\n")?;
+    std::fs::write(bad.join("b.csv"), "id,amount\n3,30\n4,40,extra\n5,fifty\n")?;
+    let bad_files = csv_union::expand_pattern(&bad.display().to_string())?;
+    let options = CsvIngestOptions { sample_size: 1, ..options };
+    let checks = csv_union::check_union(&bad_files, &UnionOptions { csv: options.clone(), threads: 2, ..UnionOptions::default() }, |_| {})?;
+    assert_eq!(checks.iter().map(|shard| shard.coercion.total()).collect::<Vec<_>>(), [0, 1]);
+    assert!(!project.join("rejects").join("b_amounts_rejects.csv").exists(), "checking writes nothing");
+
+    let fallback = CsvIngestOptions {
+        coercion_policy: CoercionPolicy::FallBackToText,
+        reject_policy: RejectPolicy::Skip,
+        ..options.clone()
+    };
+    let summary = db.import_csv_union_to_file("amounts", &bad_files, &project, TableFormat::Arrow, &UnionOptions { csv: fallback, ..union.clone() }, |_| {})?;
+    assert_eq!((summary.rows, summary.rejects.rejected), (4, 1));
+    let rejects: Vec<PathBuf> = summary.files.iter().filter_map(|file| file.rejects_path.clone()).collect();
+    assert_eq!(rejects, [project.join("rejects").join("b_amounts_rejects.csv")]);
+    let (schema, _) = table_format::read_batches(&project.join("amounts.arrow"))?;
+    assert_eq!(schema.field_with_name("amount")?.data_type().to_string(), "Utf8");
+
+    // A failed import leaves no table behind
+    let fail = CsvIngestOptions { coercion_policy: CoercionPolicy::Fail, ..options.clone() };
+    assert!(db.import_csv_union_to_file("failed", &bad_files, &project, TableFormat::Arrow, &UnionOptions { csv: fail, ..union.clone() }, |_| {}).is_err());
+    assert!(!project.join("failed.arrow").exists());
+    assert!(!project.join("failed.arrow.partial").exists());
+
+    println!("\n✅ Folder union import passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use leaf::core::CsvIngestOptions;
+use leaf::core::csv_ingest;
+use leaf::infer::{TypeInferrer, ColumnType, NumberFormat, ColumnTokens};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing inference confidence and the whole-file pass");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_full_file_inference_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // `code` is numeric for the first 5000 rows, well past the 1000-row sample
+    let csv_path = dir.join("orders.csv");
+    let mut csv = String::from("id,code,amount,region\n");
+    for i in 0..6000 {
+        let code = if i == 5000 { "n/k".to_string() } else { (i % 700).to_string() };
+        csv.push_str(&format!("{},{},{}.5,{}\n", i, code, i % 90, ["north", "south", "east"][i % 3]));
+    }
+    std::fs::write(&csv_path, &csv)?;
+
+    // The sample alone sees a clean integer column
+    let headers: Vec<String> = ["id", "code", "amount", "region"].iter().map(|s| s.to_string()).collect();
+    let sample: Vec<Vec<String>> = csv.lines().skip(1).take(1000)
+        .map(|line| line.split(',').map(String::from).collect())
+        .collect();
+    let sampled = TypeInferrer::infer_columns_with_confidence(&headers, &sample, &vec![ColumnTokens::new(&[], &[], Default::default()); 4], &NumberFormat::default(), &[None, None, None, None]);
+    for inference in &sampled {
+        println!("sample: {:?} {:.2} - {}", inference.column_type, inference.confidence, inference.reason);
+    }
+    assert_eq!(sampled[1].column_type, ColumnType::Integer, "a sample keeps 64 bits, since later rows may not fit fewer");
+    assert!(sampled[1].confidence > 0.95);
+    assert_eq!(sampled[1].reason, "All 1000 values are whole numbers from 0 to 699");
+
+    // The whole file finds the stray value
+    let mut progress_calls = 0;
+    let mut last_rows = 0;
+    let options = CsvIngestOptions { batch_size: 1000, ..CsvIngestOptions::default() };
+    let full = csv_ingest::infer_full_file(&csv_path, &options, |progress| {
+        progress_calls += 1;
+        last_rows = progress.rows;
+    })?;
+    for inference in &full {
+        println!("full:   {:?} {:.2} - {} {:?}", inference.column_type, inference.confidence, inference.reason, inference.candidates);
+    }
+    assert_eq!(progress_calls, 7, "every 1000 rows and once at the end");
+    assert_eq!(last_rows, 6000);
+
+    let types: Vec<ColumnType> = full.iter().map(|inference| inference.column_type.clone()).collect();
+    assert_eq!(types, vec![ColumnType::UInt16, ColumnType::Text, ColumnType::Float32, ColumnType::Dictionary]);
+
+    let code = &full[1];
+    assert_eq!(code.values, 6000);
+    assert!(code.confidence < 0.2, "text that is mostly numbers is a weak guess");
+    assert_eq!(code.reason, "5999 of 6000 values read as Integer (64-bit), but not 'n/k'");
+    assert_eq!(code.candidates[0].0, ColumnType::Integer);
+    assert!((code.candidates[0].1 - 5999.0 / 6000.0).abs() < 1e-6, "values after the first miss still count towards the share");
+
+    // Clean columns are near certain, and every candidate reads them completely
+    assert!(full[0].confidence > 0.99);
+    assert_eq!(full[2].candidates[0], (ColumnType::Real, 1.0));
+
+    // Only a whole-file pass narrows numbers: signed when any is negative, Float32 when it holds every value exactly.
+    // 123456.78 and 0.1 would be stored as 123456.78125 and 0.100000001, so sums would drift from the source
+    let names: Vec<String> = ["delta", "count", "share", "price"].iter().map(|s| s.to_string()).collect();
+    let mut profiles = TypeInferrer::column_profiles(&names, &[], &NumberFormat::default(), &[]);
+    for row in [["-5", "70000", "0.25", "123456.78"], ["120", "12", "2.5", "0.1"]] {
+        for (profile, value) in profiles.iter_mut().zip(row) {
+            profile.observe(value);
+        }
+    }
+    let narrowed: Vec<ColumnType> = profiles.iter().map(|profile| profile.finish_whole_file().column_type).collect();
+    assert_eq!(narrowed, [ColumnType::Int32, ColumnType::UInt32, ColumnType::Float32, ColumnType::Real]);
+    let sampled: Vec<ColumnType> = profiles.iter().map(|profile| profile.finish().column_type).collect();
+    assert_eq!(sampled, [ColumnType::Integer, ColumnType::Integer, ColumnType::Real, ColumnType::Real]);
+    assert!(full[3].confidence > 0.99);
+    assert!(full[3].reason.starts_with("Only 3 distinct values among 6000"));
+
+    // A handful of values is weak evidence even when they all agree
+    let few = TypeInferrer::infer_columns_with_confidence(&headers[..1], &[vec!["1".to_string()], vec!["2".to_string()]], &[ColumnTokens::default()], &NumberFormat::default(), &[None]);
+    assert!(few[0].confidence < 0.2);
+
+    println!("\n✅ Full-file inference passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use leaf::core::{Database, TableFormat, ImportProfile, ProfileColumn, ProfileMatch, CoercionPolicy};
+use leaf::infer::{ColumnType, NumberFormat};
+use leaf::ui::FileConfig;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing saved import profiles");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_import_profiles_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let profiles_dir = dir.join("profiles");
+    std::fs::create_dir_all(&dir)?;
+
+    // Yesterday's export: a title line, semicolons and German numbers
+    let header = "id;amount;booked;state;note";
+    let write_export = |name: &str, rows: usize| -> std::io::Result<std::path::PathBuf> {
+        let mut csv = format!("Daily export\n{}\n", header);
+        for i in 0..rows {
+            csv.push_str(&format!("{};{},{:02};{:02}.01.2024;{};-\n", i, i * 3, i % 100, i % 28 + 1, ["an", "aus"][i % 2]));
+        }
+        let path = dir.join(name);
+        std::fs::write(&path, csv)?;
+        Ok(path)
+    };
+    let monday = write_export("sales_2024-01-01.csv", 40)?;
+
+    // Configure the first file by hand and save it as a profile
+    let mut config = FileConfig::new(monday.clone());
+    config.header_row = 2;
+    config.delimiter = ';';
+    config.number_format = NumberFormat::PRESETS[1];
+    config.coercion_policy = CoercionPolicy::FallBackToText;
+    let mut first = ImportProfile {
+        name: "Daily sales".to_string(),
+        filename_pattern: ImportProfile::suggest_pattern("sales_2024-01-01.csv"),
+        columns: Vec::new(),
+        ..config.to_profile("", "")
+    };
+    assert_eq!(first.filename_pattern, "sales_*-*-*.csv");
+    for (name, data_type) in [
+        ("id", ColumnType::Int32),
+        ("amount", ColumnType::Decimal { precision: 12, scale: 2 }),
+        ("booked", ColumnType::Date),
+        ("state", ColumnType::Boolean),
+        ("note", ColumnType::Text),
+    ] {
+        first.columns.push(ProfileColumn {
+            name: name.to_string(),
+            data_type,
+            included: name != "note",
+            datetime_pattern: if name == "booked" { "%d.%m.%Y".to_string() } else { String::new() },
+            null_values: String::new(),
+            true_values: if name == "state" { "an".to_string() } else { String::new() },
+            false_values: if name == "state" { "aus".to_string() } else { String::new() },
+        });
+    }
+    let saved_path = first.save(&profiles_dir)?;
+    println!("Saved {}", saved_path.display());
+    assert_eq!(saved_path.file_name().unwrap(), "Daily_sales.json");
+
+    // Profiles round-trip through JSON; other files in the folder are skipped
+    std::fs::write(profiles_dir.join("broken.json"), "{ not a profile")?;
+    std::fs::write(profiles_dir.join("notes.txt"), "ignored")?;
+    let other = ImportProfile { name: "Another layout".to_string(), filename_pattern: "stock_??.csv".to_string(), ..first.clone() };
+    other.save(&profiles_dir)?;
+    let profiles = ImportProfile::load_all(&profiles_dir)?;
+    let names: Vec<&str> = profiles.iter().map(|profile| profile.name.as_str()).collect();
+    assert_eq!(names, ["Another layout", "Daily sales"]);
+    assert_eq!(profiles[1], first);
+
+    // A new day's export matches by its columns, whatever it is called
+    let tuesday = write_export("export (2).csv", 30)?;
+    let (matched, how) = ImportProfile::find_for_file(&profiles, &tuesday).expect("profile for the same columns");
+    println!("{} -> {} ({})", tuesday.display(), matched.name, how.display_name());
+    assert_eq!(matched.name, "Another layout", "the first profile with the same columns wins");
+    assert_eq!(how, ProfileMatch::Headers);
+
+    // A changed layout still matches by file name
+    let changed = dir.join("sales_2024-01-03.csv");
+    std::fs::write(&changed, "Daily export\nid;amount;booked;state;note;extra\n1;2,00;03.01.2024;an;-;x\n")?;
+    let (matched, how) = ImportProfile::find_for_file(&profiles, &changed).expect("profile for the file name");
+    assert_eq!((matched.name.as_str(), how), ("Daily sales", ProfileMatch::FileName));
+    assert!(ImportProfile::find_for_file(&profiles, &dir.join("stock_123.csv")).is_none());
+    assert!(profiles[0].matches_file_name("STOCK_07.csv"));
+
+    // Applying the profile gives the same import as the hand-made settings
+    let mut next = FileConfig::new(tuesday.clone());
+    let profile = &profiles[1];
+    next.apply_profile(profile, ProfileMatch::Headers);
+    next.apply_profile_columns(profile);
+    assert_eq!(next.header_row, 2);
+    assert_eq!(next.delimiter, ';');
+    assert_eq!(next.profile, Some(("Daily sales".to_string(), ProfileMatch::Headers)));
+    let options = next.ingest_options();
+    let mut db = Database::open_writable(&dir)?;
+    let summary = db.import_csv_to_file("tuesday", &tuesday, &dir, TableFormat::Arrow, &options, |_| {})?;
+    let schema: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+    println!("schema: {:?}", schema);
+    assert_eq!(schema, ["Int32", "Decimal128(12, 2)", "Date32", "Boolean", "Utf8"]);
+    assert_eq!(summary.coercion.total(), 0);
+    let totals = db.execute_query("SELECT SUM(amount), MAX(booked), SUM(CASE WHEN state THEN 1 ELSE 0 END) FROM tuesday")?;
+    println!("totals: {:?}", totals[0]);
+    assert_eq!(totals[0], vec!["1309.35", "2024-01-28", "15"]);
+
+    // Saving from a config keeps every column's choices
+    let resaved = next.to_profile("Daily sales", "sales_*.csv");
+    assert_eq!(resaved.columns, profile.columns);
+    ImportProfile::delete(&profiles_dir, "Another layout")?;
+    assert_eq!(ImportProfile::load_all(&profiles_dir)?.len(), 1);
+
+    // Names that only differ in characters file names can't hold get files of their own
+    let colon = ImportProfile { name: "Q1: sales".to_string(), ..first.clone() };
+    let question = ImportProfile { name: "Q1? sales".to_string(), ..first.clone() };
+    assert_eq!(colon.save(&profiles_dir)?.file_name().unwrap(), "Q1__sales.json");
+    assert_eq!(question.save(&profiles_dir)?.file_name().unwrap(), "Q1__sales_2.json");
+    ImportProfile::delete(&profiles_dir, "Q1: sales")?;
+    assert_eq!(question.save(&profiles_dir)?.file_name().unwrap(), "Q1__sales_2.json", "saving again finds its own file");
+    let names: Vec<String> = ImportProfile::load_all(&profiles_dir)?.into_iter().map(|profile| profile.name).collect();
+    assert_eq!(names, ["Daily sales", "Q1? sales"]);
+
+    println!("\n✅ Import profiles passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use leaf::core::{Database, TableFormat, ParquetCompression};
+use leaf::core::json_ingest::{self, JsonIngestOptions};
+use leaf::core::table_format;
+use std::io::Write;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing JSON and NDJSON imports");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_json_import_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // An API dump: nested objects, lists, a field that is always null and one that changes kind
+    let events = dir.join("events.ndjson");
+    let mut ndjson = String::new();
+    for i in 0..250 {
+        let code = if i == 200 { "\"E42\"".to_string() } else { i.to_string() };
+        let tags = if i % 3 == 0 { "[]".to_string() } else { format!("[\"t{}\", \"x\"]", i % 3) };
+        ndjson.push_str(&format!(
+            "{{\"id\": {}, \"user\": {{\"name\": \"u{}\", \"address\": {{\"city\": \"{}\"}}}}, \"tags\": {}, \"score\": {}, \"code\": {}, \"deleted_at\": null}}\n",
+            i, i % 7, ["Berlin", "Paris"][i % 2], tags, i as f64 / 2.0, code
+        ));
+        if i == 100 {
+            ndjson.push('\n');
+        }
+    }
+    std::fs::write(&events, &ndjson)?;
+    assert!(json_ingest::is_json_file(&events));
+    assert!(json_ingest::is_json_file(&dir.join("dump.JSONL.gz")));
+    assert!(!json_ingest::is_json_file(&dir.join("data.csv")));
+
+    // Nested objects become struct columns, in the order the file writes them
+    let schema = json_ingest::infer_schema(&events, &JsonIngestOptions::default())?;
+    let types: Vec<String> = schema.fields().iter().map(|f| format!("{} {}", f.name(), json_ingest::describe_type(f.data_type()))).collect();
+    println!("nested: {:?}", types);
+    assert_eq!(schema.fields().iter().map(|f| f.name().as_str()).collect::<Vec<_>>(), ["id", "user", "tags", "score", "code", "deleted_at"]);
+    assert_eq!(types, [
+        "id Int64", "user Struct<name: Utf8, address: Struct<city: Utf8>>", "tags List<Utf8>",
+        "score Float64", "code Utf8", "deleted_at Utf8",
+    ]);
+
+    // A sample can miss the value that turns `code` into text
+    let sampled = json_ingest::infer_schema(&events, &JsonIngestOptions { sample_size: Some(10), ..JsonIngestOptions::default() })?;
+    assert_eq!(sampled.field_with_name("code")?.data_type().to_string(), "Int64");
+
+    // Flattening names nested fields by their path
+    let flat_options = JsonIngestOptions { flatten: true, ..JsonIngestOptions::default() };
+    let (flat_schema, rows) = json_ingest::preview(&events, true, 5)?;
+    let names: Vec<&str> = flat_schema.fields().iter().map(|f| f.name().as_str()).collect();
+    println!("flattened: {:?}", names);
+    assert_eq!(names, ["id", "user.name", "user.address.city", "tags", "score", "code", "deleted_at"]);
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[1], ["1", "u1", "Paris", "[\"t1\",\"x\"]", "0.5", "1", ""]);
+
+    let mut db = Database::open_writable(&dir)?;
+    let mut progress_calls = 0;
+    let summary = db.import_json_to_file("events", &events, &dir, TableFormat::Arrow, &JsonIngestOptions { batch_size: 100, ..flat_options.clone() }, |progress| {
+        progress_calls += 1;
+        assert!(progress.bytes_read <= progress.total_bytes);
+    })?;
+    assert_eq!(summary.rows, 250);
+    assert_eq!(progress_calls, 3);
+    let totals = db.execute_query(
+        "SELECT COUNT(*), COUNT(DISTINCT \"user.name\"), SUM(score), MAX(code), COUNT(deleted_at) FROM events WHERE \"user.address.city\" = 'Paris'"
+    )?;
+    println!("totals: {:?}", totals[0]);
+    // `code` is text, so its maximum is the string "99"
+    assert_eq!(totals[0], vec!["125", "7", "7812.5", "99", "0"]);
+    assert_eq!(db.execute_query("SELECT code FROM events WHERE id = 200")?[0], vec!["E42"]);
+    let tags = db.execute_query("SELECT array_length(tags, 1) FROM events WHERE id = 4")?;
+    assert_eq!(tags[0], vec!["2"]);
+
+    // Without flattening the struct columns can be queried by field, and Parquet keeps them nested
+    db.import_json_to_file("nested", &events, &dir, TableFormat::Parquet(ParquetCompression::Snappy), &JsonIngestOptions::default(), |_| {})?;
+    let cities = db.execute_query("SELECT COUNT(*) FROM nested WHERE \"user\"['address']['city'] = 'Berlin'")?;
+    assert_eq!(cities[0], vec!["125"]);
+    let (schema, _) = table_format::read_batches(&dir.join("nested.parquet"))?;
+    assert!(schema.field_with_name("user")?.data_type().to_string().starts_with("Struct"));
+
+    // A top-level array, gzip compressed, with only some columns kept
+    let array = dir.join("orders.json.gz");
+    let mut encoder = flate2::write::GzEncoder::new(std::fs::File::create(&array)?, flate2::Compression::default());
+    encoder.write_all("\u{feff}[{\"order\": 1, \"total\": 9.5, \"note\": \"a\"}, {\"order\": 2, \"total\": 3}, {\"order\": 3, \"extra\": true}]".as_bytes())?;
+    encoder.finish()?;
+    let options = JsonIngestOptions { columns: Some(vec!["order".to_string(), "total".to_string()]), ..JsonIngestOptions::default() };
+    db.import_json_to_file("orders", &array, &dir, TableFormat::Arrow, &options, |_| {})?;
+    let (schema, _) = table_format::read_batches(&dir.join("orders.arrow"))?;
+    assert_eq!(schema.fields().iter().map(|f| f.name().as_str()).collect::<Vec<_>>(), ["order", "total"]);
+    let orders = db.execute_query("SELECT COUNT(*), SUM(total) FROM orders")?;
+    assert_eq!(orders[0], vec!["3", "12.5"]);
+
+    // Records that aren't objects and broken JSON fail without leaving a table behind
+    let scalars = dir.join("scalars.ndjson");
+    std::fs::write(&scalars, "{\"a\": 1}\n42\n")?;
+    let error = db.import_json_to_file("scalars", &scalars, &dir, TableFormat::Arrow, &JsonIngestOptions::default(), |_| {}).unwrap_err();
+    println!("not an object: {}", error);
+    assert!(error.to_string().contains("Record 2 is not a JSON object"));
+    let broken = dir.join("broken.json");
+    std::fs::write(&broken, "{\"a\": 1}\n{\"a\": \n")?;
+    assert!(db.import_json_to_file("broken", &broken, &dir, TableFormat::Arrow, &JsonIngestOptions::default(), |_| {}).is_err());
+    assert!(!dir.join("broken.arrow").exists() && !dir.join("broken.arrow.partial").exists());
+    // A value past the sample that doesn't fit its inferred type is reported with a hint
+    let sampled = JsonIngestOptions { sample_size: Some(10), ..flat_options.clone() };
+    let error = db.import_json_to_file("sampled", &events, &dir, TableFormat::Arrow, &sampled, |_| {}).unwrap_err();
+    println!("past the sample: {}", error);
+    assert!(error.to_string().contains("first 10 records"));
+    assert!(!dir.join("sampled.arrow").exists());
+    // So is a field that first appears past the sample, rather than being dropped
+    let late = dir.join("late.ndjson");
+    let mut lines: String = (0..20).map(|i| format!("{{\"id\": {}}}\n", i)).collect();
+    lines.push_str("{\"id\": 20, \"comment\": \"new\"}\n");
+    std::fs::write(&late, lines)?;
+    let error = db.import_json_to_file("late", &late, &dir, TableFormat::Arrow, &JsonIngestOptions { sample_size: Some(10), ..JsonIngestOptions::default() }, |_| {}).unwrap_err();
+    println!("late field: {}", error);
+    assert!(error.to_string().contains("comment") && error.to_string().contains("first 10 records"));
+    db.import_json_to_file("late", &late, &dir, TableFormat::Arrow, &JsonIngestOptions::default(), |_| {})?;
+    assert_eq!(db.execute_query("SELECT COUNT(*), COUNT(comment) FROM late")?[0], vec!["21", "1"]);
+
+    let empty = dir.join("empty.json");
+    std::fs::write(&empty, "[]")?;
+    assert!(json_ingest::infer_schema(&empty, &JsonIngestOptions::default()).is_err());
+
+    println!("\n✅ JSON import passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
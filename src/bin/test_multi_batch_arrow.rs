@@ -0,0 +1,72 @@
+use leaf::core::Database;
+use datafusion::arrow::array::{Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::ipc::writer::FileWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use std::fs::File;
+use std::sync::Arc;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing multi-batch Arrow IPC loading");
+    println!("{}", "=".repeat(60));
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("label", DataType::Utf8, true),
+    ]));
+
+    // Write a file with several batches, like pyarrow or Polars would
+    let dir = std::env::temp_dir().join("leaf_multi_batch_test");
+    std::fs::create_dir_all(&dir)?;
+    let arrow_path = dir.join("chunked.arrow");
+
+    let batch_sizes = [1000usize, 250, 0, 4096];
+    let mut writer = FileWriter::try_new(File::create(&arrow_path)?, &schema)?;
+    let mut next_id = 0i64;
+    for size in batch_sizes {
+        let ids: Vec<i64> = (next_id..next_id + size as i64).collect();
+        let labels: Vec<String> = ids.iter().map(|id| format!("row_{}", id)).collect();
+        next_id += size as i64;
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(Int64Array::from(ids)),
+            Arc::new(StringArray::from(labels)),
+        ])?;
+        writer.write(&batch)?;
+    }
+    writer.finish()?;
+
+    let expected_rows: usize = batch_sizes.iter().sum();
+    println!("Wrote {} batches with {} rows to {:?}", batch_sizes.len(), expected_rows, arrow_path);
+
+    // Load it back
+    let mut db = Database::open_writable(&dir)?;
+    db.load_table_arrow_ipc("chunked", &arrow_path)?;
+
+    let tables = db.get_tables()?;
+    let table = tables.iter().find(|t| t.name == "chunked").expect("table should be registered");
+    println!("TableInfo.row_count: {:?}", table.row_count);
+    assert_eq!(table.row_count, Some(expected_rows as i64), "row count should match the file");
+
+    let count = db.execute_count_query("SELECT COUNT(*) FROM chunked")?;
+    println!("SELECT COUNT(*): {}", count);
+    assert_eq!(count as usize, expected_rows, "query should see every batch");
+
+    let max_id = db.execute_query("SELECT MAX(id) FROM chunked")?;
+    println!("MAX(id): {}", max_id[0][0]);
+    assert_eq!(max_id[0][0], (expected_rows - 1).to_string());
+
+    let batch = db.get_table_arrow_batch("chunked")?;
+    assert_eq!(batch.num_rows(), expected_rows, "combined batch should hold every row");
+
+    // Round trip keeps every row
+    let saved_path = dir.join("chunked_saved.arrow");
+    db.save_table_arrow_ipc("chunked", &saved_path)?;
+    db.load_table_arrow_ipc("chunked_saved", &saved_path)?;
+    let saved_count = db.execute_count_query("SELECT COUNT(*) FROM chunked_saved")?;
+    assert_eq!(saved_count as usize, expected_rows, "saved file should keep every row");
+
+    println!("\n✅ All {} rows loaded from {} batches", expected_rows, batch_sizes.len());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -43,7 +43,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             output.push_str("\n```\n\n");
             output.push_str("| Column | Data Type |\n");
             output.push_str("|--------|----------|\n");
-            for row in &result.rows {
+            for row in result.rows() {
                 if row.len() >= 2 {
                     output.push_str(&format!("| {} | {} |\n", row[0], row[1]));
                 }
@@ -100,7 +100,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         
         match QueryExecutor::execute(&db, &count_query) {
             Ok(result) => {
-                if let Some(row) = result.rows.first() {
+                if let Some(row) = result.rows().next() {
                     output.push_str(&format!("- Total rows: {}\n", row[0]));
                     output.push_str(&format!("- NULL count: {}\n", row[1]));
                     output.push_str(&format!("- NOT NULL count: {}\n", row[2]));
@@ -133,7 +133,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(result) => {
                 output.push_str("| Value | NULL Status | Width | Height |\n");
                 output.push_str("|-------|-------------|-------|--------|\n");
-                for row in &result.rows {
+                for row in result.rows() {
                     output.push_str(&format!("| '{}' | {} | {} | {} |\n", 
                         row[0], row[1], row[2], row[3]));
                 }
@@ -165,7 +165,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(result) => {
                 output.push_str("| Value | NULL Status | Width | Height |\n");
                 output.push_str("|-------|-------------|-------|--------|\n");
-                for row in &result.rows {
+                for row in result.rows() {
                     output.push_str(&format!("| '{}' | {} | {} | {} |\n", 
                         row[0], row[1], row[2], row[3]));
                 }
@@ -200,7 +200,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(result) => {
             output.push_str("| width | integer_infer_blank | real_infer_dash | text_infer_blank | boolean_infer_dash | dumb_time |\n");
             output.push_str("|-------|---------------------|-----------------|------------------|--------------------|-----------|\n");
-            for row in &result.rows {
+            for row in result.rows() {
                 output.push_str(&format!("| {} | '{}' | '{}' | '{}' | '{}' | '{}' |\n", 
                     row[0], row[1], row[2], row[3], row[4], row[5]));
             }
@@ -231,7 +231,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(result) => {
             output.push_str("| good_time | dumb_time | integer_infer_blank | real_infer_blank |\n");
             output.push_str("|-----------|-----------|---------------------|------------------|\n");
-            for row in &result.rows {
+            for row in result.rows() {
                 output.push_str(&format!("| {} | '{}' | '{}' | '{}' |\n", 
                     row[0], row[1], row[2], row[3]));
             }
@@ -259,7 +259,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     match QueryExecutor::execute(&db, general_query3) {
         Ok(result) => {
-            if let Some(row) = result.rows.first() {
+            if let Some(row) = result.rows().next() {
                 output.push_str(&format!("- Total rows: {}\n", row[0]));
                 output.push_str(&format!("- Count non-null integers: {}\n", row[1]));
                 output.push_str(&format!("- Average non-null integers: {}\n", row[2]));
@@ -296,7 +296,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(result) => {
             output.push_str("| integer_infer_blank | with_default | status | nullif_result |\n");
             output.push_str("|---------------------|--------------|--------|---------------|\n");
-            for row in &result.rows {
+            for row in result.rows() {
                 output.push_str(&format!("| '{}' | {} | {} | '{}' |\n", 
                     row[0], row[1], row[2], row[3]));
             }
@@ -325,7 +325,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(result) => {
             output.push_str("| integer_infer_blank | real_infer_blank | date_infer_blank |\n");
             output.push_str("|---------------------|------------------|------------------|\n");
-            for row in &result.rows {
+            for row in result.rows() {
                 output.push_str(&format!("| '{}' | '{}' | '{}' |\n", 
                     row[0], row[1], row[2]));
             }
@@ -361,7 +361,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let blank_check = format!("SELECT COUNT(*) FROM test_data WHERE \"{}\" IS NULL", blank_col);
         match QueryExecutor::execute(&db, &blank_check) {
             Ok(result) => {
-                if let Some(r) = result.rows.first() {
+                if let Some(r) = result.rows().next() {
                     let count: i64 = r[0].parse().unwrap_or(0);
                     row.push_str(if count > 0 { "| ✓ " } else { "| ✗ " });
                 } else {
@@ -375,7 +375,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let dash_check = format!("SELECT COUNT(*) FROM test_data WHERE \"{}\" IS NULL", dash_col);
         match QueryExecutor::execute(&db, &dash_check) {
             Ok(result) => {
-                if let Some(r) = result.rows.first() {
+                if let Some(r) = result.rows().next() {
                     let count: i64 = r[0].parse().unwrap_or(0);
                     row.push_str(if count > 0 { "| ✓ " } else { "| ✗ " });
                 } else {
@@ -392,7 +392,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let display_check = format!("SELECT \"{}\" FROM test_data WHERE \"{}\" IS NULL LIMIT 1", blank_col, blank_col);
         match QueryExecutor::execute(&db, &display_check) {
             Ok(result) => {
-                if let Some(r) = result.rows.first() {
+                if let Some(r) = result.rows().next() {
                     row.push_str(if r[0].is_empty() { "| ✓ " } else { "| ✗ " });
                 } else {
                     row.push_str("| N/A ");
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use leaf::core::{Database, EnhancedGroupingProcessor};
+use leaf::core::{Database, EnhancedGroupingProcessor, TableFormat};
 use leaf::ui::{EnhancedGroupingRequest, GroupingConfig, GroupingRule};
 
 fn main() -> Result<()> {
@@ -43,6 +43,7 @@ fn main() -> Result<()> {
             },
         ],
         output_filename: Some("test_null_fix_output".to_string()),
+        output_format: TableFormat::Arrow,
     };
     
     // Process the request
@@ -31,14 +31,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get total row count
     let total_count_query = "SELECT COUNT(*) FROM test_data";
     let total_result = QueryExecutor::execute(&db, total_count_query)?;
-    println!("\nTotal rows in table: {}", total_result.rows[0][0]);
+    println!("\nTotal rows in table: {}", total_result.cell(0, 0));
     
     // Create markdown output
     let mut output = String::new();
     output.push_str("# NULL Query Test Results\n\n");
     output.push_str("## Test Data Information\n\n");
     output.push_str(&format!("- **File**: test_data_300k_correct.csv\n"));
-    output.push_str(&format!("- **Total Rows**: {}\n", total_result.rows[0][0]));
+    output.push_str(&format!("- **Total Rows**: {}\n", total_result.cell(0, 0)));
     output.push_str("- **Null Values Recognized**: Empty strings, 'NULL', 'null', 'N/A', '-' (default configuration)\n\n");
     
     // Test columns that should have nulls
@@ -73,12 +73,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Count NULL values
         let null_count_query = format!("SELECT COUNT(*) FROM test_data WHERE \"{}\" IS NULL", column);
         let null_result = QueryExecutor::execute(&db, &null_count_query)?;
-        let null_count = &null_result.rows[0][0];
+        let null_count = &null_result.cell(0, 0);
         
         // Count NOT NULL values
         let not_null_count_query = format!("SELECT COUNT(*) FROM test_data WHERE \"{}\" IS NOT NULL", column);
         let not_null_result = QueryExecutor::execute(&db, &not_null_count_query)?;
-        let not_null_count = &not_null_result.rows[0][0];
+        let not_null_count = &not_null_result.cell(0, 0);
         
         output.push_str(&format!("- **NULL count**: {}\n", null_count));
         output.push_str(&format!("- **NOT NULL count**: {}\n", not_null_count));
@@ -91,7 +91,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let sample_null_result = QueryExecutor::execute(&db, &sample_null_query)?;
         
         output.push_str("\n**Sample NULL values (should show as empty):**\n```\n");
-        for (i, row) in sample_null_result.rows.iter().enumerate() {
+        for (i, row) in sample_null_result.rows().enumerate() {
             output.push_str(&format!("Row {}: '{}'\n", i + 1, row[0]));
         }
         output.push_str("```\n");
@@ -104,7 +104,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let sample_not_null_result = QueryExecutor::execute(&db, &sample_not_null_query)?;
         
         output.push_str("\n**Sample NOT NULL values:**\n```\n");
-        for (i, row) in sample_not_null_result.rows.iter().enumerate() {
+        for (i, row) in sample_not_null_result.rows().enumerate() {
             output.push_str(&format!("Row {}: '{}'\n", i + 1, row[0]));
         }
         output.push_str("```\n\n");
@@ -120,7 +120,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let complex_query1 = "SELECT COUNT(*) FROM test_data WHERE integer_infer_blank IS NULL AND real_infer_blank IS NULL";
     let complex_result1 = QueryExecutor::execute(&db, complex_query1)?;
-    output.push_str(&format!("**Result**: {} rows\n\n", complex_result1.rows[0][0]));
+    output.push_str(&format!("**Result**: {} rows\n\n", complex_result1.cell(0, 0)));
     
     // Query 2: NULL in first row of major groups (dumb_time)
     println!("\nTesting complex query 2: NULL dumb_time (first rows of major groups)");
@@ -132,7 +132,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     output.push_str("| good_time | dumb_time | width | height |\n");
     output.push_str("|-----------|-----------|-------|--------|\n");
-    for row in &complex_result2.rows {
+    for row in complex_result2.rows() {
         output.push_str(&format!("| {} | {} | {} | {} |\n", row[0], row[1], row[2], row[3]));
     }
     output.push_str("\n");
@@ -144,11 +144,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // First, check if we have any actual empty strings (non-NULL)
     let empty_string_query = "SELECT COUNT(*) FROM test_data WHERE text_infer_blank = ''";
     let empty_result = QueryExecutor::execute(&db, &empty_string_query)?;
-    output.push_str(&format!("- Rows where text_infer_blank = '' (empty string): {}\n", empty_result.rows[0][0]));
+    output.push_str(&format!("- Rows where text_infer_blank = '' (empty string): {}\n", empty_result.cell(0, 0)));
     
     let null_query = "SELECT COUNT(*) FROM test_data WHERE text_infer_blank IS NULL";
     let null_result = QueryExecutor::execute(&db, &null_query)?;
-    output.push_str(&format!("- Rows where text_infer_blank IS NULL: {}\n\n", null_result.rows[0][0]));
+    output.push_str(&format!("- Rows where text_infer_blank IS NULL: {}\n\n", null_result.cell(0, 0)));
     
     // Query 4: COALESCE function test
     println!("\nTesting complex query 4: COALESCE function");
@@ -160,7 +160,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     output.push_str("| integer_infer_blank | with_default |\n");
     output.push_str("|---------------------|-------------|\n");
-    for row in &coalesce_result.rows {
+    for row in coalesce_result.rows() {
         output.push_str(&format!("| {} | {} |\n", row[0], row[1]));
     }
     output.push_str("\n");
@@ -0,0 +1,114 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions};
+use leaf::infer::{TypeInferrer, ColumnType, NumberFormat, ColumnTokens};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing locale number formats");
+    println!("{}", "=".repeat(60));
+
+    let english = NumberFormat::default();
+    let german = NumberFormat { decimal_separator: ',', grouping_separator: Some('.') };
+    let french = NumberFormat { decimal_separator: ',', grouping_separator: Some(' ') };
+
+    // Parsing
+    let cases: [(&NumberFormat, &str, Option<f64>); 19] = [
+        (&english, "1,234.56", Some(1234.56)),
+        (&english, "$12.50", Some(12.5)),
+        (&english, "-$1,200", Some(-1200.0)),
+        (&english, "45%", Some(0.45)),
+        (&english, "(12.00)", Some(-12.0)),
+        (&english, "USD 3.5", Some(3.5)),
+        (&english, "LOT 5", None),
+        (&english, "7 REF", None),
+        (&english, "3.5 GBP", Some(3.5)),
+        (&english, "1.5e3", Some(1500.0)),
+        (&english, "12,34", None),
+        (&english, "1-2", None),
+        (&german, "1.234,56", Some(1234.56)),
+        (&german, "1.234.567", Some(1234567.0)),
+        (&german, "12,50 €", Some(12.5)),
+        (&german, "1.5", None),
+        (&french, "1 234,56", Some(1234.56)),
+        (&french, "1\u{a0}234\u{a0}567", Some(1234567.0)),
+        (&french, "-3,5 %", Some(-0.035)),
+    ];
+    for (format, text, expected) in cases {
+        let parsed = format.parse_f64(text);
+        println!("{:<40} {:>14} -> {:?}", format.display_name(), text, parsed);
+        match (parsed, expected) {
+            (Some(value), Some(expected)) => assert!((value - expected).abs() < 1e-9, "{} parsed as {}", text, value),
+            (parsed, expected) => assert_eq!(parsed, expected, "{}", text),
+        }
+    }
+    assert_eq!(english.parse_i64("1,234"), Some(1234));
+    assert_eq!(english.parse_i64("(1,234)"), Some(-1234));
+    assert_eq!(english.parse_i64("12%"), None, "percentages are never integers");
+
+    // Labels that look like a code and a number stay text
+    let labels: Vec<Vec<String>> = ["LOT 5", "LOT 12", "ABC 123"].iter().map(|s| vec![s.to_string()]).collect();
+    let label_types = TypeInferrer::infer_column_types_with_nulls(&["lot".to_string()], &labels, &[]);
+    assert_eq!(label_types[0].1, ColumnType::Text);
+
+    // Inference picks up formatted numbers instead of falling back to Text
+    let headers: Vec<String> = ["amount", "count", "share", "date"].iter().map(|s| s.to_string()).collect();
+    let samples: Vec<Vec<String>> = [
+        ["1.234,56", "1.000", "12,5 %", "01.02.2024"],
+        ["(99,00)", "250", "3 %", "02.02.2024"],
+        ["€ 7,10", "1.250.000", "100 %", "03.02.2024"],
+    ].iter().map(|row| row.iter().map(|s| s.to_string()).collect()).collect();
+    let types = TypeInferrer::infer_column_types_with_format(&headers, &samples, &[], &german);
+    let types: Vec<ColumnType> = types.into_iter().map(|(_, column_type)| column_type).collect();
+    println!("German inference: {:?}", types);
+    assert_eq!(types[..3], [ColumnType::Decimal { precision: 38, scale: 2 }, ColumnType::Integer, ColumnType::Real]);
+    assert_ne!(types[3], ColumnType::Integer, "dotted dates are not grouped numbers");
+    let default_types = TypeInferrer::infer_column_types_with_nulls(&headers, &samples, &[]);
+    assert_eq!(default_types[0].1, ColumnType::Text, "the English format doesn't read decimal commas");
+    assert!(TypeInferrer::validate_column_type_change(&samples, 0, &ColumnType::Real, &german, None, &ColumnTokens::default()).is_ok());
+    assert!(TypeInferrer::validate_column_type_change(&samples, 0, &ColumnType::Real, &english, None, &ColumnTokens::default()).is_err());
+
+    // End to end: a semicolon-separated European file lands as exact money, Int64 counts and Float64 shares
+    let dir = std::env::temp_dir().join("leaf_number_format_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    let csv_path = dir.join("umsatz.csv");
+    std::fs::write(&csv_path, "region;umsatz;stueck;anteil\nNord;1.234,56;1.200;12,5%\nSüd;(99,50);15;-3%\nOst;€ 2.000,00;7;0,5%\n")?;
+
+    let options = CsvIngestOptions { number_format: german, ..CsvIngestOptions::default() };
+    let mut db = Database::open_writable(&dir)?;
+    let summary = db.import_csv_to_file("umsatz", &csv_path, &dir, TableFormat::Arrow, &options, |_| {})?;
+    let schema: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+    println!("Imported schema: {:?}", schema);
+    assert_eq!(schema, vec!["Utf8", "Decimal128(38, 2)", "Int64", "Float64"]);
+    assert!(summary.coercion.is_empty());
+    let totals = db.execute_query("SELECT SUM(umsatz), SUM(stueck) FROM umsatz")?;
+    println!("Totals: {:?}", totals[0]);
+    assert_eq!(totals[0], vec!["3135.06".to_string(), "1222".to_string()]);
+    let share = db.execute_query("SELECT anteil FROM umsatz WHERE region = 'Süd'")?;
+    assert_eq!(share[0][0], "-0.03");
+
+    // The in-memory import also reads grouped and currency values as numbers
+    let us_path = dir.join("sales.csv");
+    std::fs::write(&us_path, "item,price,qty\nA,\"$1,200.00\",\"1,000\"\nB,$15.25,3\n")?;
+    db.stream_insert_csv("sales", &us_path, ',', true)?;
+    let sums = db.execute_query("SELECT SUM(price), SUM(qty) FROM sales")?;
+    println!("In-memory totals: {:?}", sums[0]);
+    assert_eq!(sums[0], vec!["1215.25".to_string(), "1003".to_string()]);
+
+    // Rows inserted directly are inferred the same way
+    db.insert_data("ledger", &[
+        vec!["$12.50".to_string(), "45%".to_string(), "(12.00)".to_string()],
+        vec!["$1,234.56".to_string(), "5%".to_string(), "3".to_string()],
+    ])?;
+    let types = db.get_column_types("SELECT * FROM ledger")?;
+    assert!(types.iter().all(|data_type| data_type.is_numeric()), "{:?}", types);
+    let sums = db.execute_query("SELECT SUM(col_0), SUM(col_2) FROM ledger")?;
+    assert_eq!(sums[0], vec!["1247.06".to_string(), "-9".to_string()]);
+    db.insert_data("european", &[vec!["1.234,56".to_string()], vec!["2,5".to_string()], vec!["1.000".to_string()]])?;
+    assert_eq!(db.execute_query("SELECT SUM(col_0) FROM european")?[0], ["2237.06"], "decimal commas are read when most values use them");
+    db.insert_record("ledger", &["$1.00".to_string(), "1%".to_string(), "N/A".to_string()])?;
+    assert_eq!(db.execute_query("SELECT col_0, col_2 FROM ledger")?, [["1.00", ""]], "the table's types are kept and null tokens read as null");
+
+    println!("\n✅ Number formats passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -40,10 +40,10 @@ fn main() -> Result<()> {
                     let total_pages = (total_rows as f32 / page_size as f32).ceil() as usize;
                     
                     println!("  Page {}/{}: {} rows returned (total: {} rows)", 
-                        page + 1, total_pages, result.rows.len(), total_rows);
+                        page + 1, total_pages, result.num_rows(), total_rows);
                     
                     // Show first row of each page
-                    if let Some(first_row) = result.rows.first() {
+                    if let Some(first_row) = result.rows().next() {
                         let preview: Vec<String> = first_row.iter()
                             .take(3)
                             .map(|v| if v.len() > 15 { format!("{}...", &v[..12]) } else { v.clone() })
@@ -60,8 +60,8 @@ fn main() -> Result<()> {
                         0
                     };
                     
-                    if result.rows.len() != expected_rows {
-                        println!("    WARNING: Expected {} rows but got {}", expected_rows, result.rows.len());
+                    if result.num_rows() != expected_rows {
+                        println!("    WARNING: Expected {} rows but got {}", expected_rows, result.num_rows());
                     }
                 }
                 Err(e) => {
@@ -81,7 +81,7 @@ fn main() -> Result<()> {
     match QueryExecutor::execute_with_pagination(&db_arc, "SELECT * FROM test_data_300k", 0, 1000) {
         Ok(result) => {
             println!("  Success: {} rows returned (total: {:?})", 
-                result.rows.len(), result.total_rows);
+                result.num_rows(), result.total_rows);
         }
         Err(e) => println!("  Error: {}", e),
     }
@@ -90,7 +90,7 @@ fn main() -> Result<()> {
     println!("\nTest: Page beyond available data");
     match QueryExecutor::execute_with_pagination(&db_arc, "SELECT * FROM test_data_300k", 1000, 100) {
         Ok(result) => {
-            println!("  Success: {} rows returned (expected 0)", result.rows.len());
+            println!("  Success: {} rows returned (expected 0)", result.num_rows());
         }
         Err(e) => println!("  Error: {}", e),
     }
@@ -100,7 +100,7 @@ fn main() -> Result<()> {
     match QueryExecutor::execute_with_pagination(&db_arc, "SELECT * FROM test_data_300k WHERE width > 99999", 0, 10) {
         Ok(result) => {
             println!("  Success: {} rows returned (total: {:?})", 
-                result.rows.len(), result.total_rows);
+                result.num_rows(), result.total_rows);
             if result.total_rows != Some(0) {
                 println!("  WARNING: Expected total_rows to be 0 for query with no results");
             }
@@ -20,8 +20,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     match QueryExecutor::execute_with_pagination(&db, query, page, page_size) {
         Ok(result) => {
             println!("\nQuery executed successfully!");
-            println!("Columns: {:?}", result.columns);
-            println!("Rows returned: {}", result.rows.len());
+            println!("Columns: {:?}", result.column_names());
+            println!("Rows returned: {}", result.num_rows());
             println!("Total rows: {:?}", result.total_rows);
             
             if let Some(total) = result.total_rows {
@@ -35,8 +35,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     match QueryExecutor::execute_with_pagination(&db, query, page, page_size) {
         Ok(result) => {
             println!("\nQuery executed successfully!");
-            println!("Columns: {} columns", result.columns.len());
-            println!("Rows returned: {}", result.rows.len());
+            println!("Columns: {} columns", result.num_columns());
+            println!("Rows returned: {}", result.num_rows());
             println!("Total rows: {:?}", result.total_rows);
             
             if let Some(total) = result.total_rows {
@@ -54,10 +54,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             
             // Show first few values
-            if !result.rows.is_empty() {
+            if !result.is_empty() {
                 println!("\nFirst row sample:");
-                for (i, val) in result.rows[0].iter().enumerate().take(5) {
-                    println!("  {}: {}", result.columns.get(i).unwrap_or(&"?".to_string()), val);
+                for (i, val) in result.row(0).iter().enumerate().take(5) {
+                    println!("  {}: {}", result.column_names().get(i).unwrap_or(&"?".to_string()), val);
                 }
             }
         }
@@ -0,0 +1,71 @@
+use leaf::core::{Database, TableFormat, ParquetCompression};
+use datafusion::arrow::array::{Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing Parquet save and load");
+    println!("{}", "=".repeat(60));
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("value", DataType::Float64, true),
+    ]));
+
+    let rows = 2500i64;
+    let ids: Vec<i64> = (0..rows).collect();
+    let names: Vec<Option<String>> = ids.iter()
+        .map(|id| if id % 7 == 0 { None } else { Some(format!("name_{}", id)) })
+        .collect();
+    let values: Vec<Option<f64>> = ids.iter()
+        .map(|id| if id % 11 == 0 { None } else { Some(*id as f64 * 0.5) })
+        .collect();
+    let batch = RecordBatch::try_new(schema.clone(), vec![
+        Arc::new(Int64Array::from(ids)),
+        Arc::new(StringArray::from(names)),
+        Arc::new(Float64Array::from(values)),
+    ])?;
+
+    let dir = std::env::temp_dir().join("leaf_parquet_test");
+    std::fs::create_dir_all(&dir)?;
+
+    let mut db = Database::open_writable(&dir)?;
+    db.insert_record_batch("source", &batch)?;
+
+    for compression in [ParquetCompression::Snappy, ParquetCompression::Zstd] {
+        let format = TableFormat::Parquet(compression);
+        let path = db.save_table_as("source", &dir, format)?;
+        println!("Saved {} to {:?}", format.display_name(), path);
+
+        let table_name = format!("loaded_{:?}", compression).to_lowercase();
+        db.load_table_parquet(&table_name, &path)?;
+
+        let count = db.execute_count_query(&format!("SELECT COUNT(*) FROM {}", table_name))?;
+        assert_eq!(count, rows, "row count should survive the round trip");
+
+        let nulls = db.execute_query(&format!(
+            "SELECT COUNT(*) - COUNT(name), COUNT(*) - COUNT(value) FROM {}", table_name
+        ))?;
+        assert_eq!(nulls[0][0], "358", "null names should survive the round trip");
+        assert_eq!(nulls[0][1], "228", "null values should survive the round trip");
+
+        let loaded = db.get_table_arrow_batch(&table_name)?;
+        assert_eq!(loaded.schema().fields(), schema.fields(), "schema should survive the round trip");
+        println!("  {} rows, schema and nulls preserved", count);
+    }
+
+    // Directory loading picks up Parquet files as well
+    std::fs::remove_file(dir.join("source.parquet"))?;
+    db.save_table_as("source", &dir, TableFormat::Parquet(ParquetCompression::Zstd))?;
+    let mut reloaded = Database::open_writable(&dir)?;
+    let loaded_tables = reloaded.load_all_tables_from_directory(&dir)?;
+    println!("Loaded from directory: {:?}", loaded_tables);
+    assert!(loaded_tables.iter().any(|name| name == "source"), "directory load should include Parquet tables");
+
+    println!("\n✅ Parquet round trip passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
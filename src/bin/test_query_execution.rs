@@ -31,11 +31,11 @@ fn main() -> Result<()> {
         Ok(result) => {
             println!("Success!");
             println!("Total rows: {:?}", result.total_rows);
-            println!("Returned rows: {}", result.rows.len());
-            println!("Columns: {:?}", result.columns);
+            println!("Returned rows: {}", result.num_rows());
+            println!("Columns: {:?}", result.column_names());
             
             // Show first few rows
-            for (i, row) in result.rows.iter().take(3).enumerate() {
+            for (i, row) in result.rows().take(3).enumerate() {
                 println!("Row {}: {:?}", i, row);
             }
         }
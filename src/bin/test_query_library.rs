@@ -0,0 +1,132 @@
+use leaf::core::{Database, HistoryEntry, QueryLibrary, QueryStatus, SavedQuery};
+use leaf::core::query_library::MAX_HISTORY;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing query history and saved queries");
+    println!("{}", "=".repeat(60));
+
+    let project = std::env::temp_dir().join("leaf_query_library_test");
+    let _ = std::fs::remove_dir_all(&project);
+    std::fs::create_dir_all(&project)?;
+    let library = QueryLibrary::for_project(&project);
+    assert_eq!(library.dir(), project.join("queries"));
+
+    // A new project has nothing yet
+    assert!(library.history()?.is_empty());
+    assert!(library.saved_queries()?.is_empty());
+    assert!(library.modified().is_none());
+
+    // Every run is recorded in order, whatever its outcome
+    let entries = [
+        entry("SELECT region, SUM(amount) FROM sales GROUP BY region", 1_700_000_000, Some(4), QueryStatus::Succeeded),
+        entry("SELECT missing FROM sales", 1_700_000_060, None, QueryStatus::Failed("No field named missing".to_string())),
+        entry("SELECT * FROM sales a CROSS JOIN sales b", 1_700_000_120, None, QueryStatus::Cancelled),
+    ];
+    for entry in &entries {
+        library.record(entry)?;
+    }
+    let history = library.history()?;
+    assert_eq!(history, entries);
+    assert!(!history[0].executed_at_local().is_empty());
+
+    // Searching looks at the query text and at errors, ignoring case
+    let found = |search: &str| history.iter().filter(|entry| entry.matches(search)).count();
+    assert_eq!(found("sales"), 3);
+    assert_eq!(found("GROUP BY"), 1);
+    assert_eq!(found("no field"), 1);
+    assert_eq!(found(""), 3);
+    assert_eq!(found("customers"), 0);
+
+    // A line that can't be read doesn't lose the rest of the history
+    std::fs::OpenOptions::new().append(true).open(library.dir().join("history.jsonl"))
+        .and_then(|mut file| std::io::Write::write_all(&mut file, b"{not json\n"))?;
+    library.record(&entry("SELECT 1", 1_700_000_180, Some(1), QueryStatus::Succeeded))?;
+    assert_eq!(library.history()?.len(), 4);
+
+    // Only the newest entries are kept
+    library.clear_history()?;
+    assert!(library.history()?.is_empty());
+    for i in 0..MAX_HISTORY * 2 + 1 {
+        library.record(&entry(&format!("SELECT {}", i), i as i64, Some(1), QueryStatus::Succeeded))?;
+    }
+    let history = library.history()?;
+    assert_eq!(history.len(), MAX_HISTORY);
+    assert_eq!(history.last().unwrap().query, format!("SELECT {}", MAX_HISTORY * 2));
+    let lines = std::fs::read_to_string(library.dir().join("history.jsonl"))?.lines().count();
+    assert_eq!(lines, MAX_HISTORY, "the file is trimmed when recording");
+
+    // Reading the history never rewrites the file, however long it has grown
+    let history_path = library.dir().join("history.jsonl");
+    let mut long = std::fs::read_to_string(&history_path)?;
+    long.push_str(&long.clone());
+    long.push_str(&long.clone());
+    std::fs::write(&history_path, &long)?;
+    assert_eq!(library.history()?.len(), MAX_HISTORY);
+    assert_eq!(std::fs::read_to_string(&history_path)?, long);
+
+    // Saved queries are kept by name, sorted, and replaced when saved again
+    let morning = SavedQuery {
+        name: "Morning check: late orders".to_string(),
+        description: "Orders not shipped after two days".to_string(),
+        query: "SELECT * FROM orders WHERE shipped IS NULL".to_string(),
+    };
+    assert!(!library.has_saved_query(&morning.name));
+    let path = library.save_query(&morning)?;
+    assert!(library.has_saved_query(&morning.name));
+    assert_eq!(path, library.dir().join("Morning_check__late_orders.json"));
+    library.save_query(&SavedQuery {
+        name: "audit".to_string(),
+        description: String::new(),
+        query: "SELECT COUNT(*) FROM orders".to_string(),
+    })?;
+    let updated = SavedQuery { query: "SELECT id FROM orders WHERE shipped IS NULL".to_string(), ..morning.clone() };
+    library.save_query(&updated)?;
+    let saved = library.saved_queries()?;
+    assert_eq!(saved.iter().map(|query| query.name.as_str()).collect::<Vec<_>>(), ["audit", "Morning check: late orders"]);
+    assert_eq!(saved[1], updated);
+    assert!(saved[1].matches("not shipped") && saved[1].matches("MORNING") && !saved[0].matches("shipped"));
+    assert!(library.save_query(&SavedQuery { name: "  ".to_string(), ..morning.clone() }).is_err());
+
+    // Saving again changes the library's modification time, so open lists can reload
+    let before = library.modified().expect("the folder exists");
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    library.save_query(&SavedQuery { description: "Shipping delays".to_string(), ..updated.clone() })?;
+    assert!(library.modified().is_some_and(|after| after > before));
+
+    library.delete_query("audit")?;
+    library.delete_query("never saved")?;
+    assert_eq!(library.saved_queries()?.len(), 1);
+    assert!(!library.has_saved_query("audit"));
+
+    // Names that only differ in characters a file name can't hold don't replace each other
+    let colon = SavedQuery { name: "Q1: sales".to_string(), description: String::new(), query: "SELECT 1".to_string() };
+    let question = SavedQuery { name: "Q1? sales".to_string(), ..colon.clone() };
+    let colon_path = library.save_query(&colon)?;
+    assert!(!library.has_saved_query(&question.name));
+    let question_path = library.save_query(&question)?;
+    assert_ne!(colon_path, question_path);
+    assert_eq!(library.save_query(&colon)?, colon_path);
+    assert_eq!(library.saved_queries()?.len(), 3);
+    library.delete_query(&colon.name)?;
+    library.delete_query(&question.name)?;
+
+    // A file that can't be read doesn't hide the other saved queries
+    std::fs::create_dir_all(library.dir().join("unreadable.json"))?;
+    std::fs::write(library.dir().join("broken.json"), "{not json")?;
+    assert_eq!(library.saved_queries()?, [SavedQuery { description: "Shipping delays".to_string(), ..updated.clone() }]);
+
+    // The folder sits next to the project's tables without becoming one
+    std::fs::write(project.join("orders.csv"), "id,shipped\n1,2024-01-02\n2,\n")?;
+    let mut db = Database::open_writable(&project)?;
+    let tables = db.load_all_tables_from_directory(&project)?;
+    assert_eq!(tables, ["orders"]);
+
+    println!("\n✅ Query library passed");
+
+    std::fs::remove_dir_all(&project)?;
+    Ok(())
+}
+
+fn entry(query: &str, executed_at: i64, rows: Option<usize>, status: QueryStatus) -> HistoryEntry {
+    HistoryEntry { query: query.to_string(), executed_at, duration_ms: 125, rows, status }
+}
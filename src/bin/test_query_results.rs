@@ -0,0 +1,93 @@
+use leaf::core::{Database, QueryExecutor};
+use datafusion::arrow::array::{Array, Date32Array, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing columnar query results");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_query_results_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // A table held as several batches, one of them empty
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("amount", DataType::Float64, true),
+        Field::new("booked", DataType::Date32, true),
+        Field::new("note", DataType::Utf8, true),
+    ]));
+    let batch_sizes = [1000usize, 0, 250, 4096];
+    let mut batches = Vec::new();
+    let mut next_id = 0i64;
+    for size in batch_sizes {
+        let ids: Vec<i64> = (next_id..next_id + size as i64).collect();
+        next_id += size as i64;
+        batches.push(RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(Int64Array::from(ids.clone())),
+            Arc::new(ids.iter().map(|id| (id % 10 != 0).then_some(*id as f64 / 4.0)).collect::<Float64Array>()),
+            Arc::new(ids.iter().map(|id| Some(19_723 + (id % 366) as i32)).collect::<Date32Array>()),
+            Arc::new(ids.iter().map(|id| Some(format!("n{}", id))).collect::<StringArray>()),
+        ])?);
+    }
+    let expected_rows: usize = batch_sizes.iter().sum();
+    let mut db = Database::open_writable(&dir)?;
+    db.insert_record_batches("ledger", schema.clone(), batches)?;
+    let db = Arc::new(db);
+
+    // Every output batch is kept, not just the first
+    let result = QueryExecutor::execute(&db, "SELECT * FROM ledger")?;
+    println!("{} rows in {} batches", result.num_rows(), result.batches().len());
+    assert_eq!(result.num_rows(), expected_rows);
+    assert!(result.batches().len() > 1);
+    assert_eq!(result.column_names(), ["id", "amount", "booked", "note"]);
+    assert_eq!(result.column_types(), [DataType::Int64, DataType::Float64, DataType::Date32, DataType::Utf8]);
+
+    // Values keep their types and are only turned into text when asked for
+    let last = expected_rows - 1;
+    let (ids, index) = result.value(last, 0).expect("last row");
+    assert_eq!(ids.as_any().downcast_ref::<Int64Array>().unwrap().value(index), last as i64);
+    let (amounts, index) = result.value(1250, 1).expect("a row after the empty batch");
+    assert!(amounts.is_null(index));
+    assert_eq!(result.row(1001), ["1001", "250.25", "2024-09-26", "n1001"]);
+    assert_eq!(result.cell(1250, 1), "");
+    assert_eq!(result.cell(expected_rows, 0), "");
+    assert!(result.value(0, 4).is_none());
+    assert_eq!(result.rows().count(), expected_rows);
+
+    // A query with no rows still describes its columns
+    let empty = QueryExecutor::execute(&db, "SELECT id, note FROM ledger WHERE id < 0")?;
+    assert!(empty.is_empty());
+    assert_eq!(empty.column_names(), ["id", "note"]);
+
+    // Pages carry the total, and counting keeps an integer column
+    let page = QueryExecutor::execute_with_pagination(&db, "SELECT * FROM ledger", 3, 500)?;
+    assert_eq!((page.num_rows(), page.total_rows), (500, Some(expected_rows)));
+    assert_eq!(page.cell(0, 0), "1500");
+    let count = QueryExecutor::execute_with_pagination(&db, "SELECT COUNT(*) FROM ledger", 0, 25)?;
+    assert_eq!(count.column_types(), [DataType::Int64]);
+    assert_eq!(count.cell(0, 0), expected_rows.to_string());
+
+    // CSV exports format every cell; JSON exports keep numbers as numbers and nulls as null
+    let sample = QueryExecutor::execute(&db, "SELECT id, amount, booked, note FROM ledger WHERE id IN (10, 11) ORDER BY id")?;
+    let csv_path = dir.join("sample.csv");
+    sample.write_csv(&csv_path)?;
+    assert_eq!(std::fs::read_to_string(&csv_path)?, "id,amount,booked,note\n10,,2024-01-11,n10\n11,2.75,2024-01-12,n11\n");
+    let json_path = dir.join("sample.json");
+    sample.write_json(&json_path)?;
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path)?)?;
+    println!("json: {}", json);
+    assert_eq!(json[0]["amount"], serde_json::Value::Null);
+    assert_eq!(json[1]["id"], serde_json::json!(11));
+    assert_eq!(json[1]["amount"], serde_json::json!(2.75));
+    assert_eq!(json[1]["booked"], "2024-01-12");
+    empty.write_json(&json_path)?;
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(&json_path)?)?, serde_json::json!([]));
+
+    println!("\n✅ Query results passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use leaf::core::{Database, QueryExecutor, RefusedStatement};
+use datafusion::arrow::array::{Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use std::sync::Arc;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing read-only query checks");
+    println!("{}", "=".repeat(60));
+
+    // Keywords inside names, strings and comments are not statements
+    for query in [
+        "SELECT created_at, updated_by FROM orders",
+        "SELECT 'please update me' AS note, \"delete\" FROM orders",
+        "-- drop table orders\nSELECT * FROM orders /* insert */",
+        "WITH recent AS (SELECT * FROM orders) SELECT COUNT(*) FROM recent",
+        "SELECT id FROM orders UNION SELECT id FROM returns",
+        "EXPLAIN SELECT * FROM orders",
+        "SHOW TABLES",
+        "SHOW COLUMNS FROM orders",
+        "DESCRIBE orders",
+    ] {
+        assert_eq!(QueryExecutor::refused_statement(query)?, None, "{}", query);
+        QueryExecutor::validate_read_only(query)?;
+    }
+
+    // DDL and DML are refused by statement type
+    for (query, kind) in [
+        ("INSERT INTO orders VALUES (1)", "INSERT"),
+        ("update orders SET id = 2", "UPDATE"),
+        ("DELETE FROM orders", "DELETE"),
+        ("TRUNCATE TABLE orders", "TRUNCATE"),
+        ("CREATE TABLE copy AS SELECT * FROM orders", "CREATE TABLE"),
+        ("CREATE VIEW v AS SELECT 1", "CREATE VIEW"),
+        ("CREATE EXTERNAL TABLE t STORED AS CSV LOCATION 'x.csv'", "CREATE EXTERNAL TABLE"),
+        ("DROP TABLE orders", "DROP TABLE"),
+        ("DROP VIEW v", "DROP VIEW"),
+        ("ALTER TABLE orders ADD COLUMN note TEXT", "ALTER TABLE"),
+        ("COPY orders TO 'out.csv'", "COPY"),
+        ("SELECT * INTO backup FROM orders", "SELECT INTO"),
+        ("EXPLAIN ANALYZE INSERT INTO orders VALUES (1)", "INSERT"),
+        // Statements that aren't known to be read-only are refused as well
+        ("MERGE INTO orders USING returns ON orders.id = returns.id WHEN MATCHED THEN DELETE", "MERGE"),
+        ("SET datafusion.execution.batch_size = 10", "SET"),
+        ("START TRANSACTION", "START"),
+    ] {
+        let refused = QueryExecutor::refused_statement(query)?.unwrap_or_else(|| panic!("{} should be refused", query));
+        assert_eq!(refused.kind, kind, "{}", query);
+        assert_eq!((refused.line, refused.column), (1, 1));
+        assert!(QueryExecutor::validate_read_only(query).is_err());
+    }
+
+    // The refusal points at the statement, not the start of the query
+    let query = "SELECT * FROM orders;\n\n  -- clean up\n  DROP TABLE orders ;\nSELECT 1";
+    let refused = QueryExecutor::refused_statement(query)?.expect("the DROP should be refused");
+    let start = query.find("DROP").unwrap();
+    assert_eq!(refused, RefusedStatement {
+        kind: "DROP TABLE".to_string(),
+        line: 4,
+        column: 3,
+        range: start..start + "DROP TABLE orders".len(),
+    });
+    assert_eq!(&query[refused.range.clone()], "DROP TABLE orders");
+    let message = QueryExecutor::validate_read_only(query).unwrap_err().to_string();
+    println!("{}", message);
+    assert!(message.contains("DROP TABLE") && message.contains("line 4, column 3"));
+
+    // Columns count characters, so text before the statement can be any script
+    let query = "SELECT 'größe; ü' AS x; DELETE FROM orders";
+    let refused = QueryExecutor::refused_statement(query)?.expect("the DELETE should be refused");
+    assert_eq!((refused.line, refused.column), (1, 25));
+    assert_eq!(&query[refused.range], "DELETE FROM orders");
+
+    // Text that doesn't parse is reported, not guessed at
+    assert!(QueryExecutor::validate_read_only("SELEC * FROM orders").is_err());
+
+    // Only databases opened for viewing refuse statements
+    let dir = std::env::temp_dir().join("leaf_read_only_queries_test");
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("note", DataType::Utf8, false),
+    ]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![
+        Arc::new(Int64Array::from(vec![1, 2, 3])),
+        Arc::new(StringArray::from(vec!["created", "update me", "dropped"])),
+    ])?;
+    let mut writable = Database::open_writable(&dir)?;
+    assert!(!writable.is_readonly());
+    writable.insert_record_batches("orders", schema.clone(), vec![batch.clone()])?;
+    let writable = Arc::new(writable);
+    let created = QueryExecutor::execute(&writable, "CREATE VIEW notes AS SELECT note FROM orders")?;
+    println!("writable database ran CREATE VIEW ({} rows)", created.num_rows());
+
+    let mut readonly = Database::open_readonly(&dir)?;
+    assert!(readonly.is_readonly());
+    readonly.insert_record_batches("orders", schema, vec![batch])?;
+    let readonly = Arc::new(readonly);
+    let found = QueryExecutor::execute(&readonly, "SELECT COUNT(*) FROM orders WHERE note LIKE '%update%'")?;
+    assert_eq!(found.cell(0, 0), "1");
+    let error = QueryExecutor::execute(&readonly, "SELECT 1;\nINSERT INTO orders VALUES (4, 'new')").unwrap_err();
+    assert!(error.to_string().contains("INSERT statements can't run in read-only mode (line 2, column 1)"), "{}", error);
+    assert!(QueryExecutor::spawn(&readonly, "DROP TABLE orders").is_err());
+    assert!(Database::clone(&readonly).is_readonly(), "clones stay read-only");
+
+    println!("\n✅ Read-only queries passed");
+    Ok(())
+}
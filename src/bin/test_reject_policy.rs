@@ -0,0 +1,99 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions, RejectPolicy, RejectCounts};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing rejected-row policies");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_reject_policy_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // Two good rows, one short row (line 3), one long row (line 5) and a comment
+    let csv_path = dir.join("readings.csv");
+    std::fs::write(&csv_path, "id,sensor,value\n1,a,1.5\n2,b\n3,c,3.5\n4,d,4.5,extra,\"x,y\"\n# trailing comment\n5,e,5.5\n")?;
+
+    let cases = [
+        (RejectPolicy::Skip, 3, RejectCounts { rejected: 2, padded: 0, truncated: 0 }),
+        (RejectPolicy::Pad, 4, RejectCounts { rejected: 1, padded: 1, truncated: 0 }),
+        (RejectPolicy::Truncate, 4, RejectCounts { rejected: 1, padded: 0, truncated: 1 }),
+    ];
+
+    for (policy, expected_rows, expected_counts) in cases {
+        let output_dir = dir.join(format!("{:?}", policy).to_lowercase());
+        let options = CsvIngestOptions {
+            reject_policy: policy,
+            ..CsvIngestOptions::default()
+        };
+        let mut db = Database::open_writable(&output_dir)?;
+        let summary = db.import_csv_to_file("readings", &csv_path, &output_dir, TableFormat::Arrow, &options, |_| {})?;
+        println!("{:?}: {} rows, {:?}", policy, summary.rows, summary.rejects);
+        assert_eq!(summary.rows, expected_rows);
+        assert_eq!(summary.rejects, expected_counts);
+
+        let rejects_path = summary.rejects_path.expect("rejected rows should be saved");
+        assert_eq!(rejects_path, output_dir.join("rejects").join("readings_rejects.csv"));
+        let rejects = std::fs::read_to_string(&rejects_path)?;
+        println!("{}", rejects.trim_end());
+        assert_eq!(rejects.lines().count(), expected_counts.rejected + 1);
+        assert!(rejects.starts_with("line,reason,row\n"));
+
+        // The quarantine file is not picked up as a table when the project is reopened,
+        // while a user's own file with a similar name still is
+        std::fs::write(output_dir.join("returns_rejects.csv"), "id,reason\n1,damaged\n")?;
+        let mut reopened = Database::open_writable(&output_dir)?;
+        let tables = reopened.load_all_tables_from_directory(&output_dir)?;
+        assert_eq!(tables, ["readings", "returns_rejects"]);
+    }
+
+    // Skip writes the long row back out with its original quoting
+    let skipped = std::fs::read_to_string(dir.join("skip").join("rejects").join("readings_rejects.csv"))?;
+    assert!(skipped.contains("3,\"expected 3 fields, found 2\",\"2,b\""));
+    assert!(skipped.contains("5,\"expected 3 fields, found 5\",\"4,d,4.5,extra,\"\"x,y\"\"\""));
+
+    // Padded values become nulls
+    let mut db = Database::open_writable(&dir.join("pad"))?;
+    db.load_all_tables_from_directory(&dir.join("pad"))?;
+    let padded = db.execute_query("SELECT COUNT(*) FROM readings WHERE value IS NULL")?;
+    assert_eq!(padded[0][0], "1");
+
+    // Fail stops at the first malformed row and reports its line
+    let options = CsvIngestOptions {
+        reject_policy: RejectPolicy::Fail,
+        ..CsvIngestOptions::default()
+    };
+    let mut db = Database::open_writable(&dir)?;
+    let error = db.import_csv_to_file("readings", &csv_path, &dir, TableFormat::Arrow, &options, |_| {})
+        .expect_err("fail policy should stop the import");
+    println!("Fail: {}", error);
+    assert!(error.to_string().contains("line 3"));
+    assert!(!dir.join("readings.arrow").exists(), "a failed import should not leave a table file");
+
+    // A clean re-import removes the stale quarantine file
+    std::fs::write(&csv_path, "id,sensor,value\n1,a,1.5\n")?;
+    let skip_dir = dir.join("skip");
+    let mut db = Database::open_writable(&skip_dir)?;
+    let summary = db.import_csv_to_file("readings", &csv_path, &skip_dir, TableFormat::Arrow, &CsvIngestOptions::default(), |_| {})?;
+    assert!(summary.rejects.is_clean());
+    assert!(summary.rejects_path.is_none());
+    assert!(!skip_dir.join("rejects").join("readings_rejects.csv").exists());
+
+    // The in-memory import takes the same policies
+    std::fs::write(&csv_path, "id,sensor,value\n1,a,1.5\n2,b\n")?;
+    let rejects_path = dir.join("memory_rejects.csv");
+    let counts = db.stream_insert_csv_with_policy("memory", &csv_path, ',', true, RejectPolicy::Skip, Some(&rejects_path))?;
+    assert_eq!(counts.rejected, 1);
+    assert!(rejects_path.exists());
+    assert!(db.stream_insert_csv("memory", &csv_path, ',', true).is_err(), "the plain import still fails on malformed rows");
+
+    // Rows replacing a table that already exists go through the same policy
+    let counts = db.stream_insert_csv_with_policy("memory", &csv_path, ',', true, RejectPolicy::Pad, None)?;
+    assert_eq!(counts.padded, 1);
+    assert_eq!(db.execute_count_query("SELECT COUNT(*) FROM memory")?, 2);
+    std::fs::write(&csv_path, "id,sensor\n1,a\n")?;
+    assert!(db.stream_insert_csv_with_policy("memory", &csv_path, ',', true, RejectPolicy::Pad, None).is_err(), "the header must still match the table");
+
+    println!("\n✅ Rejected-row policies passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions};
+use leaf::core::spreadsheet::{self, CellRange, SheetSelection};
+use leaf::core::table_format;
+use std::io::Write;
+use std::path::Path;
+
+/// Write a minimal .xlsx workbook: each sheet is its `<sheetData>` rows, with inline strings
+/// and style 1 a date, 2 a date and time and 3 a time of day
+fn write_workbook(path: &Path, sheets: &[(&str, String)]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let options = zip::write::SimpleFileOptions::default();
+    let mut overrides = String::new();
+    let mut entries = String::new();
+    let mut relationships = String::new();
+    for (idx, (name, _)) in sheets.iter().enumerate() {
+        let n = idx + 1;
+        overrides.push_str(&format!(
+            "<Override PartName=\"/xl/worksheets/sheet{}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>", n
+        ));
+        entries.push_str(&format!("<sheet name=\"{}\" sheetId=\"{}\" r:id=\"rId{}\"/>", name, n, n));
+        relationships.push_str(&format!(
+            "<Relationship Id=\"rId{}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet{}.xml\"/>", n, n
+        ));
+    }
+    relationships.push_str("<Relationship Id=\"rIdStyles\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>");
+
+    zip.start_file("[Content_Types].xml", options)?;
+    write!(zip, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+        <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+        <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+        <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+        <Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>{}</Types>", overrides)?;
+    zip.start_file("_rels/.rels", options)?;
+    write!(zip, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+        <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/></Relationships>")?;
+    zip.start_file("xl/workbook.xml", options)?;
+    write!(zip, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+        xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\"><sheets>{}</sheets></workbook>", entries)?;
+    zip.start_file("xl/_rels/workbook.xml.rels", options)?;
+    write!(zip, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">{}</Relationships>", relationships)?;
+    zip.start_file("xl/styles.xml", options)?;
+    write!(zip, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+        <cellXfs count=\"4\"><xf numFmtId=\"0\"></xf><xf numFmtId=\"14\"></xf><xf numFmtId=\"22\"></xf><xf numFmtId=\"21\"></xf></cellXfs></styleSheet>")?;
+    for (idx, (_, rows)) in sheets.iter().enumerate() {
+        zip.start_file(format!("xl/worksheets/sheet{}.xml", idx + 1), options)?;
+        write!(zip, "<?xml version=\"1.0\" encoding=\"UTF-8\"?><worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>{}</sheetData></worksheet>", rows)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn text(cell: &str, value: &str) -> String {
+    format!("<c r=\"{}\" t=\"inlineStr\"><is><t>{}</t></is></c>", cell, value)
+}
+
+fn number(cell: &str, value: f64, style: u32) -> String {
+    format!("<c r=\"{}\" s=\"{}\"><v>{}</v></c>", cell, style, value)
+}
+
+fn row(number: usize, cells: &[String]) -> String {
+    format!("<row r=\"{}\">{}</row>", number, cells.concat())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing spreadsheet imports");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_spreadsheet_import_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // A title above the header, a blank row in the data, native dates and times,
+    // and formulas stored with their cached results, one of them an error
+    let mut sales = row(1, &[text("A1", "Quarterly sales")]);
+    sales.push_str(&row(3, &["Date", "Region", "Units", "Price", "Total", "Shipped", "Opens"].iter().enumerate()
+        .map(|(col, name)| text(&format!("{}3", (b'A' + col as u8) as char), name))
+        .collect::<Vec<_>>()));
+    for i in 0..20usize {
+        let r = if i < 10 { i + 4 } else { i + 5 };
+        let units = i + 1;
+        let region = ["North", "South"][i % 2];
+        let region = if i == 0 {
+            format!("<c r=\"B{}\" t=\"str\"><f>IF(C{}&lt;5,\"North\",\"South\")</f><v>North</v></c>", r, r)
+        } else {
+            text(&format!("B{}", r), region)
+        };
+        let total = if i == 7 {
+            format!("<c r=\"E{}\" t=\"e\"><f>C{}/0</f><v>#DIV/0!</v></c>", r, r)
+        } else {
+            format!("<c r=\"E{}\"><f>C{}*D{}</f><v>{}</v></c>", r, r, r, units as f64 * 2.5)
+        };
+        sales.push_str(&row(r, &[
+            number(&format!("A{}", r), 46023.0 + i as f64, 1),
+            region,
+            number(&format!("C{}", r), units as f64, 0),
+            number(&format!("D{}", r), 2.5, 0),
+            total,
+            number(&format!("F{}", r), 46023.5 + i as f64, 2),
+            number(&format!("G{}", r), 0.375, 3),
+        ]));
+    }
+    // A lookup table that starts at B2
+    let mut lookup = row(2, &[text("B2", "code"), text("C2", "name")]);
+    for (i, name) in ["alpha", "beta", "gamma"].iter().enumerate() {
+        lookup.push_str(&row(i + 3, &[number(&format!("B{}", i + 3), (i as f64 + 1.0) * 10.0, 0), text(&format!("C{}", i + 3), name)]));
+    }
+    let workbook = dir.join("Q1 report.xlsx");
+    write_workbook(&workbook, &[("Sales", sales), ("Lookup", lookup), ("Empty", String::new())])?;
+
+    assert!(spreadsheet::is_spreadsheet(&workbook));
+    assert!(spreadsheet::is_spreadsheet(&dir.join("old.XLS")));
+    assert!(!spreadsheet::is_spreadsheet(&dir.join("data.csv")));
+
+    // Ranges in A1 notation
+    let range = CellRange::parse("B2:F100")?;
+    assert_eq!(range, CellRange { start: (1, 1), end: Some((99, 5)) });
+    assert_eq!(range.to_string(), "B2:F100");
+    assert_eq!(CellRange::parse(" $aa$10 ")?.to_string(), "AA10");
+    assert!(CellRange::parse("2B").is_err());
+    assert!(CellRange::parse("A0").is_err());
+    assert!(CellRange::parse("C3:A1").is_err());
+
+    let sheets = spreadsheet::list_sheets(&workbook)?;
+    println!("sheets: {:?}", sheets);
+    assert_eq!(sheets.iter().map(|sheet| sheet.name.as_str()).collect::<Vec<_>>(), ["Sales", "Lookup", "Empty"]);
+    assert_eq!((sheets[0].rows, sheets[0].columns), (24, 7));
+    assert!(sheets[2].is_empty());
+    assert_eq!(spreadsheet::table_name_for(&workbook, "Sales", true), "Q1_report_Sales");
+    assert_eq!(spreadsheet::table_name_for(&workbook, "Sales", false), "Q1_report");
+
+    // Records are numbered by sheet row, with dates, times and cached formula values as text
+    let sales_sheet = SheetSelection { path: workbook.clone(), sheet: "Sales".to_string(), range: None };
+    let mut records = spreadsheet::open_records(&sales_sheet)?;
+    let title = records.next_record()?.unwrap();
+    assert_eq!((title.line, title.fields[0].as_str()), (1, "Quarterly sales"));
+    assert_eq!(records.next_record()?.unwrap().fields, [""]);
+    assert_eq!(records.next_record()?.unwrap().fields[0], "Date");
+    let first = records.next_record()?.unwrap();
+    println!("first row: {:?}", first.fields);
+    assert_eq!(first.fields, ["2026-01-01", "North", "1", "2.5", "2.5", "2026-01-01 12:00:00", "09:00:00"]);
+    assert_eq!(first.line, 4);
+
+    // The header is the third row of the sheet; the blank row between the data is skipped
+    let mut db = Database::open_writable(&dir)?;
+    let options = CsvIngestOptions { header_row: 2, batch_size: 8, ..CsvIngestOptions::default() };
+    let mut last = None;
+    let summary = db.import_sheet_to_file("sales", &sales_sheet, &dir, TableFormat::Arrow, &options, |progress| {
+        last = Some(*progress);
+    })?;
+    assert_eq!(summary.rows, 20);
+    let last = last.unwrap();
+    assert_eq!((last.bytes_read, last.total_bytes), (24, 24), "progress is counted in sheet rows");
+
+    let (schema, _) = table_format::read_batches(&dir.join("sales.arrow"))?;
+    let types: Vec<String> = schema.fields().iter().map(|f| format!("{} {}", f.name(), f.data_type())).collect();
+    println!("schema: {:?}", types);
+    assert_eq!(types[..5], ["Date Date32", "Region Utf8", "Units Int64", "Price Float64", "Total Float64"]);
+    assert!(types[5].starts_with("Shipped Timestamp"));
+    assert!(types[6].starts_with("Opens Time64"));
+
+    let totals = db.execute_query(
+        "SELECT COUNT(*), SUM(\"Units\"), SUM(\"Total\"), COUNT(\"Total\"), MIN(\"Date\"), MAX(\"Date\") FROM sales WHERE \"Region\" IN ('North', 'South')"
+    )?;
+    println!("totals: {:?}", totals[0]);
+    assert_eq!(totals[0], vec!["20", "210", "505", "19", "2026-01-01", "2026-01-20"]);
+
+    // A range picks out some of the columns and rows
+    let corner = SheetSelection { path: workbook.clone(), sheet: "Sales".to_string(), range: Some(CellRange::parse("A3:C8")?) };
+    db.import_sheet_to_file("corner", &corner, &dir, TableFormat::Arrow, &CsvIngestOptions::default(), |_| {})?;
+    let (schema, _) = table_format::read_batches(&dir.join("corner.arrow"))?;
+    assert_eq!(schema.fields().iter().map(|f| f.name().as_str()).collect::<Vec<_>>(), ["Date", "Region", "Units"]);
+    assert_eq!(db.execute_query("SELECT COUNT(*), SUM(\"Units\") FROM corner")?[0], vec!["5", "15"]);
+
+    // A table that doesn't start at A1, by range or by header row
+    let lookup_range = SheetSelection { path: workbook.clone(), sheet: "Lookup".to_string(), range: Some(CellRange::parse("B2")?) };
+    db.import_sheet_to_file("lookup", &lookup_range, &dir, TableFormat::Arrow, &CsvIngestOptions::default(), |_| {})?;
+    let lookup_sheet = SheetSelection { path: workbook.clone(), sheet: "Lookup".to_string(), range: None };
+    let by_header = CsvIngestOptions { header_row: 1, ..CsvIngestOptions::default() };
+    db.import_sheet_to_file("lookup_rows", &lookup_sheet, &dir, TableFormat::Arrow, &by_header, |_| {})?;
+    for table in ["lookup", "lookup_rows"] {
+        let rows = db.execute_query(&format!("SELECT code, name FROM {} ORDER BY code", table))?;
+        assert_eq!(rows, vec![vec!["10", "alpha"], vec!["20", "beta"], vec!["30", "gamma"]]);
+    }
+
+    // Whole-sheet inference and value checks use the same reader
+    let inferences = spreadsheet::infer_full_sheet(&sales_sheet, &options, |_| {})?;
+    assert_eq!(inferences.len(), 7);
+    let report = spreadsheet::check_coercion(&sales_sheet, &options, |_| {})?;
+    assert_eq!(report.total(), 0);
+
+    // Missing and empty sheets fail without leaving a table behind
+    let missing = SheetSelection { path: workbook.clone(), sheet: "Nope".to_string(), range: None };
+    assert!(db.import_sheet_to_file("missing", &missing, &dir, TableFormat::Arrow, &options, |_| {}).is_err());
+    let empty = SheetSelection { path: workbook.clone(), sheet: "Empty".to_string(), range: None };
+    let error = db.import_sheet_to_file("empty", &empty, &dir, TableFormat::Arrow, &options, |_| {}).unwrap_err();
+    println!("empty sheet: {}", error);
+    assert!(!dir.join("empty.arrow").exists() && !dir.join("missing.arrow").exists());
+
+    println!("\n✅ Spreadsheet import passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
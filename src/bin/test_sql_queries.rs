@@ -26,8 +26,8 @@ fn main() -> Result<()> {
     let info_query = "SELECT * FROM test_data LIMIT 1";
     match QueryExecutor::execute(&db_arc, info_query) {
         Ok(result) => {
-            println!("Columns: {:?}", result.columns);
-            println!("Types: {:?}", result.column_types);
+            println!("Columns: {:?}", result.column_names());
+            println!("Types: {:?}", result.column_types());
         }
         Err(e) => println!("Error getting table info: {}", e),
     }
@@ -96,11 +96,11 @@ fn main() -> Result<()> {
                 if let Some(total) = result.total_rows {
                     println!("  Total rows: {}", total);
                 }
-                println!("  Returned rows: {}", result.rows.len());
+                println!("  Returned rows: {}", result.num_rows());
                 
                 // Show first few results for non-aggregate queries
                 if !query.contains("COUNT(") && !query.contains("MIN(") && !query.contains("MAX(") {
-                    for (i, row) in result.rows.iter().take(3).enumerate() {
+                    for (i, row) in result.rows().take(3).enumerate() {
                         let row_str: Vec<String> = row.iter()
                             .take(5) // Show first 5 columns max
                             .map(|v| {
@@ -115,7 +115,7 @@ fn main() -> Result<()> {
                     }
                 } else {
                     // For aggregate queries, show the result
-                    if let Some(row) = result.rows.first() {
+                    if let Some(row) = result.rows().next() {
                         println!("  Result: {:?}", row);
                     }
                 }
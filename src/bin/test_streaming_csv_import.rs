@@ -0,0 +1,91 @@
+use leaf::core::{Database, TableFormat, ParquetCompression, CsvIngestOptions};
+use datafusion::arrow::datatypes::DataType;
+use std::io::Write;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing streaming CSV import");
+    println!("{}", "=".repeat(60));
+
+    let dir = std::env::temp_dir().join("leaf_streaming_csv_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // A CSV with two junk lines above the header
+    let csv_path = dir.join("sensor_export.csv");
+    let rows = 250_000usize;
+    {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&csv_path)?);
+        writeln!(file, "# exported by logger v2")?;
+        writeln!(file, "# units: volts")?;
+        writeln!(file, "id,sensor,reading,time")?;
+        for i in 0..rows {
+            let reading = if i % 100 == 0 { "NULL".to_string() } else { format!("{:.3}", i as f64 * 0.25) };
+            writeln!(file, "{},s{},{},{:02}:{:02}:{:02}", i, i % 8, reading, (i / 3600) % 24, (i / 60) % 60, i % 60)?;
+        }
+    }
+    let file_size = std::fs::metadata(&csv_path)?.len();
+    println!("Wrote {} rows ({} bytes)", rows, file_size);
+
+    let options = CsvIngestOptions {
+        header_row: 2,
+        batch_size: 10_000,
+        ..CsvIngestOptions::default()
+    };
+
+    for format in [TableFormat::Arrow, TableFormat::Parquet(ParquetCompression::Snappy)] {
+        let mut db = Database::open_writable(&dir)?;
+        let mut updates = 0usize;
+        let mut last_bytes = 0u64;
+        let mut last_rows = 0usize;
+        db.import_csv_to_file("sensor_export", &csv_path, &dir, format, &options, |progress| {
+            assert!(progress.bytes_read >= last_bytes, "progress should only move forward");
+            assert!(progress.rows > last_rows, "each update should add rows");
+            last_bytes = progress.bytes_read;
+            last_rows = progress.rows;
+            updates += 1;
+        })?;
+        println!("{}: {} progress updates, final {} rows / {} bytes", format.display_name(), updates, last_rows, last_bytes);
+        assert_eq!(updates, rows.div_ceil(options.batch_size), "one progress update per batch");
+        assert_eq!(last_rows, rows);
+        assert_eq!(last_bytes, file_size, "the whole file should be read");
+
+        let output_path = dir.join(format.file_name("sensor_export"));
+        assert!(output_path.exists(), "table file should be written to the output folder");
+        assert!(!dir.join(format!("sensor_export.{}.partial", format.extension())).exists(), "partial file should be renamed");
+
+        let count = db.execute_count_query("SELECT COUNT(*) FROM sensor_export")?;
+        assert_eq!(count as usize, rows);
+        let nulls = db.execute_query("SELECT COUNT(*) - COUNT(reading) FROM sensor_export")?;
+        assert_eq!(nulls[0][0], (rows / 100).to_string(), "null tokens should become nulls");
+
+        let types = db.get_column_types("SELECT * FROM sensor_export")?;
+        println!("  column types: {:?}", types);
+    }
+
+    // The in-memory header-row import uses the same reader
+    let mut db = Database::open_writable(&dir)?;
+    let delimiter = db.stream_insert_csv_with_header_row("in_memory", &csv_path, ',', 2)?;
+    assert_eq!(delimiter, ',');
+    assert_eq!(db.execute_count_query("SELECT COUNT(*) FROM in_memory")? as usize, rows);
+
+    // The plain in-memory import infers types the same way, so null tokens don't turn a column into text
+    let small_path = dir.join("small.csv");
+    std::fs::write(&small_path, "id,reading\n1,2.5\n2,NULL\n3,N/A\n4,\n")?;
+    db.stream_insert_csv("small", &small_path, ',', true)?;
+    assert_eq!(db.get_column_types("SELECT reading FROM small")?, [DataType::Float64]);
+    assert_eq!(db.execute_query("SELECT SUM(reading), COUNT(reading) FROM small")?, [["2.5", "1"]]);
+
+    // A file without a header row gets numbered columns, whether kept in memory or written to a table file
+    std::fs::write(&small_path, "1,2.5\n2,4\n")?;
+    db.stream_insert_csv("headerless", &small_path, ',', false)?;
+    assert_eq!(db.execute_query("SELECT col_0, col_1 FROM headerless")?, [["1", "2.5"], ["2", "4"]]);
+    let headerless = CsvIngestOptions { has_header: false, ..CsvIngestOptions::default() };
+    let summary = db.import_csv_to_file("headerless_file", &small_path, &dir, TableFormat::Arrow, &headerless, |_| {})?;
+    assert_eq!(summary.rows, 2);
+    assert_eq!(db.execute_query("SELECT SUM(col_0) FROM headerless_file")?, [["3"]]);
+
+    println!("\n✅ Streaming CSV import passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
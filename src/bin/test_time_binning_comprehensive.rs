@@ -1,4 +1,4 @@
-use leaf::core::{Database, time_grouping::TimeGroupingEngine};
+use leaf::core::{Database, TableFormat, time_grouping::TimeGroupingEngine};
 use leaf::ui::time_bin_dialog::{TimeBinConfig, TimeBinStrategy};
 use std::sync::Arc;
 use std::path::Path;
@@ -96,6 +96,7 @@ fn main() {
                 strategy: strategy.clone(),
                 output_column_name: format!("{}_bin", time_column),
                 output_filename: Some(format!("{}_{}_binned", table_name, strategy_name.replace(" ", "_").to_lowercase())),
+                output_format: TableFormat::Arrow,
             };
             
             match TimeGroupingEngine::apply_grouping(&db, &config, output_dir) {
@@ -199,6 +200,7 @@ fn test_synthetic_time_data() {
             strategy,
             output_column_name: "time_bin".to_string(),
             output_filename: Some(format!("synthetic_{}", name.replace(" ", "_").to_lowercase())),
+            output_format: TableFormat::Arrow,
         };
         
         match TimeGroupingEngine::apply_grouping(&db, &config, output_dir) {
@@ -1,4 +1,4 @@
-use leaf::core::{Database, time_grouping::TimeGroupingEngine};
+use leaf::core::{Database, TableFormat, time_grouping::TimeGroupingEngine};
 use leaf::ui::time_bin_dialog::{TimeBinConfig, TimeBinStrategy};
 use std::sync::Arc;
 use std::path::Path;
@@ -127,6 +127,7 @@ fn main() {
             strategy,
             output_column_name: "time_bin".to_string(),
             output_filename: Some(format!("test_{}", name.replace(" ", "_").to_lowercase())),
+            output_format: TableFormat::Arrow,
         };
         
         match TimeGroupingEngine::apply_grouping(&db, &config, output_dir) {
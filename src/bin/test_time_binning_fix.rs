@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::sync::Arc;
-use leaf::core::{Database, TimeGroupingEngine};
+use leaf::core::{Database, TableFormat, TimeGroupingEngine};
 use leaf::ui::time_bin_dialog::{TimeBinConfig, TimeBinStrategy};
 
 fn main() -> Result<()> {
@@ -127,6 +127,7 @@ fn test_with_actual_data() -> Result<()> {
         },
         output_column_name: "good_time_hourly_bin".to_string(),
         output_filename: Some("test_binning_result".to_string()),
+        output_format: TableFormat::Arrow,
     };
     
     let output_dir = std::path::Path::new(".");
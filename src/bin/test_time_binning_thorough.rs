@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::sync::Arc;
-use leaf::core::{Database, TimeGroupingEngine};
+use leaf::core::{Database, TimeGroupingEngine, TableFormat};
 use leaf::ui::time_bin_dialog::{TimeBinConfig, TimeBinStrategy};
 
 fn main() -> Result<()> {
@@ -154,6 +154,7 @@ fn test_time_column(db: &Arc<Database>, table_name: &str, column_name: &str) ->
         },
         output_column_name: format!("{}_hourly_bin", column_name),
         output_filename: Some(format!("{}_hourly_binned", table_name)),
+        output_format: TableFormat::Arrow,
     };
     
     let output_dir = std::path::Path::new(".");
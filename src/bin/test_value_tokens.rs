@@ -0,0 +1,101 @@
+use leaf::core::{Database, TableFormat, CsvIngestOptions};
+use leaf::infer::{TypeInferrer, ColumnType, NumberFormat, BooleanVocabulary, ColumnTokens};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Testing per-column null tokens and boolean words");
+    println!("{}", "=".repeat(60));
+
+    // Vocabularies
+    let on_off = BooleanVocabulary::new(&["on"], &["off"]);
+    assert_eq!(on_off.parse("ON"), Some(true));
+    assert_eq!(on_off.parse("off"), Some(false));
+    assert_eq!(on_off.parse("yes"), None);
+    assert_eq!(BooleanVocabulary::default().parse("Y"), Some(true));
+    assert_eq!(BooleanVocabulary::from_lists("pass, ok,", " fail"), BooleanVocabulary::new(&["pass", "ok"], &["fail"]));
+
+    // Tokens only apply to the column they're set for
+    let headers: Vec<String> = ["sensor", "offset", "heater", "check", "flag"].iter().map(|s| s.to_string()).collect();
+    let samples: Vec<Vec<String>> = (0..30)
+        .map(|i| vec![
+            if i % 7 == 0 { "-999".to_string() } else { format!("{}.5", i) },
+            if i % 7 == 0 { "-999".to_string() } else { i.to_string() },
+            ["on", "off"][i % 2].to_string(),
+            ["PASS", "fail", "n/a"][i % 3].to_string(),
+            ["T", "F"][i % 2].to_string(),
+        ])
+        .collect();
+    let file_nulls: Vec<String> = vec!["".to_string(), "n/a".to_string()];
+    let defaults = vec![ColumnTokens::new(&file_nulls, &[], BooleanVocabulary::default()); headers.len()];
+    let default_types: Vec<ColumnType> = TypeInferrer::infer_column_types_with_tokens(&headers, &samples, &defaults, &NumberFormat::default())
+        .into_iter().map(|(_, column_type)| column_type).collect();
+    println!("default words: {:?}", default_types);
+    assert_eq!(default_types, vec![ColumnType::Real, ColumnType::Integer, ColumnType::Dictionary, ColumnType::Dictionary, ColumnType::Dictionary]);
+
+    let tokens = vec![
+        ColumnTokens::new(&file_nulls, &["-999".to_string()], BooleanVocabulary::default()),
+        ColumnTokens::new(&file_nulls, &[], BooleanVocabulary::default()),
+        ColumnTokens::new(&file_nulls, &[], on_off.clone()),
+        ColumnTokens::new(&file_nulls, &[], BooleanVocabulary::new(&["pass"], &["fail"])),
+        ColumnTokens::new(&file_nulls, &[], BooleanVocabulary::new(&["t"], &["f"])),
+    ];
+    let inferences = TypeInferrer::infer_columns_with_confidence(&headers, &samples, &tokens, &NumberFormat::default(), &[]);
+    for inference in &inferences {
+        println!("own words:     {:?} {} ({} nulls)", inference.column_type, inference.reason, inference.nulls);
+    }
+    let types: Vec<ColumnType> = inferences.iter().map(|inference| inference.column_type.clone()).collect();
+    assert_eq!(types, vec![ColumnType::Real, ColumnType::Integer, ColumnType::Boolean, ColumnType::Boolean, ColumnType::Boolean]);
+    assert_eq!(inferences[0].nulls, 5, "-999 is null in the sensor column");
+    assert_eq!(inferences[1].nulls, 0, "but a value in the offset column");
+    assert_eq!(inferences[2].reason, "All 30 values are on or off");
+    assert_eq!(inferences[3].nulls, 10);
+
+    // Validation honours the same words
+    assert!(TypeInferrer::validate_column_type_change(&samples, 2, &ColumnType::Boolean, &NumberFormat::default(), None, &tokens[2]).is_ok());
+    assert!(TypeInferrer::validate_column_type_change(&samples, 2, &ColumnType::Boolean, &NumberFormat::default(), None, &tokens[1]).is_err());
+    assert!(ColumnType::Boolean.can_parse_value_with_tokens("fail", &NumberFormat::default(), &tokens[3]));
+    assert!(!ColumnType::Boolean.can_parse_value_with_format("fail", &NumberFormat::default()));
+
+    // End to end, inferring the types and with the types given
+    let dir = std::env::temp_dir().join("leaf_value_tokens_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+    let csv_path = dir.join("readings.csv");
+    let mut csv = headers.join(",");
+    csv.push('\n');
+    for row in &samples {
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    std::fs::write(&csv_path, &csv)?;
+
+    let options = CsvIngestOptions {
+        null_values: file_nulls.clone(),
+        column_null_values: vec![vec!["-999".to_string()]],
+        boolean_vocabularies: vec![None, None, Some(on_off), Some(BooleanVocabulary::new(&["pass"], &["fail"])), Some(BooleanVocabulary::new(&["t"], &["f"]))],
+        ..CsvIngestOptions::default()
+    };
+    let given = CsvIngestOptions {
+        column_types: Some(vec![ColumnType::Real, ColumnType::Integer, ColumnType::Boolean, ColumnType::Boolean, ColumnType::Boolean]),
+        ..options.clone()
+    };
+    let mut db = Database::open_writable(&dir)?;
+    for (name, options) in [("inferred", &options), ("given", &given)] {
+        let summary = db.import_csv_to_file(name, &csv_path, &dir, TableFormat::Arrow, options, |_| {})?;
+        let schema: Vec<String> = summary.schema.fields().iter().map(|f| f.data_type().to_string()).collect();
+        println!("{} schema: {:?}", name, schema);
+        assert_eq!(&schema[2..], ["Boolean", "Boolean", "Boolean"]);
+        assert_eq!(summary.coercion.total(), 0, "{}", name);
+
+        let counts = db.execute_query(&format!(
+            "SELECT COUNT(sensor), MIN(offset), SUM(CASE WHEN heater THEN 1 ELSE 0 END), COUNT(\"check\"), SUM(CASE WHEN \"check\" THEN 1 ELSE 0 END), SUM(CASE WHEN flag THEN 1 ELSE 0 END) FROM {}",
+            name
+        ))?;
+        println!("  {:?}", counts[0]);
+        assert_eq!(counts[0], vec!["25", "-999", "15", "20", "10", "15"]);
+    }
+
+    println!("\n✅ Value tokens passed");
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use datafusion::arrow::datatypes::DataType;
+use serde::{Deserialize, Serialize};
+
+/// Number of bad values kept as examples for each column
+const MAX_EXAMPLES: usize = 5;
+
+/// What to do with a value that can't be parsed as its column's type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CoercionPolicy {
+    /// Store the value as null and list it in the coercion report
+    #[default]
+    SetNull,
+    /// Stop the import at the first bad value
+    Fail,
+    /// Import every column that has a bad value as Text instead
+    FallBackToText,
+}
+
+impl CoercionPolicy {
+    pub const ALL: [CoercionPolicy; 3] = [CoercionPolicy::SetNull, CoercionPolicy::Fail, CoercionPolicy::FallBackToText];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CoercionPolicy::SetNull => "Set to null",
+            CoercionPolicy::Fail => "Fail the import",
+            CoercionPolicy::FallBackToText => "Import the column as Text",
+        }
+    }
+}
+
+/// A value that couldn't be converted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadValue {
+    /// 1-based line number in the source file
+    pub line: u64,
+    pub value: String,
+}
+
+/// Conversion failures for one column
+#[derive(Debug, Clone)]
+pub struct ColumnCoercion {
+    pub column_index: usize,
+    pub column: String,
+    pub data_type: DataType,
+    pub count: usize,
+    /// The first few bad values, in file order
+    pub examples: Vec<BadValue>,
+}
+
+/// Values that couldn't be parsed as their column's type, by column
+#[derive(Debug, Clone, Default)]
+pub struct CoercionReport {
+    pub columns: Vec<ColumnCoercion>,
+}
+
+impl CoercionReport {
+    pub fn record(&mut self, column_index: usize, column: &str, data_type: &DataType, line: u64, value: &str) {
+        let position = match self.columns.iter().position(|c| c.column_index == column_index) {
+            Some(position) => position,
+            None => {
+                self.columns.push(ColumnCoercion {
+                    column_index,
+                    column: column.to_string(),
+                    data_type: data_type.clone(),
+                    count: 0,
+                    examples: Vec::new(),
+                });
+                self.columns.len() - 1
+            }
+        };
+
+        let entry = &mut self.columns[position];
+        entry.count += 1;
+        if entry.examples.len() < MAX_EXAMPLES {
+            entry.examples.push(BadValue { line, value: value.to_string() });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Total number of bad values across all columns
+    pub fn total(&self) -> usize {
+        self.columns.iter().map(|c| c.count).sum()
+    }
+
+    /// Indexes of the columns with at least one bad value
+    pub fn column_indexes(&self) -> Vec<usize> {
+        self.columns.iter().map(|c| c.column_index).collect()
+    }
+}
+
+impl ColumnCoercion {
+    /// One line summary, e.g. `amount (Int64): 3 values such as "12a" (line 7)`
+    pub fn describe(&self) -> String {
+        let examples: Vec<String> = self.examples.iter()
+            .map(|bad| format!("\"{}\" (line {})", bad.value, bad.line))
+            .collect();
+        format!(
+            "{} ({}): {} value{} such as {}",
+            self.column, self.data_type, self.count,
+            if self.count == 1 { "" } else { "s" },
+            examples.join(", ")
+        )
+    }
+}
@@ -1,11 +1,10 @@
-use crate::core::{Database, DataTransformer, TransformationType};
+use crate::core::{Database, DataTransformer, TransformationType, TableFormat};
+use crate::core::table_format;
 use crate::ui::{ComputedColumnsRequest, ComputedColumnConfig, ComputationType};
 use datafusion::arrow::record_batch::RecordBatch;
-use datafusion::arrow::ipc::writer::FileWriter;
 use anyhow::{Result, anyhow};
 use std::path::Path;
 use std::sync::Arc;
-use std::fs::File;
 
 pub struct ComputedColumnsProcessor {
     transformer: DataTransformer,
@@ -36,19 +35,15 @@ impl ComputedColumnsProcessor {
         
         // Generate output filename
         let output_filename = if let Some(custom_name) = &request.output_filename {
-            // Ensure .arrow extension
-            if custom_name.ends_with(".arrow") {
-                custom_name.clone()
-            } else {
-                format!("{}.arrow", custom_name)
-            }
+            // Ensure the extension matches the output format
+            request.output_format.file_name(custom_name)
         } else {
-            self.generate_output_filename(&request.table_name, &request.configurations)
+            self.generate_output_filename(&request.table_name, &request.configurations, request.output_format)
         };
         let output_path = output_dir.join(&output_filename);
         
         // Save the transformed data
-        self.save_batch(&current_batch, &output_path)?;
+        table_format::write_batches(&output_path, &current_batch.schema(), &[current_batch], request.output_format)?;
         
         Ok(output_filename)
     }
@@ -90,6 +85,7 @@ impl ComputedColumnsProcessor {
         &self,
         table_name: &str,
         configurations: &[ComputedColumnConfig],
+        output_format: TableFormat,
     ) -> String {
         // Extract base name without extension
         let base_name = table_name.trim_end_matches(".arrow")
@@ -120,16 +116,9 @@ impl ComputedColumnsProcessor {
             suffixes.join("_")
         };
         
-        format!("{}_{}.arrow", base_name, suffix)
+        output_format.file_name(&format!("{}_{}", base_name, suffix))
     }
     
-    fn save_batch(&self, batch: &RecordBatch, output_path: &Path) -> Result<()> {
-        let file = File::create(output_path)?;
-        let mut writer = FileWriter::try_new(file, batch.schema().as_ref())?;
-        writer.write(batch)?;
-        writer.finish()?;
-        Ok(())
-    }
     
     pub fn generate_preview(
         &self,
@@ -168,7 +157,7 @@ impl ComputedColumnsProcessor {
                 let arr = array.as_any().downcast_ref::<StringArray>().unwrap();
                 arr.value(idx).to_string()
             }
-            _ => "?".to_string(),
+            _ => datafusion::arrow::util::display::array_value_to_string(array, idx).unwrap_or_else(|_| "?".to_string()),
         }
     }
 }
\ No newline at end of file
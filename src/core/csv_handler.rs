@@ -1,41 +1,47 @@
-use csv::{Reader, Writer, StringRecord, ReaderBuilder};
+use csv::Writer;
+use encoding_rs::Encoding;
 use std::fs::File;
 use std::path::Path;
-use std::io::BufReader;
+use crate::core::csv_source::CsvSource;
+use crate::core::csv_tokenizer::CsvTokenizer;
 use crate::core::error::Result;
 use std::path::PathBuf;
 
+/// Reads a CSV file's records with the shared `CsvTokenizer`
 pub struct CsvReader {
     path: PathBuf,
-    delimiter: u8,
-    reader: Option<Reader<BufReader<File>>>,
+    delimiter: char,
+    encoding: Option<&'static Encoding>,
+    reader: Option<CsvTokenizer<CsvSource>>,
 }
 
 impl CsvReader {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
         Ok(Self { 
             path: path.as_ref().to_path_buf(),
-            delimiter: b',',
+            delimiter: ',',
+            encoding: None,
             reader: None,
         })
     }
     
     pub fn set_delimiter(&mut self, delimiter: char) {
-        self.delimiter = delimiter as u8;
+        self.delimiter = delimiter;
         // Reset reader if delimiter changes
         self.reader = None;
     }
     
-    fn build_reader(&self) -> Result<Reader<BufReader<File>>> {
-        let file = File::open(&self.path)?;
-        let reader = ReaderBuilder::new()
-            .delimiter(self.delimiter)
-            .has_headers(false)
-            .from_reader(BufReader::new(file));
-        Ok(reader)
+    /// Set the file's character encoding; `None` detects it from the file
+    pub fn set_encoding(&mut self, encoding: Option<&'static Encoding>) {
+        self.encoding = encoding;
+        self.reader = None;
+    }
+    
+    fn build_reader(&self) -> Result<CsvTokenizer<CsvSource>> {
+        CsvTokenizer::from_path(&self.path, self.delimiter, false, self.encoding)
     }
     
-    fn get_or_create_reader(&mut self) -> Result<&mut Reader<BufReader<File>>> {
+    fn get_or_create_reader(&mut self) -> Result<&mut CsvTokenizer<CsvSource>> {
         if self.reader.is_none() {
             self.reader = Some(self.build_reader()?);
         }
@@ -44,41 +50,33 @@ impl CsvReader {
     
     pub fn headers(&mut self) -> Result<Vec<String>> {
         let mut reader = self.build_reader()?;
-        if let Some(result) = reader.records().next() {
-            Ok(result?.iter().map(|s| s.to_string()).collect())
-        } else {
-            Ok(Vec::new())
-        }
+        Ok(reader.next_record()?.map(|record| record.fields).unwrap_or_default())
     }
     
-    pub fn records(&mut self) -> Result<Vec<StringRecord>> {
+    pub fn records(&mut self) -> Result<Vec<Vec<String>>> {
         let mut reader = self.build_reader()?;
         let mut records = Vec::new();
-        for result in reader.records() {
-            records.push(result?);
+        while let Some(record) = reader.next_record()? {
+            records.push(record.fields);
         }
         Ok(records)
     }
     
-    pub fn sample_records(&mut self, n: usize) -> Result<Vec<StringRecord>> {
+    pub fn sample_records(&mut self, n: usize) -> Result<Vec<Vec<String>>> {
         let mut reader = self.build_reader()?;
         let mut records = Vec::new();
-        for (i, result) in reader.records().enumerate() {
-            if i >= n {
-                break;
+        while records.len() < n {
+            match reader.next_record()? {
+                Some(record) => records.push(record.fields),
+                None => break,
             }
-            records.push(result?);
         }
         Ok(records)
     }
     
-    pub fn read_record(&mut self) -> Result<Option<StringRecord>> {
+    pub fn read_record(&mut self) -> Result<Option<Vec<String>>> {
         let reader = self.get_or_create_reader()?;
-        if let Some(result) = reader.records().next() {
-            Ok(Some(result?))
-        } else {
-            Ok(None)
-        }
+        Ok(reader.next_record()?.map(|record| record.fields))
     }
 }
 
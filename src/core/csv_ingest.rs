@@ -0,0 +1,1048 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use datafusion::arrow::array::new_null_array;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use crate::core::coercion::{CoercionPolicy, CoercionReport};
+use crate::core::database::Database;
+use crate::core::csv_tokenizer::{self, CsvTokenizer, RecordSource};
+use crate::core::fixed_width::FixedWidthRecords;
+use crate::core::error::{Result, LeafError};
+use crate::core::table_format::{TableFormat, TableReader, TableWriter};
+use crate::core::text_encoding;
+use crate::infer::{TypeInferrer, ColumnType, ColumnInference, ColumnProfile, ColumnTokens, BooleanVocabulary, DateTimeFormat, NumberFormat, DEFAULT_NULL_VALUES};
+use crate::infer::datetime_format;
+
+/// What to do with a data row whose field count doesn't match the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RejectPolicy {
+    /// Stop the import at the first malformed row
+    Fail,
+    /// Leave malformed rows out of the table
+    #[default]
+    Skip,
+    /// Fill short rows with nulls; rows with extra fields are rejected
+    Pad,
+    /// Drop the extra fields of long rows; short rows are rejected
+    Truncate,
+}
+
+impl RejectPolicy {
+    pub const ALL: [RejectPolicy; 4] = [RejectPolicy::Fail, RejectPolicy::Skip, RejectPolicy::Pad, RejectPolicy::Truncate];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            RejectPolicy::Fail => "Fail the import",
+            RejectPolicy::Skip => "Skip the row",
+            RejectPolicy::Pad => "Pad short rows with nulls",
+            RejectPolicy::Truncate => "Truncate long rows",
+        }
+    }
+}
+
+/// How many rows an import rejected or repaired
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RejectCounts {
+    pub rejected: usize,
+    pub padded: usize,
+    pub truncated: usize,
+}
+
+impl RejectCounts {
+    pub fn is_clean(&self) -> bool {
+        self.rejected == 0 && self.padded == 0 && self.truncated == 0
+    }
+}
+
+/// Applies a `RejectPolicy` to rows with the wrong number of fields.
+/// Rejected rows are written to a quarantine CSV with their line number and the reason;
+/// the file is only created once the first row is rejected.
+pub struct RowValidator {
+    policy: RejectPolicy,
+    expected_columns: usize,
+    delimiter: u8,
+    rejects_path: Option<PathBuf>,
+    writer: Option<csv::Writer<File>>,
+    counts: RejectCounts,
+}
+
+impl RowValidator {
+    pub fn new(policy: RejectPolicy, expected_columns: usize, delimiter: char, rejects_path: Option<PathBuf>) -> Result<Self> {
+        // A quarantine file left by an earlier import of the same table is out of date
+        if let Some(path) = &rejects_path {
+            if path.exists() {
+                std::fs::remove_file(path)
+                    .map_err(|e| LeafError::Custom(format!("Failed to remove old rejects file {}: {}", path.display(), e)))?;
+            }
+        }
+
+        Ok(Self {
+            policy,
+            expected_columns,
+            delimiter: delimiter as u8,
+            rejects_path,
+            writer: None,
+            counts: RejectCounts::default(),
+        })
+    }
+
+    /// Check a row against the expected field count. Returns the row to import (repaired if the
+    /// policy allows it), `None` if the row was rejected, or an error under `RejectPolicy::Fail`.
+    pub fn check(&mut self, mut fields: Vec<String>, line: u64) -> Result<Option<Vec<String>>> {
+        let found = fields.len();
+        if found == self.expected_columns {
+            return Ok(Some(fields));
+        }
+
+        match self.policy {
+            RejectPolicy::Pad if found < self.expected_columns => {
+                fields.resize(self.expected_columns, String::new());
+                self.counts.padded += 1;
+                return Ok(Some(fields));
+            }
+            RejectPolicy::Truncate if found > self.expected_columns => {
+                fields.truncate(self.expected_columns);
+                self.counts.truncated += 1;
+                return Ok(Some(fields));
+            }
+            _ => {}
+        }
+
+        let reason = format!("expected {} fields, found {}", self.expected_columns, found);
+        if self.policy == RejectPolicy::Fail {
+            return Err(LeafError::Custom(format!("Malformed row at line {}: {}", line, reason)));
+        }
+
+        self.write_reject(&fields, line, &reason)?;
+        self.counts.rejected += 1;
+        Ok(None)
+    }
+
+    pub fn counts(&self) -> RejectCounts {
+        self.counts
+    }
+
+    /// The quarantine file, if any row was rejected
+    pub fn rejects_path(&self) -> Option<&Path> {
+        self.writer.as_ref().and(self.rejects_path.as_deref())
+    }
+
+    /// Flush the quarantine file
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush()
+                .map_err(|e| LeafError::Custom(format!("Failed to write rejects file: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn write_reject(&mut self, fields: &[String], line: u64, reason: &str) -> Result<()> {
+        let Some(path) = &self.rejects_path else {
+            return Ok(());
+        };
+
+        let write_error = |e: csv::Error| LeafError::Custom(format!("Failed to write rejects file: {}", e));
+        if self.writer.is_none() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| LeafError::Custom(format!("Failed to create rejects folder {}: {}", dir.display(), e)))?;
+            }
+            let mut writer = csv::Writer::from_path(path).map_err(write_error)?;
+            writer.write_record(["line", "reason", "row"]).map_err(write_error)?;
+            self.writer = Some(writer);
+        }
+
+        // The row is re-quoted with the file's own delimiter so it can be pasted back into the source
+        let mut row = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .terminator(csv::Terminator::Any(b'\n'))
+            .from_writer(Vec::new());
+        row.write_record(fields).map_err(write_error)?;
+        let row = row.into_inner()
+            .map_err(|e| LeafError::Custom(format!("Failed to write rejects file: {}", e)))?;
+        let row = String::from_utf8_lossy(&row);
+
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write_record([line.to_string().as_str(), reason, row.trim_end_matches('\n')])
+                .map_err(write_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Folder in a project where imports write their quarantine CSVs, so they are never taken for tables
+pub const REJECTS_DIR: &str = "rejects";
+
+/// The quarantine CSV for a table imported into `output_dir`: `rejects/<table_name>_rejects.csv`
+pub fn rejects_path_for(output_dir: &Path, table_name: &str) -> PathBuf {
+    output_dir.join(REJECTS_DIR).join(format!("{}_rejects.csv", table_name))
+}
+
+/// Whether a file is a quarantine CSV written by an import, i.e. a `*_rejects.csv` in a `rejects` folder.
+/// A user's own file that happens to end in `_rejects.csv` is not one.
+pub fn is_rejects_file(path: &Path) -> bool {
+    let in_rejects_dir = path.parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|dir| dir == REJECTS_DIR);
+    in_rejects_dir && path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with("_rejects.csv"))
+}
+
+/// Settings for turning a CSV file into Arrow record batches
+#[derive(Debug, Clone)]
+pub struct CsvIngestOptions {
+    pub delimiter: char,
+    /// Zero-based record number of the header row; records above it are skipped
+    pub header_row: usize,
+    /// Whether that row names the columns; when `false` it is the first data row and the columns
+    /// are named `col_0`, `col_1`, …
+    pub has_header: bool,
+    /// Trim whitespace around every field; off by default so values are kept exactly
+    pub trim: bool,
+    /// Values read as null in every column
+    pub null_values: Vec<String>,
+    /// Extra null tokens for single columns (e.g. `-999` in a sensor column), indexed like the columns
+    pub column_null_values: Vec<Vec<String>>,
+    /// Words read as true/false in each column; `BooleanVocabulary::default()` where `None` or missing
+    pub boolean_vocabularies: Vec<Option<BooleanVocabulary>>,
+    /// Number of data rows used for type inference
+    pub sample_size: usize,
+    /// Rows per record batch; bounds the memory used while importing
+    pub batch_size: usize,
+    /// Character encoding of the file; detected from its contents when `None`
+    pub encoding: Option<&'static Encoding>,
+    /// What to do with rows whose field count doesn't match the header
+    pub reject_policy: RejectPolicy,
+    /// Where rejected rows are written; they are only counted when `None`
+    pub rejects_path: Option<PathBuf>,
+    /// Column types to use instead of inferring them from the sample, one per column
+    pub column_types: Option<Vec<ColumnType>>,
+    /// What to do with values that can't be parsed as their column's type
+    pub coercion_policy: CoercionPolicy,
+    /// Decimal and thousands separators used by numeric columns
+    pub number_format: NumberFormat,
+    /// strftime pattern for each Date/DateTime column; built-in layouts are tried where `None` or missing
+    pub datetime_patterns: Vec<Option<String>>,
+    /// Timezone (`Europe/Berlin`, `+02:00`) for DateTime columns: values without an offset are read
+    /// in it and the column is stored tagged with it. Naive columns stay naive when `None`.
+    pub timezone: Option<String>,
+    /// Read the file as fixed-width columns starting at these character positions (after the first,
+    /// which starts at 0) instead of splitting it on `delimiter`
+    pub column_boundaries: Option<Vec<usize>>,
+}
+
+impl CsvIngestOptions {
+    /// Null tokens and true/false words for each of `columns` columns
+    pub fn column_tokens(&self, columns: usize) -> Vec<ColumnTokens> {
+        (0..columns)
+            .map(|col_idx| ColumnTokens::new(
+                &self.null_values,
+                self.column_null_values.get(col_idx).map_or(&[], Vec::as_slice),
+                self.boolean_vocabularies.get(col_idx).cloned().flatten().unwrap_or_default(),
+            ))
+            .collect()
+    }
+}
+
+impl Default for CsvIngestOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header_row: 0,
+            has_header: true,
+            trim: false,
+            null_values: DEFAULT_NULL_VALUES.iter().map(|value| value.to_string()).collect(),
+            column_null_values: Vec::new(),
+            boolean_vocabularies: Vec::new(),
+            sample_size: 1000,
+            batch_size: 64 * 1024,
+            encoding: None,
+            reject_policy: RejectPolicy::default(),
+            rejects_path: None,
+            column_types: None,
+            coercion_policy: CoercionPolicy::default(),
+            number_format: NumberFormat::default(),
+            datetime_patterns: Vec::new(),
+            timezone: None,
+            column_boundaries: None,
+        }
+    }
+}
+
+/// How far an import has got through its source file
+#[derive(Debug, Clone, Copy)]
+pub struct IngestProgress {
+    pub bytes_read: u64,
+    pub total_bytes: u64,
+    pub rows: usize,
+}
+
+impl IngestProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            return 1.0;
+        }
+        (self.bytes_read as f64 / self.total_bytes as f64).min(1.0) as f32
+    }
+}
+
+/// Result of writing a CSV file to a table file
+#[derive(Debug, Clone)]
+pub struct CsvIngestSummary {
+    pub output_path: PathBuf,
+    pub schema: SchemaRef,
+    pub rows: usize,
+    pub delimiter: char,
+    pub encoding: &'static Encoding,
+    pub rejects: RejectCounts,
+    /// The quarantine CSV, if any rows were rejected
+    pub rejects_path: Option<PathBuf>,
+    /// Values that couldn't be parsed and were stored as null
+    pub coercion: CoercionReport,
+}
+
+/// Reads a CSV file (or any other `RecordSource`) as a sequence of fixed-size record batches.
+/// Only the inference sample and one batch of rows are held in memory at a time.
+pub struct CsvBatchReader {
+    records: Box<dyn RecordSource>,
+    encoding: &'static Encoding,
+    headers: Vec<String>,
+    column_types: Vec<ColumnType>,
+    schema: SchemaRef,
+    time_columns: Vec<usize>,
+    delimiter: char,
+    validator: RowValidator,
+    coercion_policy: CoercionPolicy,
+    coercion: CoercionReport,
+    number_format: NumberFormat,
+    datetime_patterns: Vec<Option<String>>,
+    tokens: Vec<ColumnTokens>,
+    batch_size: usize,
+    // Rows read ahead for type inference (with their line numbers), returned before the rest of the file
+    buffered_rows: VecDeque<(u64, Vec<String>)>,
+    rows_read: usize,
+    finished: bool,
+}
+
+impl CsvBatchReader {
+    pub fn open(csv_path: &Path, options: &CsvIngestOptions) -> Result<Self> {
+        // Detect the encoding once so the delimiter sniffing and the import agree
+        let encoding = match options.encoding {
+            Some(encoding) => encoding,
+            None => text_encoding::detect_encoding(csv_path)?,
+        };
+
+        if let Some(boundaries) = &options.column_boundaries {
+            let records = FixedWidthRecords::open(csv_path, boundaries, Some(encoding))?;
+            return Self::from_records(Box::new(records), options.delimiter, encoding, options);
+        }
+
+        // Infer delimiter from header if not already specified
+        let delimiter = if options.delimiter == ',' {
+            csv_tokenizer::infer_delimiter(csv_path, options.header_row, Some(encoding))?
+        } else {
+            options.delimiter
+        };
+
+        let tokenizer = CsvTokenizer::from_path(csv_path, delimiter, options.trim, Some(encoding))?;
+        Self::from_records(Box::new(tokenizer), delimiter, encoding, options)
+    }
+
+    /// Read records that are already split into fields, such as a spreadsheet's rows, with the same
+    /// header handling, type inference and conversion as a CSV file. `delimiter` is used for the rejects file.
+    pub(crate) fn from_records(
+        mut records: Box<dyn RecordSource>,
+        delimiter: char,
+        encoding: &'static Encoding,
+        options: &CsvIngestOptions,
+    ) -> Result<Self> {
+        // Skip the records above the header row (numbered the same way as the preview)
+        for _ in 0..options.header_row {
+            if records.next_record()?.is_none() {
+                return Err(LeafError::Custom("Header row exceeds file length".to_string()));
+            }
+        }
+
+        let header = records.next_record()?
+            .ok_or_else(|| LeafError::Custom("No header row found".to_string()))?;
+        let (headers, first_row) = if options.has_header {
+            (Self::deduplicate_headers(header.fields), None)
+        } else {
+            ((0..header.fields.len()).map(|i| format!("col_{}", i)).collect(), Some(header))
+        };
+        let validator = RowValidator::new(options.reject_policy, headers.len(), delimiter, options.rejects_path.clone())?;
+        let tokens = options.column_tokens(headers.len());
+
+        let mut csv_reader = Self {
+            records,
+            encoding,
+            headers,
+            column_types: Vec::new(),
+            schema: Arc::new(Schema::empty()),
+            time_columns: Vec::new(),
+            delimiter,
+            validator,
+            coercion_policy: options.coercion_policy,
+            coercion: CoercionReport::default(),
+            number_format: options.number_format,
+            datetime_patterns: options.datetime_patterns.clone(),
+            tokens,
+            batch_size: options.batch_size.max(1),
+            buffered_rows: VecDeque::new(),
+            rows_read: 0,
+            finished: false,
+        };
+
+        // Read ahead a sample of data rows and infer the schema from it
+        if let Some(record) = first_row {
+            csv_reader.buffered_rows.push_back((record.line, record.fields));
+        }
+        while csv_reader.buffered_rows.len() < options.sample_size.max(1) {
+            match csv_reader.read_row()? {
+                Some(row) => csv_reader.buffered_rows.push_back(row),
+                None => break,
+            }
+        }
+
+        if let Some(timezone) = &options.timezone {
+            if datetime_format::parse_timezone(timezone).is_none() {
+                return Err(LeafError::Custom(format!("Unknown timezone '{}'", timezone)));
+            }
+        }
+
+        let mut inferred_types: Vec<(String, ColumnType)> = match &options.column_types {
+            Some(column_types) => {
+                if column_types.len() != csv_reader.headers.len() {
+                    return Err(LeafError::Custom(format!(
+                        "{} column types were given but the file has {} columns",
+                        column_types.len(), csv_reader.headers.len()
+                    )));
+                }
+                csv_reader.headers.iter().cloned().zip(column_types.iter().cloned()).collect()
+            }
+            None => {
+                let sample_data: Vec<Vec<String>> = csv_reader.buffered_rows.iter().map(|(_, row)| row.clone()).collect();
+                TypeInferrer::infer_column_types_with_tokens(&csv_reader.headers, &sample_data, &csv_reader.tokens, &options.number_format)
+            }
+        };
+        if options.column_types.is_none() {
+            csv_reader.apply_datetime_patterns(&mut inferred_types);
+        }
+        csv_reader.schema = csv_reader.schema_from_types(&inferred_types, options.timezone.as_deref());
+        csv_reader.column_types = inferred_types.iter().map(|(_, col_type)| col_type.clone()).collect();
+        csv_reader.time_columns = inferred_types.iter()
+            .enumerate()
+            .filter(|(_, (_, col_type))| col_type.is_time_type())
+            .map(|(idx, _)| idx)
+            .collect();
+
+        Ok(csv_reader)
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Column types in use, inferred or given in the options
+    pub fn column_types(&self) -> Vec<ColumnType> {
+        self.column_types.clone()
+    }
+
+    pub fn delimiter(&self) -> char {
+        self.delimiter
+    }
+
+    /// Column names from the header row, made unique
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    pub fn rejects(&self) -> RejectCounts {
+        self.validator.counts()
+    }
+
+    /// Values so far that couldn't be parsed as their column's type
+    pub fn coercion(&self) -> &CoercionReport {
+        &self.coercion
+    }
+
+    /// Flush the rejects file and return its path, if any rows were rejected
+    pub fn finish_rejects(&mut self) -> Result<Option<PathBuf>> {
+        self.validator.finish()?;
+        Ok(self.validator.rejects_path().map(Path::to_path_buf))
+    }
+
+    pub fn progress(&self) -> IngestProgress {
+        IngestProgress {
+            bytes_read: self.records.bytes_read(),
+            total_bytes: self.records.total_bytes(),
+            rows: self.rows_read,
+        }
+    }
+
+    /// Read the next batch of up to `batch_size` rows, or `None` at the end of the file
+    pub fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let capacity = self.batch_size.min(self.buffered_rows.len().max(1024));
+        let mut rows = Vec::with_capacity(capacity);
+        let mut lines = Vec::with_capacity(capacity);
+        while rows.len() < self.batch_size {
+            let next = match self.buffered_rows.pop_front() {
+                Some(row) => Some(row),
+                None => self.read_row()?,
+            };
+            match next {
+                Some((line, row)) => {
+                    lines.push(line);
+                    rows.push(row);
+                }
+                None => break,
+            }
+        }
+
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        self.normalize_time_columns(&mut rows);
+        self.rows_read += rows.len();
+
+        let mut invalid = Vec::new();
+        let arrays = Database::string_rows_to_arrow_arrays_checked(&self.headers, &rows, &self.schema, &self.number_format, &self.datetime_patterns, &self.tokens, |col_idx, row_idx| {
+            invalid.push((col_idx, row_idx));
+        })?;
+        for (col_idx, row_idx) in invalid {
+            let field = self.schema.field(col_idx);
+            let value = &rows[row_idx][col_idx];
+            if self.coercion_policy == CoercionPolicy::Fail {
+                return Err(LeafError::Custom(format!(
+                    "Value \"{}\" at line {} can't be read as {} for column '{}'",
+                    value, lines[row_idx], field.data_type(), field.name()
+                )));
+            }
+            self.coercion.record(col_idx, field.name(), field.data_type(), lines[row_idx], value);
+        }
+
+        let batch = RecordBatch::try_new(self.schema.clone(), arrays)
+            .map_err(|e| LeafError::Custom(format!("Failed to create record batch: {}", e)))?;
+
+        Ok(Some(batch))
+    }
+
+    /// Next data row as text, without converting it to the column types
+    fn next_row(&mut self) -> Result<Option<Vec<String>>> {
+        let next = match self.buffered_rows.pop_front() {
+            Some(row) => Some(row),
+            None => self.read_row()?,
+        };
+        if next.is_some() {
+            self.rows_read += 1;
+        }
+        Ok(next.map(|(_, row)| row))
+    }
+
+    /// Next data row with the expected number of fields. Blank and comment lines are skipped;
+    /// rows with the wrong number of fields are handled by the reject policy.
+    fn read_row(&mut self) -> Result<Option<(u64, Vec<String>)>> {
+        while !self.finished {
+            match self.records.next_record()? {
+                Some(record) => {
+                    if csv_tokenizer::is_blank_or_comment(&record.fields) {
+                        continue;
+                    }
+                    if let Some(fields) = self.validator.check(record.fields, record.line)? {
+                        return Ok(Some((record.line, fields)));
+                    }
+                }
+                None => self.finished = true,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Deduplicate column names to avoid DataFusion errors
+    fn deduplicate_headers(headers: Vec<String>) -> Vec<String> {
+        let mut seen_names = HashSet::new();
+        let mut deduplicated_headers = Vec::new();
+
+        for header in headers {
+            let mut unique_name = header.clone();
+            let mut counter = 1;
+
+            while seen_names.contains(&unique_name) {
+                unique_name = format!("{}_{}", header, counter);
+                counter += 1;
+            }
+
+            seen_names.insert(unique_name.clone());
+            deduplicated_headers.push(unique_name);
+        }
+
+        deduplicated_headers
+    }
+
+    fn datetime_pattern(&self, col_idx: usize) -> Option<&str> {
+        self.datetime_patterns.get(col_idx).and_then(|pattern| pattern.as_deref())
+    }
+
+    /// Whether the sample has a value in the column; an empty column's inferred type says nothing
+    pub(crate) fn has_sample_values(&self, col_idx: usize) -> bool {
+        self.sample_values(col_idx).next().is_some()
+    }
+
+    /// Sampled values of a column, leaving out its null tokens
+    fn sample_values(&self, col_idx: usize) -> impl Iterator<Item = &str> {
+        let tokens = &self.tokens[col_idx];
+        self.buffered_rows.iter()
+            .filter_map(move |(_, row)| row.get(col_idx).map(String::as_str))
+            .filter(move |value| !tokens.is_null(value))
+    }
+
+    /// Inferred text columns whose sample values all match their pattern become Date or DateTime
+    fn apply_datetime_patterns(&self, inferred_types: &mut [(String, ColumnType)]) {
+        for (col_idx, (_, col_type)) in inferred_types.iter_mut().enumerate() {
+            let Some(pattern) = self.datetime_pattern(col_idx) else { continue };
+            if matches!(col_type, ColumnType::Text | ColumnType::Dictionary) {
+                if let Some(pattern_type) = datetime_format::pattern_column_type(pattern, self.sample_values(col_idx)) {
+                    *col_type = pattern_type;
+                }
+            }
+        }
+    }
+
+    /// Arrow schema for the column types; DateTime columns get the precision and timezone seen in the sample
+    fn schema_from_types(&self, inferred_types: &[(String, ColumnType)], timezone: Option<&str>) -> SchemaRef {
+        let fields: Vec<Field> = inferred_types.iter()
+            .enumerate()
+            .map(|(col_idx, (name, col_type))| {
+                let data_type = match col_type {
+                    ColumnType::DateTime => {
+                        let pattern = self.datetime_pattern(col_idx).map(String::from);
+                        DateTimeFormat::infer(pattern, self.sample_values(col_idx)).arrow_type(timezone)
+                    }
+                    // Nanosecond times of day only when the sample writes more than six fraction digits
+                    ColumnType::TimeOfDay if self.sample_values(col_idx).any(|value| datetime_format::fraction_digits(value) > 6) => {
+                        DataType::Time64(TimeUnit::Nanosecond)
+                    }
+                    _ => col_type.to_arrow_type(),
+                };
+                Field::new(name, data_type, true)
+            })
+            .collect();
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Apply time normalization to columns that are detected as time types
+    fn normalize_time_columns(&self, rows: &mut [Vec<String>]) {
+        for &col_idx in &self.time_columns {
+            let column_values: Vec<String> = rows.iter()
+                .map(|row| row.get(col_idx).cloned().unwrap_or_default())
+                .collect();
+
+            let normalized_values = Database::normalize_time_column(&column_values);
+
+            for (row, normalized_value) in rows.iter_mut().zip(normalized_values) {
+                if col_idx < row.len() {
+                    row[col_idx] = normalized_value;
+                }
+            }
+        }
+    }
+}
+
+/// Stream a CSV file into an Arrow IPC or Parquet file one batch at a time.
+/// The output is written under a temporary name and only renamed into place once complete,
+/// so a failed import never leaves a truncated table in the project folder.
+pub fn ingest_csv_to_file(
+    csv_path: &Path,
+    output_path: &Path,
+    format: TableFormat,
+    options: &CsvIngestOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<CsvIngestSummary> {
+    ingest_reader_to_file(|options| CsvBatchReader::open(csv_path, options), output_path, format, options, on_progress)
+}
+
+/// `ingest_csv_to_file` for any input `open` can read; it is called again for each pass over the input
+pub(crate) fn ingest_reader_to_file(
+    open: impl Fn(&CsvIngestOptions) -> Result<CsvBatchReader>,
+    output_path: &Path,
+    format: TableFormat,
+    options: &CsvIngestOptions,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<CsvIngestSummary> {
+    // Columns can only be switched to Text before anything is written, so check every value first
+    let fallback_options;
+    let options = if options.coercion_policy == CoercionPolicy::FallBackToText {
+        fallback_options = options_with_text_fallback(&open, options)?;
+        &fallback_options
+    } else {
+        options
+    };
+
+    let mut reader = open(options)?;
+    let schema = reader.schema();
+
+    let partial_path = output_path.with_extension(format!("{}.partial", format.extension()));
+    let result = (|| {
+        let mut writer = TableWriter::try_new(&partial_path, &schema, format)?;
+        while let Some(batch) = reader.next_batch()? {
+            writer.write(&batch)?;
+            on_progress(&reader.progress());
+        }
+        writer.finish()
+    })();
+
+    let rejects_path = reader.finish_rejects();
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&partial_path);
+        return Err(e);
+    }
+    let rejects_path = rejects_path?;
+
+    std::fs::rename(&partial_path, output_path)
+        .map_err(|e| LeafError::Custom(format!("Failed to move imported table into place: {}", e)))?;
+
+    Ok(CsvIngestSummary {
+        output_path: output_path.to_path_buf(),
+        schema,
+        rows: reader.progress().rows,
+        delimiter: reader.delimiter(),
+        encoding: reader.encoding(),
+        rejects: reader.rejects(),
+        rejects_path,
+        coercion: reader.coercion().clone(),
+    })
+}
+
+/// Read the whole file and report every value that can't be parsed as its column's type,
+/// without writing anything. Rows are checked exactly as `ingest_csv_to_file` would import them.
+pub fn check_coercion(
+    csv_path: &Path,
+    options: &CsvIngestOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<CoercionReport> {
+    check_reader_coercion(|options| CsvBatchReader::open(csv_path, options), options, on_progress)
+}
+
+/// `check_coercion` for any input `open` can read
+pub(crate) fn check_reader_coercion(
+    open: impl Fn(&CsvIngestOptions) -> Result<CsvBatchReader>,
+    options: &CsvIngestOptions,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<CoercionReport> {
+    let options = CsvIngestOptions {
+        rejects_path: None,
+        coercion_policy: CoercionPolicy::SetNull,
+        ..options.clone()
+    };
+    let mut reader = open(&options)?;
+    while reader.next_batch()?.is_some() {
+        on_progress(&reader.progress());
+    }
+    Ok(reader.coercion().clone())
+}
+
+/// Infer column types from every row of the file rather than the sample, with a confidence score
+/// and reason per column. Having seen every value, numbers get the narrowest type that holds them all.
+/// Nothing is written; `on_progress` is called every `batch_size` rows.
+pub fn infer_full_file(
+    csv_path: &Path,
+    options: &CsvIngestOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<Vec<ColumnInference>> {
+    infer_reader_full(|options| CsvBatchReader::open(csv_path, options), options, on_progress)
+}
+
+/// `infer_full_file` for any input `open` can read
+pub(crate) fn infer_reader_full(
+    open: impl Fn(&CsvIngestOptions) -> Result<CsvBatchReader>,
+    options: &CsvIngestOptions,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<Vec<ColumnInference>> {
+    let options = CsvIngestOptions {
+        rejects_path: None,
+        sample_size: 1,
+        column_types: None,
+        ..options.clone()
+    };
+    let mut reader = open(&options)?;
+    let mut profiles = TypeInferrer::column_profiles(&reader.headers, &reader.tokens, &options.number_format, &options.datetime_patterns);
+    let progress_interval = options.batch_size.max(1);
+    while let Some(row) = reader.next_row()? {
+        for (profile, value) in profiles.iter_mut().zip(&row) {
+            profile.observe(value);
+        }
+        if reader.rows_read % progress_interval == 0 {
+            on_progress(&reader.progress());
+        }
+    }
+    on_progress(&reader.progress());
+    Ok(profiles.iter().map(ColumnProfile::finish_whole_file).collect())
+}
+
+/// How a CSV file's columns line up with an existing table's, matched by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaReconciliation {
+    /// Table columns the file doesn't have; their appended rows are null
+    pub missing: Vec<String>,
+    /// File columns the table doesn't have
+    pub extra: Vec<String>,
+    /// Extra columns added to the table as nullable columns, null in its existing rows
+    pub added: Vec<String>,
+    // For each column of the combined table, the file column it is read from
+    sources: Vec<Option<usize>>,
+}
+
+impl SchemaReconciliation {
+    /// Match `headers` to the table's columns: exact names first, then ignoring case and surrounding spaces
+    pub fn new(table_schema: &Schema, headers: &[String], add_new_columns: bool) -> Self {
+        let mut used = vec![false; headers.len()];
+        let mut reconciliation = Self::default();
+        for field in table_schema.fields() {
+            let name = field.name();
+            let source = headers.iter().enumerate()
+                .position(|(idx, header)| !used[idx] && header == name)
+                .or_else(|| headers.iter().enumerate()
+                    .position(|(idx, header)| !used[idx] && header.trim().eq_ignore_ascii_case(name.trim())));
+            match source {
+                Some(idx) => used[idx] = true,
+                None => reconciliation.missing.push(name.clone()),
+            }
+            reconciliation.sources.push(source);
+        }
+        for (idx, header) in headers.iter().enumerate().filter(|(idx, _)| !used[*idx]) {
+            reconciliation.extra.push(header.clone());
+            if add_new_columns {
+                reconciliation.added.push(header.clone());
+                reconciliation.sources.push(Some(idx));
+            }
+        }
+        reconciliation
+    }
+
+    /// Whether the file has exactly the table's columns
+    pub fn is_exact(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+
+    /// One line for the import dialog, e.g. `Missing (filled with nulls): region; Extra (left out): note`
+    pub fn describe(&self) -> String {
+        if self.is_exact() {
+            return "All columns match the table".to_string();
+        }
+        let mut parts = Vec::new();
+        if !self.missing.is_empty() {
+            parts.push(format!("Missing (filled with nulls): {}", self.missing.join(", ")));
+        }
+        let left_out: Vec<&String> = self.extra.iter().filter(|name| !self.added.contains(name)).collect();
+        if !self.added.is_empty() {
+            parts.push(format!("New (added to the table): {}", self.added.join(", ")));
+        }
+        if !left_out.is_empty() {
+            parts.push(format!("Extra (left out): {}", left_out.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(", ")));
+        }
+        parts.join("; ")
+    }
+
+    /// A batch from the reader `open_for_append` returned, laid out as `schema` (the `combined_schema`):
+    /// missing columns are null and extra ones dropped
+    pub(crate) fn arrange(&self, batch: &RecordBatch, schema: &SchemaRef) -> Result<RecordBatch> {
+        let arrays = self.sources.iter()
+            .zip(schema.fields())
+            .map(|(source, field)| match source {
+                Some(idx) => batch.column(*idx).clone(),
+                None => new_null_array(field.data_type(), batch.num_rows()),
+            })
+            .collect();
+        Ok(RecordBatch::try_new(schema.clone(), arrays)?)
+    }
+
+    /// The table's schema with the added columns after its own; every column is nullable
+    pub(crate) fn combined_schema(&self, table_schema: &Schema, file_schema: &Schema) -> SchemaRef {
+        let table_fields = table_schema.fields().iter().map(|field| field.as_ref().clone());
+        let added_fields = self.sources[table_schema.fields().len()..].iter()
+            .flatten()
+            .map(|&idx| file_schema.field(idx).clone());
+        let fields: Vec<Field> = table_fields.chain(added_fields)
+            .map(|field| field.with_nullable(true))
+            .collect();
+        Arc::new(Schema::new_with_metadata(fields, table_schema.metadata().clone()))
+    }
+}
+
+/// Result of appending a CSV file to an existing table file
+#[derive(Debug, Clone)]
+pub struct CsvAppendSummary {
+    /// The appended rows; `schema` is the combined table's
+    pub ingest: CsvIngestSummary,
+    /// Rows the table had before
+    pub existing_rows: usize,
+    pub columns: SchemaReconciliation,
+}
+
+/// Open a CSV to be appended to a table with `table_schema`: matched columns are read as the table's
+/// types, added columns keep their own, and columns left out are read as text so they can't fail.
+/// The table's columns keep their types, so `FallBackToText` sets unparseable values to null like `SetNull`.
+pub(crate) fn open_for_append(
+    csv_path: &Path,
+    table_schema: &Schema,
+    options: &CsvIngestOptions,
+    add_new_columns: bool,
+) -> Result<(CsvBatchReader, SchemaReconciliation)> {
+    let options = CsvIngestOptions {
+        coercion_policy: match options.coercion_policy {
+            CoercionPolicy::FallBackToText => CoercionPolicy::SetNull,
+            policy => policy,
+        },
+        ..options.clone()
+    };
+    let mut reader = CsvBatchReader::open(csv_path, &options)?;
+    let columns = SchemaReconciliation::new(table_schema, &reader.headers, add_new_columns);
+
+    let mut fields: Vec<Field> = reader.headers.iter()
+        .map(|header| Field::new(header, DataType::Utf8, true))
+        .collect();
+    for (table_idx, source) in columns.sources.iter().enumerate() {
+        let Some(idx) = *source else { continue };
+        fields[idx] = match table_schema.fields().get(table_idx) {
+            Some(field) => Field::new(field.name(), field.data_type().clone(), true),
+            None => reader.schema.field(idx).clone(),
+        };
+    }
+
+    // The file's own inference only holds for columns still read as the type it found
+    reader.column_types = fields.iter()
+        .zip(reader.schema.fields())
+        .zip(&reader.column_types)
+        .map(|((field, inferred), col_type)| if field.data_type() == inferred.data_type() {
+            col_type.clone()
+        } else {
+            ColumnType::from_arrow_type(field.data_type())
+        })
+        .collect();
+    reader.time_columns = reader.column_types.iter()
+        .enumerate()
+        .filter(|(_, col_type)| col_type.is_time_type())
+        .map(|(idx, _)| idx)
+        .collect();
+    reader.schema = Arc::new(Schema::new(fields));
+    Ok((reader, columns))
+}
+
+/// Append a CSV file to the table in `table_path`, writing the combined table to `output_path`
+/// (which may be `table_path` itself). Columns are matched by name and values read as the table's
+/// types; table columns the file lacks are null, and the file's extra columns are added as nullable
+/// columns when `add_new_columns` is set and left out otherwise. The existing rows are copied a batch
+/// at a time ahead of the new ones, and the result only replaces `output_path` once complete.
+pub fn append_csv_to_file(
+    csv_path: &Path,
+    table_path: &Path,
+    output_path: &Path,
+    options: &CsvIngestOptions,
+    add_new_columns: bool,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<CsvAppendSummary> {
+    let format = TableFormat::from_path(output_path)
+        .ok_or_else(|| LeafError::Custom(format!("Unsupported table file: {}", output_path.display())))?;
+    let table = TableReader::open(table_path)?;
+    let table_schema = table.schema();
+    let (mut reader, columns) = open_for_append(csv_path, &table_schema, options, add_new_columns)?;
+    let schema = columns.combined_schema(&table_schema, &reader.schema);
+
+    let partial_path = output_path.with_extension(format!("{}.partial", format.extension()));
+    let mut existing_rows = 0;
+    let result = (|| {
+        let mut writer = TableWriter::try_new(&partial_path, &schema, format)?;
+        for batch in table {
+            let batch = batch?;
+            existing_rows += batch.num_rows();
+            let mut arrays = batch.columns().to_vec();
+            arrays.extend(schema.fields()[arrays.len()..].iter().map(|field| new_null_array(field.data_type(), batch.num_rows())));
+            writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+        }
+        while let Some(batch) = reader.next_batch()? {
+            writer.write(&columns.arrange(&batch, &schema)?)?;
+            on_progress(&reader.progress());
+        }
+        writer.finish()
+    })();
+
+    let rejects_path = reader.finish_rejects();
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&partial_path);
+        return Err(e);
+    }
+    let rejects_path = rejects_path?;
+
+    std::fs::rename(&partial_path, output_path)
+        .map_err(|e| LeafError::Custom(format!("Failed to move appended table into place: {}", e)))?;
+
+    Ok(CsvAppendSummary {
+        ingest: CsvIngestSummary {
+            output_path: output_path.to_path_buf(),
+            schema,
+            rows: reader.progress().rows,
+            delimiter: reader.delimiter(),
+            encoding: reader.encoding(),
+            rejects: reader.rejects(),
+            rejects_path,
+            coercion: reader.coercion().clone(),
+        },
+        existing_rows,
+        columns,
+    })
+}
+
+/// Like `check_coercion`, but against the types of the table in `table_path` the file would be appended to
+pub fn check_append_coercion(
+    csv_path: &Path,
+    table_path: &Path,
+    options: &CsvIngestOptions,
+    add_new_columns: bool,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<CoercionReport> {
+    let table_schema = TableReader::open(table_path)?.schema();
+    let options = CsvIngestOptions {
+        rejects_path: None,
+        coercion_policy: CoercionPolicy::SetNull,
+        ..options.clone()
+    };
+    let (mut reader, _) = open_for_append(csv_path, &table_schema, &options, add_new_columns)?;
+    while reader.next_batch()?.is_some() {
+        on_progress(&reader.progress());
+    }
+    Ok(reader.coercion().clone())
+}
+
+/// Options that import every column with an unparseable value as Text
+fn options_with_text_fallback(
+    open: &impl Fn(&CsvIngestOptions) -> Result<CsvBatchReader>,
+    options: &CsvIngestOptions,
+) -> Result<CsvIngestOptions> {
+    let reader = open(options)?;
+    let mut column_types: Vec<ColumnType> = match &options.column_types {
+        Some(column_types) => column_types.clone(),
+        None => reader.column_types(),
+    };
+    drop(reader);
+
+    let report = check_reader_coercion(open, options, |_| {})?;
+    for col_idx in report.column_indexes() {
+        column_types[col_idx] = ColumnType::Text;
+    }
+
+    Ok(CsvIngestOptions {
+        column_types: Some(column_types),
+        coercion_policy: CoercionPolicy::SetNull,
+        ..options.clone()
+    })
+}
@@ -0,0 +1,256 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use encoding_rs::Encoding;
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use flate2::read::{DeflateDecoder, MultiGzDecoder};
+use zip::{CompressionMethod, ZipArchive};
+use crate::core::error::{Result, LeafError};
+use crate::core::text_encoding;
+
+/// Extensions offered by the CSV file pickers, including compressed inputs
+pub const PICKER_EXTENSIONS: [&str; 6] = ["csv", "tsv", "txt", "gz", "zst", "zip"];
+
+/// Extensions of the text files picked out of a zip archive
+const ARCHIVE_MEMBER_EXTENSIONS: [&str; 3] = ["csv", "tsv", "txt"];
+
+/// How a CSV input is stored on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    /// A member of a zip archive; the input path is `<archive>.zip/<member name>`
+    ZipMember { archive: PathBuf, member: String },
+}
+
+impl Compression {
+    /// Work out the compression from the file name
+    pub fn from_path(path: &Path) -> Self {
+        if let Some((archive, member)) = split_archive_member(path) {
+            return Compression::ZipMember { archive, member };
+        }
+
+        match extension(path).as_deref() {
+            Some("gz") | Some("gzip") => Compression::Gzip,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Counts the bytes pulled from the file on disk, before any decompression or decoding
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// A CSV input opened for parsing: decompressed and transcoded to UTF-8 as it is read.
+/// Plain files, `.gz`, `.zst` and members of `.zip` archives are all read as streams,
+/// so compressed inputs are never unpacked to disk.
+pub struct CsvSource {
+    reader: Box<dyn Read + Send>,
+    bytes_read: Arc<AtomicU64>,
+    total_bytes: u64,
+}
+
+impl CsvSource {
+    /// Open an input, decoding it from `encoding` or from the detected encoding when `None`.
+    /// A byte order mark in the file always takes precedence and is stripped.
+    pub fn open(path: &Path, encoding: Option<&'static Encoding>) -> Result<Self> {
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => text_encoding::detect_encoding(path)?,
+        };
+
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let (decompressed, total_bytes) = open_decompressed(path, Some(bytes_read.clone()))?;
+
+        let reader: Box<dyn Read + Send> = Box::new(
+            DecodeReaderBytesBuilder::new()
+                .encoding(Some(encoding))
+                .build(decompressed)
+        );
+
+        Ok(Self {
+            reader,
+            bytes_read,
+            total_bytes,
+        })
+    }
+
+    /// Bytes of the file read so far (compressed bytes for compressed inputs)
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Size of the input on disk (compressed size for compressed inputs)
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+}
+
+impl Read for CsvSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+/// Open an input as a stream of decompressed (but not yet decoded) bytes, along with its size on disk.
+/// When `counter` is given it is advanced by the number of bytes read from disk.
+pub fn open_decompressed(path: &Path, counter: Option<Arc<AtomicU64>>) -> Result<(Box<dyn Read + Send>, u64)> {
+    let counter = counter.unwrap_or_default();
+    let open_error = |e: std::io::Error| LeafError::Custom(format!("Failed to open {}: {}", path.display(), e));
+
+    match Compression::from_path(path) {
+        Compression::ZipMember { archive, member } => open_zip_member(&archive, &member, counter),
+        compression => {
+            let file = File::open(path).map_err(open_error)?;
+            let total_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+            let counting = CountingReader { inner: file, bytes_read: counter };
+
+            let reader: Box<dyn Read + Send> = match compression {
+                // Multi-member gzip files (e.g. concatenated daily logs) are read to the end
+                Compression::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(counting))),
+                Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(counting).map_err(open_error)?),
+                _ => Box::new(counting),
+            };
+            Ok((reader, total_bytes))
+        }
+    }
+}
+
+/// Stream one member of a zip archive. The member's data is read straight from the archive file
+/// so the reader does not borrow the `ZipArchive` and can be moved into the tokenizer.
+fn open_zip_member(archive_path: &Path, member: &str, counter: Arc<AtomicU64>) -> Result<(Box<dyn Read + Send>, u64)> {
+    let zip_error = |e: zip::result::ZipError| LeafError::Custom(format!("Failed to read {}: {}", archive_path.display(), e));
+    let io_error = |e: std::io::Error| LeafError::Custom(format!("Failed to read {}: {}", archive_path.display(), e));
+
+    let mut archive = ZipArchive::new(File::open(archive_path).map_err(io_error)?).map_err(zip_error)?;
+    let index = archive.index_for_name(member)
+        .ok_or_else(|| LeafError::Custom(format!("{} has no member named {}", archive_path.display(), member)))?;
+    let (data_start, compressed_size, method) = {
+        let entry = archive.by_index_raw(index).map_err(zip_error)?;
+        (entry.data_start(), entry.compressed_size(), entry.compression())
+    };
+
+    let mut file = archive.into_inner();
+    file.seek(SeekFrom::Start(data_start)).map_err(io_error)?;
+    let counting = CountingReader { inner: file.take(compressed_size), bytes_read: counter };
+
+    let reader: Box<dyn Read + Send> = match method {
+        CompressionMethod::Stored => Box::new(counting),
+        CompressionMethod::Deflated => Box::new(DeflateDecoder::new(BufReader::new(counting))),
+        other => {
+            return Err(LeafError::Custom(format!(
+                "{} in {} uses unsupported compression {}", member, archive_path.display(), other
+            )));
+        }
+    };
+    Ok((reader, compressed_size))
+}
+
+/// List the CSV-like members of a zip archive as input paths (`<archive>.zip/<member name>`)
+pub fn list_archive_members(archive_path: &Path) -> Result<Vec<PathBuf>> {
+    let file = File::open(archive_path)
+        .map_err(|e| LeafError::Custom(format!("Failed to open {}: {}", archive_path.display(), e)))?;
+    let archive = ZipArchive::new(file)
+        .map_err(|e| LeafError::Custom(format!("Failed to read {}: {}", archive_path.display(), e)))?;
+
+    let mut members: Vec<PathBuf> = archive.file_names()
+        .filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+        .filter(|name| {
+            extension(Path::new(name)).is_some_and(|ext| ARCHIVE_MEMBER_EXTENSIONS.contains(&ext.as_str()))
+        })
+        .map(|name| archive_path.join(name))
+        .collect();
+    members.sort();
+    Ok(members)
+}
+
+/// Whether a path names a zip archive (as opposed to one of its members)
+pub fn is_archive(path: &Path) -> bool {
+    extension(path).as_deref() == Some("zip") && path.is_file()
+}
+
+/// Table name for an input: the file name without its CSV and compression extensions,
+/// so `daily.csv.gz` and `logs.zip/daily.csv` both become `daily`
+pub fn table_name_for(path: &Path) -> String {
+    let mut name = path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("table")
+        .to_string();
+
+    for suffix in [".gz", ".gzip", ".zst", ".zstd"] {
+        if name.to_lowercase().ends_with(suffix) {
+            name.truncate(name.len() - suffix.len());
+            break;
+        }
+    }
+
+    match Path::new(&name).file_stem().and_then(|s| s.to_str()) {
+        Some(stem) if !stem.is_empty() => stem.to_string(),
+        _ => name,
+    }
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?` is one character
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, t));
+            p += 1;
+        } else if let Some((after_star, tried)) = star {
+            p = after_star;
+            t = tried + 1;
+            star = Some((after_star, tried + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Split `<archive>.zip/<member name>` into the archive path and the member name
+fn split_archive_member(path: &Path) -> Option<(PathBuf, String)> {
+    if path.exists() {
+        return None;
+    }
+
+    path.ancestors()
+        .skip(1)
+        .find(|ancestor| is_archive(ancestor))
+        .and_then(|archive| {
+            let member = path.strip_prefix(archive).ok()?;
+            // Zip member names always use forward slashes
+            let member = member.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some((archive.to_path_buf(), member))
+        })
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+}
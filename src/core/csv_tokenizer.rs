@@ -0,0 +1,161 @@
+use std::io::Read;
+use std::path::Path;
+use csv::{ByteRecord, Reader, ReaderBuilder, Terminator};
+use encoding_rs::Encoding;
+use crate::core::csv_source::CsvSource;
+use crate::core::error::{Result, LeafError};
+
+/// Delimiters tried when detecting a file's delimiter, in order of preference
+pub const CANDIDATE_DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+/// One record from a CSV file
+#[derive(Debug, Clone)]
+pub struct CsvRecord {
+    pub fields: Vec<String>,
+    /// 1-based line number the record starts on; quoted fields can span several lines
+    pub line: u64,
+}
+
+/// Where records come from for the preview, type inference and import: a delimited text file,
+/// or the rows of a spreadsheet or fixed-width file already split into fields
+pub trait RecordSource: Send {
+    fn next_record(&mut self) -> Result<Option<CsvRecord>>;
+    /// Progress through the input so far, in the units of `total_bytes`
+    fn bytes_read(&self) -> u64;
+    fn total_bytes(&self) -> u64;
+}
+
+/// RFC 4180 tokenizer shared by the preview, type inference and import.
+/// Handles quoted delimiters, `""` escapes and newlines inside quoted fields,
+/// and keeps field whitespace exactly as written unless `trim` is set.
+pub struct CsvTokenizer<R: Read> {
+    reader: Reader<R>,
+    record: ByteRecord,
+    trim: bool,
+}
+
+impl CsvTokenizer<CsvSource> {
+    /// Open a CSV file, transcoding it to UTF-8 from `encoding` (detected when `None`)
+    pub fn from_path(path: &Path, delimiter: char, trim: bool, encoding: Option<&'static Encoding>) -> Result<Self> {
+        let source = CsvSource::open(path, encoding)?;
+        Ok(Self::new(source, delimiter, trim))
+    }
+
+    /// The underlying file, for raw byte progress
+    pub fn source(&self) -> &CsvSource {
+        self.reader.get_ref()
+    }
+}
+
+impl RecordSource for CsvTokenizer<CsvSource> {
+    fn next_record(&mut self) -> Result<Option<CsvRecord>> {
+        CsvTokenizer::next_record(self)
+    }
+
+    fn bytes_read(&self) -> u64 {
+        self.source().bytes_read()
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.source().total_bytes()
+    }
+}
+
+impl<R: Read> CsvTokenizer<R> {
+    pub fn new(reader: R, delimiter: char, trim: bool) -> Self {
+        let reader = ReaderBuilder::new()
+            .delimiter(delimiter as u8)
+            .has_headers(false)
+            // Rows may have any number of fields; callers decide what to do with ragged rows
+            .flexible(true)
+            .double_quote(true)
+            // Split records on \n only and drop the \r of CRLF endings ourselves; the default CRLF
+            // terminator reports record line numbers one short in CRLF files
+            .terminator(Terminator::Any(b'\n'))
+            .from_reader(reader);
+
+        Self {
+            reader,
+            record: ByteRecord::new(),
+            trim,
+        }
+    }
+
+    /// Read the next record, or `None` at the end of the input
+    pub fn next_record(&mut self) -> Result<Option<CsvRecord>> {
+        let has_record = self.reader.read_byte_record(&mut self.record)
+            .map_err(|e| LeafError::Custom(format!("Failed to read CSV record: {}", e)))?;
+        if !has_record {
+            return Ok(None);
+        }
+
+        let line = self.record.position().map(|pos| pos.line()).unwrap_or(0);
+        let last_field = self.record.len().saturating_sub(1);
+        let fields = self.record.iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let field = match field {
+                    [rest @ .., b'\r'] if idx == last_field => rest,
+                    field => field,
+                };
+                let value = String::from_utf8_lossy(field);
+                if self.trim {
+                    value.trim().to_string()
+                } else {
+                    value.into_owned()
+                }
+            })
+            .collect();
+
+        Ok(Some(CsvRecord { fields, line }))
+    }
+
+    /// Bytes of (decoded) input consumed so far
+    pub fn bytes_read(&self) -> u64 {
+        self.reader.position().byte()
+    }
+}
+
+/// Detect the delimiter from the header record (the `header_row`-th record, zero-based)
+/// in the first part of a file. Picks the candidate that splits the header into the most fields.
+pub fn infer_delimiter(path: &Path, header_row: usize, encoding: Option<&'static Encoding>) -> Result<char> {
+    let mut sample = Vec::new();
+    CsvSource::open(path, encoding)?
+        .take(64 * 1024)
+        .read_to_end(&mut sample)
+        .map_err(|e| LeafError::Custom(format!("Failed to read CSV file: {}", e)))?;
+
+    Ok(infer_delimiter_from_sample(&sample, header_row))
+}
+
+pub fn infer_delimiter_from_sample(sample: &[u8], header_row: usize) -> char {
+    let mut best_delimiter = ',';
+    let mut max_fields = 0;
+
+    for delimiter in CANDIDATE_DELIMITERS {
+        let mut tokenizer = CsvTokenizer::new(sample, delimiter, false);
+        let mut header = None;
+        for _ in 0..=header_row {
+            header = tokenizer.next_record().ok().flatten();
+        }
+
+        let fields = header.map(|record| record.fields.len()).unwrap_or(0);
+        if fields > max_fields && fields > 1 {
+            max_fields = fields;
+            best_delimiter = delimiter;
+        }
+    }
+
+    best_delimiter
+}
+
+/// Whether a record is an empty line or a `#` comment
+pub fn is_blank_or_comment(fields: &[String]) -> bool {
+    // Empty lines
+    if fields.len() == 1 && fields[0].trim().is_empty() {
+        return true;
+    }
+
+    // Comment lines
+    fields.first().is_some_and(|field| field.trim_start().starts_with('#'))
+}
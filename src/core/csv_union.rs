@@ -0,0 +1,517 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use datafusion::arrow::array::StringArray;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use crate::core::coercion::{CoercionPolicy, CoercionReport};
+use crate::core::csv_ingest::{self, CsvBatchReader, CsvIngestOptions, IngestProgress, RejectCounts};
+use crate::core::csv_source::{self, wildcard_match};
+use crate::core::error::{Result, LeafError};
+use crate::core::table_format::{TableFormat, TableWriter};
+
+/// Name of the column recording which file each row came from
+pub const SOURCE_COLUMN: &str = "source_file";
+
+/// Extensions of the files a folder import picks up; zip archives are left out
+const FOLDER_EXTENSIONS: [&str; 5] = ["csv", "tsv", "txt", "gz", "zst"];
+
+/// How the files of a folder import are read and combined into one table
+#[derive(Debug, Clone)]
+pub struct UnionOptions {
+    /// Settings every file is read with; per-column ones are given for the first file's columns
+    pub csv: CsvIngestOptions,
+    /// Name of a column holding each row's file, relative to the folder the files share; none is added when `None`
+    pub source_column: Option<String>,
+    /// Files parsed at the same time. Rows still follow the file order, whatever the number of threads.
+    pub threads: usize,
+}
+
+impl Default for UnionOptions {
+    fn default() -> Self {
+        Self {
+            csv: CsvIngestOptions::default(),
+            source_column: Some(SOURCE_COLUMN.to_string()),
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()).min(8),
+        }
+    }
+}
+
+/// Outcome for one file of a folder import
+#[derive(Debug, Clone)]
+pub struct ShardSummary {
+    pub path: PathBuf,
+    pub rows: usize,
+    pub rejects: RejectCounts,
+    /// The file's quarantine CSV, `rejects/<file>_<table>_rejects.csv`, if any of its rows were rejected
+    pub rejects_path: Option<PathBuf>,
+    /// Values that couldn't be parsed as their column's type
+    pub coercion: CoercionReport,
+    /// Table columns this file doesn't have; null in its rows
+    pub missing: Vec<String>,
+}
+
+/// Result of importing many CSV files as one table
+#[derive(Debug, Clone)]
+pub struct CsvUnionSummary {
+    pub rows: usize,
+    /// Rows rejected or repaired across all files
+    pub rejects: RejectCounts,
+    /// One entry per file, in the order they were given
+    pub files: Vec<ShardSummary>,
+}
+
+/// A file of a folder import with the options it is read with
+struct Shard {
+    path: PathBuf,
+    options: CsvIngestOptions,
+    /// Value of the source column for its rows
+    source: String,
+}
+
+/// What one file's sample says about its columns
+struct ShardColumns {
+    headers: Vec<String>,
+    /// Type per column, `None` where the sample has no values to infer one from
+    types: Vec<Option<DataType>>,
+}
+
+enum ShardEvent {
+    Batch { batch: RecordBatch, progress: IngestProgress },
+    Done { summary: ShardSummary },
+}
+
+/// Batches a file may read ahead of the writer; files waiting for their turn stop there, which bounds memory
+const BATCHES_AHEAD: usize = 4;
+
+/// The files a folder import reads, sorted by path: the CSV files in a folder, or the files matching a
+/// pattern such as `logs/2026-*.csv`. `*` and `?` may appear in any part of the path and match ignoring case.
+pub fn expand_pattern(pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.trim();
+    let path = Path::new(pattern);
+    let mut files: Vec<PathBuf> = if path.is_dir() {
+        read_dir_names(path)
+            .filter(|file| is_folder_file(file))
+            .collect()
+    } else {
+        let mut matches = vec![PathBuf::new()];
+        for component in path.components() {
+            let part = component.as_os_str().to_string_lossy().to_lowercase();
+            if !part.contains(['*', '?']) {
+                matches.iter_mut().for_each(|dir| dir.push(component));
+                continue;
+            }
+            matches = matches.iter()
+                .flat_map(|dir| read_dir_names(if dir.as_os_str().is_empty() { Path::new(".") } else { dir }))
+                .filter(|entry| entry.file_name().is_some_and(|name| wildcard_match(&part, &name.to_string_lossy().to_lowercase())))
+                .map(|entry| entry.strip_prefix(".").map(Path::to_path_buf).unwrap_or(entry))
+                .collect();
+        }
+        matches.into_iter()
+            .filter(|file| file.is_file() && !csv_ingest::is_rejects_file(file))
+            .collect()
+    };
+    if files.is_empty() {
+        return Err(LeafError::Custom(format!("No CSV files match '{}'", pattern)));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Entries of a folder, skipping hidden ones; an unreadable folder has none
+fn read_dir_names(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+}
+
+fn is_folder_file(path: &Path) -> bool {
+    let extension = path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    path.is_file()
+        && extension.is_some_and(|ext| FOLDER_EXTENSIONS.contains(&ext.as_str()))
+        && !csv_ingest::is_rejects_file(path)
+}
+
+/// Import many CSV files with the same layout into one table file, e.g. hourly shards of a log.
+/// The files' columns are matched by name into one schema: columns missing from a file are null in its
+/// rows, and each column gets a type every file's values fit. Per-column settings in `union.csv`, types
+/// included, are given for the first file's columns and follow them by name; types are widened where needed.
+/// Files are parsed on `union.threads` threads while one writer streams their batches to `output_path`
+/// in file order, so the table is the same every time. The output is only replaced once every file has been read.
+pub fn ingest_union_to_file(
+    paths: &[PathBuf],
+    output_path: &Path,
+    format: TableFormat,
+    union: &UnionOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<CsvUnionSummary> {
+    let options = &union.csv;
+    let (shards, mut table_schema) = plan(paths, options, union.threads)?;
+
+    // Columns can only be switched to Text before anything is written, so check every file first
+    if options.coercion_policy == CoercionPolicy::FallBackToText {
+        let checked = check_shards(&shards, &table_schema, union.threads, |_| {})?;
+        let bad_columns: Vec<&str> = checked.iter()
+            .flat_map(|shard| shard.coercion.columns.iter().map(|column| column.column.as_str()))
+            .collect();
+        let fields: Vec<Field> = table_schema.fields().iter()
+            .map(|field| match bad_columns.contains(&field.name().as_str()) {
+                true => Field::new(field.name(), DataType::Utf8, true),
+                false => field.as_ref().clone(),
+            })
+            .collect();
+        table_schema = Arc::new(Schema::new(fields));
+    }
+
+    let schema = match &union.source_column {
+        Some(name) => {
+            if table_schema.fields().iter().any(|field| field.name() == name) {
+                return Err(LeafError::Custom(format!("The files already have a column named '{}'", name)));
+            }
+            let mut fields: Vec<Field> = table_schema.fields().iter().map(|field| field.as_ref().clone()).collect();
+            fields.push(Field::new(name, DataType::Utf8, true));
+            Arc::new(Schema::new(fields))
+        }
+        None => table_schema.clone(),
+    };
+
+    let partial_path = output_path.with_extension(format!("{}.partial", format.extension()));
+    let result = (|| {
+        let mut writer = TableWriter::try_new(&partial_path, &schema, format)?;
+        let files = read_shards(&shards, &table_schema, &schema, union.threads, |batch| writer.write(&batch), on_progress)?;
+        writer.finish()?;
+        Ok(files)
+    })();
+    let files = match result {
+        Ok(files) => files,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+
+    std::fs::rename(&partial_path, output_path)
+        .map_err(|e| LeafError::Custom(format!("Failed to move imported table into place: {}", e)))?;
+
+    Ok(CsvUnionSummary {
+        rows: files.iter().map(|file| file.rows).sum(),
+        rejects: RejectCounts {
+            rejected: files.iter().map(|file| file.rejects.rejected).sum(),
+            padded: files.iter().map(|file| file.rejects.padded).sum(),
+            truncated: files.iter().map(|file| file.rejects.truncated).sum(),
+        },
+        files,
+    })
+}
+
+/// Read every file the way `ingest_union_to_file` would and report the values that don't parse as
+/// their column's type, per file, without writing anything
+pub fn check_union(
+    paths: &[PathBuf],
+    union: &UnionOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<Vec<ShardSummary>> {
+    let (shards, table_schema) = plan(paths, &union.csv, union.threads)?;
+    check_shards(&shards, &table_schema, union.threads, on_progress)
+}
+
+/// The files with the options each is read with, and the one schema they are all read as
+fn plan(paths: &[PathBuf], options: &CsvIngestOptions, threads: usize) -> Result<(Vec<Shard>, SchemaRef)> {
+    let first = paths.first()
+        .ok_or_else(|| LeafError::Custom("There are no files to import".to_string()))?;
+    let first_reader = CsvBatchReader::open(first, options)?;
+    let template = first_reader.headers().to_vec();
+    let mut samples = vec![shard_columns(&first_reader, options.column_types.is_some())];
+    drop(first_reader);
+
+    let rest_options = CsvIngestOptions { column_types: None, ..options.clone() };
+    for sample in parallel_map(&paths[1..], threads, |path| sample_file(path, &rest_options, &template)) {
+        samples.push(sample?);
+    }
+
+    let base = common_dir(paths);
+    let shards = paths.iter()
+        .zip(&samples)
+        .enumerate()
+        .map(|(file_idx, (path, sample))| {
+            let file_options = if file_idx == 0 { options.clone() } else { options_for_headers(options, &template, &sample.headers) };
+            Shard {
+                path: path.clone(),
+                options: CsvIngestOptions {
+                    rejects_path: options.rejects_path.as_ref().map(|rejects| {
+                        let name = rejects.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                        rejects.with_file_name(format!("{}_{}", csv_source::table_name_for(path), name))
+                    }),
+                    ..file_options
+                },
+                source: path.strip_prefix(&base).unwrap_or(path).to_string_lossy().replace('\\', "/"),
+            }
+        })
+        .collect();
+    Ok((shards, unified_schema(&samples)))
+}
+
+/// Sample a file other than the first. Files laid out like the first reuse its per-column settings as
+/// they are; otherwise the file is opened again with the settings moved to its own columns.
+fn sample_file(path: &Path, options: &CsvIngestOptions, template: &[String]) -> Result<ShardColumns> {
+    let reader = CsvBatchReader::open(path, options)?;
+    if reader.headers() == template {
+        return Ok(shard_columns(&reader, false));
+    }
+    let file_options = options_for_headers(options, template, reader.headers());
+    drop(reader);
+    Ok(shard_columns(&CsvBatchReader::open(path, &file_options)?, false))
+}
+
+fn shard_columns(reader: &CsvBatchReader, types_given: bool) -> ShardColumns {
+    let schema = reader.schema();
+    ShardColumns {
+        headers: reader.headers().to_vec(),
+        types: schema.fields().iter()
+            .enumerate()
+            .map(|(col_idx, field)| (types_given || reader.has_sample_values(col_idx)).then(|| field.data_type().clone()))
+            .collect(),
+    }
+}
+
+/// `options` with the per-column settings of the `template` columns moved to the same-named `headers`
+fn options_for_headers(options: &CsvIngestOptions, template: &[String], headers: &[String]) -> CsvIngestOptions {
+    fn by_name<T: Clone + Default>(values: &[T], template: &[String], headers: &[String]) -> Vec<T> {
+        headers.iter()
+            .map(|header| template.iter()
+                .position(|name| name == header)
+                .and_then(|idx| values.get(idx).cloned())
+                .unwrap_or_default())
+            .collect()
+    }
+    CsvIngestOptions {
+        column_null_values: by_name(&options.column_null_values, template, headers),
+        boolean_vocabularies: by_name(&options.boolean_vocabularies, template, headers),
+        datetime_patterns: by_name(&options.datetime_patterns, template, headers),
+        column_types: None,
+        ..options.clone()
+    }
+}
+
+/// Every column of every file, in the order first seen and matched by name like `SchemaReconciliation`.
+/// Each column's type is widened until every file's values fit, starting from any type given for the first file.
+fn unified_schema(samples: &[ShardColumns]) -> SchemaRef {
+    let mut columns: Vec<(String, Option<DataType>)> = Vec::new();
+    for sample in samples {
+        for (header, data_type) in sample.headers.iter().zip(&sample.types) {
+            let position = columns.iter().position(|(name, _)| name == header)
+                .or_else(|| columns.iter().position(|(name, _)| name.trim().eq_ignore_ascii_case(header.trim())));
+            let Some(position) = position else {
+                columns.push((header.clone(), data_type.clone()));
+                continue;
+            };
+            let current = &mut columns[position].1;
+            *current = match (current.take(), data_type) {
+                (Some(current), Some(data_type)) => Some(unify_types(&current, data_type)),
+                (current, data_type) => current.or_else(|| data_type.clone()),
+            };
+        }
+    }
+    let fields: Vec<Field> = columns.into_iter()
+        .map(|(name, data_type)| Field::new(name, data_type.unwrap_or(DataType::Utf8), true))
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// A type that holds the values of both: integers widen to Int64, mixed numbers to Float64, dates to
+/// timestamps and times to the finer unit. Anything else becomes text.
+fn unify_types(a: &DataType, b: &DataType) -> DataType {
+    match (a, b) {
+        _ if a == b => a.clone(),
+        _ if a.is_integer() && b.is_integer() => DataType::Int64,
+        _ if a.is_numeric() && b.is_numeric() => DataType::Float64,
+        (DataType::Date32, DataType::Timestamp(unit, tz)) | (DataType::Timestamp(unit, tz), DataType::Date32) => {
+            DataType::Timestamp(*unit, tz.clone())
+        }
+        (DataType::Timestamp(a_unit, a_tz), DataType::Timestamp(b_unit, b_tz)) if a_tz == b_tz => {
+            DataType::Timestamp((*a_unit).max(*b_unit), a_tz.clone())
+        }
+        (DataType::Time64(_), DataType::Time64(_)) => DataType::Time64(TimeUnit::Nanosecond),
+        (DataType::Duration(a_unit), DataType::Duration(b_unit)) => DataType::Duration((*a_unit).max(*b_unit)),
+        _ => DataType::Utf8,
+    }
+}
+
+/// The deepest folder holding every file, so the source column names files relative to it
+fn common_dir(paths: &[PathBuf]) -> PathBuf {
+    let mut base = paths.first()
+        .and_then(|path| path.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    for path in paths {
+        while !path.starts_with(&base) && base.pop() {}
+    }
+    base
+}
+
+/// Read the shards without writing anything, with unparseable values set to null and no rejects files
+fn check_shards(
+    shards: &[Shard],
+    table_schema: &SchemaRef,
+    threads: usize,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<Vec<ShardSummary>> {
+    let shards: Vec<Shard> = shards.iter()
+        .map(|shard| Shard {
+            path: shard.path.clone(),
+            options: CsvIngestOptions {
+                rejects_path: None,
+                coercion_policy: CoercionPolicy::SetNull,
+                ..shard.options.clone()
+            },
+            source: shard.source.clone(),
+        })
+        .collect();
+    read_shards(&shards, table_schema, table_schema, threads, |_| Ok(()), on_progress)
+}
+
+/// Read every shard as `table_schema` on up to `threads` worker threads and hand each batch to `on_batch`
+/// on the calling thread, with the source column added when `schema` has one more column.
+/// Batches are handed on in shard order: each shard has its own channel, drained once the shards before it are done.
+/// The first error stops every file, and is returned once the workers have finished.
+fn read_shards(
+    shards: &[Shard],
+    table_schema: &SchemaRef,
+    schema: &SchemaRef,
+    threads: usize,
+    mut on_batch: impl FnMut(RecordBatch) -> Result<()>,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<Vec<ShardSummary>> {
+    let threads = threads.clamp(1, shards.len().max(1));
+    let next = AtomicUsize::new(0);
+    let cancelled = AtomicBool::new(false);
+    let (senders, receivers): (Vec<_>, Vec<_>) = shards.iter()
+        .map(|_| {
+            let (sender, receiver) = mpsc::sync_channel::<Result<ShardEvent>>(BATCHES_AHEAD);
+            (Mutex::new(Some(sender)), receiver)
+        })
+        .unzip();
+
+    let mut progress: Vec<IngestProgress> = shards.iter()
+        .map(|shard| IngestProgress {
+            bytes_read: 0,
+            total_bytes: std::fs::metadata(&shard.path).map_or(0, |metadata| metadata.len()),
+            rows: 0,
+        })
+        .collect();
+    let mut summaries: Vec<Option<ShardSummary>> = vec![None; shards.len()];
+    let mut result = Ok(());
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let (next, cancelled, senders) = (&next, &cancelled, &senders);
+            scope.spawn(move || {
+                while !cancelled.load(Ordering::Relaxed) {
+                    let file = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(shard) = shards.get(file) else { break };
+                    // The worker owns the file's sender, so the file's events end when it is done with it
+                    let Some(sender) = senders[file].lock().ok().and_then(|mut slot| slot.take()) else { break };
+                    if let Err(e) = read_shard(shard, table_schema, schema, &sender, cancelled) {
+                        let _ = sender.send(Err(e));
+                        break;
+                    }
+                }
+            });
+        }
+
+        for (file, receiver) in receivers.into_iter().enumerate() {
+            for event in receiver {
+                result = match event {
+                    Ok(ShardEvent::Batch { batch, progress: file_progress }) => {
+                        progress[file] = file_progress;
+                        let total = IngestProgress {
+                            bytes_read: progress.iter().map(|p| p.bytes_read).sum(),
+                            total_bytes: progress.iter().map(|p| p.total_bytes).sum(),
+                            rows: progress.iter().map(|p| p.rows).sum(),
+                        };
+                        on_batch(batch).map(|()| on_progress(&total))
+                    }
+                    Ok(ShardEvent::Done { summary }) => {
+                        summaries[file] = Some(summary);
+                        Ok(())
+                    }
+                    Err(e) => Err(e),
+                };
+                if result.is_err() {
+                    break;
+                }
+            }
+            // Dropping the remaining channels unblocks workers waiting to send, and they see the cancel
+            if result.is_err() {
+                cancelled.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+    });
+
+    result?;
+    Ok(summaries.into_iter().flatten().collect())
+}
+
+/// Read one file on a worker thread, sending its batches laid out as the table
+fn read_shard(
+    shard: &Shard,
+    table_schema: &SchemaRef,
+    schema: &SchemaRef,
+    sender: &SyncSender<Result<ShardEvent>>,
+    cancelled: &AtomicBool,
+) -> Result<()> {
+    let (mut reader, columns) = csv_ingest::open_for_append(&shard.path, table_schema, &shard.options, false)?;
+    while let Some(batch) = reader.next_batch()? {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let mut arrays = columns.arrange(&batch, table_schema)?.columns().to_vec();
+        if schema.fields().len() > arrays.len() {
+            arrays.push(Arc::new(StringArray::from(vec![shard.source.as_str(); batch.num_rows()])));
+        }
+        let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+        // The receiver is only gone once the import has stopped
+        if sender.send(Ok(ShardEvent::Batch { batch, progress: reader.progress() })).is_err() {
+            return Ok(());
+        }
+    }
+
+    let summary = ShardSummary {
+        path: shard.path.clone(),
+        rows: reader.progress().rows,
+        rejects: reader.rejects(),
+        rejects_path: reader.finish_rejects()?,
+        coercion: reader.coercion().clone(),
+        missing: columns.missing.clone(),
+    };
+    let _ = sender.send(Ok(ShardEvent::Done { summary }));
+    Ok(())
+}
+
+/// Run `f` on every item on up to `threads` threads, keeping the results in item order
+fn parallel_map<T: Sync, R: Send>(items: &[T], threads: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+    std::thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(idx) else { break };
+                let result = f(item);
+                if let Ok(mut slot) = results[idx].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+    results.into_iter()
+        .filter_map(|slot| slot.into_inner().ok().flatten())
+        .collect()
+}
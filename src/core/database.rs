@@ -3,12 +3,26 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use datafusion::prelude::*;
 use datafusion::arrow::record_batch::RecordBatch;
-use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::datasource::listing::{ListingTable, ListingTableConfig, ListingTableUrl};
+use futures::StreamExt;
 use datafusion::arrow::array::{StringArray, Int64Array, Float64Array, BooleanArray, Date32Array, TimestampNanosecondArray, TimestampSecondArray, TimestampMillisecondArray, TimestampMicrosecondArray};
+use datafusion::arrow::array::{Int8Array, Int16Array, Int32Array, UInt8Array, UInt16Array, UInt32Array, UInt64Array, Float32Array, Decimal128Array, Time64MicrosecondArray, Time64NanosecondArray, DurationSecondArray, DurationMillisecondArray, DurationMicrosecondArray, DurationNanosecondArray, DictionaryArray};
+use datafusion::arrow::datatypes::Int32Type;
 use datafusion::arrow::datatypes::TimeUnit;
 use tokio::runtime::Runtime;
 use crate::core::error::{Result, LeafError};
-use crate::infer::{TypeInferrer, ColumnType};
+use crate::core::table_format::{self, TableFormat, ParquetCompression, SourceFormat, TableWriter};
+use crate::core::csv_ingest::{self, CsvAppendSummary, CsvBatchReader, CsvIngestOptions, CsvIngestSummary, IngestProgress, RejectCounts, RejectPolicy};
+use crate::core::csv_union::{self, CsvUnionSummary, UnionOptions};
+use crate::core::json_ingest::{self, JsonIngestOptions, JsonIngestSummary};
+use crate::core::spreadsheet::{self, SheetSelection};
+use crate::core::text_encoding;
+use crate::infer::{TypeInferrer, ColumnTokens, NumberFormat};
+use crate::infer::{datetime_format, duration_format};
+use chrono::Timelike;
+use datafusion::arrow::array::timezone::Tz;
 
 #[derive(Debug, Clone)]
 pub struct ColumnInfo {
@@ -22,7 +36,8 @@ pub struct ColumnInfo {
 pub struct TableInfo {
     pub name: String,
     pub columns: Vec<ColumnInfo>,
-    pub row_count: i64,
+    /// `None` for tables over a CSV file, which would have to be read in full to count
+    pub row_count: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +52,51 @@ pub struct DataBatch {
     pub rows: Vec<Vec<String>>,
 }
 
+/// Where a registered table's rows come from
+#[derive(Debug, Clone)]
+enum TableSource {
+    /// Record batches held in memory, kept in their original order
+    Memory(Vec<RecordBatch>),
+    /// A file on disk scanned through a DataFusion listing table; rows are only read when a query needs them
+    File {
+        path: PathBuf,
+        format: SourceFormat,
+        /// From the file's metadata; `None` for CSV, which records no row count
+        row_count: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct RegisteredTable {
+    schema: SchemaRef,
+    source: TableSource,
+}
+
+impl RegisteredTable {
+    fn from_batch(batch: RecordBatch) -> Self {
+        Self {
+            schema: batch.schema(),
+            source: TableSource::Memory(vec![batch]),
+        }
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        match &self.source {
+            TableSource::Memory(batches) => Some(batches.iter().map(|batch| batch.num_rows()).sum()),
+            TableSource::File { row_count, .. } => *row_count,
+        }
+    }
+
+    /// Concatenate in-memory batches for callers that work on a single RecordBatch
+    fn concat_batches(schema: &SchemaRef, batches: &[RecordBatch]) -> Result<RecordBatch> {
+        match batches {
+            [batch] => Ok(batch.clone()),
+            batches => datafusion::arrow::compute::concat_batches(schema, batches)
+                .map_err(|e| LeafError::Custom(format!("Failed to combine record batches: {}", e))),
+        }
+    }
+}
+
 pub struct Database {
     // DataFusion context for in-memory analytics
     ctx: SessionContext,
@@ -44,7 +104,9 @@ pub struct Database {
     // Cache for loaded data batches
     batch_cache: HashMap<String, DataBatch>,
     // Track registered tables
-    registered_tables: HashMap<String, Arc<RecordBatch>>,
+    registered_tables: HashMap<String, RegisteredTable>,
+    // Opened for viewing, so queries may not change anything
+    readonly: bool,
 }
 
 impl Clone for Database {
@@ -52,14 +114,27 @@ impl Clone for Database {
         // Create a new runtime and context
         let runtime = Runtime::new()
             .expect("Failed to create tokio runtime for Database clone");
-        let ctx = SessionContext::new();
+        let ctx = Self::new_context();
         
-        Self {
+        let db = Self {
             ctx,
             runtime,
             batch_cache: self.batch_cache.clone(),
             registered_tables: self.registered_tables.clone(),
+            readonly: self.readonly,
+        };
+        
+        // Register every table with the new context; file-backed tables stay on disk
+        for (table_name, table) in &db.registered_tables {
+            let registered = db.table_provider(table)
+                .and_then(|provider| db.ctx.register_table(table_name.as_str(), provider)
+                    .map_err(|e| LeafError::Custom(e.to_string())));
+            if let Err(e) = registered {
+                eprintln!("[Database] Failed to re-register table '{}' in cloned context: {}", table_name, e);
+            }
         }
+        
+        db
     }
 }
 
@@ -70,69 +145,19 @@ impl Drop for Database {
 }
 
 impl Database {
-    fn parse_csv_line(line: &str, delimiter: char) -> Vec<String> {
-        let mut row = Vec::new();
-        let mut current_field = String::new();
-        let mut in_quotes = false;
-        let mut chars = line.chars().peekable();
-        
-        while let Some(ch) = chars.next() {
-            match ch {
-                '"' => {
-                    if in_quotes {
-                        // End of quoted field
-                        in_quotes = false;
-                    } else {
-                        // Start of quoted field
-                        in_quotes = true;
-                    }
-                },
-                c if c == delimiter && !in_quotes => {
-                    // End of field
-                    row.push(current_field.trim().to_string());
-                    current_field.clear();
-                },
-                _ => {
-                    current_field.push(ch);
-                }
-            }
-        }
-        
-        // Add the last field
-        row.push(current_field.trim().to_string());
-        row
-    }
-
-    /// Normalize time data by padding all entries to the highest precision found
-    /// Parse a datetime string to a timestamp in the specified unit
-    fn parse_datetime_string_to_timestamp(datetime_str: &str, unit: &TimeUnit) -> Option<i64> {
-        use chrono::NaiveDateTime;
-        
-        // Common datetime formats
-        let formats = [
-            "%Y-%m-%d %H:%M:%S%.f",
-            "%Y-%m-%d %H:%M:%S",
-            "%Y-%m-%dT%H:%M:%S%.f",
-            "%Y-%m-%dT%H:%M:%S",
-            "%Y/%m/%d %H:%M:%S",
-            "%d/%m/%Y %H:%M:%S",
-            "%m/%d/%Y %H:%M:%S",
-        ];
-        
-        for format in &formats {
-            if let Ok(datetime) = NaiveDateTime::parse_from_str(datetime_str, format) {
-                // Convert to timestamp in the requested unit
-                let timestamp = match unit {
-                    TimeUnit::Second => datetime.and_utc().timestamp(),
-                    TimeUnit::Millisecond => datetime.and_utc().timestamp_millis(),
-                    TimeUnit::Microsecond => datetime.and_utc().timestamp_micros(),
-                    TimeUnit::Nanosecond => datetime.and_utc().timestamp_nanos_opt().unwrap_or(0),
-                };
-                return Some(timestamp);
-            }
-        }
-        
-        None
+    /// DataFusion context used for every database.
+    /// File scans are not split across partitions so a plain `SELECT *` returns rows in file order,
+    /// which the grouping and delta tools rely on.
+    fn new_context() -> SessionContext {
+        let config = SessionConfig::new().with_repartition_file_scans(false);
+        SessionContext::new_with_config(config)
+    }
+
+    /// Parse a datetime string to a timestamp in the specified unit, using `pattern` when given.
+    /// Values without a UTC offset are read as wall-clock times in `zone` (UTC when `None`).
+    fn parse_datetime_string_to_timestamp(datetime_str: &str, unit: &TimeUnit, pattern: Option<&str>, zone: Option<&Tz>) -> Option<i64> {
+        let parsed = datetime_format::parse_datetime(datetime_str, pattern)?;
+        datetime_format::timestamp_in_unit(&parsed.to_utc(zone)?, unit)
     }
     
     /// Parse a time string in HH:MM:SS format to a timestamp in the specified unit
@@ -178,7 +203,7 @@ impl Database {
         Some(timestamp)
     }
 
-    fn normalize_time_column(values: &[String]) -> Vec<String> {
+    pub(crate) fn normalize_time_column(values: &[String]) -> Vec<String> {
         let mut max_fraction_digits = 0;
         
         // First pass: find the maximum fraction digits
@@ -232,76 +257,10 @@ impl Database {
         normalized_values
     }
 
-    /// Infer the most likely delimiter from a header line
-    fn infer_delimiter_from_header(header_line: &str) -> char {
-        let delimiters = [',', '\t', ';', '|'];
-        let mut best_delimiter = ',';
-        let mut max_fields = 0;
-        
-        for &delimiter in &delimiters {
-            let fields = Self::parse_csv_line(header_line, delimiter);
-            if fields.len() > max_fields && fields.len() > 1 {
-                max_fields = fields.len();
-                best_delimiter = delimiter;
-            }
-        }
-        
-        best_delimiter
-    }
-
-    /// Check if a line appears to be valid CSV data based on delimiter
-    fn is_valid_csv_line(line: &str, delimiter: char, expected_columns: usize) -> bool {
-        // Skip empty lines
-        if line.trim().is_empty() {
-            return false;
-        }
-        
-        // Skip comment lines
-        if line.trim().starts_with('#') {
-            return false;
-        }
-        
-        // Parse the line and check if it has the expected number of columns
-        let fields = Self::parse_csv_line(line, delimiter);
-        
-        // If we have a specific expected column count, validate against it
-        if expected_columns > 0 {
-            return fields.len() == expected_columns;
-        }
-        
-        // Otherwise, just check if it has multiple fields (at least 2)
-        fields.len() >= 2
-    }
-
     fn parse_date_string(date_str: &str) -> Option<i32> {
-        // Parse date in YYYY-MM-DD format
-        let parts: Vec<&str> = date_str.split('-').collect();
-        if parts.len() != 3 {
-            return None;
-        }
-        
-        let year: i32 = parts[0].parse().ok()?;
-        let month: u32 = parts[1].parse().ok()?;
-        let day: u32 = parts[2].parse().ok()?;
-        
-        // Validate date
-        if month < 1 || month > 12 || day < 1 || day > 31 {
-            return None;
-        }
-        
-        // Convert to days since epoch (1970-01-01)
-        // This is a simplified conversion - in production you'd want a proper date library
-        let days_since_epoch = (year - 1970) * 365 + (year - 1969) / 4; // Approximate leap year calculation
-        
-        // Add days for months (simplified)
-        let month_days = [0, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-        let mut days_in_year: i32 = 0;
-        for i in 1..month {
-            days_in_year += month_days[i as usize];
-        }
-        days_in_year += (day - 1) as i32;
-        
-        Some(days_since_epoch + days_in_year)
+        // Parse date in YYYY-MM-DD format to days since epoch (1970-01-01)
+        let date = chrono::NaiveDate::parse_from_str(date_str.trim(), "%Y-%m-%d").ok()?;
+        Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp().div_euclid(86_400) as i32)
     }
 
     pub fn open_readonly<P: AsRef<Path>>(_path: P) -> Result<Self> {
@@ -309,13 +268,14 @@ impl Database {
         let runtime = Runtime::new()
             .map_err(|e| LeafError::Custom(format!("Failed to create tokio runtime: {}", e)))?;
         
-        let ctx = SessionContext::new();
+        let ctx = Self::new_context();
         
         Ok(Self {
             ctx,
             runtime,
             batch_cache: HashMap::new(),
             registered_tables: HashMap::new(),
+            readonly: true,
         })
     }
 
@@ -324,18 +284,19 @@ impl Database {
         let runtime = Runtime::new()
             .map_err(|e| LeafError::Custom(format!("Failed to create tokio runtime: {}", e)))?;
         
-        let ctx = SessionContext::new();
+        let ctx = Self::new_context();
         
         Ok(Self {
             ctx,
             runtime,
             batch_cache: HashMap::new(),
             registered_tables: HashMap::new(),
+            readonly: false,
         })
     }
 
     pub fn is_readonly(&self) -> bool {
-        false // DataFusion is always writable in-memory
+        self.readonly
     }
 
     // Load a table into memory as a DataBatch (cached)
@@ -355,32 +316,50 @@ impl Database {
         Ok(batch)
     }
 
-    // Execute a DataFusion query and return as DataBatch
-    pub fn execute_query_batch(&self, query: &str) -> Result<DataBatch> {
+    /// The DataFusion session the tables are registered with; clones share its tables
+    pub fn session_context(&self) -> SessionContext {
+        self.ctx.clone()
+    }
+
+    /// Run a future on the database's runtime without waiting for it
+    pub fn spawn<F>(&self, future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.runtime.spawn(future)
+    }
+
+    /// Execute a DataFusion query and keep its output as Arrow record batches, with the query's schema
+    /// even when it returns no rows
+    pub fn execute_query_arrow(&self, query: &str) -> Result<(SchemaRef, Vec<RecordBatch>)> {
         let ctx = self.ctx.clone();
         
         let result = self.runtime.block_on(async {
             ctx.sql(query).await
         }).map_err(|e| LeafError::Custom(format!("Failed to execute query: {}", e)))?;
+        let schema = result.schema().inner().clone();
         
         let record_batches = self.runtime.block_on(async {
             result.collect().await
         }).map_err(|e| LeafError::Custom(format!("Failed to collect results: {}", e)))?;
         
-        if record_batches.is_empty() {
-            return Ok(DataBatch {
-                columns: vec![],
-                rows: vec![],
-            });
-        }
+        Ok((schema, record_batches))
+    }
+
+    // Execute a DataFusion query and return as DataBatch
+    pub fn execute_query_batch(&self, query: &str) -> Result<DataBatch> {
+        let (schema, record_batches) = self.execute_query_arrow(query)?;
         
-        // Convert first batch to DataBatch
-        let batch = &record_batches[0];
-        let columns: Vec<String> = batch.schema().fields().iter()
+        // Convert every batch to DataBatch rows; multi-batch tables produce multi-batch results
+        let columns: Vec<String> = schema.fields().iter()
             .map(|field| field.name().clone())
             .collect();
         
-        let rows = self.record_batch_to_rows(batch)?;
+        let mut rows = Vec::new();
+        for batch in &record_batches {
+            rows.extend(Self::record_batch_to_rows(batch)?);
+        }
         
         Ok(DataBatch {
             columns,
@@ -442,81 +421,84 @@ impl Database {
         self.batch_cache.remove(table_name);
         
         // Convert string values to Arrow arrays
-        let (columns, schema) = if let Some(existing_batch) = self.registered_tables.get(table_name) {
+        let (schema, arrays) = if let Some(existing_table) = self.registered_tables.get(table_name) {
             // Use existing schema to preserve column names and types
-            let existing_schema = existing_batch.schema();
+            let existing_schema = existing_table.schema.clone();
             let columns: Vec<String> = existing_schema.fields().iter()
                 .map(|field| field.name().clone())
                 .collect();
-            (columns, existing_schema.clone())
+            let arrays = Self::string_rows_to_arrow_arrays_with_schema(&columns, values, &existing_schema)?;
+            (existing_schema, arrays)
         } else {
             // Create default column names if table doesn't exist
             let columns: Vec<String> = (0..values[0].len()).map(|i| format!("col_{}", i)).collect();
-            let schema = Arc::new(Schema::new(
-                columns.iter().map(|name| Field::new(name, DataType::Utf8, true)).collect::<Vec<_>>()
-            ));
-            (columns, schema)
+            Self::string_rows_to_arrow_arrays(&columns, values)?
         };
         
-        let arrays = self.string_rows_to_arrow_arrays(&columns, values)?;
-        
         let batch = RecordBatch::try_new(schema, arrays)
             .map_err(|e| LeafError::Custom(format!("Failed to create record batch: {}", e)))?;
         
         // Safely register or replace the table
-        self.register_or_replace_table(table_name, batch.clone())?;
+        let table = RegisteredTable::from_batch(batch);
+        self.register_or_replace_table(table_name, &table)?;
         
         // Store in our cache
-        self.registered_tables.insert(table_name.to_string(), Arc::new(batch));
+        self.registered_tables.insert(table_name.to_string(), table);
         
         Ok(())
     }
 
     // Helper method to safely register or replace a table
-    fn register_or_replace_table(&mut self, table_name: &str, batch: RecordBatch) -> Result<()> {
-        // Check if table already exists in our tracking
-        if self.registered_tables.contains_key(table_name) {
-            // Remove from our internal tracking first
-            self.registered_tables.remove(table_name);
-        }
+    fn register_or_replace_table(&mut self, table_name: &str, table: &RegisteredTable) -> Result<()> {
+        // Drop any previous registration so only this table is replaced
+        self.registered_tables.remove(table_name);
+        self.ctx.deregister_table(table_name)
+            .map_err(|e| LeafError::Custom(format!("Failed to deregister table {}: {}", table_name, e)))?;
         
-        // Try to register the table
-        match self.ctx.register_batch(table_name, batch.clone()) {
-            Ok(_) => Ok(()),
-            Err(_) => {
-                // If registration fails, the table already exists in DataFusion
-                // We need to create a new context to replace it
-                // This is a workaround since DataFusion doesn't support table replacement
-                
-                // Create a new context
-                let new_ctx = SessionContext::new();
-                
-                // Register all existing tables except the one we're replacing
-                for (existing_name, existing_batch) in &self.registered_tables {
-                    if existing_name != table_name {
-                        new_ctx.register_batch(existing_name, existing_batch.as_ref().clone())
-                            .map_err(|e| LeafError::Custom(format!("Failed to re-register table {}: {}", existing_name, e)))?;
-                    }
-                }
-                
-                // Register the new table
-                new_ctx.register_batch(table_name, batch)
-                    .map_err(|e| LeafError::Custom(format!("Failed to register data: {}", e)))?;
-                
-                // Replace the context
-                self.ctx = new_ctx;
-                
-                Ok(())
+        self.ctx.register_table(table_name, self.table_provider(table)?)
+            .map_err(|e| LeafError::Custom(format!("Failed to register data: {}", e)))?;
+        
+        Ok(())
+    }
+
+    /// Build a DataFusion table provider for a registered table.
+    /// In-memory batches go into a single partition so scans keep the original row order.
+    fn table_provider(&self, table: &RegisteredTable) -> Result<Arc<dyn TableProvider>> {
+        match &table.source {
+            TableSource::Memory(batches) => {
+                let mem_table = MemTable::try_new(table.schema.clone(), vec![batches.clone()])
+                    .map_err(|e| LeafError::Custom(format!("Failed to create in-memory table: {}", e)))?;
+                Ok(Arc::new(mem_table))
+            }
+            TableSource::File { path, format, .. } => {
+                let url = Self::listing_table_url(path)?;
+                let config = ListingTableConfig::new(url)
+                    .with_listing_options(format.listing_options(path))
+                    .with_schema(table.schema.clone());
+                let listing_table = ListingTable::try_new(config)
+                    .map_err(|e| LeafError::Custom(format!("Failed to create table over {}: {}", path.display(), e)))?;
+                Ok(Arc::new(listing_table))
             }
         }
     }
 
+    /// URL of a single file. It is built as a `file://` URL so `*`, `?` and `[` in the file name
+    /// are taken literally instead of as a glob.
+    fn listing_table_url(path: &Path) -> Result<ListingTableUrl> {
+        let path = std::fs::canonicalize(path)
+            .map_err(|e| LeafError::Custom(format!("Failed to resolve {}: {}", path.display(), e)))?;
+        let url = url::Url::from_file_path(&path)
+            .map_err(|()| LeafError::Custom(format!("Invalid table path {}", path.display())))?;
+        ListingTableUrl::parse(url.as_str())
+            .map_err(|e| LeafError::Custom(format!("Invalid table path {}: {}", path.display(), e)))
+    }
+
     // Get table information from DataFusion
     pub fn get_tables(&self) -> Result<Vec<TableInfo>> {
         let mut tables = Vec::new();
         
-        for (table_name, batch) in &self.registered_tables {
-            let columns: Vec<ColumnInfo> = batch.schema().fields().iter()
+        for (table_name, table) in &self.registered_tables {
+            let columns: Vec<ColumnInfo> = table.schema.fields().iter()
                 .map(|field| ColumnInfo {
                     name: field.name().clone(),
                     data_type: self.arrow_type_to_sql_type(field.data_type()).to_string(),
@@ -528,7 +510,7 @@ impl Database {
             tables.push(TableInfo {
                 name: table_name.clone(),
                 columns,
-                row_count: batch.num_rows() as i64,
+                row_count: table.num_rows().map(|rows| rows as i64),
             });
         }
         
@@ -536,14 +518,14 @@ impl Database {
     }
 
     // Helper methods for DataFusion integration
-    fn record_batch_to_rows(&self, batch: &RecordBatch) -> Result<Vec<Vec<String>>> {
+    fn record_batch_to_rows(batch: &RecordBatch) -> Result<Vec<Vec<String>>> {
         let mut rows = Vec::new();
         
         for row_idx in 0..batch.num_rows() {
             let mut row = Vec::new();
             for col_idx in 0..batch.num_columns() {
                 let array = batch.column(col_idx);
-                let value = self.array_value_to_string(array, row_idx)?;
+                let value = Self::array_value_to_string(array, row_idx)?;
                 row.push(value);
             }
             rows.push(row);
@@ -552,7 +534,8 @@ impl Database {
         Ok(rows)
     }
 
-    fn array_value_to_string(&self, array: &datafusion::arrow::array::ArrayRef, index: usize) -> Result<String> {
+    /// One value of an array as the text shown in tables and written to CSV; nulls are empty
+    pub(crate) fn array_value_to_string(array: &datafusion::arrow::array::ArrayRef, index: usize) -> Result<String> {
         use datafusion::arrow::array::*;
         use chrono::{DateTime, Utc};
         
@@ -589,7 +572,23 @@ impl Database {
                     Ok(bool_array.value(index).to_string())
                 }
             }
-            DataType::Timestamp(unit, _) => {
+            DataType::Timestamp(unit, tz) => {
+                // Timezone-aware columns hold UTC instants; show them as wall-clock times in their zone
+                if let Some(zone) = tz.as_deref().and_then(datetime_format::parse_timezone) {
+                    if array.is_null(index) {
+                        return Ok("".to_string());
+                    }
+                    let instant = match unit {
+                        TimeUnit::Second => DateTime::from_timestamp(array.as_any().downcast_ref::<TimestampSecondArray>().unwrap().value(index), 0),
+                        TimeUnit::Millisecond => DateTime::from_timestamp_millis(array.as_any().downcast_ref::<TimestampMillisecondArray>().unwrap().value(index)),
+                        TimeUnit::Microsecond => DateTime::from_timestamp_micros(array.as_any().downcast_ref::<TimestampMicrosecondArray>().unwrap().value(index)),
+                        TimeUnit::Nanosecond => Some(DateTime::from_timestamp_nanos(array.as_any().downcast_ref::<TimestampNanosecondArray>().unwrap().value(index))),
+                    };
+                    return Ok(instant
+                        .map(|instant: DateTime<Utc>| instant.with_timezone(&zone).format("%Y-%m-%d %H:%M:%S%.f%:z").to_string())
+                        .unwrap_or_default());
+                }
+                
                 // Handle timestamp arrays - convert back to time-only format
                 match unit {
                     TimeUnit::Second => {
@@ -624,7 +623,7 @@ impl Database {
                                 // This is a datetime - format as full datetime
                                 let datetime = DateTime::from_timestamp_millis(timestamp)
                                     .unwrap_or_else(|| DateTime::from_timestamp_millis(0).unwrap());
-                                Ok(datetime.format("%Y-%m-%d %H:%M:%S").to_string())
+                                Ok(datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string())
                             } else {
                                 // This is time-of-day - convert milliseconds since midnight back to HH:MM:SS.mmm format
                                 let total_seconds = timestamp / 1_000;
@@ -646,6 +645,12 @@ impl Database {
                             Ok("".to_string())
                         } else {
                             let timestamp = timestamp_array.value(index);
+                            if timestamp > 86_400_000_000 {
+                                // This is a datetime - format as full datetime
+                                let datetime = DateTime::from_timestamp_micros(timestamp)
+                                    .unwrap_or_else(|| DateTime::from_timestamp_micros(0).unwrap());
+                                return Ok(datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string());
+                            }
                             // Convert microseconds since midnight back to HH:MM:SS.mmmmmm format
                             let total_seconds = timestamp / 1_000_000;
                             let microseconds = timestamp % 1_000_000;
@@ -661,6 +666,11 @@ impl Database {
                             Ok("".to_string())
                         } else {
                             let timestamp = timestamp_array.value(index);
+                            if timestamp > 86_400_000_000_000 {
+                                // This is a datetime - format as full datetime
+                                let datetime = DateTime::from_timestamp_nanos(timestamp);
+                                return Ok(datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string());
+                            }
                             // Convert nanoseconds since midnight back to HH:MM:SS.nnnnnnnnn format
                             let total_seconds = timestamp / 1_000_000_000;
                             let nanoseconds = timestamp % 1_000_000_000;
@@ -679,136 +689,119 @@ impl Database {
                 } else {
                     let days = date_array.value(index);
                     // Convert days since epoch (1970-01-01) to readable date
-                    // This is a simplified conversion - in production you'd want a proper date library
-                    let year = 1970 + (days / 365);
-                    let remaining_days = days % 365;
-                    
-                    // Simplified month/day calculation
-                    let month_days = [0, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-                    let mut month = 1;
-                    let mut day = remaining_days;
-                    
-                    for &days_in_month in &month_days[1..] {
-                        if day < days_in_month {
-                            break;
-                        }
-                        day -= days_in_month;
-                        month += 1;
+                    match DateTime::from_timestamp(days as i64 * 86_400, 0) {
+                        Some(datetime) => Ok(datetime.format("%Y-%m-%d").to_string()),
+                        None => Ok(days.to_string()),
                     }
-                    
-                    Ok(format!("{:04}-{:02}-{:02}", year, month, day + 1))
                 }
             }
+            DataType::Duration(unit) => {
+                if array.is_null(index) {
+                    return Ok("".to_string());
+                }
+                let nanos = match unit {
+                    TimeUnit::Second => array.as_any().downcast_ref::<DurationSecondArray>().unwrap().value(index).saturating_mul(1_000_000_000),
+                    TimeUnit::Millisecond => array.as_any().downcast_ref::<DurationMillisecondArray>().unwrap().value(index).saturating_mul(1_000_000),
+                    TimeUnit::Microsecond => array.as_any().downcast_ref::<DurationMicrosecondArray>().unwrap().value(index).saturating_mul(1_000),
+                    TimeUnit::Nanosecond => array.as_any().downcast_ref::<DurationNanosecondArray>().unwrap().value(index),
+                };
+                Ok(duration_format::format_duration(nanos))
+            }
             _ => {
-                // For other types, convert to string representation
-                Ok(format!("{:?}", array))
+                // Narrow integers, decimals, times of day, dictionaries and the rest use Arrow's formatting
+                if array.is_null(index) {
+                    return Ok("".to_string());
+                }
+                datafusion::arrow::util::display::array_value_to_string(array, index)
+                    .map_err(|e| LeafError::Custom(format!("Failed to format {} value: {}", array.data_type(), e)))
             }
         }
     }
 
-    fn string_rows_to_arrow_arrays(&self, columns: &[String], values: &[Vec<String>]) -> Result<Vec<datafusion::arrow::array::ArrayRef>> {
-        let mut arrays = Vec::new();
-        
-        for col_idx in 0..columns.len() {
-            // Try to infer the data type from the first few rows
-            let mut is_numeric = true;
-            let mut is_integer = true;
-            
-            // Check first 10 rows to infer type
-            for row in values.iter().take(10) {
-                if col_idx < row.len() {
-                    let value = &row[col_idx];
-                    if !value.is_empty() {
-                        // Check if it's numeric
-                        if !value.chars().all(|c| c.is_numeric() || c == '.' || c == '-') {
-                            is_numeric = false;
-                            break;
-                        }
-                        // Check if it's integer
-                        if value.contains('.') {
-                            is_integer = false;
-                        }
-                    }
-                }
-            }
-            
-            if is_numeric && is_integer {
-                // Create Int64 array
-                let mut int_values = Vec::new();
-                for row in values {
-                    if col_idx < row.len() {
-                        let value = &row[col_idx];
-                        if value.is_empty() {
-                            int_values.push(None);
-                        } else {
-                            match value.parse::<i64>() {
-                                Ok(int_val) => int_values.push(Some(int_val)),
-                                Err(_) => int_values.push(None),
-                            }
-                        }
-                    } else {
-                        int_values.push(None);
-                    }
-                }
-                let array = Int64Array::from(int_values);
-                arrays.push(Arc::new(array) as datafusion::arrow::array::ArrayRef);
-            } else if is_numeric {
-                // Create Float64 array
-                let mut float_values = Vec::new();
-                for row in values {
-                    if col_idx < row.len() {
-                        let value = &row[col_idx];
-                        if value.is_empty() {
-                            float_values.push(None);
-                        } else {
-                            match value.parse::<f64>() {
-                                Ok(float_val) => float_values.push(Some(float_val)),
-                                Err(_) => float_values.push(None),
-                            }
-                        }
-                    } else {
-                        float_values.push(None);
-                    }
-                }
-                let array = Float64Array::from(float_values);
-                arrays.push(Arc::new(array) as datafusion::arrow::array::ArrayRef);
-            } else {
-                // Create String array
-                let mut string_values = Vec::new();
-                for row in values {
-                    if col_idx < row.len() {
-                        string_values.push(row[col_idx].clone());
-                    } else {
-                        string_values.push(String::new());
+    /// Schema and arrays for rows without a table to match. Column types are inferred from the rows the way
+    /// a CSV import infers them, so grouped, currency, percent and accounting numbers are read as numbers.
+    /// Rows come without import settings, so numbers are read in the preset format most of their values parse in.
+    fn string_rows_to_arrow_arrays(columns: &[String], values: &[Vec<String>]) -> Result<(SchemaRef, Vec<datafusion::arrow::array::ArrayRef>)> {
+        let parsed_count = |format: &NumberFormat| values.iter().flatten().filter(|value| format.parse(value).is_some()).count();
+        let number_format = NumberFormat::PRESETS.into_iter()
+            .fold((NumberFormat::default(), 0), |best, format| {
+                let parsed = parsed_count(&format);
+                if parsed > best.1 { (format, parsed) } else { best }
+            })
+            .0;
+        let tokens = vec![ColumnTokens::default(); columns.len()];
+        let fields: Vec<Field> = TypeInferrer::infer_column_types_with_tokens(columns, values, &tokens, &number_format)
+            .into_iter()
+            .map(|(name, col_type)| Field::new(name, col_type.to_arrow_type(), true))
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+        let arrays = Self::string_rows_to_arrow_arrays_checked(columns, values, &schema, &number_format, &[], &tokens, |_, _| {})?;
+        Ok((schema, arrays))
+    }
+
+    pub(crate) fn string_rows_to_arrow_arrays_with_schema(columns: &[String], values: &[Vec<String>], schema: &Schema) -> Result<Vec<datafusion::arrow::array::ArrayRef>> {
+        Self::string_rows_to_arrow_arrays_checked(columns, values, schema, &NumberFormat::default(), &[], &[], |_, _| {})
+    }
+
+    /// Parse one column of string rows, reporting values that `parse` rejects to `on_invalid`
+    fn parse_column<T>(
+        values: &[Vec<String>],
+        col_idx: usize,
+        tokens: &ColumnTokens,
+        parse: impl Fn(&str) -> Option<T>,
+        on_invalid: &mut impl FnMut(usize, usize),
+    ) -> Vec<Option<T>> {
+        values.iter()
+            .enumerate()
+            .map(|(row_idx, row)| match row.get(col_idx) {
+                Some(value) if !tokens.is_null(value) => {
+                    let parsed = parse(value);
+                    if parsed.is_none() {
+                        on_invalid(col_idx, row_idx);
                     }
+                    parsed
                 }
-                let array = StringArray::from(string_values);
-                arrays.push(Arc::new(array) as datafusion::arrow::array::ArrayRef);
-            }
-        }
-        
-        Ok(arrays)
+                _ => None,
+            })
+            .collect()
     }
 
-    fn string_rows_to_arrow_arrays_with_schema(&self, columns: &[String], values: &[Vec<String>], schema: &Schema) -> Result<Vec<datafusion::arrow::array::ArrayRef>> {
+    /// Like `string_rows_to_arrow_arrays_with_schema`, but reads numbers in `number_format`, dates with
+    /// the per-column strftime `datetime_patterns` and nulls and booleans with the per-column `tokens`
+    /// (`ColumnTokens::default()` where missing), and calls `on_invalid(col_idx, row_idx)` for every
+    /// value that isn't a null token and can't be parsed as its column's type (it is stored as null)
+    pub(crate) fn string_rows_to_arrow_arrays_checked(
+        _columns: &[String],
+        values: &[Vec<String>],
+        schema: &Schema,
+        number_format: &NumberFormat,
+        datetime_patterns: &[Option<String>],
+        column_tokens: &[ColumnTokens],
+        mut on_invalid: impl FnMut(usize, usize),
+    ) -> Result<Vec<datafusion::arrow::array::ArrayRef>> {
         let mut arrays = Vec::new();
+        let default_tokens = ColumnTokens::default();
         
         for (col_idx, field) in schema.fields().iter().enumerate() {
             let data_type = field.data_type();
+            let tokens = column_tokens.get(col_idx).unwrap_or(&default_tokens);
             
             match data_type {
                 DataType::Int64 => {
                     // Create Int64 array
                     let mut int_values = Vec::new();
-                    for row in values {
+                    for (row_idx, row) in values.iter().enumerate() {
                         if col_idx < row.len() {
                             let value = &row[col_idx];
-                            if value.is_empty() || value.to_lowercase() == "null" || value == "-" || value.to_lowercase() == "n/a" {
+                            if tokens.is_null(value) {
                                 int_values.push(None);
                             } else {
-                                match value.parse::<i64>() {
-                                    Ok(int_val) => int_values.push(Some(int_val)),
-                                    Err(_) => int_values.push(None),
+                                match number_format.parse_i64(value) {
+                                    Some(int_val) => int_values.push(Some(int_val)),
+                                    None => {
+                                        on_invalid(col_idx, row_idx);
+                                        int_values.push(None);
+                                    }
                                 }
                             }
                         } else {
@@ -821,15 +814,18 @@ impl Database {
                 DataType::Float64 => {
                     // Create Float64 array
                     let mut float_values = Vec::new();
-                    for row in values {
+                    for (row_idx, row) in values.iter().enumerate() {
                         if col_idx < row.len() {
                             let value = &row[col_idx];
-                            if value.is_empty() || value.to_lowercase() == "null" || value == "-" || value.to_lowercase() == "n/a" {
+                            if tokens.is_null(value) {
                                 float_values.push(None);
                             } else {
-                                match value.parse::<f64>() {
-                                    Ok(float_val) => float_values.push(Some(float_val)),
-                                    Err(_) => float_values.push(None),
+                                match number_format.parse_f64(value) {
+                                    Some(float_val) => float_values.push(Some(float_val)),
+                                    None => {
+                                        on_invalid(col_idx, row_idx);
+                                        float_values.push(None);
+                                    }
                                 }
                             }
                         } else {
@@ -842,17 +838,18 @@ impl Database {
                 DataType::Boolean => {
                     // Create Boolean array
                     let mut bool_values = Vec::new();
-                    for row in values {
+                    for (row_idx, row) in values.iter().enumerate() {
                         if col_idx < row.len() {
                             let value = &row[col_idx];
-                            if value.is_empty() || value.to_lowercase() == "null" || value == "-" || value.to_lowercase() == "n/a" {
+                            if tokens.is_null(value) {
                                 bool_values.push(None);
                             } else {
-                                let lower = value.to_lowercase();
-                                match lower.as_str() {
-                                    "true" | "1" | "yes" | "y" => bool_values.push(Some(true)),
-                                    "false" | "0" | "no" | "n" => bool_values.push(Some(false)),
-                                    _ => bool_values.push(None),
+                                match tokens.booleans.parse(value) {
+                                    Some(flag) => bool_values.push(Some(flag)),
+                                    None => {
+                                        on_invalid(col_idx, row_idx);
+                                        bool_values.push(None);
+                                    }
                                 }
                             }
                         } else {
@@ -863,16 +860,18 @@ impl Database {
                     arrays.push(Arc::new(array) as datafusion::arrow::array::ArrayRef);
                 },
                 DataType::Timestamp(unit, tz) => {
-                    // Create Timestamp array with the correct precision
+                    // Create Timestamp array with the correct precision; naive values are read in the column's timezone
+                    let pattern = datetime_patterns.get(col_idx).and_then(|pattern| pattern.as_deref());
+                    let zone = tz.as_deref().and_then(datetime_format::parse_timezone);
                     let mut timestamp_values = Vec::new();
-                    for row in values {
+                    for (row_idx, row) in values.iter().enumerate() {
                         if col_idx < row.len() {
                             let value = &row[col_idx];
-                            if value.is_empty() || value.to_lowercase() == "null" || value == "-" || value.to_lowercase() == "n/a" {
+                            if tokens.is_null(value) {
                                 timestamp_values.push(None);
                             } else {
-                                // Try to parse as datetime string first (YYYY-MM-DD HH:MM:SS)
-                                if let Some(timestamp) = Self::parse_datetime_string_to_timestamp(value, unit) {
+                                // Try to parse as datetime string first (the column's pattern, ISO-8601 or YYYY-MM-DD HH:MM:SS)
+                                if let Some(timestamp) = Self::parse_datetime_string_to_timestamp(value, unit, pattern, zone.as_ref()) {
                                     timestamp_values.push(Some(timestamp));
                                 } else if let Some(timestamp) = Self::parse_time_string_to_timestamp(value, unit) {
                                     // Try to parse time string in HH:MM:SS format
@@ -891,8 +890,8 @@ impl Database {
                                             timestamp_values.push(Some(converted_ts));
                                         }
                                         Err(_) => {
-                                            // If not a number, try to parse as date string
-                                            // For now, just store as null if we can't parse
+                                            // Not a datetime, time or number
+                                            on_invalid(col_idx, row_idx);
                                             timestamp_values.push(None);
                                         }
                                     }
@@ -906,19 +905,19 @@ impl Database {
                     // Create the appropriate timestamp array based on the unit
                     let array: datafusion::arrow::array::ArrayRef = match unit {
                         TimeUnit::Second => {
-                            let array = TimestampSecondArray::from(timestamp_values);
+                            let array = TimestampSecondArray::from(timestamp_values).with_timezone_opt(tz.clone());
                             Arc::new(array)
                         },
                         TimeUnit::Millisecond => {
-                            let array = TimestampMillisecondArray::from(timestamp_values);
+                            let array = TimestampMillisecondArray::from(timestamp_values).with_timezone_opt(tz.clone());
                             Arc::new(array)
                         },
                         TimeUnit::Microsecond => {
-                            let array = TimestampMicrosecondArray::from(timestamp_values);
+                            let array = TimestampMicrosecondArray::from(timestamp_values).with_timezone_opt(tz.clone());
                             Arc::new(array)
                         },
                         TimeUnit::Nanosecond => {
-                            let array = TimestampNanosecondArray::from(timestamp_values);
+                            let array = TimestampNanosecondArray::from(timestamp_values).with_timezone_opt(tz.clone());
                             Arc::new(array)
                         },
                     };
@@ -926,17 +925,26 @@ impl Database {
                 },
                 DataType::Date32 => {
                     // Create Date32 array
+                    let pattern = datetime_patterns.get(col_idx).and_then(|pattern| pattern.as_deref());
                     let mut date_values = Vec::new();
-                    for row in values {
+                    for (row_idx, row) in values.iter().enumerate() {
                         if col_idx < row.len() {
                             let value = &row[col_idx];
-                            if value.is_empty() || value.to_lowercase() == "null" || value == "-" || value.to_lowercase() == "n/a" {
+                            if tokens.is_null(value) {
                                 date_values.push(None);
                             } else {
-                                // Try to parse as date string (YYYY-MM-DD format)
-                                match Self::parse_date_string(value) {
+                                // Try to parse with the column's pattern, or as a date string (YYYY-MM-DD format)
+                                let days = match pattern {
+                                    Some(pattern) => datetime_format::parse_datetime(value, Some(pattern))
+                                        .map(|parsed| parsed.local.and_utc().timestamp().div_euclid(86_400) as i32),
+                                    None => Self::parse_date_string(value),
+                                };
+                                match days {
                                     Some(days) => date_values.push(Some(days)),
-                                    None => date_values.push(None),
+                                    None => {
+                                        on_invalid(col_idx, row_idx);
+                                        date_values.push(None);
+                                    }
                                 }
                             }
                         } else {
@@ -946,6 +954,89 @@ impl Database {
                     let array = Date32Array::from(date_values);
                     arrays.push(Arc::new(array) as datafusion::arrow::array::ArrayRef);
                 },
+                DataType::Int8 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_i64(value).and_then(|n| i8::try_from(n).ok()), &mut on_invalid);
+                    arrays.push(Arc::new(Int8Array::from(parsed)));
+                },
+                DataType::Int16 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_i64(value).and_then(|n| i16::try_from(n).ok()), &mut on_invalid);
+                    arrays.push(Arc::new(Int16Array::from(parsed)));
+                },
+                DataType::Int32 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_i64(value).and_then(|n| i32::try_from(n).ok()), &mut on_invalid);
+                    arrays.push(Arc::new(Int32Array::from(parsed)));
+                },
+                DataType::UInt8 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_u64(value).and_then(|n| u8::try_from(n).ok()), &mut on_invalid);
+                    arrays.push(Arc::new(UInt8Array::from(parsed)));
+                },
+                DataType::UInt16 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_u64(value).and_then(|n| u16::try_from(n).ok()), &mut on_invalid);
+                    arrays.push(Arc::new(UInt16Array::from(parsed)));
+                },
+                DataType::UInt32 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_u64(value).and_then(|n| u32::try_from(n).ok()), &mut on_invalid);
+                    arrays.push(Arc::new(UInt32Array::from(parsed)));
+                },
+                DataType::UInt64 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_u64(value), &mut on_invalid);
+                    arrays.push(Arc::new(UInt64Array::from(parsed)));
+                },
+                DataType::Float32 => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_f32(value), &mut on_invalid);
+                    arrays.push(Arc::new(Float32Array::from(parsed)));
+                },
+                DataType::Decimal128(precision, scale) => {
+                    // Decimals are read digit by digit, so money keeps its exact cents
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| number_format.parse_decimal(value, *precision, *scale), &mut on_invalid);
+                    let array = Decimal128Array::from(parsed)
+                        .with_precision_and_scale(*precision, *scale)
+                        .map_err(|e| LeafError::Custom(format!("Invalid decimal type for column {}: {}", field.name(), e)))?;
+                    arrays.push(Arc::new(array));
+                },
+                DataType::Time64(unit) => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| {
+                        let time = datetime_format::parse_time_of_day(value)?;
+                        let nanos = time.num_seconds_from_midnight() as i64 * 1_000_000_000 + time.nanosecond() as i64;
+                        Some(match unit {
+                            TimeUnit::Nanosecond => nanos,
+                            _ => nanos / 1_000,
+                        })
+                    }, &mut on_invalid);
+                    let array: datafusion::arrow::array::ArrayRef = match unit {
+                        TimeUnit::Nanosecond => Arc::new(Time64NanosecondArray::from(parsed)),
+                        _ => Arc::new(Time64MicrosecondArray::from(parsed)),
+                    };
+                    arrays.push(array);
+                },
+                DataType::Duration(unit) => {
+                    let parsed = Self::parse_column(values, col_idx, tokens, |value| {
+                        let nanos = duration_format::parse_duration(value)?;
+                        Some(match unit {
+                            TimeUnit::Second => nanos / 1_000_000_000,
+                            TimeUnit::Millisecond => nanos / 1_000_000,
+                            TimeUnit::Microsecond => nanos / 1_000,
+                            TimeUnit::Nanosecond => nanos,
+                        })
+                    }, &mut on_invalid);
+                    let array: datafusion::arrow::array::ArrayRef = match unit {
+                        TimeUnit::Second => Arc::new(DurationSecondArray::from(parsed)),
+                        TimeUnit::Millisecond => Arc::new(DurationMillisecondArray::from(parsed)),
+                        TimeUnit::Microsecond => Arc::new(DurationMicrosecondArray::from(parsed)),
+                        TimeUnit::Nanosecond => Arc::new(DurationNanosecondArray::from(parsed)),
+                    };
+                    arrays.push(array);
+                },
+                DataType::Dictionary(_, _) => {
+                    // Low-cardinality text: each distinct value is stored once; nulls are kept as empty strings like Utf8
+                    let array: DictionaryArray<Int32Type> = values.iter()
+                        .map(|row| match row.get(col_idx) {
+                            Some(value) if !tokens.is_null(value) => value.as_str(),
+                            _ => "",
+                        })
+                        .collect();
+                    arrays.push(Arc::new(array));
+                },
                 _ => {
                     // Default to String array for all other types
                     let mut string_values = Vec::new();
@@ -953,7 +1044,7 @@ impl Database {
                         if col_idx < row.len() {
                             let value = &row[col_idx];
                             // Treat null values as empty strings for display
-                            if value.is_empty() || value.to_lowercase() == "null" || value == "-" || value.to_lowercase() == "n/a" {
+                            if tokens.is_null(value) {
                                 string_values.push(String::new());
                             } else {
                                 string_values.push(value.clone());
@@ -985,7 +1076,13 @@ impl Database {
     fn arrow_type_to_sql_type(&self, arrow_type: &DataType) -> &'static str {
         match arrow_type {
             DataType::Int64 => "INTEGER",
+            DataType::Int8 | DataType::Int16 | DataType::Int32 |
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => "INTEGER",
             DataType::Float64 => "REAL",
+            DataType::Float32 => "REAL",
+            DataType::Decimal128(_, _) => "DECIMAL",
+            DataType::Time64(_) => "TIME",
+            DataType::Duration(_) => "INTERVAL",
             DataType::Boolean => "BOOLEAN",
             DataType::Utf8 => "TEXT",
             DataType::Timestamp(_, _) => "TIMESTAMP",
@@ -1037,273 +1134,227 @@ impl Database {
     }
 
     pub fn stream_insert_csv(&mut self, table_name: &str, csv_path: &Path, delimiter: char, has_header: bool) -> Result<()> {
-        // Read CSV with proper header handling
-        let mut rdr = csv::ReaderBuilder::new()
-            .delimiter(delimiter as u8)
-            .has_headers(has_header)
-            .from_path(csv_path)
-            .map_err(|e| LeafError::Custom(format!("Failed to read CSV: {}", e)))?;
-        
-        let mut rows = Vec::new();
-        let mut headers = Vec::new();
-        
-        if has_header {
-            // Get headers from the first row
-            match rdr.headers() {
-                Ok(header_result) => {
-                    headers = header_result.iter().map(|s| s.to_string()).collect();
-                }
-                Err(e) => {
-                    return Err(LeafError::Custom(format!("Failed to read CSV headers: {}", e)));
-                }
+        self.stream_insert_csv_with_policy(table_name, csv_path, delimiter, has_header, RejectPolicy::Fail, None)?;
+        Ok(())
+    }
+
+    /// Import a CSV into memory, handling rows with the wrong number of fields by `policy`.
+    /// Rejected rows are written to `rejects_path` when given. Files too large for memory
+    /// should go through `import_csv_to_file` instead.
+    pub fn stream_insert_csv_with_policy(
+        &mut self,
+        table_name: &str,
+        csv_path: &Path,
+        delimiter: char,
+        has_header: bool,
+        policy: RejectPolicy,
+        rejects_path: Option<&Path>,
+    ) -> Result<RejectCounts> {
+        let options = CsvIngestOptions {
+            delimiter,
+            has_header,
+            reject_policy: policy,
+            rejects_path: rejects_path.map(Path::to_path_buf),
+            ..CsvIngestOptions::default()
+        };
+        let mut reader = CsvBatchReader::open(csv_path, &options)?;
+        
+        // A table being replaced keeps its column count; rows that don't match are left to the reject policy
+        if let Some(existing_table) = self.registered_tables.get(table_name) {
+            let expected_columns = existing_table.schema.fields().len();
+            if reader.headers().len() != expected_columns {
+                return Err(LeafError::Custom(format!(
+                    "CSV has {} columns, but table schema expects {} columns",
+                    reader.headers().len(), expected_columns
+                )));
             }
         }
         
-        // Read data rows
-        for result in rdr.records() {
-            let record = result.map_err(|e| LeafError::Custom(format!("Failed to read CSV record: {}", e)))?;
-            let row: Vec<String> = record.iter().map(|s| s.to_string()).collect();
-            rows.push(row);
-        }
-        
-        // If no headers were found, create default column names
-        if headers.is_empty() {
-            headers = (0..rows.first().map(|r| r.len()).unwrap_or(0))
-                .map(|i| format!("col_{}", i))
-                .collect();
-        }
-        
-        // If the table already exists with a schema, we need to ensure the data matches
-        if let Some(existing_batch) = self.registered_tables.get(table_name) {
-            let existing_schema = existing_batch.schema();
-            let expected_columns = existing_schema.fields().len();
-            
-            // Ensure all rows have the expected number of columns
-            for (row_idx, row) in rows.iter().enumerate() {
-                if row.len() != expected_columns {
-                    return Err(LeafError::Custom(format!(
-                        "Row {} has {} columns, but table schema expects {} columns",
-                        row_idx + 1, row.len(), expected_columns
-                    )));
-                }
-            }
+        let mut batches = Vec::new();
+        while let Some(batch) = reader.next_batch()? {
+            batches.push(batch);
         }
-        
-        // Create schema with proper column names and type inference
-        let fields: Vec<Field> = headers.iter().enumerate()
-            .map(|(col_idx, name)| {
-                // Try to infer the data type from the first few rows
-                let data_type = if !rows.is_empty() {
-                    let mut is_numeric = true;
-                    let mut is_integer = true;
-                    
-                    // Check first 10 rows to infer type
-                    for row in rows.iter().take(10) {
-                        if col_idx < row.len() {
-                            let value: &str = &row[col_idx];
-                            if !value.is_empty() {
-                                // Check if it's numeric
-                                if !value.chars().all(|c| c.is_numeric() || c == '.' || c == '-') {
-                                    is_numeric = false;
-                                    break;
-                                }
-                                // Check if it's integer
-                                if value.contains('.') {
-                                    is_integer = false;
-                                }
-                            }
-                        }
-                    }
-                    
-                    if is_numeric {
-                        if is_integer {
-                            DataType::Int64
-                        } else {
-                            DataType::Float64
-                        }
-                    } else {
-                        DataType::Utf8
-                    }
-                } else {
-                    DataType::Utf8
-                };
-                
-                Field::new(name, data_type, true)
-            })
-            .collect();
-        
-        let schema = Schema::new(fields);
-        
-        // Convert rows to Arrow arrays
-        let arrays = self.string_rows_to_arrow_arrays(&headers, &rows)?;
-        
-        let batch = RecordBatch::try_new(Arc::new(schema), arrays)
-            .map_err(|e| LeafError::Custom(format!("Failed to create record batch: {}", e)))?;
+        reader.finish_rejects()?;
         
         // Register the table with proper schema, handling replacement if it already exists
-        self.register_or_replace_table(table_name, batch.clone())?;
+        self.insert_record_batches(table_name, reader.schema(), batches)?;
         
-        // Store in our cache
-        self.registered_tables.insert(table_name.to_string(), Arc::new(batch));
-        
-        Ok(())
+        Ok(reader.rejects())
     }
 
-    /// Enhanced CSV import that can skip lines and select a specific row as header
+    /// Enhanced CSV import that can skip lines and select a specific row as header.
+    /// Rows are converted to Arrow batches as they are read and the table is kept in memory.
     pub fn stream_insert_csv_with_header_row(&mut self, table_name: &str, csv_path: &Path, delimiter: char, header_row: usize) -> Result<char> {
-        use std::io::{BufRead, BufReader};
-        use std::fs::File;
-        
-        let file = File::open(csv_path)
-            .map_err(|e| LeafError::Custom(format!("Failed to open CSV file: {}", e)))?;
-        
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines()
-            .map(|line| line.unwrap_or_default())
-            .collect();
-        
-        // Validate header row (using original lines to match UI)
-        if header_row >= lines.len() {
-            return Err(LeafError::Custom("Header row exceeds file length".to_string()));
-        }
-        
-        // Start reading from the header row (using original lines to match UI)
-        let data_lines = lines.into_iter().skip(header_row).collect::<Vec<String>>();
-        
-        if data_lines.is_empty() {
-            return Err(LeafError::Custom("No data lines found after header row".to_string()));
-        }
-        
-        // Extract headers from the first row (which is now the header)
-        let headers: Vec<String> = if !data_lines.is_empty() {
-            let header_line = &data_lines[0];
-            Self::parse_csv_line(header_line, delimiter)
-        } else {
-            return Err(LeafError::Custom("No header row found".to_string()));
-        };
-        
-        // Infer delimiter from header if not already specified
-        let inferred_delimiter = if delimiter == ',' {
-            Self::infer_delimiter_from_header(&data_lines[0])
-        } else {
-            delimiter
-        };
-        
-        // Parse data rows (skip the header row) and filter using delimiter-based validation
-        let mut rows = Vec::new();
-        let expected_columns = headers.len();
-        
-        for line in data_lines.iter().skip(1) {
-            // Use delimiter-based validation instead of hardcoded string checks
-            if Self::is_valid_csv_line(line, inferred_delimiter, expected_columns) {
-                let row = Self::parse_csv_line(line, inferred_delimiter);
-                rows.push(row);
-            }
-        }
-        
-        // If no headers were found, create default column names
-        let mut final_headers = if headers.is_empty() {
-            (0..rows.first().map(|r| r.len()).unwrap_or(0))
-                .map(|i| format!("col_{}", i))
-                .collect()
-        } else {
-            headers
+        let options = CsvIngestOptions {
+            delimiter,
+            header_row,
+            ..CsvIngestOptions::default()
         };
-        
-        // Deduplicate column names to avoid DataFusion errors
-        let mut seen_names = std::collections::HashSet::new();
-        let mut deduplicated_headers = Vec::new();
-        
-        for (i, header) in final_headers.iter().enumerate() {
-            let mut unique_name = header.clone();
-            let mut counter = 1;
-            
-            while seen_names.contains(&unique_name) {
-                unique_name = format!("{}_{}", header, counter);
-                counter += 1;
-            }
-            
-            seen_names.insert(unique_name.clone());
-            deduplicated_headers.push(unique_name);
-        }
-        
-        final_headers = deduplicated_headers;
+        let mut reader = CsvBatchReader::open(csv_path, &options)?;
+        let schema = reader.schema();
         
         // If the table already exists with a schema, we need to ensure the data matches
-        if let Some(existing_batch) = self.registered_tables.get(table_name) {
-            let existing_schema = existing_batch.schema();
-            let expected_columns = existing_schema.fields().len();
-            
-            // Ensure all rows have the expected number of columns
-            for (row_idx, row) in rows.iter().enumerate() {
-                if row.len() != expected_columns {
-                    return Err(LeafError::Custom(format!(
-                        "Row {} has {} columns, but table schema expects {} columns",
-                        row_idx + 1, row.len(), expected_columns
-                    )));
-                }
+        if let Some(existing_table) = self.registered_tables.get(table_name) {
+            let expected_columns = existing_table.schema.fields().len();
+            if schema.fields().len() != expected_columns {
+                return Err(LeafError::Custom(format!(
+                    "CSV has {} columns, but table schema expects {} columns",
+                    schema.fields().len(), expected_columns
+                )));
             }
         }
         
-        // Use the same type inference system as the UI (with null awareness)
-        
-        // Convert rows to the format expected by TypeInferrer
-        let sample_data: Vec<Vec<String>> = rows.iter().take(20).cloned().collect();
-        
-        // Infer types using the null-aware system (same as UI)
-        // For now, use default null values since we don't have access to user config here
-        let default_null_values = vec!["".to_string(), "NULL".to_string(), "null".to_string(), "N/A".to_string(), "-".to_string()];
-        let inferred_types = TypeInferrer::infer_column_types_with_nulls(&final_headers, &sample_data, &default_null_values);
-        
-        // Convert to Arrow schema
-        let fields: Vec<Field> = inferred_types.iter()
-            .map(|(name, col_type)| {
-                let data_type = col_type.to_arrow_type();
-                Field::new(name, data_type, true)
-            })
-            .collect();
-        
-        let schema = Schema::new(fields);
-        
-        // Apply time normalization to columns that are detected as time types
-        let mut normalized_rows = rows.clone();
-        for (col_idx, (name, col_type)) in inferred_types.iter().enumerate() {
-            if col_type.is_time_type() {
-                // Extract column values
-                let mut column_values = Vec::new();
-                for row in &normalized_rows {
-                    if col_idx < row.len() {
-                        column_values.push(row[col_idx].clone());
-                    } else {
-                        column_values.push(String::new());
-                    }
-                }
-                
-                // Normalize the time column
-                let normalized_values = Self::normalize_time_column(&column_values);
-                
-                // Update the rows with normalized values
-                for (row_idx, normalized_value) in normalized_values.iter().enumerate() {
-                    if row_idx < normalized_rows.len() && col_idx < normalized_rows[row_idx].len() {
-                        normalized_rows[row_idx][col_idx] = normalized_value.clone();
-                    }
-                }
-            }
+        let mut batches = Vec::new();
+        while let Some(batch) = reader.next_batch()? {
+            batches.push(batch);
         }
         
-        // Convert rows to Arrow arrays using the same type inference
-        let arrays = self.string_rows_to_arrow_arrays_with_schema(&final_headers, &normalized_rows, &schema)?;
-        
-        let batch = RecordBatch::try_new(Arc::new(schema), arrays)
-            .map_err(|e| LeafError::Custom(format!("Failed to create record batch: {}", e)))?;
-        
         // Register the table with proper schema, handling replacement if it already exists
-        self.register_or_replace_table(table_name, batch.clone())?;
+        self.insert_record_batches(table_name, schema, batches)?;
         
-        // Store in our cache
-        self.registered_tables.insert(table_name.to_string(), Arc::new(batch));
+        Ok(reader.delimiter())
+    }
+
+    /// Import a CSV straight into `<table_name>.<ext>` in `output_dir`, one batch at a time,
+    /// and register the table over the written file. Memory use stays bounded by the batch size
+    /// no matter how large the CSV is. `on_progress` is called after every batch.
+    /// Rows rejected by the options' reject policy go to `rejects/<table_name>_rejects.csv` in `output_dir`.
+    pub fn import_csv_to_file(
+        &mut self,
+        table_name: &str,
+        csv_path: &Path,
+        output_dir: &Path,
+        format: TableFormat,
+        options: &CsvIngestOptions,
+        on_progress: impl FnMut(&IngestProgress),
+    ) -> Result<CsvIngestSummary> {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| LeafError::Custom(format!("Failed to create directory: {}", e)))?;
         
-        Ok(inferred_delimiter)
+        let output_path = output_dir.join(format.file_name(table_name));
+        let options = CsvIngestOptions {
+            rejects_path: Some(csv_ingest::rejects_path_for(output_dir, table_name)),
+            ..options.clone()
+        };
+        let summary = csv_ingest::ingest_csv_to_file(csv_path, &output_path, format, &options, on_progress)?;
+        
+        self.register_file_table(table_name, &summary.output_path, format.source_format())?;
+
+        Ok(summary)
+    }
+
+    /// Import many CSV files, e.g. a folder of hourly shards, as one table (see `csv_union::ingest_union_to_file`)
+    /// and register it. Each file's rejected rows go to its own `rejects/<file>_<table_name>_rejects.csv` in `output_dir`.
+    pub fn import_csv_union_to_file(
+        &mut self,
+        table_name: &str,
+        csv_paths: &[PathBuf],
+        output_dir: &Path,
+        format: TableFormat,
+        union: &UnionOptions,
+        on_progress: impl FnMut(&IngestProgress),
+    ) -> Result<CsvUnionSummary> {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| LeafError::Custom(format!("Failed to create directory: {}", e)))?;
+
+        let output_path = output_dir.join(format.file_name(table_name));
+        let union = UnionOptions {
+            csv: CsvIngestOptions {
+                rejects_path: Some(csv_ingest::rejects_path_for(output_dir, table_name)),
+                ..union.csv.clone()
+            },
+            ..union.clone()
+        };
+        let summary = csv_union::ingest_union_to_file(csv_paths, &output_path, format, &union, on_progress)?;
+
+        self.register_file_table(table_name, &output_path, format.source_format())?;
+
+        Ok(summary)
+    }
+
+    /// Import a JSON array or NDJSON file as a table file in `output_dir` and register it
+    /// (see `json_ingest::ingest_json_to_file`)
+    pub fn import_json_to_file(
+        &mut self,
+        table_name: &str,
+        json_path: &Path,
+        output_dir: &Path,
+        format: TableFormat,
+        options: &JsonIngestOptions,
+        on_progress: impl FnMut(&IngestProgress),
+    ) -> Result<JsonIngestSummary> {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| LeafError::Custom(format!("Failed to create directory: {}", e)))?;
+
+        let output_path = output_dir.join(format.file_name(table_name));
+        let summary = json_ingest::ingest_json_to_file(json_path, &output_path, format, options, on_progress)?;
+
+        self.register_file_table(table_name, &summary.output_path, format.source_format())?;
+
+        Ok(summary)
+    }
+
+    /// Import a sheet of a spreadsheet workbook as a table file in `output_dir` and register it.
+    /// The sheet's cells go through the same type inference, conversion and reject handling as a CSV file.
+    pub fn import_sheet_to_file(
+        &mut self,
+        table_name: &str,
+        selection: &SheetSelection,
+        output_dir: &Path,
+        format: TableFormat,
+        options: &CsvIngestOptions,
+        on_progress: impl FnMut(&IngestProgress),
+    ) -> Result<CsvIngestSummary> {
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| LeafError::Custom(format!("Failed to create directory: {}", e)))?;
+
+        let output_path = output_dir.join(format.file_name(table_name));
+        let options = CsvIngestOptions {
+            rejects_path: Some(csv_ingest::rejects_path_for(output_dir, table_name)),
+            ..options.clone()
+        };
+        let summary = spreadsheet::ingest_sheet_to_file(selection, &output_path, format, &options, on_progress)?;
+
+        self.register_file_table(table_name, &summary.output_path, format.source_format())?;
+
+        Ok(summary)
+    }
+
+    /// Append a CSV to an existing table instead of replacing it, matching columns by name
+    /// (see `csv_ingest::append_csv_to_file`). The table is the one registered under `table_name`,
+    /// or else its Arrow or Parquet file in `output_dir`. The combined table is written to
+    /// `<table_name>.arrow` in `output_dir` and registered over it; a Parquet file it came from there is removed.
+    pub fn append_csv_to_table(
+        &mut self,
+        table_name: &str,
+        csv_path: &Path,
+        output_dir: &Path,
+        options: &CsvIngestOptions,
+        add_new_columns: bool,
+        on_progress: impl FnMut(&IngestProgress),
+    ) -> Result<CsvAppendSummary> {
+        let table_path = match self.registered_tables.get(table_name).map(|table| &table.source) {
+            Some(TableSource::File { path, format: SourceFormat::Arrow | SourceFormat::Parquet, .. }) => path.clone(),
+            // In-memory and CSV-backed tables are saved first so they can be read back a batch at a time
+            Some(_) => self.save_table_as(table_name, output_dir, TableFormat::Arrow)?,
+            None => table_format::find_table_file(output_dir, table_name)
+                .ok_or_else(|| LeafError::Custom(format!("There is no table '{}' to append to", table_name)))?,
+        };
+
+        let output_path = output_dir.join(TableFormat::Arrow.file_name(table_name));
+        let options = CsvIngestOptions {
+            rejects_path: Some(csv_ingest::rejects_path_for(output_dir, table_name)),
+            ..options.clone()
+        };
+        let summary = csv_ingest::append_csv_to_file(csv_path, &table_path, &output_path, &options, add_new_columns, on_progress)?;
+
+        if table_path.parent() == Some(output_dir) && !Self::is_same_file(&table_path, &output_path) {
+            std::fs::remove_file(&table_path)
+                .map_err(|e| LeafError::Custom(format!("Failed to remove {}: {}", table_path.display(), e)))?;
+        }
+        self.register_file_table(table_name, &output_path, SourceFormat::Arrow)?;
+
+        Ok(summary)
     }
 
     pub fn begin_transaction(&mut self) -> Result<()> {
@@ -1344,53 +1395,46 @@ impl Database {
     }
 
     pub fn insert_record_batch(&mut self, table_name: &str, batch: &RecordBatch) -> Result<()> {
+        self.insert_record_batches(table_name, batch.schema(), vec![batch.clone()])
+    }
+
+    /// Register a table made of several record batches, replacing any table with the same name
+    pub fn insert_record_batches(&mut self, table_name: &str, schema: SchemaRef, batches: Vec<RecordBatch>) -> Result<()> {
         // Clear cache for this table since data changed
         self.batch_cache.remove(table_name);
         
-        // Register the batch as a table
-        self.ctx.register_batch(table_name, batch.clone())
-            .map_err(|e| LeafError::Custom(format!("Failed to register record batch: {}", e)))?;
+        let table = RegisteredTable { schema, source: TableSource::Memory(batches) };
+        self.register_or_replace_table(table_name, &table)?;
         
         // Store in our cache
-        self.registered_tables.insert(table_name.to_string(), Arc::new(batch.clone()));
+        self.registered_tables.insert(table_name.to_string(), table);
         
         Ok(())
     }
 
     pub fn load_table_arrow_batch(&mut self, table_name: &str) -> Result<Arc<RecordBatch>> {
-        if let Some(batch) = self.registered_tables.get(table_name) {
-            return Ok(batch.clone());
-        }
-        
-        // Try to query the table to get its data
-        let query = format!("SELECT * FROM '{}'", table_name);
-        let ctx = self.ctx.clone();
-        
-        let result = self.runtime.block_on(async {
-            ctx.sql(&query).await
-        }).map_err(|e| LeafError::Custom(format!("Failed to load table: {}", e)))?;
-        
-        let record_batches = self.runtime.block_on(async {
-            result.collect().await
-        }).map_err(|e| LeafError::Custom(format!("Failed to collect table data: {}", e)))?;
-        
-        if record_batches.is_empty() {
-            return Err(LeafError::Custom("No data found in table".to_string()));
+        if self.registered_tables.contains_key(table_name) {
+            return self.get_table_arrow_batch(table_name);
         }
         
-        let batch = Arc::new(record_batches[0].clone());
-        self.registered_tables.insert(table_name.to_string(), batch.clone());
+        let batch = Arc::new(self.collect_table(table_name)?);
+        self.registered_tables.insert(table_name.to_string(), RegisteredTable::from_batch(batch.as_ref().clone()));
         
         Ok(batch)
     }
 
     /// Non-mutable version of load_table_arrow_batch for use with Arc<Database>
     pub fn get_table_arrow_batch(&self, table_name: &str) -> Result<Arc<RecordBatch>> {
-        if let Some(batch) = self.registered_tables.get(table_name) {
-            return Ok(batch.clone());
+        if let Some(RegisteredTable { schema, source: TableSource::Memory(batches) }) = self.registered_tables.get(table_name) {
+            return Ok(Arc::new(RegisteredTable::concat_batches(schema, batches)?));
         }
         
-        // Try to query the table to get its data
+        // File-backed tables are read from disk here; they are never cached in memory
+        Ok(Arc::new(self.collect_table(table_name)?))
+    }
+
+    /// Query every row of a table from the DataFusion context as a single batch
+    fn collect_table(&self, table_name: &str) -> Result<RecordBatch> {
         let query = format!("SELECT * FROM '{}'", table_name);
         let ctx = self.ctx.clone();
         
@@ -1398,6 +1442,7 @@ impl Database {
             ctx.sql(&query).await
         }).map_err(|e| LeafError::Custom(format!("Failed to load table: {}", e)))?;
         
+        let schema: SchemaRef = Arc::new(result.schema().into());
         let record_batches = self.runtime.block_on(async {
             result.collect().await
         }).map_err(|e| LeafError::Custom(format!("Failed to collect table data: {}", e)))?;
@@ -1406,12 +1451,7 @@ impl Database {
             return Err(LeafError::Custom("No data found in table".to_string()));
         }
         
-        let batch = Arc::new(record_batches[0].clone());
-        
-        // Note: We can't insert into registered_tables here since we don't have mutable access
-        // This is a limitation when working with Arc<Database>
-        
-        Ok(batch)
+        RegisteredTable::concat_batches(&schema, &record_batches)
     }
 
     fn warn_if_cloud_folder(_path: &Path) {
@@ -1420,86 +1460,127 @@ impl Database {
 
     // === HYBRID PERSISTENCE METHODS ===
 
-    /// Save a table in both Arrow IPC (fast cache) and Parquet (persistent) formats
-    pub fn save_table_dual(&mut self, table_name: &str, base_path: &Path) -> Result<()> {
-        // Get the table data as RecordBatch
-        let batch = self.load_table_arrow_batch(table_name)?;
-        
+    /// Save a table into a directory as `<table_name>.<ext>` in the given format
+    pub fn save_table_as(&mut self, table_name: &str, base_path: &Path, format: TableFormat) -> Result<PathBuf> {
         // Create directory if it doesn't exist
         std::fs::create_dir_all(base_path)
             .map_err(|e| LeafError::Custom(format!("Failed to create directory: {}", e)))?;
         
-        // Save as Arrow IPC (fast cache)
-        let arrow_path = base_path.join(format!("{}.arrow", table_name));
-        self.save_table_arrow_ipc(table_name, &arrow_path)?;
-        
-        // For now, skip Parquet due to version conflicts
-        // TODO: Add Parquet support when Arrow versions are compatible
-                // Table saved successfully
+        let path = base_path.join(format.file_name(table_name));
+        self.save_table_file(table_name, &path, format)?;
         
-        Ok(())
+        Ok(path)
     }
 
     /// Save a table as Arrow IPC file (fast cache format)
     pub fn save_table_arrow_ipc(&mut self, table_name: &str, path: &Path) -> Result<()> {
-        use datafusion::arrow::ipc::writer::FileWriter;
-        use std::fs::File;
-        
-        let batch = self.load_table_arrow_batch(table_name)?;
-        
-        let file = File::create(path)
-            .map_err(|e| LeafError::Custom(format!("Failed to create Arrow IPC file: {}", e)))?;
+        self.save_table_file(table_name, path, TableFormat::Arrow)
+    }
+
+    /// Save a table as a Parquet file with the given compression
+    pub fn save_table_parquet(&mut self, table_name: &str, path: &Path, compression: ParquetCompression) -> Result<()> {
+        self.save_table_file(table_name, path, TableFormat::Parquet(compression))
+    }
+
+    fn save_table_file(&mut self, table_name: &str, path: &Path, format: TableFormat) -> Result<()> {
+        let table = match self.registered_tables.get(table_name) {
+            Some(table) => table.clone(),
+            None => RegisteredTable::from_batch(self.load_table_arrow_batch(table_name)?.as_ref().clone()),
+        };
         
-        let mut writer = FileWriter::try_new(file, &batch.schema())
-            .map_err(|e| LeafError::Custom(format!("Failed to create Arrow IPC writer: {}", e)))?;
+        match &table.source {
+            // Keep the original batch boundaries when writing an in-memory table
+            TableSource::Memory(batches) => table_format::write_batches(path, &table.schema, batches, format),
+            TableSource::File { path: source_path, .. } => {
+                // The table already lives in this file; rewriting it would truncate the data being read
+                if Self::is_same_file(source_path, path) {
+                    return Ok(());
+                }
+                self.stream_table_to_file(table_name, &table.schema, path, format)
+            }
+        }
+    }
+
+    fn is_same_file(a: &Path, b: &Path) -> bool {
+        match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Copy a table into a file one batch at a time, so file-backed tables never load fully into memory
+    fn stream_table_to_file(&self, table_name: &str, schema: &SchemaRef, path: &Path, format: TableFormat) -> Result<()> {
+        let ctx = self.ctx.clone();
+        let mut writer = TableWriter::try_new(path, schema, format)?;
         
-        writer.write(&batch)
-            .map_err(|e| LeafError::Custom(format!("Failed to write Arrow IPC data: {}", e)))?;
+        self.runtime.block_on(async {
+            let df = ctx.table(table_name).await
+                .map_err(|e| LeafError::Custom(format!("Failed to read table {}: {}", table_name, e)))?;
+            let mut stream = df.execute_stream().await
+                .map_err(|e| LeafError::Custom(format!("Failed to scan table {}: {}", table_name, e)))?;
+            
+            while let Some(batch) = stream.next().await {
+                let batch = batch
+                    .map_err(|e| LeafError::Custom(format!("Failed to read batch from {}: {}", table_name, e)))?;
+                writer.write(&batch)?;
+            }
+            Ok::<(), LeafError>(())
+        })?;
         
         writer.finish()
-            .map_err(|e| LeafError::Custom(format!("Failed to finish Arrow IPC file: {}", e)))?;
-        
-        Ok(())
     }
 
-    /// Load a table from Arrow IPC file (fast cache format)
+    /// Register a table over an Arrow IPC file; rows stay on disk until queried
     pub fn load_table_arrow_ipc(&mut self, table_name: &str, path: &Path) -> Result<()> {
-        use datafusion::arrow::ipc::reader::FileReader;
-        use std::fs::File;
-        
-        let file = File::open(path)
-            .map_err(|e| LeafError::Custom(format!("Failed to open Arrow IPC file: {}", e)))?;
-        
-        let reader = FileReader::try_new(file, None)
-            .map_err(|e| LeafError::Custom(format!("Failed to create Arrow IPC reader: {}", e)))?;
-        
-        let mut batches = Vec::new();
-        for batch_result in reader {
-            let batch = batch_result
-                .map_err(|e| LeafError::Custom(format!("Failed to read Arrow IPC batch: {}", e)))?;
-            batches.push(batch);
-        }
-        
-        if batches.is_empty() {
-            return Err(LeafError::Custom("No data found in Arrow IPC file".to_string()));
+        self.register_file_table(table_name, path, SourceFormat::Arrow)
+    }
+
+    /// Register a table over a Parquet file; rows stay on disk until queried
+    pub fn load_table_parquet(&mut self, table_name: &str, path: &Path) -> Result<()> {
+        self.register_file_table(table_name, path, SourceFormat::Parquet)
+    }
+
+    /// Register a table over a CSV file; rows stay on disk until queried
+    pub fn load_table_csv(&mut self, table_name: &str, path: &Path, delimiter: char, has_header: bool) -> Result<()> {
+        // DataFusion only reads UTF-8, so files in other encodings are transcoded into memory, with or without a header
+        if text_encoding::detect_encoding(path)? != encoding_rs::UTF_8 {
+            self.stream_insert_csv_with_policy(table_name, path, delimiter, has_header, RejectPolicy::default(), None)?;
+            return Ok(());
         }
+        self.register_file_table(table_name, path, SourceFormat::Csv { delimiter: delimiter as u8, has_header })
+    }
+
+    /// Register a DataFusion listing table over a file, replacing any table with the same name.
+    /// Queries read only the columns and row groups they need.
+    pub fn register_file_table(&mut self, table_name: &str, path: &Path, format: SourceFormat) -> Result<()> {
+        // Clear cache for this table since data changed
+        self.batch_cache.remove(table_name);
         
-        // Combine all batches into one (for simplicity)
-        let combined_batch = if batches.len() == 1 {
-            batches.remove(0)
-        } else {
-            // For multiple batches, we'd need to concatenate them
-            // For now, just use the first batch
-            batches.remove(0)
+        let url = Self::listing_table_url(path)?;
+        let options = format.listing_options(path);
+        let state = self.ctx.state();
+        let schema = self.runtime.block_on(async {
+            options.infer_schema(&state, &url).await
+        }).map_err(|e| LeafError::Custom(format!("Failed to read schema from {}: {}", path.display(), e)))?;
+        
+        // Arrow and Parquet record their row count in metadata; CSV is left uncounted rather than scanned
+        let row_count = table_format::file_row_count(path, format)?;
+        let table = RegisteredTable {
+            schema,
+            source: TableSource::File {
+                path: path.to_path_buf(),
+                format,
+                row_count,
+            },
         };
+        self.register_or_replace_table(table_name, &table)?;
         
-        // Register the table
-        self.insert_record_batch(table_name, &combined_batch)?;
+        self.registered_tables.insert(table_name.to_string(), table);
         
         Ok(())
     }
 
-    /// Load all tables from a directory (Arrow IPC format only for now)
+    /// Register every table file in a directory (Arrow IPC, Parquet and CSV) without loading its rows
     pub fn load_all_tables_from_directory(&mut self, directory: &Path) -> Result<Vec<String>> {
         let mut loaded_tables = Vec::new();
         
@@ -1507,6 +1588,7 @@ impl Database {
             return Ok(loaded_tables);
         }
         
+        let mut table_files = Vec::new();
         for entry in std::fs::read_dir(directory)
             .map_err(|e| LeafError::Custom(format!("Failed to read directory: {}", e)))? {
             
@@ -1514,20 +1596,45 @@ impl Database {
                 .map_err(|e| LeafError::Custom(format!("Failed to read directory entry: {}", e)))?;
             
             let path = entry.path();
-            let file_name = path.file_name()
+            // Rows quarantined by a CSV import are not a table of their own
+            if csv_ingest::is_rejects_file(&path) {
+                continue;
+            }
+            if let Some(format) = SourceFormat::from_path(&path) {
+                table_files.push((path, format));
+            }
+        }
+        
+        // Arrow files sort first, then Parquet, then CSV, so the fastest format wins when a table exists in several
+        let priority = |format: &SourceFormat| match format {
+            SourceFormat::Arrow => 0,
+            SourceFormat::Parquet => 1,
+            SourceFormat::Csv { .. } => 2,
+        };
+        table_files.sort_by_key(|(path, format)| (priority(format), path.clone()));
+        
+        for (path, format) in table_files {
+            let table_name = path.file_stem()
                 .and_then(|n| n.to_str())
-                .ok_or_else(|| LeafError::Custom("Invalid file name".to_string()))?;
+                .ok_or_else(|| LeafError::Custom("Invalid file name".to_string()))?
+                .to_string();
             
-            if let Some(table_name) = file_name.strip_suffix(".arrow") {
-                // Load Arrow IPC files
-                match self.load_table_arrow_ipc(table_name, &path) {
-                    Ok(_) => {
-                        loaded_tables.push(table_name.to_string());
-                        // Table loaded successfully
-                    }
-                    Err(e) => {
-                        eprintln!("[Database] Failed to load table '{}' from Arrow IPC: {}", table_name, e);
-                    }
+            if loaded_tables.contains(&table_name) {
+                eprintln!("[Database] Skipping {:?}: table '{}' was already loaded from another file", path, table_name);
+                continue;
+            }
+            
+            // CSVs go through the same decoding as imports, so files that aren't UTF-8 read correctly
+            let loaded = match format {
+                SourceFormat::Csv { delimiter, has_header } => self.load_table_csv(&table_name, &path, delimiter as char, has_header),
+                format => self.register_file_table(&table_name, &path, format),
+            };
+            match loaded {
+                Ok(_) => {
+                    loaded_tables.push(table_name);
+                }
+                Err(e) => {
+                    eprintln!("[Database] Failed to load table '{}' from {:?}: {}", table_name, path, e);
                 }
             }
         }
@@ -1535,15 +1642,15 @@ impl Database {
         Ok(loaded_tables)
     }
 
-    /// Save all current tables in Arrow IPC format
-    pub fn save_all_tables(&mut self, base_path: &Path) -> Result<Vec<String>> {
+    /// Save all current tables in the given format
+    pub fn save_all_tables(&mut self, base_path: &Path, format: TableFormat) -> Result<Vec<String>> {
         let mut saved_tables = Vec::new();
         
         // Collect table names first to avoid borrowing issues
         let table_names: Vec<String> = self.registered_tables.keys().cloned().collect();
         
         for table_name in table_names {
-            match self.save_table_dual(&table_name, base_path) {
+            match self.save_table_as(&table_name, base_path, format) {
                 Ok(_) => {
                     saved_tables.push(table_name);
                 }
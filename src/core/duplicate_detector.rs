@@ -5,7 +5,9 @@ use datafusion::arrow::array::{StringArray, Int32Array, Int64Array, Float64Array
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::arrow::compute;
+use datafusion::arrow::util::display::array_value_to_string;
 use crate::core::error::Result;
+use crate::core::table_format::{self, TableFormat};
 
 /// Configuration for duplicate group detection
 #[derive(Debug, Clone)]
@@ -141,6 +143,15 @@ impl DuplicateDetector {
                     }
                 }
             }
+            data_type if data_type.is_integer() || matches!(data_type, DataType::Dictionary(_, _)) => {
+                // Narrower integer widths and dictionary-encoded strings
+                for row_idx in 0..group_col.len() {
+                    if !group_col.is_null(row_idx) {
+                        let group_id = array_value_to_string(group_col, row_idx)?;
+                        groups.entry(group_id).or_insert_with(Vec::new).push(row_idx);
+                    }
+                }
+            }
             _ => {
                 return Err(crate::core::error::LeafError::Custom(
                     format!("Group column must be string or integer type, found: {:?}", group_col.data_type())
@@ -359,7 +370,7 @@ impl DuplicateDetector {
                 if column.is_null(row_idx) {
                     Ok("NULL".to_string())
                 } else {
-                    Ok(format!("{:?}", column))
+                    Ok(array_value_to_string(column, row_idx)?)
                 }
             }
         }
@@ -371,6 +382,17 @@ impl DuplicateDetector {
         batch: &RecordBatch,
         result: &DuplicateDetectionResult,
         output_path: &std::path::Path,
+    ) -> Result<usize> {
+        self.create_clean_file(batch, result, output_path, TableFormat::Arrow)
+    }
+
+    /// Create a new table file in the given format with duplicate groups removed
+    pub fn create_clean_file(
+        &self,
+        batch: &RecordBatch,
+        result: &DuplicateDetectionResult,
+        output_path: &std::path::Path,
+        format: TableFormat,
     ) -> Result<usize> {
         // Collect all row indices to keep
         let mut rows_to_keep = Vec::new();
@@ -428,11 +450,8 @@ impl DuplicateDetector {
         
         let new_batch = RecordBatch::try_new(batch.schema().clone(), new_columns)?;
         
-        // Write to Arrow file
-        let file = std::fs::File::create(output_path)?;
-        let mut writer = datafusion::arrow::ipc::writer::FileWriter::try_new(file, &new_batch.schema())?;
-        writer.write(&new_batch)?;
-        writer.finish()?;
+        // Write to the output file
+        table_format::write_batches(output_path, &new_batch.schema(), &[new_batch], format)?;
         
         Ok(rows_to_keep.len())
     }
@@ -1,13 +1,12 @@
-use crate::core::Database;
+use crate::core::{Database, TableFormat};
+use crate::core::table_format;
 use crate::ui::{EnhancedGroupingRequest, GroupingConfig, GroupingRule};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::arrow::array::{Array, ArrayRef, Int64Array, StringArray, TimestampNanosecondArray};
 use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
-use datafusion::arrow::ipc::writer::FileWriter;
 use anyhow::{Result, anyhow};
 use std::path::Path;
 use std::sync::Arc;
-use std::fs::File;
 
 pub struct EnhancedGroupingProcessor;
 
@@ -34,19 +33,15 @@ impl EnhancedGroupingProcessor {
         
         // Generate output filename
         let output_filename = if let Some(custom_name) = &request.output_filename {
-            // Ensure .arrow extension
-            if custom_name.ends_with(".arrow") {
-                custom_name.clone()
-            } else {
-                format!("{}.arrow", custom_name)
-            }
+            // Ensure the extension matches the output format
+            request.output_format.file_name(custom_name)
         } else {
-            self.generate_output_filename(&request.table_name, &request.configurations)
+            self.generate_output_filename(&request.table_name, &request.configurations, request.output_format)
         };
         let output_path = output_dir.join(&output_filename);
         
         // Save the transformed data
-        self.save_batch(&current_batch, &output_path)?;
+        table_format::write_batches(&output_path, &current_batch.schema(), &[current_batch], request.output_format)?;
         
         Ok(output_filename)
     }
@@ -299,7 +294,8 @@ impl EnhancedGroupingProcessor {
                     }
                 }
             }
-            _ => Err(anyhow!("Unsupported data type: {:?}", array.data_type()))
+            // Narrow integers, decimals, times of day, dictionaries and the rest use Arrow's formatting
+            _ => Ok(datafusion::arrow::util::display::array_value_to_string(array, idx)?)
         }
     }
     
@@ -307,6 +303,7 @@ impl EnhancedGroupingProcessor {
         &self,
         table_name: &str,
         configurations: &[GroupingConfig],
+        output_format: TableFormat,
     ) -> String {
         let base_name = table_name.trim_end_matches(".arrow")
             .trim_end_matches(".csv")
@@ -323,14 +320,6 @@ impl EnhancedGroupingProcessor {
             suffixes.join("_")
         };
         
-        format!("{}_with_{}.arrow", base_name, suffix)
-    }
-    
-    fn save_batch(&self, batch: &RecordBatch, output_path: &Path) -> Result<()> {
-        let file = File::create(output_path)?;
-        let mut writer = FileWriter::try_new(file, batch.schema().as_ref())?;
-        writer.write(batch)?;
-        writer.finish()?;
-        Ok(())
+        output_format.file_name(&format!("{}_with_{}", base_name, suffix))
     }
 }
\ No newline at end of file
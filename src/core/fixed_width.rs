@@ -0,0 +1,181 @@
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use encoding_rs::Encoding;
+use crate::core::csv_source::CsvSource;
+use crate::core::csv_tokenizer::{CsvRecord, RecordSource, CANDIDATE_DELIMITERS};
+use crate::core::error::{Result, LeafError};
+
+/// Lines read to guess where the columns of a fixed-width file start
+pub const SAMPLE_LINES: usize = 200;
+
+/// Extensions of files that may be fixed-width; others, like `.csv` and `.tsv`, are always read as delimited
+const FIXED_WIDTH_EXTENSIONS: [&str; 3] = ["txt", "dat", "prn"];
+
+/// The first `max_lines` lines of a file as text, decoded and without line endings
+pub fn sample_lines(path: &Path, encoding: Option<&'static Encoding>, max_lines: usize) -> Result<Vec<String>> {
+    let mut records = FixedWidthRecords::open(path, &[], encoding)?;
+    let mut lines = Vec::new();
+    while lines.len() < max_lines && records.read_line()? {
+        lines.push(records.current_line().to_string());
+    }
+    Ok(lines)
+}
+
+/// Guess column boundaries from how the lines line up: a column starts wherever some line has text
+/// right after a character position that is blank in every line. Returns the character positions
+/// where the second and later columns start; blank lines are ignored.
+pub fn guess_boundaries(lines: &[String]) -> Vec<usize> {
+    let mut occupied: Vec<bool> = Vec::new();
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        for (pos, c) in line.chars().enumerate() {
+            if pos >= occupied.len() {
+                occupied.resize(pos + 1, false);
+            }
+            occupied[pos] |= !c.is_whitespace();
+        }
+    }
+    let first = occupied.iter().position(|&filled| filled).unwrap_or(0);
+    (first + 1..occupied.len())
+        .filter(|&pos| occupied[pos] && !occupied[pos - 1])
+        .collect()
+}
+
+/// Column boundaries for a file that looks fixed-width rather than delimited: it is a `.txt`, `.dat` or `.prn`
+/// file or has no extension, its first line has none of the usual delimiters, its lines line up in at least
+/// two columns and its header names start at those columns. `None` otherwise.
+pub fn detect(path: &Path, encoding: Option<&'static Encoding>) -> Result<Option<Vec<usize>>> {
+    if !may_be_fixed_width(path) {
+        return Ok(None);
+    }
+    let lines = sample_lines(path, encoding, SAMPLE_LINES)?;
+    let Some(header) = lines.iter().find(|line| !line.trim().is_empty()) else {
+        return Ok(None);
+    };
+    if header.contains(CANDIDATE_DELIMITERS) {
+        return Ok(None);
+    }
+    // A space inside a single column's values, like "2024-01-01 10:00:00", is not a gap in its header
+    let header: Vec<char> = header.chars().collect();
+    let starts_name = |pos: usize| {
+        header.get(pos).is_some_and(|c| !c.is_whitespace()) && header.get(pos - 1).is_some_and(|c| c.is_whitespace())
+    };
+    let boundaries = guess_boundaries(&lines);
+    Ok(Some(boundaries).filter(|boundaries| !boundaries.is_empty() && boundaries.iter().all(|&pos| starts_name(pos))))
+}
+
+/// Whether a file's name allows it to be fixed-width, looking past `.gz` and `.zst`
+fn may_be_fixed_width(path: &Path) -> bool {
+    let name = path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = name.trim_end_matches(".gz").trim_end_matches(".zst");
+    match Path::new(name).extension() {
+        Some(extension) => FIXED_WIDTH_EXTENSIONS.iter().any(|fixed| extension == *fixed),
+        None => true,
+    }
+}
+
+/// Cut a line at the boundaries (character positions) and trim the padding off each field.
+/// Fields past the end of a short line are empty.
+pub fn split_line(line: &str, boundaries: &[usize]) -> Vec<String> {
+    let offsets: Vec<usize> = if line.is_ascii() {
+        Vec::new()
+    } else {
+        line.char_indices().map(|(offset, _)| offset).collect()
+    };
+    let byte_offset = |pos: usize| if offsets.is_empty() {
+        pos.min(line.len())
+    } else {
+        offsets.get(pos).copied().unwrap_or(line.len())
+    };
+    std::iter::once(0).chain(boundaries.iter().copied())
+        .zip(boundaries.iter().copied().map(Some).chain(std::iter::once(None)))
+        .map(|(start, end)| {
+            let start = byte_offset(start);
+            let end = end.map_or(line.len(), byte_offset);
+            line[start..end.max(start)].trim().to_string()
+        })
+        .collect()
+}
+
+/// Boundaries typed as a list of positions, like `8, 20, 31`; sorted and without duplicates
+pub fn parse_boundaries(text: &str) -> Result<Vec<usize>> {
+    let mut boundaries = text.split([',', ' '])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<usize>()
+            .ok()
+            .filter(|&pos| pos > 0)
+            .ok_or_else(|| LeafError::Custom(format!("'{}' is not a column position", part))))
+        .collect::<Result<Vec<usize>>>()?;
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    Ok(boundaries)
+}
+
+pub fn format_boundaries(boundaries: &[usize]) -> String {
+    boundaries.iter().map(usize::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// The lines of a fixed-width file cut into fields at fixed character positions.
+/// A blank line is a single empty field, which the import skips.
+pub(crate) struct FixedWidthRecords {
+    reader: BufReader<CsvSource>,
+    boundaries: Vec<usize>,
+    buffer: String,
+    line: u64,
+}
+
+impl FixedWidthRecords {
+    pub(crate) fn open(path: &Path, boundaries: &[usize], encoding: Option<&'static Encoding>) -> Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(CsvSource::open(path, encoding)?),
+            boundaries: boundaries.to_vec(),
+            buffer: String::new(),
+            line: 0,
+        })
+    }
+
+    /// Read the next line into the buffer; false at the end of the file
+    fn read_line(&mut self) -> Result<bool> {
+        self.buffer.clear();
+        let read = self.reader.read_line(&mut self.buffer)
+            .map_err(|e| LeafError::Custom(format!("Failed to read line {}: {}", self.line + 1, e)))?;
+        if read > 0 {
+            self.line += 1;
+        }
+        Ok(read > 0)
+    }
+
+    fn current_line(&self) -> &str {
+        self.buffer.trim_end_matches(['\n', '\r'])
+    }
+}
+
+impl RecordSource for FixedWidthRecords {
+    fn next_record(&mut self) -> Result<Option<CsvRecord>> {
+        if !self.read_line()? {
+            return Ok(None);
+        }
+        let line = self.current_line();
+        let fields = if line.trim().is_empty() {
+            vec![String::new()]
+        } else {
+            split_line(line, &self.boundaries)
+        };
+        Ok(Some(CsvRecord { fields, line: self.line }))
+    }
+
+    fn bytes_read(&self) -> u64 {
+        self.reader.get_ref().bytes_read()
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.reader.get_ref().total_bytes()
+    }
+}
+
+/// The records of a fixed-width file, for previewing it
+pub fn open_records(path: &Path, boundaries: &[usize], encoding: Option<&'static Encoding>) -> Result<Box<dyn RecordSource>> {
+    Ok(Box::new(FixedWidthRecords::open(path, boundaries, encoding)?))
+}
@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use crate::core::coercion::CoercionPolicy;
+use crate::core::csv_ingest::RejectPolicy;
+use crate::core::csv_source::wildcard_match;
+use crate::core::csv_tokenizer::{CsvTokenizer, RecordSource};
+use crate::core::fixed_width;
+use crate::core::error::{Result, LeafError};
+use crate::infer::{ColumnType, NumberFormat};
+
+/// Import settings saved under a name, re-applied to files with the same layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProfile {
+    pub name: String,
+    /// Filename pattern with `*` and `?` wildcards, e.g. `sales_*.csv`; empty matches by headers only
+    #[serde(default)]
+    pub filename_pattern: String,
+    /// One-based row holding the column names
+    pub header_row: usize,
+    pub delimiter: char,
+    /// Fixed-width column positions, which replace the delimiter when set
+    #[serde(default)]
+    pub column_boundaries: Option<Vec<usize>>,
+    pub trim_whitespace: bool,
+    /// Encoding label such as `windows-1252`; detected from the file when `None`
+    pub encoding: Option<String>,
+    pub sample_size: usize,
+    pub reject_policy: RejectPolicy,
+    pub coercion_policy: CoercionPolicy,
+    pub number_format: NumberFormat,
+    #[serde(default)]
+    pub timezone: String,
+    pub null_values: Vec<String>,
+    /// The header signature: every column of the file the profile was saved from, in order
+    pub columns: Vec<ProfileColumn>,
+}
+
+/// Saved settings for one column, matched by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileColumn {
+    pub name: String,
+    pub data_type: ColumnType,
+    pub included: bool,
+    #[serde(default)]
+    pub datetime_pattern: String,
+    #[serde(default)]
+    pub null_values: String,
+    #[serde(default)]
+    pub true_values: String,
+    #[serde(default)]
+    pub false_values: String,
+}
+
+/// Why a profile was picked for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileMatch {
+    Headers,
+    FileName,
+    /// Applied by hand in the import dialog
+    Chosen,
+}
+
+impl ProfileMatch {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ProfileMatch::Headers => "matched by its columns",
+            ProfileMatch::FileName => "matched by file name",
+            ProfileMatch::Chosen => "applied by hand",
+        }
+    }
+}
+
+impl ImportProfile {
+    /// Where profiles are kept, shared by every project: `<config dir>/leaf/import_profiles`
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("leaf").join("import_profiles"))
+    }
+
+    /// Every profile in `dir`, sorted by name. A missing folder has none; unreadable files are skipped.
+    pub fn load_all(dir: &Path) -> Result<Vec<ImportProfile>> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut profiles = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match Self::load(&path) {
+                Ok(profile) => profiles.push(profile),
+                Err(e) => eprintln!("Warning: Skipping import profile {}: {}", path.display(), e),
+            }
+        }
+        profiles.sort_by_cached_key(|profile| profile.name.to_lowercase());
+        Ok(profiles)
+    }
+
+    pub fn load(path: &Path) -> Result<ImportProfile> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| LeafError::Custom(format!("Invalid import profile {}: {}", path.display(), e)))
+    }
+
+    /// Write the profile to `dir`, replacing one saved under the same name
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        if self.name.trim().is_empty() {
+            return Err(LeafError::Custom("An import profile needs a name".to_string()));
+        }
+        std::fs::create_dir_all(dir)?;
+        let path = Self::path_for(dir, &self.name);
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| LeafError::Custom(format!("Failed to write import profile: {}", e)))?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    pub fn delete(dir: &Path, name: &str) -> Result<()> {
+        let path = Self::path_for(dir, name);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// File for a profile name, with characters that aren't safe in file names replaced
+    fn path_for(dir: &Path, name: &str) -> PathBuf {
+        json_path_for(dir, name)
+    }
+
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        self.encoding.as_deref().and_then(|label| Encoding::for_label(label.as_bytes()))
+    }
+
+    /// Whether `headers` are the profile's columns, ignoring case and surrounding spaces
+    pub fn matches_headers(&self, headers: &[String]) -> bool {
+        headers.len() == self.columns.len()
+            && headers.iter()
+                .zip(&self.columns)
+                .all(|(header, column)| header.trim().eq_ignore_ascii_case(column.name.trim()))
+    }
+
+    pub fn matches_file_name(&self, file_name: &str) -> bool {
+        let pattern = self.filename_pattern.trim();
+        !pattern.is_empty() && wildcard_match(&pattern.to_lowercase(), &file_name.to_lowercase())
+    }
+
+    /// A pattern for later files from the same source: runs of digits, such as dates, become `*`
+    pub fn suggest_pattern(file_name: &str) -> String {
+        let mut pattern = String::new();
+        for c in file_name.chars() {
+            if !c.is_ascii_digit() {
+                pattern.push(c);
+            } else if !pattern.ends_with('*') {
+                pattern.push('*');
+            }
+        }
+        pattern
+    }
+
+    /// The settings that decide what this profile reads as a file's header row
+    fn header_layout(&self) -> (usize, char, &Option<Vec<usize>>, bool, &Option<String>) {
+        (self.header_row, self.delimiter, &self.column_boundaries, self.trim_whitespace, &self.encoding)
+    }
+
+    /// The header row of `path` read the way this profile reads files
+    fn read_headers(&self, path: &Path) -> Result<Vec<String>> {
+        let mut tokenizer: Box<dyn RecordSource> = match &self.column_boundaries {
+            Some(boundaries) => fixed_width::open_records(path, boundaries, self.encoding())?,
+            None => Box::new(CsvTokenizer::from_path(path, self.delimiter, self.trim_whitespace, self.encoding())?),
+        };
+        for _ in 1..self.header_row.max(1) {
+            if tokenizer.next_record()?.is_none() {
+                return Ok(Vec::new());
+            }
+        }
+        Ok(tokenizer.next_record()?.map(|record| record.fields).unwrap_or_default())
+    }
+
+    /// The profile for a file: one whose columns match its header row, or else one whose
+    /// filename pattern matches its name. The file is read once per distinct header layout, not per profile.
+    pub fn find_for_file<'a>(profiles: &'a [ImportProfile], path: &Path) -> Option<(&'a ImportProfile, ProfileMatch)> {
+        let mut headers_read: Vec<(&ImportProfile, Option<Vec<String>>)> = Vec::new();
+        let by_headers = profiles.iter().find(|profile| {
+            let read = match headers_read.iter().position(|(read_by, _)| read_by.header_layout() == profile.header_layout()) {
+                Some(idx) => idx,
+                None => {
+                    headers_read.push((profile, profile.read_headers(path).ok()));
+                    headers_read.len() - 1
+                }
+            };
+            headers_read[read].1.as_ref().is_some_and(|headers| profile.matches_headers(headers))
+        });
+        if let Some(profile) = by_headers {
+            return Some((profile, ProfileMatch::Headers));
+        }
+        let file_name = path.file_name()?.to_str()?;
+        profiles.iter()
+            .find(|profile| profile.matches_file_name(file_name))
+            .map(|profile| (profile, ProfileMatch::FileName))
+    }
+}
+
+/// JSON file for something saved under a name, with characters that aren't safe in file names replaced.
+/// Names that only differ in those characters, like `Q1: sales` and `Q1? sales`, get numbered files
+/// (`Q1__sales.json`, `Q1__sales_2.json`), so saving one never replaces the other.
+pub(crate) fn json_path_for(dir: &Path, name: &str) -> PathBuf {
+    let stem: String = name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let numbered = |n: usize| if n == 1 { dir.join(format!("{}.json", stem)) } else { dir.join(format!("{}_{}.json", stem, n)) };
+
+    // The file already saved under this name, wherever a clash put it
+    let saved = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter(|path| path.file_stem().and_then(|file_stem| file_stem.to_str()).is_some_and(|file_stem| file_stem.starts_with(&stem)))
+        .find(|path| saved_name(path).is_some_and(|saved| saved.trim() == name.trim()));
+    if let Some(path) = saved {
+        return path;
+    }
+    (1..).map(&numbered).find(|path| !path.exists()).unwrap_or_else(|| numbered(1))
+}
+
+/// The `name` field of a JSON file saved with `json_path_for`
+fn saved_name(path: &Path) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    json.get("name")?.as_str().map(String::from)
+}
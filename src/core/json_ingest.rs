@@ -0,0 +1,312 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use datafusion::arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
+use datafusion::arrow::json::reader::{infer_json_schema_from_iterator, ReaderBuilder};
+use serde_json::{Map, Value};
+use crate::core::csv_ingest::IngestProgress;
+use crate::core::csv_source;
+use crate::core::error::{Result, LeafError};
+use crate::core::table_format::{TableFormat, TableWriter};
+
+/// Extensions offered in the file picker for JSON inputs, compressed ones included
+pub const PICKER_EXTENSIONS: [&str; 5] = ["json", "ndjson", "jsonl", "gz", "zst"];
+
+/// Records read to infer the schema unless the options say otherwise
+pub const DEFAULT_SAMPLE_SIZE: usize = 10_000;
+
+/// Settings for importing a JSON or NDJSON file as a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonIngestOptions {
+    /// Turn nested objects into top-level columns named by their path, like `user.address.city`;
+    /// otherwise they become struct columns. Arrays are kept as list columns either way.
+    pub flatten: bool,
+    /// Records read to infer the schema; `None` reads every record, so no later value can fail to fit,
+    /// but keeps them all in memory during an import. A field that only appears after the sample fails the import.
+    pub sample_size: Option<usize>,
+    pub batch_size: usize,
+    /// Columns to keep, by name; `None` keeps all of them
+    pub columns: Option<Vec<String>>,
+}
+
+impl Default for JsonIngestOptions {
+    fn default() -> Self {
+        Self {
+            flatten: false,
+            sample_size: Some(DEFAULT_SAMPLE_SIZE),
+            batch_size: 64 * 1024,
+            columns: None,
+        }
+    }
+}
+
+/// Result of importing a JSON file into a table file
+#[derive(Debug, Clone)]
+pub struct JsonIngestSummary {
+    pub output_path: PathBuf,
+    pub rows: usize,
+}
+
+/// Whether a file looks like JSON or NDJSON by its extension, looking past `.gz` and `.zst`
+pub fn is_json_file(path: &Path) -> bool {
+    let name = path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = name.trim_end_matches(".gz").trim_end_matches(".zst");
+    [".json", ".ndjson", ".jsonl"].iter().any(|extension| name.ends_with(extension))
+}
+
+/// The records of a JSON file, read one at a time: the values of NDJSON (or any whitespace-separated
+/// objects), or the elements of a top-level array. An array is parsed whole before its first record.
+struct JsonRecords {
+    values: serde_json::StreamDeserializer<'static, serde_json::de::IoRead<BufReader<Box<dyn Read + Send>>>, Value>,
+    pending: std::vec::IntoIter<Value>,
+    flatten: bool,
+    count: usize,
+    failed: bool,
+}
+
+impl JsonRecords {
+    /// Open a file, decompressing it if needed; returns the records and the file's size on disk
+    fn open(path: &Path, flatten: bool, counter: Option<Arc<AtomicU64>>) -> Result<(Self, u64)> {
+        let (reader, total_bytes) = csv_source::open_decompressed(path, counter)?;
+        let mut reader = BufReader::new(reader);
+        // serde_json doesn't accept a byte order mark
+        if reader.fill_buf()?.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            reader.consume(3);
+        }
+        let records = Self {
+            values: serde_json::Deserializer::from_reader(reader).into_iter(),
+            pending: Vec::new().into_iter(),
+            flatten,
+            count: 0,
+            failed: false,
+        };
+        Ok((records, total_bytes))
+    }
+}
+
+impl Iterator for JsonRecords {
+    type Item = Result<Map<String, Value>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let value = match self.pending.next() {
+            Some(value) => value,
+            None => match self.values.next()? {
+                Ok(Value::Array(items)) => {
+                    self.pending = items.into_iter();
+                    return self.next();
+                }
+                Ok(value) => value,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(LeafError::Custom(format!("Invalid JSON after record {}: {}", self.count, e))));
+                }
+            },
+        };
+        self.count += 1;
+        match value {
+            Value::Object(map) if self.flatten => Some(Ok(flatten_object(map))),
+            Value::Object(map) => Some(Ok(map)),
+            value => {
+                self.failed = true;
+                let mut text = value.to_string();
+                text.truncate(50);
+                Some(Err(LeafError::Custom(format!("Record {} is not a JSON object: {}", self.count, text))))
+            }
+        }
+    }
+}
+
+/// Nested objects as top-level keys joined with dots: `{"user": {"id": 1}}` becomes `{"user.id": 1}`
+fn flatten_object(map: Map<String, Value>) -> Map<String, Value> {
+    fn flatten_into(flat: &mut Map<String, Value>, prefix: Option<&str>, map: Map<String, Value>) {
+        for (key, value) in map {
+            let key = match prefix {
+                Some(prefix) => format!("{}.{}", prefix, key),
+                None => key,
+            };
+            match value {
+                Value::Object(inner) => flatten_into(flat, Some(&key), inner),
+                value => {
+                    flat.insert(key, value);
+                }
+            }
+        }
+    }
+    let mut flat = Map::new();
+    flatten_into(&mut flat, None, map);
+    flat
+}
+
+/// Infer an Arrow schema from the file's records, nested structs and lists included.
+/// Fields are in the order they first appear; a field holding different kinds of values becomes text.
+pub fn infer_schema(path: &Path, options: &JsonIngestOptions) -> Result<SchemaRef> {
+    let (mut records, _) = JsonRecords::open(path, options.flatten, None)?;
+    let sample = read_sample(&mut records, options)?;
+    schema_of(&sample, path, options)
+}
+
+/// The records `options.sample_size` asks for, from the start of the file
+fn read_sample(records: &mut JsonRecords, options: &JsonIngestOptions) -> Result<Vec<Value>> {
+    records.by_ref()
+        .take(options.sample_size.unwrap_or(usize::MAX))
+        .map(|record| record.map(Value::Object))
+        .collect()
+}
+
+/// The schema of sampled records, keeping the columns `options` asks for
+fn schema_of(sample: &[Value], path: &Path, options: &JsonIngestOptions) -> Result<SchemaRef> {
+    let schema = infer_json_schema_from_iterator(sample.iter().map(Ok))?;
+    if schema.fields().is_empty() {
+        return Err(LeafError::Custom(format!("No JSON records found in {}", path.display())));
+    }
+
+    let fields: Vec<Field> = schema.fields().iter()
+        .filter(|field| options.columns.as_ref().is_none_or(|columns| columns.contains(field.name())))
+        .map(|field| field.as_ref().clone().with_data_type(storable_type(field.data_type())))
+        .collect();
+    Ok(Arc::new(Schema::new(fields)))
+}
+
+/// A record without the fields `options.columns` leaves out
+fn keep_columns(record: Value, options: &JsonIngestOptions) -> Value {
+    match (record, &options.columns) {
+        (Value::Object(mut map), Some(columns)) => {
+            map.retain(|key, _| columns.contains(key));
+            Value::Object(map)
+        }
+        (record, _) => record,
+    }
+}
+
+/// Fields that were null in every record are typed as text so they can be stored and queried
+fn storable_type(data_type: &DataType) -> DataType {
+    match data_type {
+        DataType::Null => DataType::Utf8,
+        DataType::List(item) => DataType::List(Arc::new(item.as_ref().clone().with_data_type(storable_type(item.data_type())))),
+        DataType::Struct(fields) => DataType::Struct(fields.iter()
+            .map(|field| field.as_ref().clone().with_data_type(storable_type(field.data_type())))
+            .collect::<Fields>()),
+        data_type => data_type.clone(),
+    }
+}
+
+/// A short name for a column type, spelling out nested ones like `Struct<city: Utf8>` and `List<Int64>`
+pub fn describe_type(data_type: &DataType) -> String {
+    match data_type {
+        DataType::List(item) => format!("List<{}>", describe_type(item.data_type())),
+        DataType::Struct(fields) => format!(
+            "Struct<{}>",
+            fields.iter()
+                .map(|field| format!("{}: {}", field.name(), describe_type(field.data_type())))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        data_type => data_type.to_string(),
+    }
+}
+
+/// The schema of the first `max_records` records and their values as text, for the import preview.
+/// Strings are shown as they are, missing and null values as empty cells and anything else as JSON.
+pub fn preview(path: &Path, flatten: bool, max_records: usize) -> Result<(SchemaRef, Vec<Vec<String>>)> {
+    let options = JsonIngestOptions { flatten, sample_size: Some(max_records), ..JsonIngestOptions::default() };
+    let schema = infer_schema(path, &options)?;
+    let (records, _) = JsonRecords::open(path, flatten, None)?;
+    let rows = records
+        .take(max_records)
+        .map(|record| {
+            let record = record?;
+            Ok(schema.fields().iter()
+                .map(|field| match record.get(field.name()) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(text)) => text.clone(),
+                    Some(value) => value.to_string(),
+                })
+                .collect())
+        })
+        .collect::<Result<Vec<Vec<String>>>>()?;
+    Ok((schema, rows))
+}
+
+/// Import a JSON array or NDJSON file (optionally gzip or zstd compressed) into a table file.
+/// The schema is inferred from the first `options.sample_size` records, which are then imported from
+/// memory, so the file is read once. The records are decoded a batch at a time and written to
+/// `output_path`, which is only replaced once the whole file has been read.
+pub fn ingest_json_to_file(
+    json_path: &Path,
+    output_path: &Path,
+    format: TableFormat,
+    options: &JsonIngestOptions,
+    mut on_progress: impl FnMut(&IngestProgress),
+) -> Result<JsonIngestSummary> {
+    let batch_size = options.batch_size.max(1);
+    let counter = Arc::new(AtomicU64::new(0));
+    let (mut file_records, total_bytes) = JsonRecords::open(json_path, options.flatten, Some(counter.clone()))?;
+    let sample = read_sample(&mut file_records, options)?;
+    let schema = schema_of(&sample, json_path, options)?;
+    let mut records = sample.into_iter().map(Ok).chain(file_records.map(|record| record.map(Value::Object)))
+        .map(|record| record.map(|record| keep_columns(record, options)));
+    // Values of a field that became text elsewhere in the file are stored as their JSON text.
+    // Strict mode fails on fields the sample didn't have instead of dropping them.
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(batch_size)
+        .with_coerce_primitive(true)
+        .with_strict_mode(true)
+        .build_decoder()?;
+
+    let partial_path = output_path.with_extension(format!("{}.partial", format.extension()));
+    let result = (|| {
+        let mut writer = TableWriter::try_new(&partial_path, &schema, format)?;
+        let mut rows = 0;
+        let mut chunk = Vec::with_capacity(batch_size);
+        loop {
+            chunk.clear();
+            for record in records.by_ref().take(batch_size) {
+                chunk.push(record?);
+            }
+            if chunk.is_empty() {
+                break;
+            }
+            let decoded = decoder.serialize(&chunk).and_then(|()| decoder.flush()).map_err(|e| {
+                let hint = match options.sample_size {
+                    Some(sample_size) if rows + chunk.len() > sample_size => {
+                        format!(" (columns and types were inferred from the first {} records; a larger sample may find the right ones)", sample_size)
+                    }
+                    _ => String::new(),
+                };
+                LeafError::Custom(format!("Failed to read records {}-{}: {}{}", rows + 1, rows + chunk.len(), e, hint))
+            })?;
+            if let Some(batch) = decoded {
+                rows += batch.num_rows();
+                writer.write(&batch)?;
+            }
+            on_progress(&IngestProgress {
+                bytes_read: counter.load(Ordering::Relaxed),
+                total_bytes,
+                rows,
+            });
+        }
+        writer.finish()?;
+        Ok(rows)
+    })();
+
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial_path);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial_path, output_path)
+        .map_err(|e| LeafError::Custom(format!("Failed to move imported table into place: {}", e)))?;
+
+    Ok(JsonIngestSummary {
+        output_path: output_path.to_path_buf(),
+        rows,
+    })
+}
@@ -7,12 +7,29 @@ pub mod transformations;
 pub mod time_grouping;
 pub mod computed_columns_processor;
 pub mod enhanced_grouping_processor;
+pub mod table_format;
+pub mod csv_ingest;
+pub mod csv_tokenizer;
+pub mod csv_source;
+pub mod coercion;
+pub mod text_encoding;
+pub mod import_profile;
+pub mod csv_union;
+pub mod json_ingest;
+pub mod spreadsheet;
+pub mod fixed_width;
+pub mod query_library;
 
 pub use database::{Database, TableInfo};
 pub use csv_handler::{CsvReader, CsvWriter};
 pub use duplicate_detector::{DuplicateDetector, DuplicateDetectionConfig, DuplicateDetectionResult, DuplicateGroup};
-pub use query::{QueryResult, QueryExecutor};
+pub use query::{QueryResult, QueryExecutor, RefusedStatement, RunningQuery};
 pub use transformations::{DataTransformer, TransformationType, TransformationConfig};
 pub use time_grouping::TimeGroupingEngine;
 pub use computed_columns_processor::ComputedColumnsProcessor;
-pub use enhanced_grouping_processor::EnhancedGroupingProcessor; 
\ No newline at end of file
+pub use enhanced_grouping_processor::EnhancedGroupingProcessor;
+pub use table_format::{TableFormat, ParquetCompression};
+pub use csv_ingest::{CsvIngestOptions, IngestProgress, RejectCounts, RejectPolicy, SchemaReconciliation};
+pub use coercion::{CoercionPolicy, CoercionReport};
+pub use import_profile::{ImportProfile, ProfileColumn, ProfileMatch};
+pub use query_library::{HistoryEntry, QueryLibrary, QueryStatus, SavedQuery}; 
\ No newline at end of file
@@ -1,122 +1,469 @@
-use crate::core::{Database, error::{Result, LeafError}};
-use std::sync::Arc;
-use datafusion::arrow::datatypes::DataType;
+use crate::core::{Database, CsvWriter, error::{Result, LeafError}};
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, SchemaRef};
+use datafusion::arrow::json::{writer::JsonArray, WriterBuilder};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::physical_plan::{execute_stream, ExecutionPlan};
+use datafusion::prelude::SessionContext;
+use datafusion::sql::parser::{DFParser, Statement as DFStatement};
+use datafusion::sql::sqlparser::ast::{Query, SetExpr, Statement};
+use datafusion::sql::sqlparser::dialect::GenericDialect;
+use datafusion::sql::sqlparser::tokenizer::{Token, Tokenizer};
+use futures::StreamExt;
 
 pub struct QueryExecutor;
 
 impl QueryExecutor {
+    /// Run a query and wait for its result
     pub fn execute(db: &Arc<Database>, query: &str) -> Result<QueryResult> {
-        // Validate query in read-only mode
-        if db.is_readonly() {
-            Self::validate_read_only(query)?;
-        }
-        
-        // Execute the query
-        let rows = db.execute_query(query)?;
-        let columns = db.get_column_names(query)?;
-        let column_types = db.get_column_types(query)?;
-        
-        Ok(QueryResult {
-            columns,
-            column_types,
-            rows,
-            total_rows: None,
-        })
+        Self::wait(Self::spawn(db, query)?)
     }
     
+    /// Run a query once and return one page of its rows, with the total counted from all of them
     pub fn execute_with_pagination(
         db: &Arc<Database>,
         query: &str,
         page: usize,
         page_size: usize,
     ) -> Result<QueryResult> {
+        Ok(Self::execute(db, query)?.page(page, page_size))
+    }
+
+    /// Block until a running query has finished
+    fn wait(running: RunningQuery) -> Result<QueryResult> {
+        running.outcome.recv().unwrap_or_else(|_| Err(RunningQuery::cancelled()))
+    }
+
+    /// Start a query on the database's runtime and return without waiting for it.
+    /// The query runs once; its result is kept whole so it can be paged through with `QueryResult::page`.
+    pub fn spawn(db: &Arc<Database>, query: &str) -> Result<RunningQuery> {
         // Validate query in read-only mode
         if db.is_readonly() {
             Self::validate_read_only(query)?;
         }
+        Ok(RunningQuery::start(db, query.to_string()))
+    }
+    
+    /// Refuse DDL and DML statements, naming the first one and where it starts
+    pub fn validate_read_only(query: &str) -> Result<()> {
+        match Self::refused_statement(query)? {
+            Some(refused) => Err(LeafError::Custom(refused.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// The first statement in `query` that read-only mode refuses, if any
+    pub fn refused_statement(query: &str) -> Result<Option<RefusedStatement>> {
+        let statements = DFParser::parse_sql(query)
+            .map_err(|e| LeafError::Custom(format!("Failed to parse query: {}", e)))?;
+        let spans = statement_spans(query)?;
 
-        // Special case: if the query is a SELECT COUNT(*) query, use only execute_count_query
-        let query_trimmed = query.trim().to_uppercase();
-        if query_trimmed.starts_with("SELECT COUNT(*)") {
-            let count = db.execute_count_query(query)?;
-            return Ok(QueryResult {
-                columns: vec!["COUNT(*)".to_string()],
-                column_types: vec![DataType::Int64],
-                rows: vec![vec![count.to_string()]],
-                total_rows: Some(1),
-            });
+        for (index, statement) in statements.iter().enumerate() {
+            let Some(kind) = refused_kind(statement) else {
+                continue;
+            };
+            let (start, end) = spans.get(index).copied().unwrap_or((0, query.len()));
+            let (line, column) = line_and_column(query, start);
+            return Ok(Some(RefusedStatement { kind, line, column, range: start..end }));
         }
-        
-        // First, get the total count for pagination
-        // For simple SELECT * FROM table queries, we can get count directly
-        let count_query = if query_trimmed.starts_with("SELECT * FROM") {
-            // Extract table name from SELECT * FROM "table_name"
-            if let Some(table_name_part) = query.split("FROM").nth(1) {
-                // Extract just the table name, ignoring any WHERE/ORDER BY/GROUP BY/LIMIT clause
-                let table_name_upper = table_name_part.to_uppercase();
-                let end_pos = table_name_upper
-                    .find(" WHERE ")
-                    .or_else(|| table_name_upper.find(" ORDER "))
-                    .or_else(|| table_name_upper.find(" GROUP "))
-                    .or_else(|| table_name_upper.find(" LIMIT "))
-                    .or_else(|| table_name_upper.find(" HAVING "))
-                    .unwrap_or(table_name_part.len());
-                
-                let table_name = &table_name_part[..end_pos];
-                let table_name = table_name.trim().trim_matches('"').trim_matches('\'');
-
-                // Check if table exists
-                match db.table_exists(table_name) {
-                    Ok(exists) => {
-                        if !exists {
-                            return Err(LeafError::Custom(format!("Table '{}' does not exist", table_name)));
-                        }
-                    }
-                    Err(e) => {
-                        return Err(LeafError::Custom(format!("Error checking if table exists: {}", e)));
-                    }
-                }
+        Ok(None)
+    }
+}
 
-                format!("SELECT COUNT(*) FROM \"{}\"", table_name)
-            } else {
-                format!("SELECT COUNT(*) FROM ({})", query)
-            }
-        } else {
-            format!("SELECT COUNT(*) FROM ({})", query)
-        };
-        let total_rows = db.execute_count_query(&count_query)? as usize;
+/// A statement read-only mode won't run, and where it is in the query text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefusedStatement {
+    /// Statement type, such as `INSERT` or `CREATE TABLE`
+    pub kind: String,
+    /// Line and column the statement starts at, both counted from 1
+    pub line: usize,
+    pub column: usize,
+    /// Byte range of the statement, without its semicolon
+    pub range: Range<usize>,
+}
 
-        // Then get the paginated results
-        let paginated_query = format!("{} LIMIT {} OFFSET {}", query, page_size, page * page_size);
-        let mut result = Self::execute(db, &paginated_query)?;
-        result.total_rows = Some(total_rows);
+impl std::fmt::Display for RefusedStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} statements can't run in read-only mode (line {}, column {})", self.kind, self.line, self.column)
+    }
+}
 
-        Ok(result)
+/// The statement type to refuse for a DDL or DML statement
+fn refused_kind(statement: &DFStatement) -> Option<String> {
+    match statement {
+        DFStatement::Statement(statement) => refused_sql_kind(statement),
+        DFStatement::CreateExternalTable(_) => Some("CREATE EXTERNAL TABLE".to_string()),
+        DFStatement::CopyTo(_) => Some("COPY".to_string()),
+        DFStatement::Explain(explain) => refused_kind(&explain.statement),
     }
-    
-    pub fn validate_read_only(query: &str) -> Result<()> {
-        let query_upper = query.to_uppercase();
-        let forbidden_keywords = [
-            "INSERT", "UPDATE", "DELETE", "DROP", "CREATE", "ALTER", 
-            "TRUNCATE", "REPLACE", "ATTACH", "DETACH", "PRAGMA"
-        ];
-        
-        for keyword in &forbidden_keywords {
-            if query_upper.contains(keyword) {
-                return Err(LeafError::Custom(
-                    format!("Query contains forbidden keyword '{}' in read-only mode", keyword)
-                ));
+}
+
+/// Read-only mode allows queries that write nothing, EXPLAIN of an allowed statement, DESCRIBE and SHOW.
+/// Every other statement is refused, so statement kinds added to the parser later are refused too.
+fn refused_sql_kind(statement: &Statement) -> Option<String> {
+    let kind = match statement {
+        Statement::Query(query) => return query_writes(query),
+        Statement::Explain { statement, .. } => return refused_sql_kind(statement),
+        Statement::ExplainTable { .. }
+        | Statement::ShowFunctions { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowStatus { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowCreate { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowCollation { .. } => return None,
+        // Names for statements whose first keyword doesn't say enough
+        Statement::Insert(_) | Statement::Directory { .. } => "INSERT",
+        Statement::Copy { .. } | Statement::CopyIntoSnowflake { .. } => "COPY",
+        Statement::Load { .. } => "LOAD DATA",
+        Statement::CreateTable { .. } => "CREATE TABLE",
+        Statement::CreateView { .. } => "CREATE VIEW",
+        Statement::CreateVirtualTable { .. } => "CREATE VIRTUAL TABLE",
+        Statement::CreateIndex { .. } => "CREATE INDEX",
+        Statement::CreateRole { .. } => "CREATE ROLE",
+        Statement::CreateSecret { .. } => "CREATE SECRET",
+        Statement::CreateSchema { .. } => "CREATE SCHEMA",
+        Statement::CreateDatabase { .. } => "CREATE DATABASE",
+        Statement::CreateFunction { .. } => "CREATE FUNCTION",
+        Statement::CreateProcedure { .. } => "CREATE PROCEDURE",
+        Statement::CreateMacro { .. } => "CREATE MACRO",
+        Statement::CreateStage { .. } => "CREATE STAGE",
+        Statement::CreateSequence { .. } => "CREATE SEQUENCE",
+        Statement::CreateType { .. } => "CREATE TYPE",
+        Statement::CreateExtension { .. } => "CREATE EXTENSION",
+        Statement::AlterTable { .. } => "ALTER TABLE",
+        Statement::AlterIndex { .. } => "ALTER INDEX",
+        Statement::AlterView { .. } => "ALTER VIEW",
+        Statement::AlterRole { .. } => "ALTER ROLE",
+        Statement::Drop { object_type, .. } => return Some(format!("DROP {}", object_type)),
+        Statement::DropFunction { .. } => "DROP FUNCTION",
+        Statement::DropSecret { .. } => "DROP SECRET",
+        Statement::AttachDatabase { .. } | Statement::AttachDuckDBDatabase { .. } => "ATTACH",
+        Statement::Cache { .. } => "CACHE TABLE",
+        Statement::UNCache { .. } => "UNCACHE TABLE",
+        // Anything else by its first keyword, e.g. UPDATE, TRUNCATE or SET
+        statement => {
+            let text = statement.to_string();
+            return Some(text.split_whitespace().next().unwrap_or("This").to_uppercase());
+        }
+    };
+    Some(kind.to_string())
+}
+
+/// Queries can write too, through `SELECT ... INTO` or DML in a `WITH` clause
+fn query_writes(query: &Query) -> Option<String> {
+    if let Some(with) = &query.with {
+        if let Some(kind) = with.cte_tables.iter().find_map(|cte| query_writes(&cte.query)) {
+            return Some(kind);
+        }
+    }
+    set_expr_writes(&query.body)
+}
+
+fn set_expr_writes(body: &SetExpr) -> Option<String> {
+    match body {
+        SetExpr::Select(select) => select.into.as_ref().map(|_| "SELECT INTO".to_string()),
+        SetExpr::Query(query) => query_writes(query),
+        SetExpr::SetOperation { left, right, .. } => set_expr_writes(left).or_else(|| set_expr_writes(right)),
+        SetExpr::Insert(statement) | SetExpr::Update(statement) => refused_sql_kind(statement),
+        SetExpr::Values(_) | SetExpr::Table(_) => None,
+    }
+}
+
+/// Byte ranges of the statements in `query`, split at semicolons outside strings and comments
+fn statement_spans(query: &str) -> Result<Vec<(usize, usize)>> {
+    let tokens = Tokenizer::new(&GenericDialect {}, query)
+        .tokenize_with_location()
+        .map_err(|e| LeafError::Custom(format!("Failed to parse query: {}", e)))?;
+
+    let mut spans = Vec::new();
+    let mut start = None;
+    for token in tokens {
+        let offset = byte_offset(query, token.location.line as usize, token.location.column as usize);
+        match token.token {
+            Token::SemiColon => {
+                if let Some(start) = start.take() {
+                    spans.push((start, query[..offset].trim_end().len()));
+                }
+            }
+            Token::Whitespace(_) | Token::EOF => {}
+            _ => {
+                start.get_or_insert(offset);
             }
         }
-        
-        Ok(())
     }
+    if let Some(start) = start {
+        spans.push((start, query.trim_end().len()));
+    }
+    Ok(spans)
+}
+
+/// Byte offset of a 1-based line and column, where columns count characters
+fn byte_offset(query: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = query.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum();
+    query[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(query.len(), |(offset, _)| line_start + offset)
 }
 
+/// 1-based line and column of a byte offset
+fn line_and_column(query: &str, offset: usize) -> (usize, usize) {
+    let before = &query[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// A query running on the database's runtime, started by `QueryExecutor::spawn`.
+/// Cancelling or dropping it aborts the task, which stops the DataFusion stream at its next batch.
+pub struct RunningQuery {
+    task: tokio::task::JoinHandle<()>,
+    progress: Arc<QueryProgress>,
+    outcome: mpsc::Receiver<Result<QueryResult>>,
+    started: Instant,
+}
+
+impl RunningQuery {
+    fn start(db: &Arc<Database>, query: String) -> Self {
+        let progress = Arc::new(QueryProgress::default());
+        let (sender, outcome) = mpsc::channel();
+        let ctx = db.session_context();
+        let task_progress = progress.clone();
+        let task = db.spawn(async move {
+            let result = collect_query(&ctx, &query, &task_progress).await
+                .map(|(schema, batches)| QueryResult::new(schema, batches));
+            let _ = sender.send(result);
+        });
+        Self { task, progress, outcome, started: Instant::now() }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Rows the query has produced so far
+    pub fn rows_returned(&self) -> usize {
+        self.progress.rows_returned.load(Ordering::Relaxed)
+    }
+
+    /// Rows read from the tables so far.
+    /// `None` when the tables don't report it, as in-memory tables don't.
+    pub fn rows_scanned(&self) -> Option<usize> {
+        self.progress.rows_scanned()
+    }
+
+    /// The rows received so far, so the first pages can be shown before the query finishes.
+    /// `None` until the query has been planned.
+    pub fn partial_result(&self) -> Option<QueryResult> {
+        let schema = self.progress.plan.lock().unwrap_or_else(PoisonError::into_inner).as_ref()?.schema();
+        Some(QueryResult::new(schema, self.progress.batches.lock().unwrap_or_else(PoisonError::into_inner).clone()))
+    }
+
+    pub fn cancel(&self) {
+        self.task.abort();
+    }
+
+    /// The outcome once the query has finished, which can be taken once; `None` while it is still running
+    pub fn try_result(&self) -> Option<Result<QueryResult>> {
+        match self.outcome.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(Self::cancelled())),
+        }
+    }
+
+    fn cancelled() -> LeafError {
+        LeafError::Custom("The query was cancelled".to_string())
+    }
+}
+
+impl Drop for RunningQuery {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// How far a running query has got, shared with the task running it. A lock poisoned by a panicking
+/// executor thread still holds whole batches, so it is used as it is rather than panicking the UI thread.
+#[derive(Default)]
+struct QueryProgress {
+    // The statement being executed; its scans count their rows as they read them
+    plan: Mutex<Option<Arc<dyn ExecutionPlan>>>,
+    // The output so far, batches being cheap to share
+    batches: Mutex<Vec<RecordBatch>>,
+    rows_returned: AtomicUsize,
+}
+
+impl QueryProgress {
+    fn rows_scanned(&self) -> Option<usize> {
+        self.plan.lock().unwrap_or_else(PoisonError::into_inner).as_ref().and_then(scanned_by)
+    }
+}
+
+/// Rows read so far by a plan's scans, the nodes without inputs; `None` when none of them count rows
+fn scanned_by(plan: &Arc<dyn ExecutionPlan>) -> Option<usize> {
+    let children = plan.children();
+    if children.is_empty() {
+        return plan.metrics().and_then(|metrics| metrics.output_rows());
+    }
+    children.into_iter().filter_map(scanned_by).reduce(|a, b| a + b)
+}
+
+/// Plan a query and collect its output batch by batch, reporting progress as they arrive
+async fn collect_query(ctx: &SessionContext, query: &str, progress: &QueryProgress) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let frame = ctx.sql(query).await
+        .map_err(|e| LeafError::Custom(format!("Failed to execute query: {}", e)))?;
+    let plan = frame.create_physical_plan().await
+        .map_err(|e| LeafError::Custom(format!("Failed to plan query: {}", e)))?;
+    *progress.plan.lock().unwrap_or_else(PoisonError::into_inner) = Some(plan.clone());
+    let mut stream = execute_stream(plan.clone(), ctx.task_ctx())
+        .map_err(|e| LeafError::Custom(format!("Failed to execute query: {}", e)))?;
+    while let Some(batch) = stream.next().await {
+        let batch = batch.map_err(|e| LeafError::Custom(format!("Failed to collect results: {}", e)))?;
+        progress.rows_returned.fetch_add(batch.num_rows(), Ordering::Relaxed);
+        progress.batches.lock().unwrap_or_else(PoisonError::into_inner).push(batch);
+        // Let other queries on the runtime run, and a cancel take effect, between batches
+        tokio::task::yield_now().await;
+    }
+    let batches = progress.batches.lock().unwrap_or_else(PoisonError::into_inner).clone();
+    Ok((plan.schema(), batches))
+}
+
+/// The output of a query as the Arrow record batches DataFusion produced, in order.
+/// Values keep their types; cells are only formatted as text when they are shown or exported.
 #[derive(Debug, Clone)]
 pub struct QueryResult {
-    pub columns: Vec<String>,
-    pub column_types: Vec<DataType>,  // Add column type information
-    pub rows: Vec<Vec<String>>, 
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    // Index of the first row of each batch, for finding a row's batch by binary search
+    batch_starts: Vec<usize>,
+    num_rows: usize,
+    /// Rows the whole query returned when this result is one page of it
     pub total_rows: Option<usize>,
-} 
\ No newline at end of file
+}
+
+impl QueryResult {
+    pub fn new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Self {
+        let mut batch_starts = Vec::with_capacity(batches.len());
+        let mut num_rows = 0;
+        for batch in &batches {
+            batch_starts.push(num_rows);
+            num_rows += batch.num_rows();
+        }
+        Self { schema, batches, batch_starts, num_rows, total_rows: None }
+    }
+
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    pub fn batches(&self) -> &[RecordBatch] {
+        &self.batches
+    }
+
+    pub fn column_names(&self) -> Vec<String> {
+        self.schema.fields().iter().map(|field| field.name().clone()).collect()
+    }
+
+    pub fn column_types(&self) -> Vec<DataType> {
+        self.schema.fields().iter().map(|field| field.data_type().clone()).collect()
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.schema.fields().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_rows == 0
+    }
+
+    /// Rows `page * page_size` onwards, at most `page_size` of them, sliced without copying.
+    /// The page's `total_rows` is this result's row count.
+    pub fn page(&self, page: usize, page_size: usize) -> QueryResult {
+        let start = page.saturating_mul(page_size).min(self.num_rows);
+        let end = start.saturating_add(page_size).min(self.num_rows);
+        let batches = self.batches.iter().zip(&self.batch_starts)
+            .filter(|(batch, &batch_start)| batch_start < end && batch_start + batch.num_rows() > start)
+            .map(|(batch, &batch_start)| {
+                let offset = start.saturating_sub(batch_start);
+                let length = end.min(batch_start + batch.num_rows()) - batch_start - offset;
+                batch.slice(offset, length)
+            })
+            .collect();
+        let mut result = QueryResult::new(self.schema.clone(), batches);
+        result.total_rows = Some(self.num_rows);
+        result
+    }
+
+    /// The batch holding a row and the row's index within it
+    fn locate(&self, row: usize) -> Option<(&RecordBatch, usize)> {
+        if row >= self.num_rows {
+            return None;
+        }
+        // Empty batches share their start with the next one, so take the last batch starting at or before the row
+        let batch = self.batch_starts.partition_point(|&start| start <= row) - 1;
+        Some((&self.batches[batch], row - self.batch_starts[batch]))
+    }
+
+    /// The array holding a cell and the cell's index within it
+    pub fn value(&self, row: usize, column: usize) -> Option<(&ArrayRef, usize)> {
+        let (batch, index) = self.locate(row)?;
+        Some((batch.columns().get(column)?, index))
+    }
+
+    /// A cell as text, formatted the way tables show it; empty for nulls and cells out of range
+    pub fn cell(&self, row: usize, column: usize) -> String {
+        self.value(row, column)
+            .map(|(array, index)| Database::array_value_to_string(array, index).unwrap_or_else(|e| e.to_string()))
+            .unwrap_or_default()
+    }
+
+    /// A row's cells as text
+    pub fn row(&self, row: usize) -> Vec<String> {
+        (0..self.num_columns()).map(|column| self.cell(row, column)).collect()
+    }
+
+    /// Every row's cells as text, formatted one row at a time
+    pub fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        (0..self.num_rows).map(|row| self.row(row))
+    }
+
+    /// Write the result as CSV with a header row, formatting one row at a time
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = CsvWriter::from_path(path)?;
+        writer.write_headers(&self.column_names())?;
+        for row in self.rows() {
+            writer.write_record(&row)?;
+        }
+        writer.flush()
+    }
+
+    /// Write the result as a JSON array of objects. Numbers and booleans stay JSON numbers and booleans,
+    /// and nulls are written as null.
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let mut file = std::fs::File::create(path)
+            .map_err(|e| LeafError::Custom(format!("Failed to create {}: {}", path.display(), e)))?;
+        // Arrow's writer writes nothing at all without rows
+        if self.is_empty() {
+            return file.write_all(b"[]")
+                .map_err(|e| LeafError::Custom(format!("Failed to write {}: {}", path.display(), e)));
+        }
+        let mut writer = WriterBuilder::new()
+            .with_explicit_nulls(true)
+            .build::<_, JsonArray>(BufWriter::new(file));
+        writer.write_batches(&self.batches.iter().collect::<Vec<_>>())
+            .and_then(|_| writer.finish())
+            .map_err(|e| LeafError::Custom(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
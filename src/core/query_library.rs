@@ -0,0 +1,221 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use crate::core::import_profile::json_path_for;
+use crate::core::error::{Result, LeafError};
+
+/// History entries kept per project; older ones are dropped
+pub const MAX_HISTORY: usize = 1000;
+
+/// How an executed query ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QueryStatus {
+    Succeeded,
+    Failed(String),
+    Cancelled,
+}
+
+impl QueryStatus {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            QueryStatus::Succeeded => "succeeded",
+            QueryStatus::Failed(_) => "failed",
+            QueryStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// One executed query, as recorded in a project's history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub query: String,
+    /// When the query started, in seconds since the Unix epoch
+    pub executed_at: i64,
+    pub duration_ms: u64,
+    /// Rows returned; `None` when the query didn't finish
+    pub rows: Option<usize>,
+    pub status: QueryStatus,
+}
+
+impl HistoryEntry {
+    /// The start time in the local timezone, e.g. `2024-03-05 08:15:02`
+    pub fn executed_at_local(&self) -> String {
+        chrono::DateTime::from_timestamp(self.executed_at, 0)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default()
+    }
+
+    /// Whether the query text or its error contains `search`, ignoring case
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.trim().to_lowercase();
+        self.query.to_lowercase().contains(&search)
+            || matches!(&self.status, QueryStatus::Failed(error) if error.to_lowercase().contains(&search))
+    }
+}
+
+/// A query saved under a name, to be re-opened in a new window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub query: String,
+}
+
+impl SavedQuery {
+    /// Whether the name, description or query text contains `search`, ignoring case
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.trim().to_lowercase();
+        [&self.name, &self.description, &self.query]
+            .iter()
+            .any(|text| text.to_lowercase().contains(&search))
+    }
+}
+
+/// A project's query history and saved queries, kept in its `queries` folder so they travel with the data
+#[derive(Debug, Clone)]
+pub struct QueryLibrary {
+    dir: PathBuf,
+}
+
+impl QueryLibrary {
+    pub fn for_project(project: &Path) -> Self {
+        Self { dir: project.join("queries") }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.dir.join("history.jsonl")
+    }
+
+    /// When anything in the library last changed, for noticing queries recorded or saved elsewhere
+    pub fn modified(&self) -> Option<SystemTime> {
+        let mut paths = vec![self.dir.clone()];
+        if let Ok(entries) = std::fs::read_dir(&self.dir) {
+            paths.extend(entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()));
+        }
+        paths.iter()
+            .filter_map(|path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+            .max()
+    }
+
+    /// The newest `MAX_HISTORY` executed queries, oldest first. Lines that can't be read are skipped.
+    pub fn history(&self) -> Result<Vec<HistoryEntry>> {
+        let path = self.history_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let entries: Vec<HistoryEntry> = std::fs::read_to_string(&path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    eprintln!("Warning: Skipping query history line in {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect();
+        let skip = entries.len().saturating_sub(MAX_HISTORY);
+        Ok(entries.into_iter().skip(skip).collect())
+    }
+
+    /// Add an executed query to the end of the history. Once the file has grown well past
+    /// `MAX_HISTORY` lines, the oldest are dropped.
+    pub fn record(&self, entry: &HistoryEntry) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let line = serde_json::to_string(entry)
+            .map_err(|e| LeafError::Custom(format!("Failed to write query history: {}", e)))?;
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(self.history_path())?;
+        writeln!(file, "{}", line)?;
+        drop(file);
+        self.compact_history()
+    }
+
+    /// Keep the newest `MAX_HISTORY` lines once the file holds more than twice as many
+    fn compact_history(&self) -> Result<()> {
+        let path = self.history_path();
+        let history = std::fs::read_to_string(&path)?;
+        let lines: Vec<&str> = history.lines().filter(|line| !line.trim().is_empty()).collect();
+        if lines.len() > MAX_HISTORY * 2 {
+            let kept: String = lines[lines.len() - MAX_HISTORY..].iter().map(|line| format!("{}\n", line)).collect();
+            std::fs::write(&path, kept)?;
+        }
+        Ok(())
+    }
+
+    pub fn clear_history(&self) -> Result<()> {
+        self.write_history(&[])
+    }
+
+    fn write_history(&self, entries: &[HistoryEntry]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let mut lines = String::new();
+        for entry in entries {
+            let line = serde_json::to_string(entry)
+                .map_err(|e| LeafError::Custom(format!("Failed to write query history: {}", e)))?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        std::fs::write(self.history_path(), lines)?;
+        Ok(())
+    }
+
+    /// Saved queries, sorted by name. Unreadable files are skipped.
+    pub fn saved_queries(&self) -> Result<Vec<SavedQuery>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut queries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let saved = match std::fs::read_to_string(&path) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    eprintln!("Warning: Skipping saved query {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            match serde_json::from_str::<SavedQuery>(&saved) {
+                Ok(query) => queries.push(query),
+                Err(e) => eprintln!("Warning: Skipping saved query {}: {}", path.display(), e),
+            }
+        }
+        queries.sort_by_cached_key(|query| query.name.to_lowercase());
+        Ok(queries)
+    }
+
+    /// Whether a query is saved under `name`, to ask before `save_query` replaces it
+    pub fn has_saved_query(&self, name: &str) -> bool {
+        json_path_for(&self.dir, name).exists()
+    }
+
+    /// Write a saved query, replacing one saved under the same name.
+    /// Names that only differ in characters file names can't hold are kept apart.
+    pub fn save_query(&self, query: &SavedQuery) -> Result<PathBuf> {
+        if query.name.trim().is_empty() {
+            return Err(LeafError::Custom("A saved query needs a name".to_string()));
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        let path = json_path_for(&self.dir, &query.name);
+        let json = serde_json::to_string_pretty(query)
+            .map_err(|e| LeafError::Custom(format!("Failed to write saved query: {}", e)))?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    pub fn delete_query(&self, name: &str) -> Result<()> {
+        let path = json_path_for(&self.dir, name);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use calamine::{Data, Range, Reader};
+use chrono::Timelike;
+use crate::core::coercion::CoercionReport;
+use crate::core::csv_ingest::{self, CsvBatchReader, CsvIngestOptions, CsvIngestSummary, IngestProgress};
+use crate::core::csv_tokenizer::{CsvRecord, RecordSource};
+use crate::core::error::{Result, LeafError};
+use crate::core::table_format::TableFormat;
+use crate::infer::ColumnInference;
+
+/// Extensions offered in the file picker for spreadsheet workbooks
+pub const PICKER_EXTENSIONS: [&str; 5] = ["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+/// Whether a file is an Excel or OpenDocument workbook, by its extension
+pub fn is_spreadsheet(path: &Path) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| PICKER_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// A worksheet and how much of it is filled in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetInfo {
+    pub name: String,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl SheetInfo {
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0 || self.columns == 0
+    }
+}
+
+/// The worksheets of a workbook in workbook order, with their used size. Every sheet is read to size it.
+pub fn list_sheets(path: &Path) -> Result<Vec<SheetInfo>> {
+    let mut workbook = open_workbook(path)?;
+    workbook.sheet_names().into_iter()
+        .map(|name| {
+            let range = workbook.worksheet_range(&name)
+                .map_err(|e| LeafError::Custom(format!("Failed to read sheet '{}' of {}: {}", name, path.display(), e)))?;
+            let (rows, columns) = range.get_size();
+            Ok(SheetInfo { name, rows, columns })
+        })
+        .collect()
+}
+
+/// A block of cells in A1 notation: `B2:F100`, or just `B2` for everything below and to the right of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRange {
+    /// Zero-based (row, column) of the top-left cell
+    pub start: (u32, u32),
+    /// Zero-based (row, column) of the bottom-right cell; `None` runs to the end of the sheet
+    pub end: Option<(u32, u32)>,
+}
+
+impl CellRange {
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = || LeafError::Custom(format!("'{}' is not a cell range like B2:F100 or B2", text.trim()));
+        let (start, end) = match text.trim().split_once(':') {
+            Some((start, end)) => (start, Some(end)),
+            None => (text.trim(), None),
+        };
+        let start = parse_cell(start).ok_or_else(invalid)?;
+        let end = end.map(|end| parse_cell(end).ok_or_else(invalid)).transpose()?;
+        if end.is_some_and(|end| end.0 < start.0 || end.1 < start.1) {
+            return Err(LeafError::Custom(format!("The range {} ends above or left of where it starts", text.trim())));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for CellRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", column_letters(self.start.1), self.start.0 + 1)?;
+        if let Some((row, column)) = self.end {
+            write!(f, ":{}{}", column_letters(column), row + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// A cell reference like `AB12` as zero-based (row, column)
+fn parse_cell(text: &str) -> Option<(u32, u32)> {
+    let text = text.trim().replace('$', "");
+    let split = text.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = text.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let column = letters.to_ascii_uppercase().bytes()
+        .try_fold(0u32, |column, letter| column.checked_mul(26)?.checked_add((letter - b'A' + 1) as u32))?;
+    let row: u32 = digits.parse().ok()?;
+    (row > 0).then(|| (row - 1, column - 1))
+}
+
+/// Column letters for a zero-based column index: 0 is `A`, 26 is `AA`
+fn column_letters(column: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = column + 1;
+    while n > 0 {
+        letters.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().map(|&letter| letter as char).collect()
+}
+
+/// Which sheet of which workbook to import, and optionally which cells of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SheetSelection {
+    pub path: PathBuf,
+    pub sheet: String,
+    pub range: Option<CellRange>,
+}
+
+/// A table name for a sheet: the workbook's name, followed by the sheet's when the workbook has several
+pub fn table_name_for(path: &Path, sheet: &str, several_sheets: bool) -> String {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_else(|| "sheet".to_string());
+    let name = if several_sheets { format!("{}_{}", stem, sheet) } else { stem };
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn open_workbook(path: &Path) -> Result<calamine::Sheets<std::io::BufReader<std::fs::File>>> {
+    calamine::open_workbook_auto(path)
+        .map_err(|e| LeafError::Custom(format!("Failed to open workbook {}: {}", path.display(), e)))
+}
+
+/// The rows of a sheet's selected cells as text records, for the CSV import pipeline.
+/// Records are numbered by their row in the sheet; a row with no values is a single empty field,
+/// which the import skips as a blank line. Progress is counted in rows rather than bytes.
+struct SheetRecords {
+    cells: Range<Data>,
+    next_row: u32,
+    first_row: u32,
+    last_row: u32,
+    columns: (u32, u32),
+}
+
+impl SheetRecords {
+    fn open(selection: &SheetSelection) -> Result<Self> {
+        let path = &selection.path;
+        let mut workbook = open_workbook(path)?;
+        let cells = workbook.worksheet_range(&selection.sheet)
+            .map_err(|e| LeafError::Custom(format!("Failed to read sheet '{}' of {}: {}", selection.sheet, path.display(), e)))?;
+        let (used_start, used_end) = match (cells.start(), cells.end()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(LeafError::Custom(format!("Sheet '{}' is empty", selection.sheet))),
+        };
+        // Without a range, rows count from the top of the sheet so header rows match the sheet's numbering
+        let range = selection.range.unwrap_or(CellRange { start: (0, used_start.1), end: None });
+        let (last_row, last_column) = range.end.unwrap_or(used_end);
+        Ok(Self {
+            cells,
+            next_row: range.start.0,
+            first_row: range.start.0,
+            last_row: last_row.min(used_end.0),
+            columns: (range.start.1, last_column.min(used_end.1)),
+        })
+    }
+}
+
+impl RecordSource for SheetRecords {
+    fn next_record(&mut self) -> Result<Option<CsvRecord>> {
+        if self.next_row > self.last_row {
+            return Ok(None);
+        }
+        let row = self.next_row;
+        self.next_row += 1;
+        let mut fields: Vec<String> = (self.columns.0..=self.columns.1)
+            .map(|column| self.cells.get_value((row, column)).map(cell_text).unwrap_or_default())
+            .collect();
+        if fields.iter().all(String::is_empty) {
+            fields = vec![String::new()];
+        }
+        Ok(Some(CsvRecord { fields, line: row as u64 + 1 }))
+    }
+
+    fn bytes_read(&self) -> u64 {
+        (self.next_row - self.first_row) as u64
+    }
+
+    fn total_bytes(&self) -> u64 {
+        (self.last_row + 1).saturating_sub(self.first_row) as u64
+    }
+}
+
+/// A cell's value as the text type inference reads. Formulas arrive as their cached results;
+/// dates are written the way `TypeInferrer` recognizes them, and error cells are left empty.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(text) | Data::DateTimeIso(text) | Data::DurationIso(text) => text.clone(),
+        Data::Int(value) => value.to_string(),
+        // Excel stores every number as a float, so whole ones are written without a fraction
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => (*value as i64).to_string(),
+        Data::Float(value) => value.to_string(),
+        Data::Bool(value) => value.to_string(),
+        Data::DateTime(datetime) if datetime.is_duration() => {
+            let seconds = (datetime.as_f64() * 86_400.0).round() as i64;
+            format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+        }
+        Data::DateTime(datetime) => match datetime.as_datetime() {
+            Some(value) if datetime.as_f64() < 1.0 => value.format("%H:%M:%S").to_string(),
+            Some(value) if value.num_seconds_from_midnight() == 0 && value.nanosecond() == 0 => value.format("%Y-%m-%d").to_string(),
+            Some(value) => value.format("%Y-%m-%d %H:%M:%S%.f").to_string(),
+            None => datetime.as_f64().to_string(),
+        },
+    }
+}
+
+/// The records of a sheet, for previewing it
+pub fn open_records(selection: &SheetSelection) -> Result<Box<dyn RecordSource>> {
+    Ok(Box::new(SheetRecords::open(selection)?))
+}
+
+/// Read a sheet with the same header handling, type inference and conversion as a CSV file
+pub fn open_reader(selection: &SheetSelection, options: &CsvIngestOptions) -> Result<CsvBatchReader> {
+    let records = SheetRecords::open(selection)?;
+    CsvBatchReader::from_records(Box::new(records), ',', encoding_rs::UTF_8, options)
+}
+
+/// Import a sheet into a table file, as `csv_ingest::ingest_csv_to_file` does for a CSV file
+pub fn ingest_sheet_to_file(
+    selection: &SheetSelection,
+    output_path: &Path,
+    format: TableFormat,
+    options: &CsvIngestOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<CsvIngestSummary> {
+    csv_ingest::ingest_reader_to_file(|options| open_reader(selection, options), output_path, format, options, on_progress)
+}
+
+/// Every value in a sheet that won't parse as its column's type, without writing anything
+pub fn check_coercion(
+    selection: &SheetSelection,
+    options: &CsvIngestOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<CoercionReport> {
+    csv_ingest::check_reader_coercion(|options| open_reader(selection, options), options, on_progress)
+}
+
+/// Column types inferred from every row of a sheet rather than the sample
+pub fn infer_full_sheet(
+    selection: &SheetSelection,
+    options: &CsvIngestOptions,
+    on_progress: impl FnMut(&IngestProgress),
+) -> Result<Vec<ColumnInference>> {
+    csv_ingest::infer_reader_full(|options| open_reader(selection, options), options, on_progress)
+}
+
+/// The sheet's file name and sheet, for messages: `sales.xlsx [Q1]`
+pub fn describe(path: &Path, sheet: &str) -> String {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    format!("{} [{}]", file_name, sheet)
+}
+
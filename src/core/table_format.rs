@@ -0,0 +1,377 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::ipc::reader::{FileReader, read_footer_length};
+use datafusion::arrow::ipc::writer::FileWriter;
+use datafusion::arrow::ipc::{root_as_footer, root_as_message};
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchReader};
+use datafusion::datasource::file_format::arrow::ArrowFormat;
+use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::listing::ListingOptions;
+use datafusion::parquet::arrow::ArrowWriter;
+use datafusion::parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use datafusion::parquet::basic::{Compression, ZstdLevel};
+use datafusion::parquet::file::properties::WriterProperties;
+use crate::core::error::{Result, LeafError};
+
+/// File formats Leaf can load tables from and save tables to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableFormat {
+    #[default]
+    Arrow,
+    Parquet(ParquetCompression),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetCompression {
+    Snappy,
+    Zstd,
+}
+
+impl TableFormat {
+    /// Every selectable output format, in the order shown in the UI
+    pub const ALL: [TableFormat; 3] = [
+        TableFormat::Arrow,
+        TableFormat::Parquet(ParquetCompression::Snappy),
+        TableFormat::Parquet(ParquetCompression::Zstd),
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TableFormat::Arrow => "arrow",
+            TableFormat::Parquet(_) => "parquet",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TableFormat::Arrow => "Arrow IPC",
+            TableFormat::Parquet(ParquetCompression::Snappy) => "Parquet (snappy)",
+            TableFormat::Parquet(ParquetCompression::Zstd) => "Parquet (zstd)",
+        }
+    }
+
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "arrow" | "ipc" | "feather" => Some(TableFormat::Arrow),
+            "parquet" => Some(TableFormat::Parquet(ParquetCompression::Snappy)),
+            _ => None,
+        }
+    }
+
+    /// The source format used to scan a file written in this format
+    pub fn source_format(&self) -> SourceFormat {
+        match self {
+            TableFormat::Arrow => SourceFormat::Arrow,
+            TableFormat::Parquet(_) => SourceFormat::Parquet,
+        }
+    }
+
+    /// Build an output file name, replacing any table file extension the user typed
+    pub fn file_name(&self, name: &str) -> String {
+        let stem = name
+            .trim_end_matches(".arrow")
+            .trim_end_matches(".parquet");
+        format!("{}.{}", stem, self.extension())
+    }
+}
+
+/// Writes record batches to a table file one batch at a time.
+/// Columns the format can't hold are converted first (see `storage_schema`); the schema is set when they are.
+pub enum TableWriter {
+    Arrow(FileWriter<File>, Option<SchemaRef>),
+    Parquet(ArrowWriter<File>, Option<SchemaRef>),
+}
+
+impl TableWriter {
+    pub fn try_new(path: &Path, schema: &SchemaRef, format: TableFormat) -> Result<Self> {
+        let file = File::create(path)
+            .map_err(|e| LeafError::Custom(format!("Failed to create {}: {}", path.display(), e)))?;
+
+        match format {
+            TableFormat::Arrow => {
+                let storage = storage_schema(schema, format);
+                let writer = FileWriter::try_new(file, storage.as_ref().unwrap_or(schema))
+                    .map_err(|e| LeafError::Custom(format!("Failed to create Arrow IPC writer: {}", e)))?;
+                Ok(TableWriter::Arrow(writer, storage))
+            }
+            TableFormat::Parquet(compression) => {
+                let compression = match compression {
+                    ParquetCompression::Snappy => Compression::SNAPPY,
+                    ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+                };
+                let properties = WriterProperties::builder()
+                    .set_compression(compression)
+                    .build();
+                let storage = storage_schema(schema, format);
+                let writer = ArrowWriter::try_new(file, storage.clone().unwrap_or_else(|| schema.clone()), Some(properties))
+                    .map_err(|e| LeafError::Custom(format!("Failed to create Parquet writer: {}", e)))?;
+                Ok(TableWriter::Parquet(writer, storage))
+            }
+        }
+    }
+
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            TableWriter::Arrow(writer, storage) => writer.write(&to_storage(batch, storage)?)
+                .map_err(|e| LeafError::Custom(format!("Failed to write Arrow IPC data: {}", e))),
+            TableWriter::Parquet(writer, storage) => writer.write(&to_storage(batch, storage)?)
+                .map_err(|e| LeafError::Custom(format!("Failed to write Parquet data: {}", e))),
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self {
+            TableWriter::Arrow(mut writer, _) => writer.finish()
+                .map_err(|e| LeafError::Custom(format!("Failed to finish Arrow IPC file: {}", e))),
+            TableWriter::Parquet(writer, _) => writer.close()
+                .map(|_| ())
+                .map_err(|e| LeafError::Custom(format!("Failed to finish Parquet file: {}", e))),
+        }
+    }
+}
+
+/// The schema a table is stored with in `format`, if it differs from `schema`.
+/// Arrow IPC files allow one dictionary per column for the whole file while every batch brings its own,
+/// so dictionary columns are stored as plain values there; Parquet keeps the encoding. Parquet has no
+/// duration type, so durations are stored there as Int64 counts of their unit.
+fn storage_schema(schema: &SchemaRef, format: TableFormat) -> Option<SchemaRef> {
+    let storage_type = |data_type: &DataType| match (format, data_type) {
+        (TableFormat::Arrow, DataType::Dictionary(_, value_type)) => Some(value_type.as_ref().clone()),
+        (TableFormat::Parquet(_), DataType::Duration(_)) => Some(DataType::Int64),
+        _ => None,
+    };
+    if !schema.fields().iter().any(|field| storage_type(field.data_type()).is_some()) {
+        return None;
+    }
+    let fields: Vec<Field> = schema.fields().iter()
+        .map(|field| match storage_type(field.data_type()) {
+            Some(data_type) => field.as_ref().clone().with_data_type(data_type),
+            None => field.as_ref().clone(),
+        })
+        .collect();
+    Some(Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())))
+}
+
+/// Convert a batch to the storage schema, when there is one
+fn to_storage(batch: &RecordBatch, storage: &Option<SchemaRef>) -> Result<RecordBatch> {
+    let Some(schema) = storage else { return Ok(batch.clone()) };
+    let columns = batch.columns().iter()
+        .zip(schema.fields())
+        .map(|(column, field)| cast(column, field.data_type()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Write record batches to a file in the given format
+pub fn write_batches(path: &Path, schema: &SchemaRef, batches: &[RecordBatch], format: TableFormat) -> Result<()> {
+    let mut writer = TableWriter::try_new(path, schema, format)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.finish()
+}
+
+/// Read every record batch from an Arrow IPC or Parquet file
+pub fn read_batches(path: &Path) -> Result<(SchemaRef, Vec<RecordBatch>)> {
+    let format = TableFormat::from_path(path)
+        .ok_or_else(|| LeafError::Custom(format!("Unsupported table file: {}", path.display())))?;
+    let file = File::open(path)
+        .map_err(|e| LeafError::Custom(format!("Failed to open {}: {}", path.display(), e)))?;
+
+    match format {
+        TableFormat::Arrow => {
+            let reader = FileReader::try_new(file, None)
+                .map_err(|e| LeafError::Custom(format!("Failed to create Arrow IPC reader: {}", e)))?;
+            let schema = reader.schema();
+            let expected_batches = reader.num_batches();
+
+            let batches = reader.collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| LeafError::Custom(format!("Failed to read Arrow IPC batch: {}", e)))?;
+
+            if batches.len() != expected_batches {
+                return Err(LeafError::Custom(format!(
+                    "Arrow IPC file declares {} batches but {} could be read",
+                    expected_batches, batches.len()
+                )));
+            }
+            Ok((schema, batches))
+        }
+        TableFormat::Parquet(_) => {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+                .map_err(|e| LeafError::Custom(format!("Failed to open Parquet file: {}", e)))?;
+            let schema = builder.schema().clone();
+            let expected_rows = builder.metadata().file_metadata().num_rows() as usize;
+
+            let reader = builder.build()
+                .map_err(|e| LeafError::Custom(format!("Failed to create Parquet reader: {}", e)))?;
+            let batches = reader.collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| LeafError::Custom(format!("Failed to read Parquet batch: {}", e)))?;
+
+            let read_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+            if read_rows != expected_rows {
+                return Err(LeafError::Custom(format!(
+                    "Parquet file declares {} rows but {} could be read",
+                    expected_rows, read_rows
+                )));
+            }
+            Ok((schema, batches))
+        }
+    }
+}
+
+/// Reads a table file one record batch at a time, so rewriting a table never holds all of it in memory
+pub enum TableReader {
+    Arrow(FileReader<File>),
+    Parquet(ParquetRecordBatchReader),
+}
+
+impl TableReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let format = TableFormat::from_path(path)
+            .ok_or_else(|| LeafError::Custom(format!("Unsupported table file: {}", path.display())))?;
+        let file = File::open(path)
+            .map_err(|e| LeafError::Custom(format!("Failed to open {}: {}", path.display(), e)))?;
+
+        match format {
+            TableFormat::Arrow => FileReader::try_new(file, None)
+                .map(TableReader::Arrow)
+                .map_err(|e| LeafError::Custom(format!("Failed to create Arrow IPC reader: {}", e))),
+            TableFormat::Parquet(_) => ParquetRecordBatchReaderBuilder::try_new(file)
+                .and_then(|builder| builder.build())
+                .map(TableReader::Parquet)
+                .map_err(|e| LeafError::Custom(format!("Failed to create Parquet reader: {}", e))),
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        match self {
+            TableReader::Arrow(reader) => reader.schema(),
+            TableReader::Parquet(reader) => reader.schema(),
+        }
+    }
+}
+
+impl Iterator for TableReader {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            TableReader::Arrow(reader) => reader.next()
+                .map(|batch| batch.map_err(|e| LeafError::Custom(format!("Failed to read Arrow IPC batch: {}", e)))),
+            TableReader::Parquet(reader) => reader.next()
+                .map(|batch| batch.map_err(|e| LeafError::Custom(format!("Failed to read Parquet batch: {}", e)))),
+        }
+    }
+}
+
+/// The Arrow or Parquet file a table is saved in within `dir`, preferring Arrow like a project load does
+pub fn find_table_file(dir: &Path, table_name: &str) -> Option<PathBuf> {
+    [TableFormat::Arrow, TableFormat::Parquet(ParquetCompression::Snappy)].iter()
+        .map(|format| dir.join(format.file_name(table_name)))
+        .find(|path| path.is_file())
+}
+
+/// Files a table can be scanned from lazily, without loading it into memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Arrow,
+    Parquet,
+    Csv { delimiter: u8, has_header: bool },
+}
+
+impl SourceFormat {
+    /// Detect the format from a file extension; CSV and TSV files are assumed to have a header row
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "arrow" | "ipc" | "feather" => Some(SourceFormat::Arrow),
+            "parquet" => Some(SourceFormat::Parquet),
+            "csv" => Some(SourceFormat::Csv { delimiter: b',', has_header: true }),
+            "tsv" => Some(SourceFormat::Csv { delimiter: b'\t', has_header: true }),
+            _ => None,
+        }
+    }
+
+    /// Listing options for a DataFusion listing table over a single file of this format
+    pub fn listing_options(&self, path: &Path) -> ListingOptions {
+        let options = match self {
+            SourceFormat::Arrow => ListingOptions::new(Arc::new(ArrowFormat)),
+            SourceFormat::Parquet => ListingOptions::new(Arc::new(ParquetFormat::default())),
+            SourceFormat::Csv { delimiter, has_header } => ListingOptions::new(Arc::new(
+                CsvFormat::default()
+                    .with_delimiter(*delimiter)
+                    .with_has_header(*has_header)
+            )),
+        };
+
+        // Match the file's own extension so `.ipc` and `.feather` files are listed too
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| format!(".{}", ext))
+            .unwrap_or_default();
+        options.with_file_extension(extension)
+    }
+}
+
+/// Row count stored in a file's metadata, without reading any column data.
+/// Returns `None` for formats that don't record one (CSV).
+pub fn file_row_count(path: &Path, format: SourceFormat) -> Result<Option<usize>> {
+    match format {
+        SourceFormat::Arrow => arrow_row_count(path).map(Some),
+        SourceFormat::Parquet => {
+            let file = File::open(path)
+                .map_err(|e| LeafError::Custom(format!("Failed to open {}: {}", path.display(), e)))?;
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+                .map_err(|e| LeafError::Custom(format!("Failed to open Parquet file: {}", e)))?;
+            Ok(Some(builder.metadata().file_metadata().num_rows() as usize))
+        }
+        SourceFormat::Csv { .. } => Ok(None),
+    }
+}
+
+/// Sum the row counts recorded in each record batch header listed in the Arrow IPC footer
+fn arrow_row_count(path: &Path) -> Result<usize> {
+    let ipc_error = |e: String| LeafError::Custom(format!("Failed to read Arrow IPC metadata from {}: {}", path.display(), e));
+
+    let mut file = File::open(path)
+        .map_err(|e| LeafError::Custom(format!("Failed to open {}: {}", path.display(), e)))?;
+
+    // The file ends with the footer, its length and the "ARROW1" magic
+    let mut trailer = [0u8; 10];
+    file.seek(SeekFrom::End(-10)).map_err(|e| ipc_error(e.to_string()))?;
+    file.read_exact(&mut trailer).map_err(|e| ipc_error(e.to_string()))?;
+    let footer_len = read_footer_length(trailer).map_err(|e| ipc_error(e.to_string()))?;
+
+    let mut footer_data = vec![0u8; footer_len];
+    file.seek(SeekFrom::End(-10 - footer_len as i64)).map_err(|e| ipc_error(e.to_string()))?;
+    file.read_exact(&mut footer_data).map_err(|e| ipc_error(e.to_string()))?;
+    let footer = root_as_footer(&footer_data).map_err(|e| ipc_error(e.to_string()))?;
+
+    let mut rows = 0usize;
+    for block in footer.recordBatches().into_iter().flatten() {
+        let mut metadata = vec![0u8; block.metaDataLength() as usize];
+        file.seek(SeekFrom::Start(block.offset() as u64)).map_err(|e| ipc_error(e.to_string()))?;
+        file.read_exact(&mut metadata).map_err(|e| ipc_error(e.to_string()))?;
+
+        // Skip the continuation marker (if present) and the metadata length prefix
+        let message_data = if metadata.len() >= 8 && metadata[..4] == [0xff; 4] {
+            &metadata[8..]
+        } else if metadata.len() >= 4 {
+            &metadata[4..]
+        } else {
+            return Err(ipc_error("truncated record batch header".to_string()));
+        };
+
+        let message = root_as_message(message_data).map_err(|e| ipc_error(e.to_string()))?;
+        let batch = message.header_as_record_batch()
+            .ok_or_else(|| ipc_error("block is not a record batch".to_string()))?;
+        rows += batch.length() as usize;
+    }
+
+    Ok(rows)
+}
@@ -0,0 +1,88 @@
+use std::io::Read;
+use std::path::Path;
+use encoding_rs::Encoding;
+use crate::core::csv_source;
+use crate::core::error::{Result, LeafError};
+
+/// Encodings offered in the import dialogs, in the order shown
+pub const SELECTABLE_ENCODINGS: [&Encoding; 11] = [
+    encoding_rs::UTF_8,
+    encoding_rs::UTF_16LE,
+    encoding_rs::UTF_16BE,
+    encoding_rs::WINDOWS_1252,
+    encoding_rs::ISO_8859_15,
+    encoding_rs::WINDOWS_1250,
+    encoding_rs::ISO_8859_2,
+    encoding_rs::WINDOWS_1251,
+    encoding_rs::SHIFT_JIS,
+    encoding_rs::GBK,
+    encoding_rs::EUC_KR,
+];
+
+/// Human readable name for the encoding picker
+pub fn display_name(encoding: &'static Encoding) -> &'static str {
+    match encoding.name() {
+        "UTF-8" => "UTF-8",
+        "UTF-16LE" => "UTF-16 (little endian)",
+        "UTF-16BE" => "UTF-16 (big endian)",
+        "windows-1252" => "Windows-1252 / Latin-1",
+        "ISO-8859-15" => "ISO-8859-15 (Latin-9)",
+        "windows-1250" => "Windows-1250 (Central European)",
+        "ISO-8859-2" => "ISO-8859-2 (Latin-2)",
+        "windows-1251" => "Windows-1251 (Cyrillic)",
+        "Shift_JIS" => "Shift_JIS (Japanese)",
+        "GBK" => "GBK (Chinese)",
+        "EUC-KR" => "EUC-KR (Korean)",
+        name => name,
+    }
+}
+
+/// Detect the text encoding of a file from its first 64 KB (after decompression)
+pub fn detect_encoding(path: &Path) -> Result<&'static Encoding> {
+    let mut sample = Vec::new();
+    csv_source::open_decompressed(path, None)?.0
+        .take(64 * 1024)
+        .read_to_end(&mut sample)
+        .map_err(|e| LeafError::Custom(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    Ok(detect_encoding_from_sample(&sample))
+}
+
+/// Detect the encoding of a byte sample:
+/// a byte order mark wins, then UTF-16 without a BOM is recognised by its zero bytes,
+/// then valid UTF-8, and anything else is taken as Windows-1252 (a superset of Latin-1)
+pub fn detect_encoding_from_sample(sample: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(sample) {
+        return encoding;
+    }
+
+    if let Some(encoding) = detect_utf16_without_bom(sample) {
+        return encoding;
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => encoding_rs::UTF_8,
+        // The sample may end part way through a multi-byte character
+        Err(e) if e.error_len().is_none() => encoding_rs::UTF_8,
+        Err(_) => encoding_rs::WINDOWS_1252,
+    }
+}
+
+/// Mostly-ASCII UTF-16 text has a zero byte in every other position
+fn detect_utf16_without_bom(sample: &[u8]) -> Option<&'static Encoding> {
+    let pairs = sample.len() / 2;
+    if pairs < 4 {
+        return None;
+    }
+
+    let even_zeros = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+    let odd_zeros = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+
+    if odd_zeros * 10 >= pairs * 7 && even_zeros * 10 <= pairs {
+        Some(encoding_rs::UTF_16LE)
+    } else if even_zeros * 10 >= pairs * 7 && odd_zeros * 10 <= pairs {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 use datafusion::arrow::array::{Int64Array, StringArray};
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use crate::core::error::Result;
+use crate::core::csv_ingest::{CsvIngestOptions, RejectPolicy};
 use crate::ui::time_bin_dialog::{TimeBinStrategy as GroupingStrategy, TimeBinConfig as TimeBasedGroupingConfig};
 
 pub struct TimeGroupingEngine;
@@ -31,12 +32,12 @@ impl TimeGroupingEngine {
         
         // Create new table with grouping column
         let output_table_name = if let Some(custom_name) = &config.output_filename {
-            // Remove .arrow extension if provided
-            custom_name.trim_end_matches(".arrow").to_string()
+            // Remove any table file extension if provided
+            custom_name.trim_end_matches(".arrow").trim_end_matches(".parquet").to_string()
         } else {
             format!("{}_grouped", config.selected_table)
         };
-        Self::create_grouped_table(database, &rows, &column_names, &groups, &config.output_column_name, &output_table_name, output_dir)?;
+        Self::create_grouped_table(database, &rows, &column_names, &groups, &config.output_column_name, &output_table_name, output_dir, config.output_format)?;
         
         Ok(output_table_name)
     }
@@ -202,6 +203,7 @@ impl TimeGroupingEngine {
         group_column_name: &str,
         output_table_name: &str,
         output_dir: &std::path::Path,
+        output_format: crate::core::TableFormat,
     ) -> Result<()> {
         // Create new rows with grouping column
         let mut new_rows = Vec::new();
@@ -242,12 +244,10 @@ impl TimeGroupingEngine {
         csv_writer.flush()
             .map_err(|e| crate::core::error::LeafError::Custom(format!("Failed to flush CSV writer: {}", e)))?;
         
-        // Import the CSV with automatic type inference
-        new_db.stream_insert_csv(output_table_name, &temp_csv_path, ',', true)?;
-        
-        // Save the table as an Arrow file
-        let output_path = output_dir.join(format!("{}.arrow", output_table_name));
-        new_db.save_table_arrow_ipc(output_table_name, &output_path)?;
+        // Stream the CSV into a table file in the requested format, with automatic type inference
+        let options = CsvIngestOptions { reject_policy: RejectPolicy::Fail, ..CsvIngestOptions::default() };
+        let summary = new_db.import_csv_to_file(output_table_name, &temp_csv_path, output_dir, output_format, &options, |_| {})?;
+        let output_path = summary.output_path;
         
         // Clean up temporary CSV file
         let _ = std::fs::remove_file(&temp_csv_path);
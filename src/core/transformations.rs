@@ -126,6 +126,7 @@ impl DataTransformer {
 
     /// Compute delta between consecutive values in an array
     fn compute_delta(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        let array = &widen_numeric(array)?;
         match array.data_type() {
             DataType::Int64 => {
                 let int_array = array.as_any().downcast_ref::<Int64Array>().unwrap();
@@ -290,7 +291,8 @@ impl DataTransformer {
                 let bool_array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
                 bool_array.value(row_idx).to_string()
             }
-            _ => format!("{:?}", array.data_type()),
+            _ => datafusion::arrow::util::display::array_value_to_string(array, row_idx)
+                .unwrap_or_else(|_| format!("{:?}", array.data_type())),
         }
     }
 
@@ -314,7 +316,7 @@ impl DataTransformer {
 
         for field in schema.fields() {
             match field.data_type() {
-                DataType::Int64 | DataType::Float64 => {
+                data_type if data_type.is_numeric() => {
                     numeric_columns.push(field.name().to_string());
                 }
                 _ => {}
@@ -364,6 +366,7 @@ impl DataTransformer {
 
     /// Compute cumulative sum of an array
     fn compute_cumulative_sum(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        let array = &widen_numeric(array)?;
         match array.data_type() {
             DataType::Int64 => {
                 let int_array = array.as_any().downcast_ref::<Int64Array>().unwrap();
@@ -424,6 +427,7 @@ impl DataTransformer {
 
     /// Compute percentage of total for each value
     fn compute_percentage(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        let array = &widen_numeric(array)?;
         match array.data_type() {
             DataType::Int64 => {
                 let int_array = array.as_any().downcast_ref::<Int64Array>().unwrap();
@@ -508,6 +512,8 @@ impl DataTransformer {
             return Err(anyhow!("Arrays must have the same length for ratio calculation"));
         }
 
+        let numerator = &widen_numeric(numerator)?;
+        let denominator = &widen_numeric(denominator)?;
         let mut values = Vec::with_capacity(numerator.len());
 
         match (numerator.data_type(), denominator.data_type()) {
@@ -597,4 +603,14 @@ impl DataTransformer {
         
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+
+/// Widen narrow integers to Int64 and Float32 or decimals to Float64, the types the computations work on
+fn widen_numeric(array: &ArrayRef) -> Result<ArrayRef> {
+    match array.data_type() {
+        DataType::Int8 | DataType::Int16 | DataType::Int32 |
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => Ok(compute::cast(array, &DataType::Int64)?),
+        DataType::UInt64 | DataType::Float32 | DataType::Decimal128(_, _) => Ok(compute::cast(array, &DataType::Float64)?),
+        _ => Ok(array.clone()),
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+use super::{datetime_format, duration_format, ColumnTokens, ColumnType, NumberFormat, TypeInferrer};
+use super::{DECIMAL_PRECISION, DICTIONARY_MAX_DISTINCT, DICTIONARY_MIN_VALUES};
+
+/// How many values a candidate type can read, and the first one it couldn't.
+/// Values after a miss are still counted, so the share shows how close the column came to the type.
+#[derive(Debug, Clone, Default)]
+struct Tally {
+    matched: usize,
+    first_miss: Option<String>,
+}
+
+impl Tally {
+    /// Whether every value so far could be read as the type
+    fn possible(&self) -> bool {
+        self.first_miss.is_none()
+    }
+
+    fn check(&mut self, value: &str, matches: impl FnOnce(&str) -> bool) {
+        if matches(value) {
+            self.matched += 1;
+        } else if self.first_miss.is_none() {
+            self.first_miss = Some(value.to_string());
+        }
+    }
+}
+
+/// The type chosen for a column, how sure the choice is and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnInference {
+    pub column_type: ColumnType,
+    /// From 0 to 1. Low when few values were seen, or when a column falls back to text
+    /// although most of its values would read as another type.
+    pub confidence: f32,
+    /// Share of non-null values each candidate type can read, highest first
+    pub candidates: Vec<(ColumnType, f32)>,
+    pub reason: String,
+    /// Non-null values seen
+    pub values: usize,
+    pub nulls: usize,
+}
+
+/// Running counts of the types a column's values can be read as.
+/// Values are fed one at a time, so a column can be profiled from a sample or from a whole file.
+#[derive(Debug, Clone)]
+pub struct ColumnProfile {
+    header: String,
+    tokens: ColumnTokens,
+    number_format: NumberFormat,
+    datetime_pattern: Option<String>,
+    /// The timestamp unit the header names, e.g. `elapsed (ms)`
+    header_type: Option<ColumnType>,
+    values: usize,
+    nulls: usize,
+    boolean: Tally,
+    integer: Tally,
+    float: Tally,
+    datetime: Tally,
+    date: Tally,
+    time_of_day: Tally,
+    duration: Tally,
+    pattern: Tally,
+    header_hint: Tally,
+    has_decimal: bool,
+    has_currency: bool,
+    max_scale: u8,
+    int_range: Option<(i64, i64)>,
+    /// Whether a Float32 holds every number exactly, so sums and averages stay the same
+    fits_f32: bool,
+    distinct: HashSet<String>,
+}
+
+impl ColumnProfile {
+    pub fn new(header: &str, tokens: ColumnTokens, number_format: &NumberFormat, datetime_pattern: Option<&str>) -> Self {
+        Self {
+            header: header.to_string(),
+            tokens,
+            number_format: *number_format,
+            datetime_pattern: datetime_pattern.map(String::from),
+            header_type: TypeInferrer::detect_time_unit_from_header(header),
+            values: 0,
+            nulls: 0,
+            boolean: Tally::default(),
+            integer: Tally::default(),
+            float: Tally::default(),
+            datetime: Tally::default(),
+            date: Tally::default(),
+            time_of_day: Tally::default(),
+            duration: Tally::default(),
+            pattern: Tally::default(),
+            header_hint: Tally::default(),
+            has_decimal: false,
+            has_currency: false,
+            max_scale: 0,
+            int_range: None,
+            fits_f32: true,
+            distinct: HashSet::new(),
+        }
+    }
+
+    pub fn observe(&mut self, value: &str) {
+        if self.tokens.is_null(value) {
+            self.nulls += 1;
+            return;
+        }
+        self.values += 1;
+        if self.distinct.len() <= DICTIONARY_MAX_DISTINCT {
+            self.distinct.insert(value.to_string());
+        }
+
+        let booleans = &self.tokens.booleans;
+        self.boolean.check(value, |value| booleans.parse(value).is_some());
+
+        let integer = self.number_format.parse_i64(value);
+        if let Some(n) = integer {
+            self.int_range = Some(match self.int_range {
+                Some((min, max)) => (min.min(n), max.max(n)),
+                None => (n, n),
+            });
+        }
+        self.integer.check(value, |_| integer.is_some());
+
+        let float = self.number_format.parse_f64(value);
+        if let Some(number) = float {
+            self.has_decimal |= integer.is_none();
+            self.fits_f32 &= f64::from(number as f32) == number;
+            // Currency amounts are kept exact as decimals
+            if let Some(parsed) = self.number_format.parse(value) {
+                self.has_currency |= parsed.currency;
+                self.max_scale = self.max_scale.max(parsed.scale);
+            }
+        }
+        self.float.check(value, |_| float.is_some());
+
+        self.datetime.check(value, TypeInferrer::is_datetime);
+        self.date.check(value, TypeInferrer::is_date);
+        self.time_of_day.check(value, TypeInferrer::is_time);
+        self.duration.check(value, |value| duration_format::parse_duration(value).is_some());
+        if let Some(pattern) = &self.datetime_pattern {
+            self.pattern.check(value, |value| datetime_format::parse_datetime(value, Some(pattern)).is_some());
+        }
+        if let Some(header_type) = &self.header_type {
+            let (number_format, tokens) = (&self.number_format, &self.tokens);
+            self.header_hint.check(value, |value| header_type.can_parse_value_with_tokens(value, number_format, tokens));
+        }
+    }
+
+    /// Pick the most specific type every value can be read as, falling back to text.
+    /// Numbers keep their 64-bit types, since more of the file may not fit a narrower one.
+    pub fn finish(&self) -> ColumnInference {
+        self.finish_with(false)
+    }
+
+    /// `finish` for a profile fed every value of a file, so numbers get the narrowest type that holds them all
+    pub fn finish_whole_file(&self) -> ColumnInference {
+        self.finish_with(true)
+    }
+
+    fn finish_with(&self, whole_file: bool) -> ColumnInference {
+        let n = self.values;
+        let all = |tally: &Tally| n > 0 && tally.possible();
+
+        let (column_type, reason) = if let Some(header_type) = &self.header_type {
+            (header_type.clone(), format!("The header '{}' names the time unit", self.header))
+        } else if n == 0 {
+            (ColumnType::Text, "Every value is empty or a null token".to_string())
+        } else if all(&self.boolean) {
+            (ColumnType::Boolean, format!("All {} values are {}", n, self.tokens.booleans.describe()))
+        } else if all(&self.float) && self.has_currency {
+            let scale = self.max_scale.min(DECIMAL_PRECISION);
+            (
+                ColumnType::Decimal { precision: DECIMAL_PRECISION, scale: scale as i8 },
+                format!("Amounts with a currency are kept exact, with {} decimal places", scale),
+            )
+        } else if all(&self.integer) {
+            let (min, max) = self.int_range.unwrap_or((0, 0));
+            let column_type = if whole_file { TypeInferrer::narrowest_integer((min, max)) } else { ColumnType::Integer };
+            (column_type, format!("All {} values are whole numbers from {} to {}", n, min, max))
+        } else if all(&self.float) {
+            if self.has_decimal && whole_file && self.fits_f32 {
+                (ColumnType::Float32, format!("All {} values are numbers a 32-bit float holds exactly", n))
+            } else if self.has_decimal {
+                (ColumnType::Real, format!("All {} values are numbers, some with decimals", n))
+            } else {
+                (ColumnType::Integer, format!("All {} values are numbers without decimals", n))
+            }
+        } else if all(&self.datetime) {
+            (ColumnType::DateTime, format!("All {} values are dates with times", n))
+        } else if all(&self.date) {
+            (ColumnType::Date, format!("All {} values are dates", n))
+        } else if all(&self.time_of_day) && !TypeInferrer::is_duration_header(&self.header) {
+            (ColumnType::TimeOfDay, format!("All {} values are times of day", n))
+        } else if all(&self.duration) {
+            (ColumnType::Duration, format!("All {} values are elapsed times", n))
+        } else if n >= DICTIONARY_MIN_VALUES && self.distinct.len() * 10 <= n && self.distinct.len() <= DICTIONARY_MAX_DISTINCT {
+            (ColumnType::Dictionary, format!("Only {} distinct values among {}, so each is stored once", self.distinct.len(), n))
+        } else {
+            (ColumnType::Text, String::new())
+        };
+
+        // A date/time pattern chosen for the column reads text that the built-in layouts don't
+        let (column_type, reason) = match &self.datetime_pattern {
+            Some(pattern) if matches!(column_type, ColumnType::Text | ColumnType::Dictionary) && all(&self.pattern) => {
+                (datetime_format::pattern_type(pattern), format!("All {} values match the pattern {}", n, pattern))
+            }
+            _ => (column_type, reason),
+        };
+
+        let candidates = self.candidates();
+        let evidence = n as f32 / (n as f32 + 10.0);
+        let (confidence, reason) = match &column_type {
+            _ if self.header_type.is_some() => (evidence * self.share(&self.header_hint), reason),
+            ColumnType::Text | ColumnType::Dictionary if n > 0 => {
+                let best_share = candidates.first().map_or(0.0, |(_, share)| *share);
+                let reason = match (self.fallback_reason(&candidates), reason.is_empty()) {
+                    (Some(fallback), true) => fallback,
+                    (Some(fallback), false) => format!("{}; {}", reason, fallback),
+                    (None, true) => "The values are free text".to_string(),
+                    (None, false) => reason,
+                };
+                (evidence * (1.0 - best_share), reason)
+            }
+            _ => (evidence, reason),
+        };
+
+        ColumnInference {
+            column_type,
+            confidence,
+            candidates,
+            reason,
+            values: n,
+            nulls: self.nulls,
+        }
+    }
+
+    fn share(&self, tally: &Tally) -> f32 {
+        if self.values == 0 {
+            0.0
+        } else {
+            tally.matched as f32 / self.values as f32
+        }
+    }
+
+    fn tallies(&self) -> Vec<(ColumnType, &Tally)> {
+        let mut tallies = vec![
+            (ColumnType::Boolean, &self.boolean),
+            (ColumnType::Integer, &self.integer),
+            (ColumnType::Real, &self.float),
+            (ColumnType::DateTime, &self.datetime),
+            (ColumnType::Date, &self.date),
+            (ColumnType::TimeOfDay, &self.time_of_day),
+            (ColumnType::Duration, &self.duration),
+        ];
+        if let Some(pattern) = &self.datetime_pattern {
+            tallies.push((datetime_format::pattern_type(pattern), &self.pattern));
+        }
+        tallies
+    }
+
+    fn candidates(&self) -> Vec<(ColumnType, f32)> {
+        let mut candidates: Vec<(ColumnType, f32)> = self.tallies().into_iter()
+            .filter(|(_, tally)| tally.matched > 0)
+            .map(|(column_type, tally)| (column_type, self.share(tally)))
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        // A pattern can read the same type as a built-in check; keep the better share
+        let mut seen = Vec::new();
+        candidates.retain(|(column_type, _)| {
+            let new = !seen.contains(column_type);
+            seen.push(column_type.clone());
+            new
+        });
+        candidates
+    }
+
+    /// Why a column that is mostly one type stays text, e.g. "997 of 1000 values read as Integer (64-bit), but not 'n/k'"
+    fn fallback_reason(&self, candidates: &[(ColumnType, f32)]) -> Option<String> {
+        let (best_type, _) = candidates.first()?;
+        let (_, tally) = self.tallies().into_iter().find(|(column_type, _)| column_type == best_type)?;
+        Some(format!(
+            "{} of {} values read as {}, but not '{}'",
+            tally.matched, self.values, best_type.display_name(), tally.first_miss.as_deref().unwrap_or("")
+        ))
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use serde::{Deserialize, Serialize};
+use super::ColumnType;
+
+/// ISO-8601 layouts with a UTC offset (`Z`, `+02:00` or `+0200`)
+const OFFSET_FORMATS: [&str; 2] = [
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+    "%Y-%m-%d %H:%M:%S%.f%#z",
+];
+
+/// Layouts without an offset; `%.f` also matches values without a fraction
+const NAIVE_FORMATS: [&str; 8] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S%.f",
+    "%d/%m/%Y %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y/%m/%d %H:%M",
+];
+
+/// A date and time read from text, with the UTC offset it was written with, if any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedDateTime {
+    pub local: NaiveDateTime,
+    pub offset: Option<FixedOffset>,
+}
+
+impl ParsedDateTime {
+    /// The instant this value refers to. Values without an offset are read as wall-clock
+    /// times in `zone`, or as UTC when no zone is given.
+    pub fn to_utc(&self, zone: Option<&Tz>) -> Option<DateTime<Utc>> {
+        match (self.offset, zone) {
+            (Some(offset), _) => offset.from_local_datetime(&self.local).single().map(|dt| dt.with_timezone(&Utc)),
+            (None, Some(zone)) => zone.from_local_datetime(&self.local).earliest().map(|dt| dt.with_timezone(&Utc)),
+            (None, None) => Some(self.local.and_utc()),
+        }
+    }
+}
+
+/// Parse a date/time with a strftime `pattern`, or with the built-in ISO-8601 and common layouts when `None`.
+/// A pattern without time fields reads the value as midnight on that date.
+pub fn parse_datetime(value: &str, pattern: Option<&str>) -> Option<ParsedDateTime> {
+    let value = value.trim();
+
+    if let Some(pattern) = pattern {
+        if let Ok(datetime) = DateTime::parse_from_str(value, pattern) {
+            return Some(ParsedDateTime { local: datetime.naive_local(), offset: Some(*datetime.offset()) });
+        }
+        if let Ok(local) = NaiveDateTime::parse_from_str(value, pattern) {
+            return Some(ParsedDateTime { local, offset: None });
+        }
+        return NaiveDate::parse_from_str(value, pattern).ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|local| ParsedDateTime { local, offset: None });
+    }
+
+    for format in &OFFSET_FORMATS {
+        if let Ok(datetime) = DateTime::parse_from_str(value, format) {
+            return Some(ParsedDateTime { local: datetime.naive_local(), offset: Some(*datetime.offset()) });
+        }
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(ParsedDateTime { local: datetime.naive_local(), offset: Some(*datetime.offset()) });
+    }
+    NAIVE_FORMATS.iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|local| ParsedDateTime { local, offset: None })
+}
+
+/// Parse a time of day such as `09:30`, `17:45:00` or `17:45:00.250`
+pub fn parse_time_of_day(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    NaiveTime::parse_from_str(value, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .ok()
+}
+
+/// strftime fields that read a time of day
+const TIME_FIELDS: [&str; 13] = ["%H", "%I", "%k", "%l", "%M", "%S", "%T", "%R", "%r", "%X", "%c", "%s", "%+"];
+
+/// The type a pattern reads a column as: Date when it has no time fields, DateTime otherwise.
+/// `None` when there are no values or some non-empty value doesn't match.
+pub fn pattern_column_type<'a>(pattern: &str, values: impl IntoIterator<Item = &'a str>) -> Option<ColumnType> {
+    let mut values = values.into_iter().filter(|value| !value.is_empty()).peekable();
+    values.peek()?;
+    if !values.all(|value| parse_datetime(value, Some(pattern)).is_some()) {
+        return None;
+    }
+    Some(pattern_type(pattern))
+}
+
+/// DateTime when the pattern has time fields, Date otherwise
+pub fn pattern_type(pattern: &str) -> ColumnType {
+    if TIME_FIELDS.iter().any(|field| pattern.contains(field)) {
+        ColumnType::DateTime
+    } else {
+        ColumnType::Date
+    }
+}
+
+/// Parse a timezone name (`UTC`, `Europe/Berlin`) or fixed offset (`+02:00`)
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse::<Tz>().ok()
+}
+
+/// Number of fraction-of-second digits written in a value such as `10:00:00.123456`
+pub fn fraction_digits(value: &str) -> u8 {
+    // Seconds follow the second colon of the time; a later colon belongs to the offset
+    let Some(first) = value.find(':') else { return 0 };
+    let Some(second) = value[first + 1..].find(':') else { return 0 };
+    let seconds = &value[first + second + 2..];
+    let mut chars = seconds.chars().skip_while(|c| c.is_ascii_digit());
+    match chars.next() {
+        Some('.') | Some(',') => chars.take_while(|c| c.is_ascii_digit()).count().min(9) as u8,
+        _ => 0,
+    }
+}
+
+/// A UTC instant as a count of `unit`s since the epoch
+pub fn timestamp_in_unit(datetime: &DateTime<Utc>, unit: &TimeUnit) -> Option<i64> {
+    match unit {
+        TimeUnit::Second => Some(datetime.timestamp()),
+        TimeUnit::Millisecond => Some(datetime.timestamp_millis()),
+        TimeUnit::Microsecond => Some(datetime.timestamp_micros()),
+        TimeUnit::Nanosecond => datetime.timestamp_nanos_opt(),
+    }
+}
+
+/// How the values of a DateTime column are read and stored
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DateTimeFormat {
+    /// strftime pattern chosen for the column; the built-in layouts are tried when `None`
+    pub pattern: Option<String>,
+    /// Most fraction-of-second digits seen in the sample; sets the stored precision
+    pub fraction_digits: u8,
+    /// Whether any sample value carries a UTC offset such as `Z` or `+02:00`
+    pub has_offset: bool,
+}
+
+impl DateTimeFormat {
+    /// Work out the precision and whether offsets are present from sample values
+    pub fn infer<'a>(pattern: Option<String>, values: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut format = Self { pattern, ..Self::default() };
+        for value in values {
+            if let Some(parsed) = parse_datetime(value, format.pattern.as_deref()) {
+                format.fraction_digits = format.fraction_digits.max(fraction_digits(value));
+                format.has_offset |= parsed.offset.is_some();
+            }
+        }
+        format
+    }
+
+    pub fn unit(&self) -> TimeUnit {
+        match self.fraction_digits {
+            0 => TimeUnit::Second,
+            1..=3 => TimeUnit::Millisecond,
+            4..=6 => TimeUnit::Microsecond,
+            _ => TimeUnit::Nanosecond,
+        }
+    }
+
+    /// Arrow type for the column. Values are stored as UTC instants tagged with `timezone`;
+    /// columns with offsets but no chosen timezone are tagged UTC, and the rest stay naive.
+    pub fn arrow_type(&self, timezone: Option<&str>) -> DataType {
+        let timezone = match timezone {
+            Some(timezone) => Some(timezone),
+            None if self.has_offset => Some("UTC"),
+            None => None,
+        };
+        DataType::Timestamp(self.unit(), timezone.map(Into::into))
+    }
+}
@@ -0,0 +1,135 @@
+/// Unit suffixes accepted in durations such as `1h 30m` or `250ms`, with their length in nanoseconds
+const UNITS: [(&str, i64); 11] = [
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("sec", 1_000_000_000),
+    ("m", 60_000_000_000),
+    ("min", 60_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("hr", 3_600_000_000_000),
+    ("d", 86_400_000_000_000),
+];
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Parse an elapsed time in nanoseconds. Accepts clock notation (`1:30:00`, `36:00:00.5`, `1:30`
+/// as hours and minutes), unit-suffixed parts (`1h 30m`, `2.5s`, `3d4h`) and ISO-8601 (`PT1H30M`).
+/// Bare numbers are rejected, as their unit is unknown.
+pub fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, value),
+    };
+    if value.is_empty() {
+        return None;
+    }
+
+    let nanos = if value.contains(':') {
+        parse_clock(value)?
+    } else if let Some(iso) = value.strip_prefix("PT").or_else(|| value.strip_prefix("pt")) {
+        parse_units(&iso.to_lowercase())?
+    } else {
+        parse_units(value)?
+    };
+    Some(if negative { -nanos } else { nanos })
+}
+
+/// `H:MM:SS(.f)` or `H:MM`, with any number of hours
+fn parse_clock(value: &str) -> Option<i64> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours, minutes] => (*hours, *minutes, "0"),
+        [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        _ => return None,
+    };
+    if hours.is_empty() || !hours.chars().all(|c| c.is_ascii_digit()) || minutes.len() != 2 || !minutes.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let minutes: i64 = minutes.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    let seconds = parse_seconds(seconds)?;
+    if seconds >= 60 * NANOS_PER_SECOND {
+        return None;
+    }
+    hours.parse::<i64>().ok()?
+        .checked_mul(3_600 * NANOS_PER_SECOND)?
+        .checked_add(minutes * 60 * NANOS_PER_SECOND)?
+        .checked_add(seconds)
+}
+
+/// Seconds with an optional fraction, in nanoseconds
+fn parse_seconds(text: &str) -> Option<i64> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if whole.is_empty() || !whole.chars().all(|c| c.is_ascii_digit()) || fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let fraction_nanos = format!("{:0<9}", fraction).parse::<i64>().ok()?;
+    whole.parse::<i64>().ok()?.checked_mul(NANOS_PER_SECOND)?.checked_add(fraction_nanos)
+}
+
+/// A sequence of number-unit pairs, optionally separated by spaces: `1h 30m`, `2.5s`, `5 min`
+fn parse_units(text: &str) -> Option<i64> {
+    let mut rest = text.trim();
+    let mut total: i64 = 0;
+    let mut parts = 0;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let (number, after) = rest.split_at(number_len);
+        let after = after.trim_start();
+        let unit_len = after.find(|c: char| c.is_ascii_digit() || c == ' ').unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_len);
+        let (_, unit_nanos) = UNITS.iter().find(|(name, _)| *name == unit)?;
+        if number.is_empty() || number.matches('.').count() > 1 {
+            return None;
+        }
+        let amount: f64 = number.parse().ok()?;
+        total = total.checked_add((amount * *unit_nanos as f64).round() as i64)?;
+        parts += 1;
+        rest = after.trim_start();
+    }
+    (parts > 0).then_some(total)
+}
+
+/// Format nanoseconds as `1h 30m 5.25s`, `250ms` or `0s`
+pub fn format_duration(nanos: i64) -> String {
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let sign = if nanos < 0 { "-" } else { "" };
+    let mut remaining = nanos.unsigned_abs();
+    let nanos_per_second = NANOS_PER_SECOND as u64;
+
+    if remaining < nanos_per_second {
+        return match remaining {
+            r if r % 1_000_000 == 0 => format!("{}{}ms", sign, r / 1_000_000),
+            r if r % 1_000 == 0 => format!("{}{}us", sign, r / 1_000),
+            r => format!("{}{}ns", sign, r),
+        };
+    }
+
+    let mut parts = Vec::new();
+    for (unit, length) in [("d", 86_400), ("h", 3_600), ("m", 60)] {
+        let length = length * nanos_per_second;
+        if remaining >= length {
+            parts.push(format!("{}{}", remaining / length, unit));
+            remaining %= length;
+        }
+    }
+    if remaining > 0 {
+        let seconds = remaining / nanos_per_second;
+        let fraction = remaining % nanos_per_second;
+        if fraction == 0 {
+            parts.push(format!("{}s", seconds));
+        } else {
+            let fraction = format!("{:09}", fraction);
+            parts.push(format!("{}.{}s", seconds, fraction.trim_end_matches('0')));
+        }
+    }
+    format!("{}{}", sign, parts.join(" "))
+}
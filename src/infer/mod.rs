@@ -1,10 +1,37 @@
 use serde::{Deserialize, Serialize};
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 
+mod column_profile;
+pub mod datetime_format;
+pub mod duration_format;
+mod number_format;
+mod value_tokens;
+pub use column_profile::{ColumnInference, ColumnProfile};
+pub use datetime_format::DateTimeFormat;
+pub use number_format::NumberFormat;
+pub use value_tokens::{BooleanVocabulary, ColumnTokens, DEFAULT_NULL_VALUES};
+
+/// Precision used for inferred decimal columns (the Decimal128 maximum)
+const DECIMAL_PRECISION: u8 = 38;
+/// A text column needs at least this many sampled values to be dictionary-encoded...
+const DICTIONARY_MIN_VALUES: usize = 20;
+/// ...and at most this many distinct ones, and no more than one distinct value in ten
+const DICTIONARY_MAX_DISTINCT: usize = 1000;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ColumnType {
     Integer,
+    Int8,
+    Int16,
+    Int32,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
     Real,
+    Float32,
+    /// Exact fixed-point numbers such as money, stored as Decimal128
+    Decimal { precision: u8, scale: i8 },
     Text,
     Boolean,
     Date,
@@ -13,6 +40,12 @@ pub enum ColumnType {
     TimeMilliseconds,
     TimeMicroseconds,
     TimeNanoseconds,
+    /// Time of day (HH:MM:SS), stored as Time64
+    TimeOfDay,
+    /// Elapsed time such as `1h 30m` or `00:01:30.5`
+    Duration,
+    /// Text with few distinct values, stored dictionary-encoded
+    Dictionary,
     Blob,
 }
 
@@ -20,7 +53,16 @@ impl ColumnType {
     pub fn to_sql_type(&self) -> &'static str {
         match self {
             ColumnType::Integer => "INTEGER",
+            ColumnType::Int8 => "TINYINT",
+            ColumnType::Int16 => "SMALLINT",
+            ColumnType::Int32 => "INT",
+            ColumnType::UInt8 => "TINYINT UNSIGNED",
+            ColumnType::UInt16 => "SMALLINT UNSIGNED",
+            ColumnType::UInt32 => "INT UNSIGNED",
+            ColumnType::UInt64 => "BIGINT UNSIGNED",
             ColumnType::Real => "DOUBLE",
+            ColumnType::Float32 => "REAL",
+            ColumnType::Decimal { .. } => "DECIMAL",
             ColumnType::Text => "VARCHAR",
             ColumnType::Boolean => "BOOLEAN",
             ColumnType::Date => "DATE",
@@ -29,6 +71,9 @@ impl ColumnType {
             ColumnType::TimeMilliseconds => "TIMESTAMP",
             ColumnType::TimeMicroseconds => "TIMESTAMP",
             ColumnType::TimeNanoseconds => "TIMESTAMP",
+            ColumnType::TimeOfDay => "TIME",
+            ColumnType::Duration => "INTERVAL",
+            ColumnType::Dictionary => "VARCHAR",
             ColumnType::Blob => "BLOB",
         }
     }
@@ -36,7 +81,16 @@ impl ColumnType {
     pub fn to_arrow_type(&self) -> DataType {
         match self {
             ColumnType::Integer => DataType::Int64,
+            ColumnType::Int8 => DataType::Int8,
+            ColumnType::Int16 => DataType::Int16,
+            ColumnType::Int32 => DataType::Int32,
+            ColumnType::UInt8 => DataType::UInt8,
+            ColumnType::UInt16 => DataType::UInt16,
+            ColumnType::UInt32 => DataType::UInt32,
+            ColumnType::UInt64 => DataType::UInt64,
             ColumnType::Real => DataType::Float64,
+            ColumnType::Float32 => DataType::Float32,
+            ColumnType::Decimal { precision, scale } => DataType::Decimal128(*precision, *scale),
             ColumnType::Text => DataType::Utf8,
             ColumnType::Boolean => DataType::Boolean,
             ColumnType::Date => DataType::Date32,
@@ -45,6 +99,9 @@ impl ColumnType {
             ColumnType::TimeMilliseconds => DataType::Timestamp(TimeUnit::Millisecond, None),
             ColumnType::TimeMicroseconds => DataType::Timestamp(TimeUnit::Microsecond, None),
             ColumnType::TimeNanoseconds => DataType::Timestamp(TimeUnit::Nanosecond, None),
+            ColumnType::TimeOfDay => DataType::Time64(TimeUnit::Microsecond),
+            ColumnType::Duration => DataType::Duration(TimeUnit::Microsecond),
+            ColumnType::Dictionary => DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
             ColumnType::Blob => DataType::Binary,
         }
     }
@@ -52,7 +109,16 @@ impl ColumnType {
     pub fn display_name(&self) -> &'static str {
         match self {
             ColumnType::Integer => "Integer (64-bit)",
+            ColumnType::Int8 => "Integer (8-bit)",
+            ColumnType::Int16 => "Integer (16-bit)",
+            ColumnType::Int32 => "Integer (32-bit)",
+            ColumnType::UInt8 => "Unsigned (8-bit)",
+            ColumnType::UInt16 => "Unsigned (16-bit)",
+            ColumnType::UInt32 => "Unsigned (32-bit)",
+            ColumnType::UInt64 => "Unsigned (64-bit)",
             ColumnType::Real => "Float (64-bit)",
+            ColumnType::Float32 => "Float (32-bit)",
+            ColumnType::Decimal { .. } => "Decimal",
             ColumnType::Text => "Text",
             ColumnType::Boolean => "Boolean",
             ColumnType::Date => "Date",
@@ -61,10 +127,39 @@ impl ColumnType {
             ColumnType::TimeMilliseconds => "Time (milliseconds)",
             ColumnType::TimeMicroseconds => "Time (microseconds)",
             ColumnType::TimeNanoseconds => "Time (nanoseconds)",
+            ColumnType::TimeOfDay => "Time of day",
+            ColumnType::Duration => "Duration",
+            ColumnType::Dictionary => "Text (dictionary)",
             ColumnType::Blob => "Binary",
         }
     }
 
+    /// The type a column stored as `data_type` is read as. Timestamps are taken as DateTime,
+    /// since the unit alone can't tell them from elapsed-time columns.
+    pub fn from_arrow_type(data_type: &DataType) -> ColumnType {
+        match data_type {
+            DataType::Int64 => ColumnType::Integer,
+            DataType::Int8 => ColumnType::Int8,
+            DataType::Int16 => ColumnType::Int16,
+            DataType::Int32 => ColumnType::Int32,
+            DataType::UInt8 => ColumnType::UInt8,
+            DataType::UInt16 => ColumnType::UInt16,
+            DataType::UInt32 => ColumnType::UInt32,
+            DataType::UInt64 => ColumnType::UInt64,
+            DataType::Float64 => ColumnType::Real,
+            DataType::Float32 => ColumnType::Float32,
+            DataType::Decimal128(precision, scale) => ColumnType::Decimal { precision: *precision, scale: *scale },
+            DataType::Boolean => ColumnType::Boolean,
+            DataType::Date32 => ColumnType::Date,
+            DataType::Timestamp(_, _) => ColumnType::DateTime,
+            DataType::Time64(_) => ColumnType::TimeOfDay,
+            DataType::Duration(_) => ColumnType::Duration,
+            DataType::Dictionary(_, _) => ColumnType::Dictionary,
+            DataType::Binary => ColumnType::Blob,
+            _ => ColumnType::Text,
+        }
+    }
+
     pub fn is_time_type(&self) -> bool {
         matches!(self,
             ColumnType::TimeSeconds |
@@ -74,26 +169,85 @@ impl ColumnType {
         )
     }
 
+    /// The types offered in the column config grid. Decimal is listed with two decimal places;
+    /// its scale follows the data when picked.
+    pub const SELECTABLE: [ColumnType; 22] = [
+        ColumnType::Text,
+        ColumnType::Dictionary,
+        ColumnType::Integer,
+        ColumnType::Int32,
+        ColumnType::Int16,
+        ColumnType::Int8,
+        ColumnType::UInt64,
+        ColumnType::UInt32,
+        ColumnType::UInt16,
+        ColumnType::UInt8,
+        ColumnType::Real,
+        ColumnType::Float32,
+        ColumnType::Decimal { precision: 38, scale: 2 },
+        ColumnType::Boolean,
+        ColumnType::Date,
+        ColumnType::DateTime,
+        ColumnType::TimeOfDay,
+        ColumnType::Duration,
+        ColumnType::TimeSeconds,
+        ColumnType::TimeMilliseconds,
+        ColumnType::TimeMicroseconds,
+        ColumnType::TimeNanoseconds,
+    ];
+
+    /// Display name with the parameters of parameterised types, e.g. `Decimal(38, 2)`
+    pub fn label(&self) -> String {
+        match self {
+            ColumnType::Decimal { precision, scale } => format!("Decimal({}, {})", precision, scale),
+            other => other.display_name().to_string(),
+        }
+    }
+
+    /// Whether two types are the same choice in the grid, ignoring parameters
+    pub fn same_kind(&self, other: &ColumnType) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
     // Check if a value can be converted to this type
     pub fn can_parse_value(&self, value: &str) -> bool {
-        if value.is_empty() || value.to_lowercase() == "null" {
+        self.can_parse_value_with_format(value, &NumberFormat::default())
+    }
+
+    // Same as can_parse_value, reading numbers in the given format
+    pub fn can_parse_value_with_format(&self, value: &str, number_format: &NumberFormat) -> bool {
+        self.can_parse_value_with_tokens(value, number_format, &ColumnTokens::default())
+    }
+
+    // Same as can_parse_value_with_format, with the column's own null tokens and true/false words
+    pub fn can_parse_value_with_tokens(&self, value: &str, number_format: &NumberFormat, tokens: &ColumnTokens) -> bool {
+        if tokens.is_null(value) {
             return true; // Null values are always valid
         }
 
         match self {
-            ColumnType::Integer => value.parse::<i64>().is_ok(),
-            ColumnType::Real => value.parse::<f64>().is_ok(),
+            ColumnType::Integer => number_format.parse_i64(value).is_some(),
+            ColumnType::Int8 => number_format.parse_i64(value).is_some_and(|n| i8::try_from(n).is_ok()),
+            ColumnType::Int16 => number_format.parse_i64(value).is_some_and(|n| i16::try_from(n).is_ok()),
+            ColumnType::Int32 => number_format.parse_i64(value).is_some_and(|n| i32::try_from(n).is_ok()),
+            ColumnType::UInt8 => number_format.parse_i64(value).is_some_and(|n| u8::try_from(n).is_ok()),
+            ColumnType::UInt16 => number_format.parse_i64(value).is_some_and(|n| u16::try_from(n).is_ok()),
+            ColumnType::UInt32 => number_format.parse_i64(value).is_some_and(|n| u32::try_from(n).is_ok()),
+            ColumnType::UInt64 => number_format.parse_u64(value).is_some(),
+            ColumnType::Real => number_format.parse_f64(value).is_some(),
+            ColumnType::Float32 => number_format.parse_f32(value).is_some(),
+            ColumnType::Decimal { precision, scale } => number_format.parse_decimal(value, *precision, *scale).is_some(),
             ColumnType::Text => true, // Any string is valid text
-            ColumnType::Boolean => {
-                let lower = value.to_lowercase();
-                matches!(lower.as_str(), "true" | "false" | "1" | "0" | "yes" | "no" | "y" | "n")
-            },
+            ColumnType::Boolean => tokens.booleans.parse(value).is_some(),
             ColumnType::Date => TypeInferrer::is_date(value),
             ColumnType::DateTime => TypeInferrer::is_datetime(value),
             ColumnType::TimeSeconds => TypeInferrer::is_timestamp_seconds(value),
             ColumnType::TimeMilliseconds => TypeInferrer::is_timestamp_milliseconds(value),
             ColumnType::TimeMicroseconds => TypeInferrer::is_timestamp_microseconds(value),
             ColumnType::TimeNanoseconds => TypeInferrer::is_timestamp_nanoseconds(value),
+            ColumnType::TimeOfDay => datetime_format::parse_time_of_day(value).is_some(),
+            ColumnType::Duration => duration_format::parse_duration(value).is_some(),
+            ColumnType::Dictionary => true,
             ColumnType::Blob => true, // Assume any string can be binary
         }
     }
@@ -121,206 +275,107 @@ impl TypeInferrer {
         samples: &[Vec<String>],
         null_values: &[String],
     ) -> Vec<(String, ColumnType)> {
-        headers
-            .iter()
+        Self::infer_column_types_with_format(headers, samples, null_values, &NumberFormat::default())
+    }
+
+    /// Infer column types, reading numbers written in the given format
+    pub fn infer_column_types_with_format(
+        headers: &[String],
+        samples: &[Vec<String>],
+        null_values: &[String],
+        number_format: &NumberFormat,
+    ) -> Vec<(String, ColumnType)> {
+        let tokens = vec![ColumnTokens::new(null_values, &[], BooleanVocabulary::default()); headers.len()];
+        Self::infer_column_types_with_tokens(headers, samples, &tokens, number_format)
+    }
+
+    /// Infer column types, with null tokens and true/false words for each column
+    pub fn infer_column_types_with_tokens(
+        headers: &[String],
+        samples: &[Vec<String>],
+        tokens: &[ColumnTokens],
+        number_format: &NumberFormat,
+    ) -> Vec<(String, ColumnType)> {
+        let patterns = vec![None; headers.len()];
+        headers.iter()
+            .cloned()
+            .zip(Self::infer_columns_with_confidence(headers, samples, tokens, number_format, &patterns))
+            .map(|(header, inference)| (header, inference.column_type))
+            .collect()
+    }
+    
+    /// Infer column types with a confidence score, the candidate types and the reason for each choice.
+    /// Text columns whose values all match their date/time pattern become Date or DateTime.
+    pub fn infer_columns_with_confidence(
+        headers: &[String],
+        samples: &[Vec<String>],
+        tokens: &[ColumnTokens],
+        number_format: &NumberFormat,
+        datetime_patterns: &[Option<String>],
+    ) -> Vec<ColumnInference> {
+        let mut profiles = Self::column_profiles(headers, tokens, number_format, datetime_patterns);
+        for row in samples {
+            for (profile, value) in profiles.iter_mut().zip(row) {
+                profile.observe(value);
+            }
+        }
+        profiles.iter().map(ColumnProfile::finish).collect()
+    }
+
+    /// An empty profile per column, to be fed values with `ColumnProfile::observe`.
+    /// Columns without tokens use `ColumnTokens::default()`.
+    pub fn column_profiles(
+        headers: &[String],
+        tokens: &[ColumnTokens],
+        number_format: &NumberFormat,
+        datetime_patterns: &[Option<String>],
+    ) -> Vec<ColumnProfile> {
+        headers.iter()
             .enumerate()
             .map(|(idx, header)| {
-                let column_type = Self::infer_column_type_with_nulls(header, samples, idx, null_values);
-                (header.clone(), column_type)
+                let pattern = datetime_patterns.get(idx).and_then(|pattern| pattern.as_deref());
+                let tokens = tokens.get(idx).cloned().unwrap_or_default();
+                ColumnProfile::new(header, tokens, number_format, pattern)
             })
             .collect()
     }
     
     fn infer_column_type(header: &str, samples: &[Vec<String>], col_idx: usize) -> ColumnType {
-        // First check header for time unit hints
-        if let Some(time_type) = Self::detect_time_unit_from_header(header) {
-            return time_type;
-        }
-
-        let mut is_int = true;
-        let mut is_float = true;
-        let mut is_bool = true;
-        let mut is_date = true;
-        let mut is_datetime = true;
-        let mut is_time = true;
-        let mut non_empty_count = 0;
-        let mut has_decimal = false;
-        
+        let tokens = ColumnTokens::new(&["null".to_string()], &[], BooleanVocabulary::default());
+        let mut profile = ColumnProfile::new(header, tokens, &NumberFormat::default(), None);
         for row in samples {
             if let Some(value) = row.get(col_idx) {
-                if value.is_empty() || value.to_lowercase() == "null" {
-                    continue;
-                }
-                
-                non_empty_count += 1;
-                
-                // Check boolean
-                if is_bool {
-                    let lower = value.to_lowercase();
-                    if !matches!(lower.as_str(), "true" | "false" | "1" | "0" | "yes" | "no" | "y" | "n") {
-                        is_bool = false;
-                    }
-                }
-                
-                // Check integer
-                if is_int {
-                    if value.parse::<i64>().is_err() {
-                        is_int = false;
-                    }
-                }
-                
-                // Check float (but track if we see decimals)
-                if is_float {
-                    match value.parse::<f64>() {
-                        Ok(_) => {
-                            if value.contains('.') {
-                                has_decimal = true;
-                            }
-                        }
-                        Err(_) => is_float = false,
-                    }
-                }
-                
-                // Check date
-                if is_date && !Self::is_date(value) {
-                    is_date = false;
-                }
-                
-                // Check datetime
-                if is_datetime && !Self::is_datetime(value) {
-                    is_datetime = false;
-                }
-                
-                // Check time (HH:MM:SS format)
-                if is_time && !Self::is_time(value) {
-                    is_time = false;
-                }
+                profile.observe(value);
             }
         }
-        
-        // Return the most specific type that matches
-        if non_empty_count == 0 {
-            ColumnType::Text
-        } else if is_bool {
-            ColumnType::Boolean
-        } else if is_int {
-            ColumnType::Integer
-        } else if is_float {
-            // Only use Real if we actually saw decimal values
-            if has_decimal {
-                ColumnType::Real
-            } else {
-                ColumnType::Integer
-            }
-        } else if is_datetime {
-            ColumnType::DateTime
-        } else if is_date {
-            ColumnType::Date
-        } else if is_time {
-            // Determine the appropriate time unit based on the data
-            Self::detect_time_unit_from_data(samples, col_idx)
-        } else {
-            ColumnType::Text
-        }
+        profile.finish().column_type
     }
 
-    fn infer_column_type_with_nulls(header: &str, samples: &[Vec<String>], col_idx: usize, null_values: &[String]) -> ColumnType {
-        // First check header for time unit hints
-        if let Some(time_type) = Self::detect_time_unit_from_header(header) {
-            return time_type;
-        }
-
-        let mut is_int = true;
-        let mut is_float = true;
-        let mut is_bool = true;
-        let mut is_date = true;
-        let mut is_datetime = true;
-        let mut is_time = true;
-        let mut non_empty_count = 0;
-        let mut has_decimal = false;
-        
-        for row in samples {
-            if let Some(value) = row.get(col_idx) {
-                // Check if value is a null value (case-insensitive)
-                let is_null = value.is_empty() || 
-                    null_values.iter().any(|null_val| value.to_lowercase() == null_val.to_lowercase());
-                
-                if is_null {
-                    continue;
-                }
-                
-                non_empty_count += 1;
-                
-                // Check boolean
-                if is_bool {
-                    let lower = value.to_lowercase();
-                    if !matches!(lower.as_str(), "true" | "false" | "1" | "0" | "yes" | "no" | "y" | "n") {
-                        is_bool = false;
-                    }
-                }
-                
-                // Check integer
-                if is_int {
-                    if value.parse::<i64>().is_err() {
-                        is_int = false;
-                    }
-                }
-                
-                // Check float (but track if we see decimals)
-                if is_float {
-                    match value.parse::<f64>() {
-                        Ok(_) => {
-                            if value.contains('.') {
-                                has_decimal = true;
-                            }
-                        }
-                        Err(_) => is_float = false,
-                    }
-                }
-                
-                // Check date
-                if is_date && !Self::is_date(value) {
-                    is_date = false;
-                }
-                
-                // Check datetime
-                if is_datetime && !Self::is_datetime(value) {
-                    is_datetime = false;
-                }
-                
-                // Check time (HH:MM:SS format)
-                if is_time && !Self::is_time(value) {
-                    is_time = false;
-                }
-            }
-        }
-        
-        // Return the most specific type that matches
-        if non_empty_count == 0 {
-            ColumnType::Text
-        } else if is_bool {
-            ColumnType::Boolean
-        } else if is_int {
-            ColumnType::Integer
-        } else if is_float {
-            // Only use Real if we actually saw decimal values
-            if has_decimal {
-                ColumnType::Real
+    /// The smallest integer type holding every value from `min` to `max`, unsigned when none is negative.
+    /// Only used once a whole file has been read, since a sample rarely shows a column's full range.
+    fn narrowest_integer((min, max): (i64, i64)) -> ColumnType {
+        if min >= 0 {
+            if max <= u8::MAX as i64 {
+                ColumnType::UInt8
+            } else if max <= u16::MAX as i64 {
+                ColumnType::UInt16
+            } else if max <= u32::MAX as i64 {
+                ColumnType::UInt32
             } else {
                 ColumnType::Integer
             }
-        } else if is_datetime {
-            ColumnType::DateTime
-        } else if is_date {
-            ColumnType::Date
-        } else if is_time {
-            // Determine the appropriate time unit based on the data
-            Self::detect_time_unit_from_data(samples, col_idx)
+        } else if min >= i32::MIN as i64 && max <= i32::MAX as i64 {
+            ColumnType::Int32
         } else {
-            ColumnType::Text
+            ColumnType::Integer
         }
     }
 
+    fn is_duration_header(header: &str) -> bool {
+        let header_lower = header.to_lowercase();
+        ["duration", "elapsed", "runtime", "uptime", "lap"].iter().any(|hint| header_lower.contains(hint))
+    }
+
     fn detect_time_unit_from_header(header: &str) -> Option<ColumnType> {
         let header_lower = header.to_lowercase();
         
@@ -348,36 +403,6 @@ impl TypeInferrer {
         None
     }
 
-    fn detect_time_unit_from_data(samples: &[Vec<String>], col_idx: usize) -> ColumnType {
-        let mut max_fraction_digits = 0;
-        
-        for row in samples {
-            if let Some(value) = row.get(col_idx) {
-                if value.is_empty() || value.to_lowercase() == "null" {
-                    continue;
-                }
-                
-                // Check for fractional part in time format
-                let parts: Vec<&str> = value.split(':').collect();
-                if parts.len() == 3 {
-                    let seconds_part = parts[2];
-                    if let Some(dot_pos) = seconds_part.find('.') {
-                        let fraction_str = &seconds_part[dot_pos + 1..];
-                        max_fraction_digits = max_fraction_digits.max(fraction_str.len());
-                    }
-                }
-            }
-        }
-        
-        // Determine time unit based on fraction digits
-        match max_fraction_digits {
-            0 => ColumnType::TimeSeconds,      // No fraction
-            1..=3 => ColumnType::TimeMilliseconds,  // 1-3 digits (e.g., .3, .32, .325)
-            4..=6 => ColumnType::TimeMicroseconds,  // 4-6 digits (e.g., .3250, .325000)
-            _ => ColumnType::TimeSeconds,      // Default fallback
-        }
-    }
-    
     fn is_date(value: &str) -> bool {
         // Common date formats
         let formats = [
@@ -397,6 +422,10 @@ impl TypeInferrer {
     }
 
     fn is_datetime(value: &str) -> bool {
+        // ISO-8601 with offsets and fractions, plus the common layouts
+        if datetime_format::parse_datetime(value, None).is_some() {
+            return true;
+        }
         // Common datetime formats
         let formats = [
             "%Y-%m-%d %H:%M:%S",
@@ -521,13 +550,20 @@ impl TypeInferrer {
         samples: &[Vec<String>],
         col_idx: usize,
         target_type: &ColumnType,
+        number_format: &NumberFormat,
+        datetime_pattern: Option<&str>,
+        tokens: &ColumnTokens,
     ) -> Result<(), String> {
         let mut invalid_values = Vec::new();
         
         for (row_idx, row) in samples.iter().enumerate() {
             if let Some(value) = row.get(col_idx) {
-                if !value.is_empty() && value.to_lowercase() != "null" {
-                    if !target_type.can_parse_value(value) {
+                if !tokens.is_null(value) {
+                    let parses = match (target_type, datetime_pattern) {
+                        (ColumnType::Date | ColumnType::DateTime, Some(pattern)) => datetime_format::parse_datetime(value, Some(pattern)).is_some(),
+                        _ => target_type.can_parse_value_with_tokens(value, number_format, tokens),
+                    };
+                    if !parses {
                         invalid_values.push((row_idx + 1, value.clone()));
                         if invalid_values.len() >= 5 {
                             // Limit to first 5 invalid values to avoid overwhelming the user
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+
+/// Currency symbols stripped from the start or end of a number
+const CURRENCY_SYMBOLS: [char; 13] = ['$', '€', '£', '¥', '₹', '₽', '₩', '₺', '₪', '฿', '₫', '₴', '¢'];
+
+/// ISO 4217 codes of widely used currencies, accepted before or after a number.
+/// Kept to a fixed list so labels like `LOT 5` or `REF 7` are not read as amounts.
+const CURRENCY_CODES: [&str; 36] = [
+    "USD", "EUR", "GBP", "JPY", "CNY", "CHF", "CAD", "AUD", "NZD", "HKD", "SGD", "SEK",
+    "NOK", "DKK", "PLN", "CZK", "HUF", "RON", "BGN", "RUB", "UAH", "TRY", "ILS", "INR",
+    "KRW", "TWD", "THB", "VND", "IDR", "MYR", "PHP", "ZAR", "BRL", "MXN", "ARS", "AED",
+];
+
+/// How numbers are written in a file: which character separates the decimals and which groups the thousands.
+/// Parsing also accepts currency symbols or ISO codes, a trailing `%` and accounting negatives like `(12.00)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumberFormat {
+    pub decimal_separator: char,
+    /// `None` when thousands are never grouped; `' '` also accepts non-breaking spaces
+    pub grouping_separator: Option<char>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            grouping_separator: Some(','),
+        }
+    }
+}
+
+/// A number read from text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParsedNumber {
+    pub value: f64,
+    /// Set when the text had no decimal part and no percent sign
+    pub integer: Option<i64>,
+    /// Whether a currency symbol or code was written
+    pub currency: bool,
+    /// Decimal places needed to hold the value exactly (a percent sign adds two)
+    pub scale: u8,
+}
+
+/// The pieces of a formatted number, before conversion
+struct NumberParts {
+    negative: bool,
+    percent: bool,
+    currency: bool,
+    /// Integer digits with grouping removed; empty for `.5`
+    digits: String,
+    fraction: Option<String>,
+}
+
+impl NumberParts {
+    /// Decimal places in the written value, counting the shift of a percent sign
+    fn scale(&self) -> usize {
+        self.fraction.as_ref().map_or(0, |fraction| fraction.len()) + if self.percent { 2 } else { 0 }
+    }
+}
+
+impl NumberFormat {
+    /// The formats offered in the import dialog
+    pub const PRESETS: [NumberFormat; 5] = [
+        NumberFormat { decimal_separator: '.', grouping_separator: Some(',') },
+        NumberFormat { decimal_separator: ',', grouping_separator: Some('.') },
+        NumberFormat { decimal_separator: ',', grouping_separator: Some(' ') },
+        NumberFormat { decimal_separator: '.', grouping_separator: Some('\'') },
+        NumberFormat { decimal_separator: '.', grouping_separator: None },
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match (self.decimal_separator, self.grouping_separator) {
+            ('.', Some(',')) => "1,234.56 (English)",
+            (',', Some('.')) => "1.234,56 (German, Spanish, Italian…)",
+            (',', Some(' ')) => "1 234,56 (French, Nordic…)",
+            ('.', Some('\'')) => "1'234.56 (Swiss)",
+            ('.', None) => "1234.56 (no grouping)",
+            (',', None) => "1234,56 (no grouping)",
+            _ => "Custom",
+        }
+    }
+
+    pub fn parse_f64(&self, value: &str) -> Option<f64> {
+        // Plain Rust syntax (exponents, inf, NaN) is still accepted when '.' is the decimal separator
+        if self.decimal_separator == '.' {
+            if let Ok(number) = value.trim().parse::<f64>() {
+                return Some(number);
+            }
+        }
+        self.parse(value).map(|number| number.value)
+    }
+
+    pub fn parse_i64(&self, value: &str) -> Option<i64> {
+        if let Ok(number) = value.trim().parse::<i64>() {
+            return Some(number);
+        }
+        self.parse(value).and_then(|number| number.integer)
+    }
+
+    pub fn parse_u64(&self, value: &str) -> Option<u64> {
+        if let Ok(number) = value.trim().parse::<u64>() {
+            return Some(number);
+        }
+        let parts = self.parts(value)?;
+        if parts.negative || parts.percent || parts.fraction.is_some() {
+            return None;
+        }
+        parts.digits.parse().ok()
+    }
+
+    /// Parse as f32, rejecting values outside its range
+    pub fn parse_f32(&self, value: &str) -> Option<f32> {
+        self.parse_f64(value)
+            .filter(|number| !number.is_finite() || number.abs() <= f32::MAX as f64)
+            .map(|number| number as f32)
+    }
+
+    /// Parse as a Decimal128 with `scale` decimal places, rounding extra places half away
+    /// from zero. `None` when the value needs more than `precision` digits.
+    pub fn parse_decimal(&self, value: &str, precision: u8, scale: i8) -> Option<i128> {
+        let parts = match self.parts(value) {
+            Some(parts) => parts,
+            None => {
+                // Exponent notation goes through f64
+                let number = self.parse_f64(value).filter(|number| number.is_finite())?;
+                let scaled = (number * 10f64.powi(scale as i32)).round();
+                return (scaled.abs() < 10f64.powi(precision as i32)).then_some(scaled as i128);
+            }
+        };
+
+        let written_scale = parts.scale() as i32;
+        let mantissa: i128 = format!("{}{}", parts.digits, parts.fraction.as_deref().unwrap_or("")).parse().ok()?;
+        let shift = scale as i32 - written_scale;
+        let mut unscaled = if shift >= 0 {
+            mantissa.checked_mul(10i128.checked_pow(shift as u32)?)?
+        } else {
+            let divisor = 10i128.checked_pow((-shift) as u32)?;
+            let (quotient, remainder) = (mantissa / divisor, mantissa % divisor);
+            if remainder * 2 >= divisor { quotient + 1 } else { quotient }
+        };
+        if parts.negative {
+            unscaled = -unscaled;
+        }
+        (unscaled.unsigned_abs() < 10u128.checked_pow(precision as u32)?).then_some(unscaled)
+    }
+
+    /// Parse a formatted number such as `1.234,56`, `$12.50`, `45%` or `(12.00)`.
+    /// Percentages are returned as fractions, so `45%` is 0.45.
+    pub fn parse(&self, value: &str) -> Option<ParsedNumber> {
+        let parts = self.parts(value)?;
+
+        let normalized = match &parts.fraction {
+            Some(fraction) => format!("{}.{}", if parts.digits.is_empty() { "0" } else { &parts.digits }, fraction),
+            None => parts.digits.clone(),
+        };
+        let mut number: f64 = normalized.parse().ok()?;
+        if parts.percent {
+            number /= 100.0;
+        }
+        if parts.negative {
+            number = -number;
+        }
+
+        let integer = if parts.fraction.is_none() && !parts.percent {
+            parts.digits.parse::<i64>().ok().map(|n| if parts.negative { -n } else { n })
+        } else {
+            None
+        };
+
+        Some(ParsedNumber {
+            value: number,
+            integer,
+            currency: parts.currency,
+            scale: parts.scale().min(u8::MAX as usize) as u8,
+        })
+    }
+
+    /// Split a formatted number into sign, digits and decoration
+    fn parts(&self, value: &str) -> Option<NumberParts> {
+        let mut text = value.trim();
+        let mut negative = false;
+
+        // Accounting negative: (12.00)
+        if let Some(inner) = text.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')) {
+            negative = true;
+            text = inner.trim();
+        }
+
+        let mut percent = false;
+        if let Some(rest) = text.strip_suffix('%') {
+            percent = true;
+            text = rest.trim_end();
+        }
+
+        // Signs and currency may come in either order at the front: -$12, $-12
+        let mut signed = false;
+        let mut currency = false;
+        loop {
+            if let Some(rest) = strip_currency_prefix(text) {
+                currency = true;
+                text = rest;
+            } else if !signed && (text.starts_with('-') || text.starts_with('+')) {
+                signed = true;
+                negative ^= text.starts_with('-');
+                text = text[1..].trim_start();
+            } else {
+                break;
+            }
+        }
+        if let Some(rest) = strip_currency_suffix(text) {
+            currency = true;
+            text = rest;
+        }
+        if !percent {
+            if let Some(rest) = text.strip_suffix('%') {
+                percent = true;
+                text = rest.trim_end();
+            }
+        }
+
+        let (integer_part, fraction_part) = match text.split_once(self.decimal_separator) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (text, None),
+        };
+
+        let digits = self.integer_digits(integer_part)?;
+        if let Some(fraction) = fraction_part {
+            if !fraction.chars().all(|c| c.is_ascii_digit()) || (digits.is_empty() && fraction.is_empty()) {
+                return None;
+            }
+        } else if digits.is_empty() {
+            return None;
+        }
+
+        Some(NumberParts {
+            negative,
+            percent,
+            currency,
+            digits,
+            fraction: fraction_part.map(str::to_string),
+        })
+    }
+
+    /// Digits of the integer part with valid thousands grouping removed
+    fn integer_digits(&self, text: &str) -> Option<String> {
+        let is_separator = |c: char| match self.grouping_separator {
+            Some(' ') => c == ' ' || c == '\u{a0}' || c == '\u{202f}',
+            Some(separator) => c == separator,
+            None => false,
+        };
+
+        if !text.contains(is_separator) {
+            return text.chars().all(|c| c.is_ascii_digit()).then(|| text.to_string());
+        }
+
+        // Grouped: 1-3 leading digits, then groups of exactly three
+        let groups: Vec<&str> = text.split(is_separator).collect();
+        let first_ok = (1..=3).contains(&groups[0].len());
+        let rest_ok = groups[1..].iter().all(|group| group.len() == 3);
+        let all_digits = groups.iter().all(|group| group.chars().all(|c| c.is_ascii_digit()));
+        (first_ok && rest_ok && all_digits).then(|| groups.concat())
+    }
+}
+
+fn strip_currency_prefix(text: &str) -> Option<&str> {
+    let mut chars = text.chars();
+    if let Some(first) = chars.next() {
+        if CURRENCY_SYMBOLS.contains(&first) {
+            return Some(chars.as_str().trim_start());
+        }
+    }
+    // ISO code followed by a space: USD 12.50
+    match text.split_once(' ') {
+        Some((code, rest)) if is_currency_code(code) => Some(rest.trim_start()),
+        _ => None,
+    }
+}
+
+fn strip_currency_suffix(text: &str) -> Option<&str> {
+    if let Some(last) = text.chars().last() {
+        if CURRENCY_SYMBOLS.contains(&last) {
+            return Some(text[..text.len() - last.len_utf8()].trim_end());
+        }
+    }
+    // ISO code after a space: 12,50 EUR
+    match text.rsplit_once(' ') {
+        Some((rest, code)) if is_currency_code(code) => Some(rest.trim_end()),
+        _ => None,
+    }
+}
+
+fn is_currency_code(text: &str) -> bool {
+    CURRENCY_CODES.contains(&text)
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+/// Values read as null unless a file says otherwise
+pub const DEFAULT_NULL_VALUES: [&str; 5] = ["", "NULL", "null", "N/A", "-"];
+
+/// Words read as true and false in a Boolean column, compared ignoring case
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BooleanVocabulary {
+    pub true_values: Vec<String>,
+    pub false_values: Vec<String>,
+}
+
+impl Default for BooleanVocabulary {
+    fn default() -> Self {
+        Self::new(&["true", "1", "yes", "y"], &["false", "0", "no", "n"])
+    }
+}
+
+impl BooleanVocabulary {
+    pub fn new(true_values: &[&str], false_values: &[&str]) -> Self {
+        Self {
+            true_values: true_values.iter().map(|value| value.to_string()).collect(),
+            false_values: false_values.iter().map(|value| value.to_string()).collect(),
+        }
+    }
+
+    /// The vocabularies offered in the import dialog, besides the default
+    pub fn presets() -> Vec<BooleanVocabulary> {
+        vec![
+            Self::default(),
+            Self::new(&["on"], &["off"]),
+            Self::new(&["t"], &["f"]),
+            Self::new(&["pass"], &["fail"]),
+        ]
+    }
+
+    /// Words from comma-separated lists, e.g. `on, enabled`; blank entries are dropped
+    pub fn from_lists(true_list: &str, false_list: &str) -> Self {
+        let split = |list: &str| list.split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+            .collect();
+        Self {
+            true_values: split(true_list),
+            false_values: split(false_list),
+        }
+    }
+
+    pub fn parse(&self, value: &str) -> Option<bool> {
+        if self.true_values.iter().any(|word| word.eq_ignore_ascii_case(value)) {
+            Some(true)
+        } else if self.false_values.iter().any(|word| word.eq_ignore_ascii_case(value)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// The words as written in the dialog, e.g. `on or off`
+    pub fn describe(&self) -> String {
+        format!("{} or {}", self.true_values.join("/"), self.false_values.join("/"))
+    }
+}
+
+/// How one column writes missing values and true/false
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnTokens {
+    /// Values read as null, compared ignoring case; empty values are always null
+    pub null_values: Vec<String>,
+    pub booleans: BooleanVocabulary,
+}
+
+impl Default for ColumnTokens {
+    fn default() -> Self {
+        Self {
+            null_values: DEFAULT_NULL_VALUES.iter().map(|value| value.to_string()).collect(),
+            booleans: BooleanVocabulary::default(),
+        }
+    }
+}
+
+impl ColumnTokens {
+    /// The file's null tokens with the column's own added
+    pub fn new(file_null_values: &[String], column_null_values: &[String], booleans: BooleanVocabulary) -> Self {
+        Self {
+            null_values: file_null_values.iter().chain(column_null_values).cloned().collect(),
+            booleans,
+        }
+    }
+
+    pub fn is_null(&self, value: &str) -> bool {
+        value.is_empty() || self.null_values.iter().any(|null| null.eq_ignore_ascii_case(value))
+    }
+}
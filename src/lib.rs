@@ -7,9 +7,25 @@ pub mod ui;
 mod tests {
     use crate::core::QueryResult;
     use crate::ui::plots::{PlotConfiguration, PlotSpecificConfig, LineChartConfig, ScatterPlotConfig, BarChartConfig, HistogramConfig, Plot};
-    use datafusion::arrow::datatypes::DataType;
+    use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use std::sync::Arc;
     use std::time::Instant;
 
+    /// A query result holding the rows as Int64 and Utf8 columns
+    fn result_from_rows(columns: Vec<String>, column_types: Vec<DataType>, rows: Vec<Vec<String>>) -> QueryResult {
+        let fields: Vec<Field> = columns.iter().zip(&column_types).map(|(name, data_type)| Field::new(name, data_type.clone(), true)).collect();
+        let arrays: Vec<ArrayRef> = column_types.iter().enumerate().map(|(index, data_type)| match data_type {
+            DataType::Int64 => Arc::new(rows.iter().map(|row| row[index].parse::<i64>().ok()).collect::<Int64Array>()) as ArrayRef,
+            _ => Arc::new(rows.iter().map(|row| Some(row[index].as_str())).collect::<StringArray>()) as ArrayRef,
+        }).collect();
+        let schema = Arc::new(Schema::new(fields));
+        let mut result = QueryResult::new(schema.clone(), vec![RecordBatch::try_new(schema, arrays).unwrap()]);
+        result.total_rows = Some(rows.len());
+        result
+    }
+
     /// Test data creation helper
     fn create_test_data() -> QueryResult {
         let columns = vec!["X".to_string(), "Y".to_string(), "Category".to_string()];
@@ -26,12 +42,7 @@ mod tests {
             vec!["10".to_string(), "55".to_string(), "B".to_string()],
         ];
         
-        QueryResult {
-            columns,
-            rows,
-            column_types: vec![DataType::Int64, DataType::Int64, DataType::Utf8],
-            total_rows: Some(10),
-        }
+        result_from_rows(columns, vec![DataType::Int64, DataType::Int64, DataType::Utf8], rows)
     }
 
     /// Create large test dataset for performance testing
@@ -44,12 +55,7 @@ mod tests {
             rows.push(vec![i.to_string(), (i * 2).to_string(), category.to_string()]);
         }
         
-        QueryResult {
-            columns,
-            rows,
-            column_types: vec![DataType::Int64, DataType::Int64, DataType::Utf8],
-            total_rows: Some(size),
-        }
+        result_from_rows(columns, vec![DataType::Int64, DataType::Int64, DataType::Utf8], rows)
     }
 
     #[test]
@@ -305,12 +311,7 @@ mod tests {
             rows.push(vec![i.to_string(), (i * 2).to_string()]);
         }
         
-        let data = QueryResult {
-            columns,
-            rows,
-            column_types: vec![DataType::Int64, DataType::Int64],
-            total_rows: Some(1000),
-        };
+        let data = result_from_rows(columns, vec![DataType::Int64, DataType::Int64], rows);
 
         let config = PlotConfiguration {
             title: "Large Dataset Test".to_string(),
@@ -507,7 +508,7 @@ mod tests {
         let total_points = plot_data.series.iter().map(|s| s.points.len()).sum::<usize>();
         
         println!("📊 {}: {} points processed in {:?}, rendered {} points", 
-                test_name, data.rows.len(), duration, total_points);
+                test_name, data.num_rows(), duration, total_points);
         
         // Performance assertions
         assert!(duration.as_secs() < 10, "{} should process in under 10 seconds", test_name);
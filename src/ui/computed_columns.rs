@@ -1,6 +1,7 @@
 use egui;
 use datafusion::arrow::datatypes::DataType;
-use crate::core::{Database, TableInfo, TransformationType};
+use crate::core::{Database, TableInfo, TransformationType, TableFormat};
+use crate::ui::format_picker::table_format_picker;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -98,6 +99,7 @@ pub struct ComputedColumnsDialog {
     pub show_preview: bool,
     pub preview_data: Option<PreviewData>,
     pub output_filename: String,
+    pub output_format: TableFormat,
 }
 
 #[derive(Debug, Clone)]
@@ -133,6 +135,7 @@ impl Default for ComputedColumnsDialog {
             show_preview: false,
             preview_data: None,
             output_filename: String::new(),
+            output_format: TableFormat::default(),
         }
     }
 }
@@ -374,10 +377,14 @@ impl ComputedColumnsDialog {
                             ui.label("Output filename (optional):");
                             ui.text_edit_singleline(&mut self.output_filename)
                                 .on_hover_text("Leave empty to auto-generate filename");
-                            if !self.output_filename.is_empty() && !self.output_filename.ends_with(".arrow") {
-                                ui.label(egui::RichText::new("(.arrow will be added)").weak());
+                            let extension = format!(".{}", self.output_format.extension());
+                            if !self.output_filename.is_empty() && !self.output_filename.ends_with(&extension) {
+                                ui.label(egui::RichText::new(format!("({} will be added)", extension)).weak());
                             }
                         });
+                        ui.horizontal(|ui| {
+                            table_format_picker(ui, "computed_columns_output_format", &mut self.output_format);
+                        });
                     }
                 }
                 
@@ -460,6 +467,7 @@ impl ComputedColumnsDialog {
                     } else {
                         Some(self.output_filename.clone())
                     },
+                    output_format: self.output_format,
                 });
                 self.visible = false;
             }
@@ -483,7 +491,7 @@ impl ComputedColumnsDialog {
                         .zip(types.into_iter())
                         .filter_map(|(col, dtype)| {
                             match dtype {
-                                DataType::Int64 | DataType::Float64 => Some(col),
+                                dtype if dtype.is_numeric() => Some(col),
                                 _ => None,
                             }
                         })
@@ -576,4 +584,5 @@ pub struct ComputedColumnsRequest {
     pub table_name: String,
     pub configurations: Vec<ComputedColumnConfig>,
     pub output_filename: Option<String>,
+    pub output_format: TableFormat,
 }
\ No newline at end of file
@@ -294,7 +294,7 @@ impl TransformationDialog {
                     let mut numeric_columns = Vec::new();
                     for (column, data_type) in columns.iter().zip(types.iter()) {
                         match data_type {
-                            DataType::Int64 | DataType::Float64 => {
+                            data_type if data_type.is_numeric() => {
                                 numeric_columns.push(column.clone());
                             }
                             _ => {}
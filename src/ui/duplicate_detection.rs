@@ -2,7 +2,8 @@ use egui::{Ui, Window, Button, Checkbox, ComboBox, TextEdit, Label, ScrollArea,
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
-use crate::core::{Database, DuplicateDetector, DuplicateDetectionConfig, DuplicateDetectionResult, duplicate_detector::DuplicateGroup};
+use crate::ui::format_picker::table_format_picker;
+use crate::core::{Database, TableFormat, DuplicateDetector, DuplicateDetectionConfig, DuplicateDetectionResult, duplicate_detector::DuplicateGroup};
 
 /// UI state for duplicate detection dialog
 pub struct DuplicateDetectionDialog {
@@ -26,8 +27,10 @@ pub struct DuplicateDetectionDialog {
     pub is_detecting: bool,
     /// Error message if detection failed
     pub error_message: Option<String>,
-    /// Output directory for clean Arrow files
+    /// Output directory for clean table files
     pub output_directory: PathBuf,
+    /// File format of the exported clean table
+    pub output_format: TableFormat,
     /// Success message after creating clean file
     pub success_message: Option<String>,
     /// Whether to show success message
@@ -50,6 +53,7 @@ impl Default for DuplicateDetectionDialog {
             is_detecting: false,
             error_message: None,
             output_directory: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            output_format: TableFormat::default(),
             success_message: None,
             show_success: false,
             db: None,
@@ -168,7 +172,8 @@ impl DuplicateDetectionDialog {
             }
 
             if let Some(_result) = &self.detection_result {
-                if ui.button("Export Clean Arrow File").clicked() {
+                table_format_picker(ui, "duplicate_clean_output_format", &mut self.output_format);
+                if ui.button("Export Clean File").clicked() {
                     self.export_clean_arrow_file(db);
                 }
             }
@@ -274,7 +279,7 @@ impl DuplicateDetectionDialog {
         self.is_detecting = false;
     }
 
-    /// Export clean Arrow file
+    /// Export clean table file in the selected format
     fn export_clean_arrow_file(&mut self, db: &Arc<Database>) {
         if self.selected_table.is_empty() {
             self.error_message = Some("No table selected".to_string());
@@ -302,21 +307,25 @@ impl DuplicateDetectionDialog {
         match db.get_table_arrow_batch(&self.selected_table) {
             Ok(batch) => {
                 // Open file dialog to select export location
+                let format = self.output_format;
                 if let Some(output_path) = rfd::FileDialog::new()
-                    .set_title("Save Clean Arrow File")
+                    .set_title("Save Clean Table File")
                     .set_directory(&self.output_directory)
-                    .add_filter("Arrow Files", &["arrow"])
+                    .add_filter(format.display_name(), &[format.extension()])
+                    .set_file_name(format.file_name(&format!("{}_clean", self.selected_table)))
                     .save_file() {
                     
-                    // Create clean Arrow file
-                    match detector.create_clean_arrow_file(
+                    // Create clean table file
+                    match detector.create_clean_file(
                         &batch,
                         result,
                         &output_path,
+                        format,
                     ) {
                         Ok(kept_rows) => {
                             self.success_message = Some(format!(
-                                "✅ Created clean Arrow file: {}\nKept {} rows (first occurrence of each group), removed {} duplicate rows",
+                                "✅ Created clean {} file: {}\nKept {} rows (first occurrence of each group), removed {} duplicate rows",
+                                format.display_name(),
                                 output_path.display(),
                                 kept_rows,
                                 batch.num_rows() - kept_rows
@@ -324,7 +333,7 @@ impl DuplicateDetectionDialog {
                             self.show_success = true;
                         }
                         Err(e) => {
-                            self.error_message = Some(format!("Failed to create clean Arrow file: {}", e));
+                            self.error_message = Some(format!("Failed to create clean file: {}", e));
                         }
                     }
                 }
@@ -1,6 +1,7 @@
 use egui;
 use datafusion::arrow::datatypes::DataType;
-use crate::core::{Database, TableInfo};
+use crate::core::{Database, TableInfo, TableFormat};
+use crate::ui::format_picker::table_format_picker;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -53,6 +54,7 @@ pub struct EnhancedGroupingDialog {
     pub show_preview: bool,
     pub example_type: String,
     pub output_filename: String,
+    pub output_format: TableFormat,
 }
 
 impl Default for EnhancedGroupingDialog {
@@ -74,6 +76,7 @@ impl Default for EnhancedGroupingDialog {
             show_preview: false,
             example_type: "Value Change".to_string(),
             output_filename: String::new(),
+            output_format: TableFormat::default(),
         }
     }
 }
@@ -229,10 +232,14 @@ impl EnhancedGroupingDialog {
                                 self.output_filename = format!("{}_groupid_{}", 
                                     self.selected_table.as_ref().unwrap(), timestamp);
                             }
-                            if !self.output_filename.is_empty() && !self.output_filename.ends_with(".arrow") {
-                                ui.label(egui::RichText::new("(.arrow will be added)").weak());
+                            let extension = format!(".{}", self.output_format.extension());
+                            if !self.output_filename.is_empty() && !self.output_filename.ends_with(&extension) {
+                                ui.label(egui::RichText::new(format!("({} will be added)", extension)).weak());
                             }
                         });
+                        ui.horizontal(|ui| {
+                            table_format_picker(ui, "grouping_output_format", &mut self.output_format);
+                        });
                     }
                     
                     // Example Preview with dropdown
@@ -372,6 +379,7 @@ impl EnhancedGroupingDialog {
                     } else {
                         Some(self.output_filename.clone())
                     },
+                    output_format: self.output_format,
                 });
                 self.visible = false;
             }
@@ -444,4 +452,5 @@ pub struct EnhancedGroupingRequest {
     pub table_name: String,
     pub configurations: Vec<GroupingConfig>,
     pub output_filename: Option<String>,
+    pub output_format: TableFormat,
 }
\ No newline at end of file
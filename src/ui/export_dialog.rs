@@ -1,8 +1,6 @@
 use egui::{Context, Id};
 use rfd::FileDialog;
-use std::fs::File;
-use std::io::Write;
-use crate::core::{QueryResult, CsvWriter};
+use crate::core::QueryResult;
 
 pub struct ExportDialog {
     id: Id,
@@ -83,35 +81,10 @@ impl ExportDialog {
     }
     
     fn export_csv(&self, path: &std::path::Path) {
-        if let Ok(mut writer) = CsvWriter::from_path(path) {
-            // Write headers
-            let _ = writer.write_headers(&self.result.columns);
-            
-            // Write rows
-            for row in &self.result.rows {
-                let _ = writer.write_record(row);
-            }
-            
-            let _ = writer.flush();
-        }
+        let _ = self.result.write_csv(path);
     }
     
     fn export_json(&self, path: &std::path::Path) {
-        let json_data: Vec<serde_json::Map<String, serde_json::Value>> = self.result.rows
-            .iter()
-            .map(|row| {
-                let mut map = serde_json::Map::new();
-                for (i, value) in row.iter().enumerate() {
-                    if let Some(column) = self.result.columns.get(i) {
-                        map.insert(column.clone(), serde_json::Value::String(value.clone()));
-                    }
-                }
-                map
-            })
-            .collect();
-        
-        if let Ok(json_string) = serde_json::to_string_pretty(&json_data) {
-            let _ = File::create(path).and_then(|mut file| file.write_all(json_string.as_bytes()));
-        }
+        let _ = self.result.write_json(path);
     }
-} 
\ No newline at end of file
+}
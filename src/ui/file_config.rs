@@ -1,8 +1,23 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use datafusion::arrow::datatypes::SchemaRef;
 use egui::{Context, Id};
-use crate::core::{Database, CsvReader};
-use crate::infer::{TypeInferrer, ColumnType};
+use crate::core::{Database, TableFormat, CsvIngestOptions, IngestProgress, RejectCounts, RejectPolicy, CoercionPolicy, CoercionReport};
+use crate::core::{ImportProfile, ProfileColumn, ProfileMatch, SchemaReconciliation};
+use crate::core::csv_ingest;
+use crate::core::table_format::{self, TableReader};
+use crate::core::csv_source;
+use crate::core::csv_union::{self, UnionOptions};
+use crate::core::json_ingest::{self, JsonIngestOptions};
+use crate::core::spreadsheet::{self, CellRange, SheetSelection};
+use crate::core::fixed_width;
+use crate::core::csv_tokenizer::{self, CsvTokenizer, RecordSource};
+use crate::core::text_encoding::{self, SELECTABLE_ENCODINGS};
+use encoding_rs::Encoding;
+use crate::ui::format_picker::table_format_picker;
+use crate::infer::{TypeInferrer, ColumnType, ColumnInference, ColumnTokens, BooleanVocabulary, NumberFormat, DEFAULT_NULL_VALUES};
+use crate::infer::datetime_format;
 
 #[derive(Clone)]
 pub struct FileConfig {
@@ -10,10 +25,97 @@ pub struct FileConfig {
     pub table_name: String,
     pub header_row: usize,
     pub delimiter: char,
+    /// Character positions where the columns of a fixed-width file start, after the first;
+    /// `None` splits the file on `delimiter`
+    pub column_boundaries: Option<Vec<usize>>,
+    pub trim_whitespace: bool,
+    pub reject_policy: RejectPolicy,
+    pub coercion_policy: CoercionPolicy,
+    pub number_format: NumberFormat,
+    /// Timezone for DateTime columns (`Europe/Berlin`, `+02:00`); empty keeps naive values as written
+    pub timezone: String,
+    pub encoding: &'static Encoding,
+    pub detected_encoding: &'static Encoding,
     pub sample_size: usize,
     pub columns: Vec<ColumnConfig>,
     pub null_values: Vec<String>,
     pub preview_data: Option<PreviewData>,
+    /// Whether the column inferences come from reading the whole file rather than the sample
+    pub scanned_whole_file: bool,
+    /// The saved import profile applied to this file, and why it was picked
+    pub profile: Option<(String, ProfileMatch)>,
+    /// Append to the project's table of the same name instead of replacing it
+    pub append: bool,
+    /// When appending, add the file's extra columns to the table rather than leaving them out
+    pub add_new_columns: bool,
+    /// The files imported together as this one table; `path` is the first of them, used for the preview
+    pub union: Option<UnionSource>,
+    /// Settings for a JSON or NDJSON file, which replace the delimited-text ones
+    pub json: Option<JsonSource>,
+    /// The sheet of a spreadsheet workbook this table is read from
+    pub sheet: Option<SheetSource>,
+}
+
+/// Which sheet of a workbook, and which of its cells, become the table
+#[derive(Clone)]
+pub struct SheetSource {
+    /// Every sheet of the workbook, to pick from
+    pub sheets: Vec<String>,
+    pub sheet: String,
+    /// Cells to read in A1 notation, like `B2:F100`; empty reads the whole sheet
+    pub range: String,
+}
+
+impl SheetSource {
+    fn selection(&self, path: &Path) -> crate::core::error::Result<SheetSelection> {
+        let range = self.range.trim();
+        Ok(SheetSelection {
+            path: path.to_path_buf(),
+            sheet: self.sheet.clone(),
+            range: if range.is_empty() { None } else { Some(CellRange::parse(range)?) },
+        })
+    }
+}
+
+/// How a JSON file becomes a table
+#[derive(Clone, Default)]
+pub struct JsonSource {
+    /// Nested objects become dotted columns rather than struct columns
+    pub flatten: bool,
+    /// Schema of the previewed records, for showing the column types
+    pub schema: Option<SchemaRef>,
+}
+
+impl JsonSource {
+    fn options(&self, columns: &[ColumnConfig]) -> JsonIngestOptions {
+        JsonIngestOptions {
+            flatten: self.flatten,
+            columns: columns.iter().any(|col| !col.included)
+                .then(|| columns.iter().filter(|col| col.included).map(|col| col.name.clone()).collect()),
+            ..JsonIngestOptions::default()
+        }
+    }
+}
+
+/// A folder or file pattern imported as one table
+#[derive(Clone)]
+pub struct UnionSource {
+    pub pattern: String,
+    pub files: Vec<PathBuf>,
+    /// Add a `source_file` column naming each row's file
+    pub source_column: bool,
+    pub parallel: bool,
+}
+
+impl UnionSource {
+    fn options(&self, csv: &CsvIngestOptions) -> UnionOptions {
+        let defaults = UnionOptions::default();
+        UnionOptions {
+            csv: csv.clone(),
+            source_column: self.source_column.then_some(defaults.source_column).flatten(),
+            threads: if self.parallel { defaults.threads } else { 1 },
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -21,34 +123,243 @@ pub struct ColumnConfig {
     pub name: String,
     pub data_type: ColumnType,
     pub included: bool,
+    /// strftime pattern for Date/DateTime values; empty tries the built-in layouts
+    pub datetime_pattern: String,
+    /// Why the type was inferred and how sure the guess is
+    pub inference: Option<ColumnInference>,
+    /// Comma-separated null tokens for this column only, on top of the file's
+    pub null_values: String,
+    /// Comma-separated words read as true and false; both empty uses the default words
+    pub true_values: String,
+    pub false_values: String,
+}
+
+impl ColumnConfig {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            data_type: ColumnType::Text,
+            included: true,
+            datetime_pattern: String::new(),
+            inference: None,
+            null_values: String::new(),
+            true_values: String::new(),
+            false_values: String::new(),
+        }
+    }
+    
+    fn column_null_values(&self) -> Vec<String> {
+        self.null_values.split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+            .collect()
+    }
+    
+    /// The column's true/false words, or `None` for the default ones
+    fn booleans(&self) -> Option<BooleanVocabulary> {
+        if self.true_values.trim().is_empty() && self.false_values.trim().is_empty() {
+            None
+        } else {
+            Some(BooleanVocabulary::from_lists(&self.true_values, &self.false_values))
+        }
+    }
+    
+    fn tokens(&self, file_null_values: &[String]) -> ColumnTokens {
+        ColumnTokens::new(file_null_values, &self.column_null_values(), self.booleans().unwrap_or_default())
+    }
 }
 
 #[derive(Clone)]
 pub struct PreviewData {
     pub rows: Vec<Vec<String>>,
     pub original_row_numbers: Vec<usize>,
+    pub inferred_delimiter: char,
+    /// The first lines as written, for placing fixed-width column boundaries on; empty for other files
+    pub lines: Vec<String>,
 }
 
 impl FileConfig {
     pub fn new(path: PathBuf) -> Self {
-        let table_name = path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("table")
-            .to_string();
+        let table_name = csv_source::table_name_for(&path);
+        let json = json_ingest::is_json_file(&path).then(JsonSource::default);
+        let detected_encoding = if spreadsheet::is_spreadsheet(&path) {
+            encoding_rs::UTF_8
+        } else {
+            text_encoding::detect_encoding(&path).unwrap_or(encoding_rs::UTF_8)
+        };
+        // Plain text files without any of the usual delimiters whose lines and header line up are read as fixed-width
+        let column_boundaries = if json.is_some() || spreadsheet::is_spreadsheet(&path) {
+            None
+        } else {
+            fixed_width::detect(&path, Some(detected_encoding)).ok().flatten()
+        };
         
         Self {
             path,
             table_name,
             header_row: 1, // Default to row 1 (1-indexed) instead of 0
             delimiter: ',',
+            column_boundaries,
+            trim_whitespace: false,
+            reject_policy: RejectPolicy::default(),
+            coercion_policy: CoercionPolicy::default(),
+            number_format: NumberFormat::default(),
+            timezone: String::new(),
+            encoding: detected_encoding,
+            detected_encoding,
             sample_size: 1000,
             columns: Vec::new(),
-            null_values: vec!["", "NULL", "null", "N/A", "-"].into_iter().map(String::from).collect(),
+            null_values: DEFAULT_NULL_VALUES.iter().map(|value| value.to_string()).collect(),
             preview_data: None,
+            scanned_whole_file: false,
+            profile: None,
+            append: false,
+            add_new_columns: false,
+            union: None,
+            json,
+            sheet: None,
+        }
+    }
+    
+    /// The table file in `project_dir` this file is appended to, if it is and the table exists
+    pub fn append_target(&self, project_dir: &Path) -> Option<PathBuf> {
+        if !self.append {
+            return None;
+        }
+        table_format::find_table_file(project_dir, &self.table_name)
+    }
+    
+    /// Import settings for this file, with the column types chosen in the grid
+    pub fn ingest_options(&self) -> CsvIngestOptions {
+        CsvIngestOptions {
+            delimiter: self.delimiter,
+            header_row: self.header_row.saturating_sub(1),
+            trim: self.trim_whitespace,
+            null_values: self.null_values.clone(),
+            column_null_values: self.columns.iter().map(ColumnConfig::column_null_values).collect(),
+            boolean_vocabularies: self.columns.iter().map(ColumnConfig::booleans).collect(),
+            sample_size: self.sample_size,
+            encoding: Some(self.encoding),
+            reject_policy: self.reject_policy,
+            column_types: if self.columns.is_empty() {
+                None
+            } else {
+                Some(self.columns.iter().map(|col| col.data_type.clone()).collect())
+            },
+            coercion_policy: self.coercion_policy,
+            number_format: self.number_format,
+            datetime_patterns: self.columns.iter()
+                .map(|col| Some(col.datetime_pattern.trim().to_string()).filter(|pattern| !pattern.is_empty()))
+                .collect(),
+            timezone: Some(self.timezone.trim().to_string()).filter(|timezone| !timezone.is_empty()),
+            column_boundaries: self.column_boundaries.clone(),
+            ..CsvIngestOptions::default()
+        }
+    }
+    
+    /// These settings as a profile that can be applied to later files with the same layout
+    pub fn to_profile(&self, name: &str, filename_pattern: &str) -> ImportProfile {
+        ImportProfile {
+            name: name.trim().to_string(),
+            filename_pattern: filename_pattern.trim().to_string(),
+            header_row: self.header_row,
+            delimiter: self.delimiter,
+            column_boundaries: self.column_boundaries.clone(),
+            trim_whitespace: self.trim_whitespace,
+            // Only an encoding picked by hand is saved; otherwise each file is detected again
+            encoding: (self.encoding != self.detected_encoding).then(|| self.encoding.name().to_string()),
+            sample_size: self.sample_size,
+            reject_policy: self.reject_policy,
+            coercion_policy: self.coercion_policy,
+            number_format: self.number_format,
+            timezone: self.timezone.clone(),
+            null_values: self.null_values.clone(),
+            columns: self.columns.iter()
+                .map(|col| ProfileColumn {
+                    name: col.name.clone(),
+                    data_type: col.data_type.clone(),
+                    included: col.included,
+                    datetime_pattern: col.datetime_pattern.clone(),
+                    null_values: col.null_values.clone(),
+                    true_values: col.true_values.clone(),
+                    false_values: col.false_values.clone(),
+                })
+                .collect(),
+        }
+    }
+    
+    /// Take the file-wide settings and the per-column patterns and value words of a profile.
+    /// Column types are inferred when the preview loads, so `apply_profile_columns` follows it.
+    pub fn apply_profile(&mut self, profile: &ImportProfile, how: ProfileMatch) {
+        self.header_row = profile.header_row;
+        self.delimiter = profile.delimiter;
+        self.column_boundaries = profile.column_boundaries.clone();
+        self.trim_whitespace = profile.trim_whitespace;
+        self.encoding = profile.encoding().unwrap_or(self.detected_encoding);
+        self.sample_size = profile.sample_size;
+        self.reject_policy = profile.reject_policy;
+        self.coercion_policy = profile.coercion_policy;
+        self.number_format = profile.number_format;
+        self.timezone = profile.timezone.clone();
+        self.null_values = profile.null_values.clone();
+        self.columns = profile.columns.iter()
+            .map(|saved| ColumnConfig {
+                datetime_pattern: saved.datetime_pattern.clone(),
+                null_values: saved.null_values.clone(),
+                true_values: saved.true_values.clone(),
+                false_values: saved.false_values.clone(),
+                ..ColumnConfig::new(saved.name.clone())
+            })
+            .collect();
+        self.profile = Some((profile.name.clone(), how));
+    }
+    
+    /// Use the profile's type and selection for each column it knows by name; others keep the inferred type
+    pub fn apply_profile_columns(&mut self, profile: &ImportProfile) {
+        for col in &mut self.columns {
+            if let Some(saved) = profile.columns.iter().find(|saved| saved.name.trim().eq_ignore_ascii_case(col.name.trim())) {
+                col.data_type = saved.data_type.clone();
+                col.included = saved.included;
+            }
+        }
+    }
+    
+    /// The file's records as the preview shows them: its delimited lines, or the rows of its sheet
+    fn open_records(&self) -> crate::core::error::Result<Box<dyn RecordSource>> {
+        match (&self.sheet, &self.column_boundaries) {
+            (Some(sheet), _) => spreadsheet::open_records(&sheet.selection(&self.path)?),
+            (None, Some(boundaries)) => fixed_width::open_records(&self.path, boundaries, Some(self.encoding)),
+            (None, None) => Ok(Box::new(CsvTokenizer::from_path(&self.path, self.delimiter, self.trim_whitespace, Some(self.encoding))?)),
+        }
+    }
+    
+    /// Read the file as fixed-width columns, placed where its first lines line up
+    fn use_fixed_width(&mut self) {
+        let lines = fixed_width::sample_lines(&self.path, Some(self.encoding), fixed_width::SAMPLE_LINES).unwrap_or_default();
+        self.column_boundaries = Some(fixed_width::guess_boundaries(&lines));
+    }
+    
+    /// Add a fixed-width column boundary at a character position, or remove the one there
+    fn toggle_column_boundary(&mut self, position: usize) {
+        let Some(boundaries) = &mut self.column_boundaries else {
+            return;
+        };
+        match boundaries.binary_search(&position) {
+            Ok(idx) => {
+                boundaries.remove(idx);
+            }
+            Err(idx) => boundaries.insert(idx, position),
         }
     }
     
     pub fn file_name(&self) -> String {
+        if let Some(union) = &self.union {
+            return format!("{} ({} files)", union.pattern, union.files.len());
+        }
+        if let Some(sheet) = &self.sheet {
+            return spreadsheet::describe(&self.path, &sheet.sheet);
+        }
         self.path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown")
@@ -62,12 +373,71 @@ pub struct FileConfigDialog {
     pub files: Vec<FileConfig>,
     pub current_file_index: usize,
     pub create_database: bool,
+    pub storage_format: TableFormat,
     
     // UI state
     null_value_input: String,
+    // Fixed-width column positions being typed, replaced by the current ones while not focused
+    boundaries_input: String,
     pub error: Option<String>,
     processing_state: Arc<Mutex<ProcessingState>>,
     needs_resampling: bool,
+    // Reports kept on screen after an import that rejected or repaired rows
+    finished_reports: Option<Vec<ImportReport>>,
+    // Unparseable values found before importing, waiting for the user to go ahead or go back
+    value_checks: Option<Vec<ValueCheck>>,
+    // Whole-file type inference, run alongside the dialog rather than behind the overlay
+    scan_state: Arc<Mutex<ScanState>>,
+    // Bumped on every resample so a scan of stale settings is dropped
+    scan_generation: usize,
+    // Saved import profiles, offered for every file and matched to new ones
+    profiles: Vec<ImportProfile>,
+    profile_name_input: String,
+    profile_pattern_input: String,
+    // Schemas of the project's existing tables, read once for the append preview
+    table_schemas: HashMap<PathBuf, Option<SchemaRef>>,
+}
+
+/// Values in one file that don't parse as their column's type, found before importing
+#[derive(Clone)]
+pub struct ValueCheck {
+    pub file_index: usize,
+    /// The file of a folder import the values are in
+    pub shard: Option<PathBuf>,
+    pub report: CoercionReport,
+}
+
+/// Outcome of importing one file, shown when the import finishes
+#[derive(Clone)]
+pub struct ImportReport {
+    pub file_name: String,
+    pub table_name: String,
+    pub rows: usize,
+    pub rejects: RejectCounts,
+    /// Quarantine CSVs of the rejected rows; one per file of a folder import
+    pub rejects_paths: Vec<PathBuf>,
+    /// Rows the table had and how the file's columns matched its columns, when the file was appended to it
+    pub appended: Option<(usize, SchemaReconciliation)>,
+    /// Files of a folder import that lacked some of the table's columns, left empty in their rows
+    pub missing_columns: usize,
+}
+
+impl ImportReport {
+    /// Whether the report should stay on screen: rows were rejected or repaired, or columns didn't match
+    fn needs_attention(&self) -> bool {
+        !self.rejects.is_clean()
+            || self.appended.as_ref().is_some_and(|(_, columns)| !columns.is_exact())
+            || self.missing_columns > 0
+    }
+}
+
+/// Progress of reading a whole file to infer its column types
+#[derive(Clone)]
+pub enum ScanState {
+    Idle,
+    Running { file_index: usize, rows: usize, fraction: f32 },
+    Done { file_index: usize, generation: usize, inferences: Vec<ColumnInference> },
+    Failed(String),
 }
 
 #[derive(Clone)]
@@ -75,62 +445,12 @@ pub enum ProcessingState {
     Idle,
     Loading(f32, String),
     Processing(String, f32),
-    Complete,
+    Checked(Vec<ValueCheck>),
+    Complete(Vec<ImportReport>),
     Error(String),
 }
 
 impl FileConfigDialog {
-    // Helper function to parse CSV line properly, handling quoted strings
-    fn parse_csv_line(line: &str, delimiter: char) -> Vec<String> {
-        let mut row = Vec::new();
-        let mut current_field = String::new();
-        let mut in_quotes = false;
-        let mut chars = line.chars().peekable();
-        
-        while let Some(ch) = chars.next() {
-            match ch {
-                '"' => {
-                    if in_quotes {
-                        // End of quoted field
-                        in_quotes = false;
-                    } else {
-                        // Start of quoted field
-                        in_quotes = true;
-                    }
-                },
-                c if c == delimiter && !in_quotes => {
-                    // End of field
-                    row.push(current_field.trim().to_string());
-                    current_field.clear();
-                },
-                _ => {
-                    current_field.push(ch);
-                }
-            }
-        }
-        
-        // Add the last field
-        row.push(current_field.trim().to_string());
-        row
-    }
-
-    /// Infer the most likely delimiter from a header line
-    fn infer_delimiter_from_header(header_line: &str) -> char {
-        let delimiters = [',', '\t', ';', '|'];
-        let mut best_delimiter = ',';
-        let mut max_fields = 0;
-        
-        for &delimiter in &delimiters {
-            let fields = Self::parse_csv_line(header_line, delimiter);
-            if fields.len() > max_fields && fields.len() > 1 {
-                max_fields = fields.len();
-                best_delimiter = delimiter;
-            }
-        }
-        
-        best_delimiter
-    }
-
     /// Get the display name for a delimiter
     fn delimiter_display_name(delimiter: char) -> &'static str {
         match delimiter {
@@ -149,26 +469,50 @@ impl FileConfigDialog {
             files: Vec::new(),
             current_file_index: 0,
             create_database: false,
+            storage_format: TableFormat::default(),
             null_value_input: String::new(),
+            boundaries_input: String::new(),
             error: None,
             processing_state: Arc::new(Mutex::new(ProcessingState::Idle)),
             needs_resampling: false,
+            finished_reports: None,
+            value_checks: None,
+            scan_state: Arc::new(Mutex::new(ScanState::Idle)),
+            scan_generation: 0,
+            profiles: Self::load_profiles(),
+            profile_name_input: String::new(),
+            profile_pattern_input: String::new(),
+            table_schemas: HashMap::new(),
         }
     }
     
+    /// The saved import profiles; none when the config folder can't be read
+    fn load_profiles() -> Vec<ImportProfile> {
+        let Some(dir) = ImportProfile::default_dir() else {
+            return Vec::new();
+        };
+        ImportProfile::load_all(&dir).unwrap_or_else(|e| {
+            eprintln!("Warning: Could not load import profiles: {}", e);
+            Vec::new()
+        })
+    }
+    
     pub fn open(&mut self, path: PathBuf) {
         self.database_path = Some(path);
         self.show = true;
         self.create_database = false;
         self.files.clear();
         self.current_file_index = 0;
+        self.table_schemas.clear();
     }
     
     pub fn open_with_csv_selection(&mut self) {
         // First, let user select CSV files
         if let Some(csv_files) = rfd::FileDialog::new()
-            .add_filter("CSV files", &["csv"])
-            .set_title("Select CSV files to import")
+            .add_filter("CSV files", &csv_source::PICKER_EXTENSIONS)
+            .add_filter("JSON files", &json_ingest::PICKER_EXTENSIONS)
+            .add_filter("Spreadsheets", &spreadsheet::PICKER_EXTENSIONS)
+            .set_title("Select CSV, JSON or spreadsheet files to import")
             .pick_files()
         {
             if !csv_files.is_empty() {
@@ -207,18 +551,182 @@ impl FileConfigDialog {
         self.error = None;
         self.null_value_input.clear();
         self.needs_resampling = false;
+        self.finished_reports = None;
+        self.value_checks = None;
+        self.scan_generation += 1;
+        self.profiles = Self::load_profiles();
+        self.profile_name_input.clear();
+        self.profile_pattern_input.clear();
+        self.table_schemas.clear();
         if let Ok(mut state) = self.processing_state.lock() {
             *state = ProcessingState::Idle;
         }
+        if let Ok(mut state) = self.scan_state.lock() {
+            *state = ScanState::Idle;
+        }
     }
     
     pub fn add_file(&mut self, path: PathBuf) {
-        let config = FileConfig::new(path);
+        // Each CSV inside a zip archive becomes its own file to configure
+        if csv_source::is_archive(&path) {
+            match csv_source::list_archive_members(&path) {
+                Ok(members) if members.is_empty() => {
+                    self.error = Some(format!("No CSV files found in {}", path.display()));
+                }
+                Ok(members) => {
+                    for member in members {
+                        self.add_file(member);
+                    }
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+            return;
+        }
+        if spreadsheet::is_spreadsheet(&path) {
+            self.add_workbook(path);
+            return;
+        }
+        
+        // A saved profile for the same layout pre-fills the settings
+        let mut config = FileConfig::new(path);
+        let matched = ImportProfile::find_for_file(&self.profiles, &config.path)
+            .filter(|_| config.json.is_none())
+            .map(|(profile, how)| (profile.clone(), how));
+        if let Some((profile, how)) = &matched {
+            config.apply_profile(profile, *how);
+        }
+        self.files.push(config);
+        self.current_file_index = self.files.len() - 1;
+        self.load_preview_for_current_file();
+        if let Some((profile, _)) = matched {
+            self.files[self.current_file_index].apply_profile_columns(&profile);
+        }
+    }
+    
+    /// Add each sheet of a workbook that has any cells filled in as its own table
+    fn add_workbook(&mut self, path: PathBuf) {
+        let sheets = match spreadsheet::list_sheets(&path) {
+            Ok(sheets) => sheets,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        let names: Vec<String> = sheets.iter().map(|sheet| sheet.name.clone()).collect();
+        let filled: Vec<&str> = sheets.iter()
+            .filter(|sheet| !sheet.is_empty())
+            .map(|sheet| sheet.name.as_str())
+            .collect();
+        if filled.is_empty() {
+            self.error = Some(format!("No data found in {}", path.display()));
+            return;
+        }
+        for name in &filled {
+            let mut config = FileConfig::new(path.clone());
+            config.table_name = spreadsheet::table_name_for(&path, name, filled.len() > 1);
+            config.sheet = Some(SheetSource { sheets: names.clone(), sheet: name.to_string(), range: String::new() });
+            self.files.push(config);
+            self.current_file_index = self.files.len() - 1;
+            self.load_preview_for_current_file();
+        }
+    }
+    
+    /// Add the CSV files in a folder, or matching a pattern like `logs/2026-*.csv`, as one table named after
+    /// their folder. The first file is previewed and its settings are used for all of them.
+    pub fn add_folder(&mut self, pattern: String) {
+        let files = match csv_union::expand_pattern(&pattern) {
+            Ok(files) => files,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        let mut config = FileConfig::new(files[0].clone());
+        if let Some(folder) = files[0].parent() {
+            config.table_name = csv_source::table_name_for(folder);
+        }
+        config.union = Some(UnionSource { pattern, files, source_column: true, parallel: true });
         self.files.push(config);
         self.current_file_index = self.files.len() - 1;
         self.load_preview_for_current_file();
     }
     
+    /// Match the current folder import's pattern again, previewing the new first file if it changed
+    fn refresh_union_files(&mut self) {
+        let Some(union) = self.files.get_mut(self.current_file_index).and_then(|config| config.union.as_mut()) else {
+            return;
+        };
+        match csv_union::expand_pattern(&union.pattern) {
+            Ok(files) => {
+                let first = files[0].clone();
+                union.files = files;
+                self.error = None;
+                if self.files[self.current_file_index].path != first {
+                    self.files[self.current_file_index].path = first;
+                    self.load_preview_for_current_file();
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+    
+    /// Apply a saved profile to the file being configured, chosen from the list
+    fn apply_profile_to_current_file(&mut self, profile_index: usize) {
+        let Some(profile) = self.profiles.get(profile_index).cloned() else {
+            return;
+        };
+        let Some(config) = self.files.get_mut(self.current_file_index) else {
+            return;
+        };
+        config.apply_profile(&profile, ProfileMatch::Chosen);
+        self.scan_generation += 1;
+        self.load_preview_for_current_file();
+        if let Some(config) = self.files.get_mut(self.current_file_index) {
+            config.apply_profile_columns(&profile);
+        }
+    }
+    
+    /// Save the current file's settings as a profile, replacing one with the same name
+    fn save_current_file_as_profile(&mut self) {
+        let Some(config) = self.files.get_mut(self.current_file_index) else {
+            return;
+        };
+        let Some(dir) = ImportProfile::default_dir() else {
+            self.error = Some("No folder for saving import profiles on this system".to_string());
+            return;
+        };
+        let name = match self.profile_name_input.trim() {
+            "" => config.table_name.clone(),
+            name => name.to_string(),
+        };
+        let profile = config.to_profile(&name, &self.profile_pattern_input);
+        match profile.save(&dir) {
+            Ok(_) => {
+                config.profile = Some((profile.name.clone(), ProfileMatch::Chosen));
+                self.profiles = Self::load_profiles();
+                self.profile_name_input.clear();
+                self.profile_pattern_input.clear();
+            }
+            Err(e) => self.error = Some(format!("Failed to save import profile: {}", e)),
+        }
+    }
+    
+    fn delete_profile(&mut self, name: &str) {
+        let Some(dir) = ImportProfile::default_dir() else {
+            return;
+        };
+        if let Err(e) = ImportProfile::delete(&dir, name) {
+            self.error = Some(format!("Failed to delete import profile: {}", e));
+            return;
+        }
+        for config in &mut self.files {
+            if config.profile.as_ref().is_some_and(|(applied, _)| applied == name) {
+                config.profile = None;
+            }
+        }
+        self.profiles = Self::load_profiles();
+    }
+    
     pub fn show(&mut self, ctx: &Context) -> Option<PathBuf> {
         if !self.show {
             return None;
@@ -234,10 +742,16 @@ impl FileConfigDialog {
                     ctx.request_repaint();
                     ProcessingState::Processing(msg.clone(), *progress)
                 }
-                ProcessingState::Complete => {
-                    created_db_path = self.database_path.clone();
-                    self.show = false;
-                    ProcessingState::Complete
+                ProcessingState::Checked(checks) => ProcessingState::Checked(checks.clone()),
+                ProcessingState::Complete(reports) => {
+                    // Close straight away unless some rows need the user's attention
+                    if !reports.iter().any(ImportReport::needs_attention) {
+                        created_db_path = self.database_path.clone();
+                        self.show = false;
+                    } else {
+                        self.finished_reports = Some(reports.clone());
+                    }
+                    ProcessingState::Complete(reports.clone())
                 }
                 ProcessingState::Error(error_msg) => {
                     self.error = Some(error_msg.clone());
@@ -251,19 +765,119 @@ impl FileConfigDialog {
             ProcessingState::Idle
         };
         
+        self.apply_scan_result(ctx);
+        
         // Reset state after Complete or Error
-        match current_state {
-            ProcessingState::Complete | ProcessingState::Error(_) => {
+        match &current_state {
+            ProcessingState::Complete(_) | ProcessingState::Error(_) => {
                 if let Ok(mut state) = self.processing_state.lock() {
                     *state = ProcessingState::Idle;
                 }
-                if matches!(current_state, ProcessingState::Complete) {
+                if created_db_path.is_some() {
                     return created_db_path;
                 }
             }
+            ProcessingState::Checked(checks) => {
+                if let Ok(mut state) = self.processing_state.lock() {
+                    *state = ProcessingState::Idle;
+                }
+                if checks.iter().all(|check| check.report.is_empty()) {
+                    self.apply_value_checks(checks.clone());
+                    self.start_database_creation();
+                    return None;
+                }
+                self.value_checks = Some(checks.clone());
+            }
             _ => {}
         }
         
+        // Unparseable values are shown before anything is written
+        if self.value_checks.is_some() {
+            self.show_value_checks(ctx);
+            return None;
+        }
+        
+        // Summary of rejected and repaired rows, and of appended columns, once the import has finished
+        if let Some(reports) = &self.finished_reports {
+            let mut open_project = false;
+            egui::Window::new("Import Finished")
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .default_width(500.0)
+                .show(ctx, |ui| {
+                    if reports.iter().any(|report| !report.rejects.is_clean()) {
+                        ui.label("Some rows did not match their file's header:");
+                    } else {
+                        ui.label("Some files were imported into tables with different columns:");
+                    }
+                    ui.add_space(10.0);
+                    egui::Grid::new("import_reports")
+                        .striped(true)
+                        .spacing([16.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.strong("Table");
+                            ui.strong("Imported");
+                            ui.strong("Rejected");
+                            ui.strong("Padded");
+                            ui.strong("Truncated");
+                            ui.end_row();
+                            for report in reports {
+                                ui.label(&report.table_name).on_hover_text(&report.file_name);
+                                ui.label(report.rows.to_string());
+                                ui.label(report.rejects.rejected.to_string());
+                                ui.label(report.rejects.padded.to_string());
+                                ui.label(report.rejects.truncated.to_string());
+                                ui.end_row();
+                            }
+                        });
+                    
+                    let mismatched: Vec<_> = reports.iter()
+                        .filter_map(|report| Some((report, report.appended.as_ref()?)))
+                        .filter(|(_, (_, columns))| !columns.is_exact())
+                        .collect();
+                    if !mismatched.is_empty() {
+                        ui.add_space(10.0);
+                        for (report, (existing_rows, columns)) in mismatched {
+                            ui.label(format!(
+                                "{} → {} (appended after {} rows): {}",
+                                report.file_name, report.table_name, existing_rows, columns.describe()
+                            ));
+                        }
+                    }
+                    
+                    for report in reports.iter().filter(|report| report.missing_columns > 0) {
+                        ui.add_space(10.0);
+                        ui.label(format!(
+                            "{} → {}: {} of the files lack some of the table's columns, which are empty in their rows",
+                            report.file_name, report.table_name, report.missing_columns
+                        ));
+                    }
+                    
+                    let rejects_files: Vec<_> = reports.iter()
+                        .flat_map(|report| &report.rejects_paths)
+                        .collect();
+                    if !rejects_files.is_empty() {
+                        ui.add_space(10.0);
+                        ui.label("Rejected rows were saved with their line numbers to:");
+                        for path in rejects_files {
+                            ui.label(egui::RichText::new(path.display().to_string()).monospace());
+                        }
+                    }
+                    
+                    ui.add_space(10.0);
+                    if ui.button("Open Project").clicked() {
+                        open_project = true;
+                    }
+                });
+            
+            if open_project {
+                self.finished_reports = None;
+                self.show = false;
+                return self.database_path.clone();
+            }
+            return None;
+        }
+        
         // Show progress overlay if processing
         if let ProcessingState::Processing(message, progress) = &current_state {
             egui::Area::new(egui::Id::new("progress_overlay"))
@@ -312,18 +926,25 @@ impl FileConfigDialog {
             self.render_content(ui);
         });
         
-        // Handle database creation after UI rendering
+        // Check every value before creating the database
         if self.create_database {
-            if let Some(path) = self.start_database_creation() {
-                self.show = false;
-                return Some(path);
-            }
+            self.start_value_check();
             self.create_database = false;
         }
         created_db_path
     }
     
     fn render_content(&mut self, ui: &mut egui::Ui) {
+        let scan_state = self.scan_state.lock().map(|state| state.clone()).unwrap_or(ScanState::Idle);
+        let mut start_scan = false;
+        let mut apply_profile = None;
+        let mut save_profile = false;
+        let mut delete_profile = None;
+        let mut refresh_union = false;
+        let mut remove_file = false;
+        let mut toggle_boundary = None;
+        let current_file_index = self.current_file_index;
+        
         // Use vertical layout with bottom panel for buttons
         egui::TopBottomPanel::bottom("bottom_buttons")
             .show_inside(ui, |ui| {
@@ -387,6 +1008,14 @@ impl FileConfigDialog {
                             }
                         }
                     });
+                    ui.horizontal(|ui| {
+                        table_format_picker(ui, "project_storage_format", &mut self.storage_format);
+                        ui.label(
+                            egui::RichText::new("Tables are saved in the project folder in this format")
+                                .size(12.0)
+                                .color(egui::Color32::from_gray(150))
+                        );
+                    });
                 });
                 if let Some(error) = &self.error.clone() {
                     ui.horizontal(|ui| {
@@ -439,10 +1068,15 @@ impl FileConfigDialog {
                             }
                             
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if !self.files.is_empty() && ui.button("Remove").on_hover_text("Leave this file out of the import").clicked() {
+                                    remove_file = true;
+                                }
                                 if ui.button("Add Files...").clicked() {
                                     if let Some(paths) = rfd::FileDialog::new()
-                                        .add_filter("CSV files", &["csv"])
-                                        .set_title("Select CSV files")
+                                        .add_filter("CSV files", &csv_source::PICKER_EXTENSIONS)
+                                        .add_filter("JSON files", &json_ingest::PICKER_EXTENSIONS)
+                                        .add_filter("Spreadsheets", &spreadsheet::PICKER_EXTENSIONS)
+                                        .set_title("Select CSV, JSON or spreadsheet files")
                                         .pick_files()
                                     {
                                         for path in paths {
@@ -450,6 +1084,17 @@ impl FileConfigDialog {
                                         }
                                     }
                                 }
+                                if ui.button("Add Folder as Table...")
+                                    .on_hover_text("Import every CSV file in a folder into one table, e.g. hourly shards of a log")
+                                    .clicked()
+                                {
+                                    if let Some(folder) = rfd::FileDialog::new()
+                                        .set_title("Select a folder of CSV files")
+                                        .pick_folder()
+                                    {
+                                        self.add_folder(folder.join("*.csv").display().to_string());
+                                    }
+                                }
                             });
                         });
                         
@@ -461,6 +1106,11 @@ impl FileConfigDialog {
                         ui.separator();
                         ui.add_space(5.0);
                         
+                        // An earlier file in this import writes the table before this one is appended to it
+                        let imported_earlier = self.files.get(current_file_index).is_some_and(|current| {
+                            self.files[..current_file_index].iter().any(|file| file.table_name == current.table_name)
+                        });
+                        
                         if let Some(config) = self.files.get_mut(self.current_file_index) {
                             // Table name
                             ui.horizontal(|ui| {
@@ -468,45 +1118,190 @@ impl FileConfigDialog {
                                 ui.text_edit_singleline(&mut config.table_name);
                             });
                             
-                            ui.add_space(10.0);
-                            
-                            // Header configuration
-                            ui.group(|ui| {
-                                ui.set_width(ui.available_width());
-                                ui.label(egui::RichText::new("Header Configuration").size(16.0).strong());
-                                ui.add_space(5.0);
-                                
+                            // Folder import: the files unioned into this table
+                            if let Some(union) = &mut config.union {
                                 ui.horizontal(|ui| {
-                                    ui.label("Header Row:");
-                                    
-                                    // Convert to 1-indexed for display
-                                    let mut header_row_display = config.header_row; // 1-indexed for display
-                                    
-                                    // Always show 1-50 range for header row selection
-                                    let max_rows = 50;
-                                    
-                                    let response = ui.add(
-                                        egui::DragValue::new(&mut header_row_display)
-                                            .range(1..=max_rows)
-                                            .speed(1)
-                                    );
-                                    
-                                    if response.changed() {
-                                        config.header_row = header_row_display; // 1-indexed
-                                        // Trigger resampling
-                                        self.needs_resampling = true;
+                                    ui.label("Files:");
+                                    let response = ui.text_edit_singleline(&mut union.pattern)
+                                        .on_hover_text("A folder, or a pattern where * and ? match any characters, e.g. logs/2026-*.csv");
+                                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                        refresh_union = true;
+                                    }
+                                    if ui.button("Refresh").clicked() {
+                                        refresh_union = true;
                                     }
-                                    
-                                    ui.label("(1-50)");
                                 });
-                                
-                                ui.add_space(5.0);
                                 ui.label(
-                                    egui::RichText::new("The green highlighted row in the preview is your header")
+                                    egui::RichText::new(format!(
+                                        "{} files matched; the columns of all of them are combined by name, previewing {}",
+                                        union.files.len(),
+                                        config.path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()
+                                    ))
                                         .size(12.0)
                                         .color(egui::Color32::from_gray(150))
                                 );
-                            });
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(&mut union.source_column, format!("Add {} column", csv_union::SOURCE_COLUMN))
+                                        .on_hover_text("Record the file each row came from");
+                                    ui.checkbox(&mut union.parallel, "Parse files in parallel")
+                                        .on_hover_text("Faster for many files, but rows of different files are interleaved");
+                                });
+                            }
+                            
+                            // Append mode, offered when the project already has a table of this name
+                            let existing_table = self.database_path.as_deref()
+                                .and_then(|dir| table_format::find_table_file(dir, &config.table_name));
+                            if config.union.is_none() && config.json.is_none() && config.sheet.is_none() && (existing_table.is_some() || imported_earlier) {
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(&mut config.append, "Append to existing table")
+                                        .on_hover_text("Add this file's rows to the table instead of replacing it. Columns are matched by name and values are read as the table's types.");
+                                    if config.append {
+                                        ui.checkbox(&mut config.add_new_columns, "Add new columns")
+                                            .on_hover_text("Columns the table doesn't have are added to it, empty in its existing rows; otherwise they are left out");
+                                    }
+                                });
+                                if config.append {
+                                    match &existing_table {
+                                        Some(path) => {
+                                            let schema = self.table_schemas.entry(path.clone())
+                                                .or_insert_with(|| TableReader::open(path).map(|reader| reader.schema()).ok());
+                                            match schema {
+                                                Some(schema) => {
+                                                    let headers: Vec<String> = config.columns.iter().map(|col| col.name.clone()).collect();
+                                                    let columns = SchemaReconciliation::new(schema, &headers, config.add_new_columns);
+                                                    let color = if columns.is_exact() {
+                                                        egui::Color32::from_rgb(76, 175, 80)
+                                                    } else {
+                                                        egui::Color32::from_rgb(230, 170, 40)
+                                                    };
+                                                    ui.label(egui::RichText::new(columns.describe()).size(12.0).color(color));
+                                                }
+                                                None => {
+                                                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "The existing table file can't be read");
+                                                }
+                                            }
+                                        }
+                                        None => {
+                                            ui.label(
+                                                egui::RichText::new("Appends to the table created by an earlier file in this import")
+                                                    .size(12.0)
+                                                    .color(egui::Color32::from_gray(150))
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+                            
+                            ui.add_space(10.0);
+                            
+                            // JSON files have their own settings; the rest of the panel is for delimited text
+                            if let Some(json) = &mut config.json {
+                                if Self::json_settings(ui, json, &mut config.columns) {
+                                    self.needs_resampling = true;
+                                }
+                                return;
+                            }
+                            
+                            // Spreadsheets: the sheet and cells to read, in place of the delimited-text settings below
+                            if let Some(sheet) = &mut config.sheet {
+                                if Self::sheet_settings(ui, sheet) {
+                                    self.needs_resampling = true;
+                                }
+                                ui.add_space(10.0);
+                            }
+                            
+                            // Import profile: settings saved from an earlier file with the same layout
+                            if config.sheet.is_none() {
+                                ui.group(|ui| {
+                                    ui.set_width(ui.available_width());
+                                    ui.horizontal(|ui| {
+                                        ui.label("Import Profile:");
+                                        match &config.profile {
+                                            Some((name, how)) => {
+                                                ui.label(egui::RichText::new(name).strong());
+                                                ui.label(
+                                                    egui::RichText::new(format!("({})", how.display_name()))
+                                                        .size(12.0)
+                                                        .color(egui::Color32::from_gray(150))
+                                                );
+                                                if ui.small_button("🗑").on_hover_text("Delete this profile").clicked() {
+                                                    delete_profile = Some(name.clone());
+                                                }
+                                            }
+                                            None => {
+                                                ui.label(
+                                                    egui::RichText::new("none")
+                                                        .size(12.0)
+                                                        .color(egui::Color32::from_gray(150))
+                                                );
+                                            }
+                                        }
+                                        if !self.profiles.is_empty() {
+                                            egui::ComboBox::new(format!("apply_profile_{}", current_file_index), "")
+                                                .selected_text("Apply...")
+                                                .show_ui(ui, |ui| {
+                                                    for (idx, profile) in self.profiles.iter().enumerate() {
+                                                        if ui.selectable_label(false, &profile.name).clicked() {
+                                                            apply_profile = Some(idx);
+                                                        }
+                                                    }
+                                                });
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.add(egui::TextEdit::singleline(&mut self.profile_name_input)
+                                            .hint_text(config.table_name.as_str())
+                                            .desired_width(120.0));
+                                        ui.add(egui::TextEdit::singleline(&mut self.profile_pattern_input)
+                                            .hint_text(ImportProfile::suggest_pattern(&config.file_name()))
+                                            .desired_width(140.0))
+                                            .on_hover_text("Later files whose name matches this pattern get the profile (* and ? are wildcards). Files with the same columns get it either way.");
+                                        if ui.button("💾 Save Profile").clicked() {
+                                            save_profile = true;
+                                        }
+                                    });
+                                });
+                            
+                                ui.add_space(10.0);
+                            }
+                            
+                            // Header configuration
+                            ui.group(|ui| {
+                                ui.set_width(ui.available_width());
+                                ui.label(egui::RichText::new("Header Configuration").size(16.0).strong());
+                                ui.add_space(5.0);
+                                
+                                ui.horizontal(|ui| {
+                                    ui.label("Header Row:");
+                                    
+                                    // Convert to 1-indexed for display
+                                    let mut header_row_display = config.header_row; // 1-indexed for display
+                                    
+                                    // Always show 1-50 range for header row selection
+                                    let max_rows = 50;
+                                    
+                                    let response = ui.add(
+                                        egui::DragValue::new(&mut header_row_display)
+                                            .range(1..=max_rows)
+                                            .speed(1)
+                                    );
+                                    
+                                    if response.changed() {
+                                        config.header_row = header_row_display; // 1-indexed
+                                        // Trigger resampling
+                                        self.needs_resampling = true;
+                                    }
+                                    
+                                    ui.label("(1-50)");
+                                });
+                                
+                                ui.add_space(5.0);
+                                ui.label(
+                                    egui::RichText::new("The green highlighted row in the preview is your header")
+                                        .size(12.0)
+                                        .color(egui::Color32::from_gray(150))
+                                );
+                            });
                             
                             ui.add_space(10.0);
                             
@@ -528,41 +1323,196 @@ impl FileConfigDialog {
                                 }
                             });
                             
+                            // Whole-file inference, for columns whose sample doesn't tell the full story
+                            ui.horizontal(|ui| {
+                                match &scan_state {
+                                    ScanState::Running { file_index, rows, fraction } if *file_index == current_file_index => {
+                                        ui.add(egui::ProgressBar::new(*fraction)
+                                            .desired_width(160.0)
+                                            .text(format!("{} rows", rows)));
+                                    }
+                                    _ => {
+                                        let scanning = matches!(scan_state, ScanState::Running { .. });
+                                        if ui.add_enabled(!scanning, egui::Button::new("🔍 Scan Whole File"))
+                                            .on_hover_text("Read every row to check the inferred types; this can take a while for large files")
+                                            .on_disabled_hover_text("Another file is being scanned")
+                                            .clicked()
+                                        {
+                                            start_scan = true;
+                                        }
+                                        if config.scanned_whole_file {
+                                            ui.label(
+                                                egui::RichText::new("Types checked against every row")
+                                                    .size(12.0)
+                                                    .color(egui::Color32::from_gray(150))
+                                            );
+                                        }
+                                    }
+                                }
+                            });
+                            
                             ui.add_space(10.0);
                             
-                            // Delimiter
-                            ui.horizontal(|ui| {
-                                ui.label("Delimiter:");
+                            // Delimiter and encoding, which a spreadsheet doesn't have
+                            if config.sheet.is_none() {
+                                ui.horizontal(|ui| {
+                                    ui.label("Delimiter:");
                                 
-                                // Try to infer delimiter from header if we have preview data
-                                let inferred_delimiter = if let Some(ref preview) = config.preview_data {
-                                    if let Some(header_line) = preview.rows.get(config.header_row.saturating_sub(1)) {
-                                        if let Some(header_str) = header_line.get(0) {
-                                            Self::infer_delimiter_from_header(header_str)
-                                        } else {
-                                            ','
+                                    // Delimiter detected from the header record when the preview was loaded
+                                    let inferred_delimiter = config.preview_data.as_ref()
+                                        .map(|preview| preview.inferred_delimiter)
+                                        .unwrap_or(',');
+                                
+                                    // Show inferred delimiter if it's different from current selection
+                                    if inferred_delimiter != config.delimiter {
+                                        ui.label(format!("Inferred: {}", Self::delimiter_display_name(inferred_delimiter)));
+                                        if ui.button("Use Inferred").clicked() {
+                                            config.delimiter = inferred_delimiter;
+                                            self.needs_resampling = true;
                                         }
-                                    } else {
-                                        ','
                                     }
-                                } else {
-                                    ','
-                                };
                                 
-                                // Show inferred delimiter if it's different from current selection
-                                if inferred_delimiter != config.delimiter {
-                                    ui.label(format!("Inferred: {}", Self::delimiter_display_name(inferred_delimiter)));
-                                    if ui.button("Use Inferred").clicked() {
-                                        config.delimiter = inferred_delimiter;
+                                    let previous_delimiter = config.delimiter;
+                                    let fixed = config.column_boundaries.is_some();
+                                    for (delimiter, label) in [(',', "Comma"), ('\t', "Tab"), (';', "Semicolon"), ('|', "Pipe")] {
+                                        if ui.radio(!fixed && config.delimiter == delimiter, label).clicked() {
+                                            config.delimiter = delimiter;
+                                            config.column_boundaries = None;
+                                        }
                                     }
+                                    if ui.radio(fixed, "Fixed width")
+                                        .on_hover_text("Columns at fixed character positions, guessed from how the lines line up")
+                                        .clicked() && !fixed
+                                    {
+                                        config.use_fixed_width();
+                                    }
+                                    if config.delimiter != previous_delimiter || config.column_boundaries.is_some() != fixed {
+                                        self.needs_resampling = true;
+                                    }
+                                });
+                                
+                                // Fixed-width columns, edited by clicking the lines in the preview
+                                if let Some(boundaries) = &config.column_boundaries {
+                                    let positions = fixed_width::format_boundaries(boundaries);
+                                    ui.horizontal(|ui| {
+                                        ui.label("Columns start at: 0,");
+                                        let response = ui.add(egui::TextEdit::singleline(&mut self.boundaries_input)
+                                            .desired_width(200.0))
+                                            .on_hover_text("Character positions where the second and later columns start, like 8, 20, 31");
+                                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                            match fixed_width::parse_boundaries(&self.boundaries_input) {
+                                                Ok(typed) => {
+                                                    config.column_boundaries = Some(typed);
+                                                    self.needs_resampling = true;
+                                                }
+                                                Err(e) => self.error = Some(e.to_string()),
+                                            }
+                                        } else if !response.has_focus() {
+                                            self.boundaries_input = positions;
+                                        }
+                                        if ui.button("Guess Again").clicked() {
+                                            config.use_fixed_width();
+                                            self.needs_resampling = true;
+                                        }
+                                    });
+                                    ui.label(
+                                        egui::RichText::new("Type the positions and press Enter, or click between two characters in the preview to split a column there and on a line to remove it")
+                                            .size(12.0)
+                                            .color(egui::Color32::from_gray(150))
+                                    );
                                 }
+                            
+                                // Character encoding
+                                ui.horizontal(|ui| {
+                                    ui.label("Encoding:");
+                                    let previous_encoding = config.encoding;
+                                    egui::ComboBox::from_id_salt(format!("encoding_{}", self.current_file_index))
+                                        .selected_text(text_encoding::display_name(config.encoding))
+                                        .show_ui(ui, |ui| {
+                                            for encoding in SELECTABLE_ENCODINGS {
+                                                ui.selectable_value(&mut config.encoding, encoding, text_encoding::display_name(encoding));
+                                            }
+                                        });
+                                    if config.encoding != previous_encoding {
+                                        self.needs_resampling = true;
+                                    }
                                 
-                                ui.radio_value(&mut config.delimiter, ',', "Comma");
-                                ui.radio_value(&mut config.delimiter, '\t', "Tab");
-                                ui.radio_value(&mut config.delimiter, ';', "Semicolon");
-                                ui.radio_value(&mut config.delimiter, '|', "Pipe");
+                                    if config.encoding != config.detected_encoding {
+                                        ui.label(format!("Detected: {}", text_encoding::display_name(config.detected_encoding)));
+                                        if ui.button("Use Detected").clicked() {
+                                            config.encoding = config.detected_encoding;
+                                            self.needs_resampling = true;
+                                        }
+                                    }
+                                });
+                            }
+                            
+                            // Decimal and thousands separators
+                            ui.horizontal(|ui| {
+                                ui.label("Numbers:");
+                                let previous_format = config.number_format;
+                                egui::ComboBox::from_id_salt(format!("number_format_{}", self.current_file_index))
+                                    .selected_text(config.number_format.display_name())
+                                    .show_ui(ui, |ui| {
+                                        for format in NumberFormat::PRESETS {
+                                            ui.selectable_value(&mut config.number_format, format, format.display_name());
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Currency symbols, percentages and (negative) amounts are recognised in every format");
+                                if config.number_format != previous_format {
+                                    self.needs_resampling = true;
+                                }
+                            });
+                            
+                            // Timezone for DateTime columns
+                            ui.horizontal(|ui| {
+                                ui.label("Timezone:");
+                                ui.add(egui::TextEdit::singleline(&mut config.timezone)
+                                    .hint_text("as written")
+                                    .desired_width(140.0))
+                                    .on_hover_text("Times without a UTC offset are read in this timezone (e.g. Europe/Berlin, UTC or +02:00), and time columns are stored tagged with it");
+                                let timezone = config.timezone.trim();
+                                if !timezone.is_empty() && datetime_format::parse_timezone(timezone).is_none() {
+                                    ui.colored_label(egui::Color32::RED, "Unknown timezone");
+                                }
+                            });
+                            
+                            // Rows whose field count doesn't match the header
+                            ui.horizontal(|ui| {
+                                ui.label("Malformed rows:");
+                                egui::ComboBox::from_id_salt(format!("reject_policy_{}", self.current_file_index))
+                                    .selected_text(config.reject_policy.display_name())
+                                    .show_ui(ui, |ui| {
+                                        for policy in RejectPolicy::ALL {
+                                            ui.selectable_value(&mut config.reject_policy, policy, policy.display_name());
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(format!("Rejected rows are saved to rejects/{}_rejects.csv in the project folder", config.table_name));
+                            });
+                            
+                            // Values that don't parse as their column's type
+                            ui.horizontal(|ui| {
+                                ui.label("Unparseable values:");
+                                egui::ComboBox::from_id_salt(format!("coercion_policy_{}", self.current_file_index))
+                                    .selected_text(config.coercion_policy.display_name())
+                                    .show_ui(ui, |ui| {
+                                        for policy in CoercionPolicy::ALL {
+                                            ui.selectable_value(&mut config.coercion_policy, policy, policy.display_name());
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Every value is checked before the import starts");
                             });
                             
+                            // Whitespace handling
+                            if ui.checkbox(&mut config.trim_whitespace, "Trim whitespace around values")
+                                .on_hover_text("Off keeps every value exactly as written in the file")
+                                .changed() {
+                                self.needs_resampling = true;
+                            }
+                            
                             ui.add_space(10.0);
                             
                             // Null values
@@ -640,6 +1590,9 @@ impl FileConfigDialog {
                                                 .column(Column::auto().at_least(60.0)) // Include
                                                 .column(Column::auto().at_least(100.0).resizable(true)) // Column
                                                 .column(Column::auto().at_least(100.0)) // Type
+                                                .column(Column::auto().at_least(70.0)) // Confidence
+                                                .column(Column::auto().at_least(120.0)) // Format
+                                                .column(Column::auto().at_least(60.0)) // Values
                                                 .header(20.0, |mut header| {
                                                     header.col(|ui| {
                                                         ui.label(egui::RichText::new("Include").strong());
@@ -650,6 +1603,16 @@ impl FileConfigDialog {
                                                     header.col(|ui| {
                                                         ui.label(egui::RichText::new("Type").strong());
                                                     });
+                                                    header.col(|ui| {
+                                                        ui.label(egui::RichText::new("Confidence").strong());
+                                                    });
+                                                    header.col(|ui| {
+                                                        ui.label(egui::RichText::new("Format").strong());
+                                                    });
+                                                    header.col(|ui| {
+                                                        ui.label(egui::RichText::new("Values").strong())
+                                                            .on_hover_text("Null tokens and true/false words for single columns");
+                                                    });
                                                 })
                                                 .body(|mut body| {
                                                     for (col_idx, col) in config.columns.iter_mut().enumerate() {
@@ -665,36 +1628,46 @@ impl FileConfigDialog {
                                                                 let old_type = col.data_type.clone();
                                                                 let mut new_type = col.data_type.clone();
                                                                 egui::ComboBox::new(format!("type_{}_{}", self.current_file_index, col_idx), "")
-                                                                    .selected_text(col.data_type.display_name())
+                                                                    .selected_text(col.data_type.label())
                                                                     .width(120.0)
                                                                     .show_ui(ui, |ui| {
                                                                         ui.set_max_height(200.0); // Force dropdown to open downward
-                                                                        ui.selectable_value(&mut new_type, ColumnType::Text, "Text");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::Integer, "Integer (64-bit)");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::Real, "Float (64-bit)");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::Boolean, "Boolean");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::Date, "Date");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::DateTime, "Time");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::TimeSeconds, "Time (seconds)");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::TimeMilliseconds, "Time (milliseconds)");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::TimeMicroseconds, "Time (microseconds)");
-                                                                        ui.selectable_value(&mut new_type, ColumnType::TimeNanoseconds, "Time (nanoseconds)");
+                                                                        for option in ColumnType::SELECTABLE {
+                                                                            if ui.selectable_label(old_type.same_kind(&option), option.display_name()).clicked() && !old_type.same_kind(&option) {
+                                                                                new_type = option;
+                                                                            }
+                                                                        }
                                                                     });
                                                                 // Only validate and update if the type actually changed
                                                                 if new_type != old_type {
                                                                     let preview_data = config.preview_data.clone();
                                                                     let header_row = config.header_row;
                                                                     let sample_size = config.sample_size;
+                                                                    let number_format = config.number_format;
+                                                                    let datetime_pattern = col.datetime_pattern.trim().to_string();
                                                                     if let Some(preview) = &preview_data {
                                                                         let sample_data: Vec<Vec<String>> = preview.rows.iter()
                                                                             .skip(header_row + 1)
                                                                             .take(sample_size)
                                                                             .cloned()
                                                                             .collect();
+                                                                        // Decimals keep as many places as the sample writes
+                                                                        if let ColumnType::Decimal { precision, .. } = new_type {
+                                                                            let scale = sample_data.iter()
+                                                                                .filter_map(|row| row.get(col_idx))
+                                                                                .filter_map(|value| number_format.parse(value))
+                                                                                .map(|number| number.scale)
+                                                                                .max()
+                                                                                .unwrap_or(0);
+                                                                            new_type = ColumnType::Decimal { precision, scale: scale.min(precision) as i8 };
+                                                                        }
                                                                         if let Err(validation_error) = TypeInferrer::validate_column_type_change(
                                                                             &sample_data,
                                                                             col_idx,
-                                                                            &new_type
+                                                                            &new_type,
+                                                                            &number_format,
+                                                                            Some(datetime_pattern.as_str()).filter(|pattern| !pattern.is_empty()),
+                                                                            &col.tokens(&config.null_values)
                                                                         ) {
                                                                             // Show error, do not update col.data_type
                                                                             self.error = Some(validation_error);
@@ -707,6 +1680,50 @@ impl FileConfigDialog {
                                                                     }
                                                                 }
                                                             });
+                                                            row.col(|ui| {
+                                                                if let Some(inference) = &col.inference {
+                                                                    Self::confidence_label(ui, inference, &col.data_type, config.scanned_whole_file);
+                                                                }
+                                                            });
+                                                            row.col(|ui| {
+                                                                // Date/time pattern, for columns that hold or could hold dates
+                                                                if !matches!(col.data_type, ColumnType::Text | ColumnType::Dictionary | ColumnType::Date | ColumnType::DateTime) {
+                                                                    return;
+                                                                }
+                                                                let response = ui.add(egui::TextEdit::singleline(&mut col.datetime_pattern)
+                                                                    .hint_text("auto")
+                                                                    .desired_width(120.0))
+                                                                    .on_hover_text("strftime pattern, e.g. %d.%m.%Y %H:%M or %Y-%m-%dT%H:%M:%S%.f%z");
+                                                                let pattern = col.datetime_pattern.trim();
+                                                                if !response.lost_focus() || pattern.is_empty() {
+                                                                    return;
+                                                                }
+                                                                if let Some(preview) = &config.preview_data {
+                                                                    let tokens = col.tokens(&config.null_values);
+                                                                    let values = preview.rows.iter()
+                                                                        .skip(config.header_row + 1)
+                                                                        .take(config.sample_size)
+                                                                        .filter_map(|row| row.get(col_idx))
+                                                                        .map(String::as_str)
+                                                                        .filter(|value| !tokens.is_null(value));
+                                                                    match datetime_format::pattern_column_type(pattern, values) {
+                                                                        Some(pattern_type) => {
+                                                                            if matches!(col.data_type, ColumnType::Text | ColumnType::Dictionary) {
+                                                                                col.data_type = pattern_type;
+                                                                            }
+                                                                        }
+                                                                        None => {
+                                                                            self.error = Some(format!("Some values in '{}' don't match the pattern {}", col.name, pattern));
+                                                                        }
+                                                                    }
+                                                                }
+                                                            });
+                                                            row.col(|ui| {
+                                                                // Null tokens and true/false words for this column; the types are inferred again on change
+                                                                if Self::column_values_menu(ui, col) {
+                                                                    self.needs_resampling = true;
+                                                                }
+                                                            });
                                                         });
                                                     }
                                                 });
@@ -723,10 +1740,21 @@ impl FileConfigDialog {
                         ui.label(egui::RichText::new("Data Preview").size(16.0).strong());
                         ui.add_space(8.0);
                         
-                        let preview_height = ui.available_height();
+                        let mut preview_height = ui.available_height();
                         
                         if let Some(config) = self.files.get(self.current_file_index) {
                             if let Some(preview) = &config.preview_data {
+                                // Fixed-width files also show their lines as written, with the column boundaries on them
+                                if let Some(boundaries) = config.column_boundaries.as_ref().filter(|_| !preview.lines.is_empty()) {
+                                    egui::ScrollArea::both()
+                                        .id_salt(format!("fixed_width_lines_{}", self.current_file_index))
+                                        .max_height(preview_height * 0.4)
+                                        .show(ui, |ui| {
+                                            toggle_boundary = Self::fixed_width_ruler(ui, &preview.lines, boundaries);
+                                        });
+                                    ui.separator();
+                                    preview_height = ui.available_height();
+                                }
                                 egui::ScrollArea::both()
                                     .id_salt(format!("preview_scroll_{}", self.current_file_index))
                                     .max_height(preview_height)
@@ -802,155 +1830,679 @@ impl FileConfigDialog {
                     }); // End right column
                 }); // End horizontal_top
             }); // End CentralPanel
+        if let Some(position) = toggle_boundary {
+            if let Some(config) = self.files.get_mut(self.current_file_index) {
+                config.toggle_column_boundary(position);
+                self.needs_resampling = true;
+            }
+        }
         if self.needs_resampling {
             self.needs_resampling = false;
+            // Any whole-file scan in flight was for the old settings
+            self.scan_generation += 1;
             self.load_preview_for_current_file();
         }
+        if start_scan {
+            self.start_full_scan();
+        }
+        if let Some(profile_index) = apply_profile {
+            self.apply_profile_to_current_file(profile_index);
+        }
+        if save_profile {
+            self.save_current_file_as_profile();
+        }
+        if let Some(name) = delete_profile {
+            self.delete_profile(&name);
+        }
+        if refresh_union {
+            self.refresh_union_files();
+        }
+        if remove_file {
+            self.remove_current_file();
+        }
+    }
+    
+    /// A fixed-width file's lines under a scale of character positions, with a line at each column boundary.
+    /// Returns the position between two characters that was clicked, to add or remove a boundary there.
+    fn fixed_width_ruler(ui: &mut egui::Ui, lines: &[String], boundaries: &[usize]) -> Option<usize> {
+        let font = egui::FontId::monospace(12.0);
+        let char_width = ui.fonts(|fonts| fonts.glyph_width(&font, '0'));
+        let row_height = ui.fonts(|fonts| fonts.row_height(&font));
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) + 2;
+        let size = egui::vec2(width as f32 * char_width, (lines.len() + 1) as f32 * row_height);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+        let painter = ui.painter_at(rect);
+        let position_at = |x: f32| ((x - rect.left()) / char_width).round() as usize;
+        
+        for position in (0..width).step_by(10) {
+            painter.text(
+                rect.left_top() + egui::vec2(position as f32 * char_width, 0.0),
+                egui::Align2::LEFT_TOP,
+                position.to_string(),
+                font.clone(),
+                egui::Color32::from_gray(120),
+            );
+        }
+        for (row, line) in lines.iter().enumerate() {
+            painter.text(
+                rect.left_top() + egui::vec2(0.0, (row + 1) as f32 * row_height),
+                egui::Align2::LEFT_TOP,
+                line.replace('\t', " "),
+                font.clone(),
+                egui::Color32::from_gray(200),
+            );
+        }
+        let boundary_x = |position: usize| rect.left() + position as f32 * char_width;
+        for &position in boundaries {
+            painter.vline(boundary_x(position), rect.y_range(), egui::Stroke::new(1.5, egui::Color32::from_rgb(100, 200, 100)));
+        }
+        if let Some(pointer) = response.hover_pos() {
+            painter.vline(boundary_x(position_at(pointer.x)), rect.y_range(), egui::Stroke::new(1.0, egui::Color32::from_gray(90)));
+        }
+        
+        let clicked = if response.clicked() { response.interact_pointer_pos() } else { None };
+        response.on_hover_cursor(egui::CursorIcon::PointingHand);
+        clicked.map(|pointer| position_at(pointer.x)).filter(|&position| position > 0)
+    }
+    
+    /// Drop the file being configured from the import and show the one after it
+    fn remove_current_file(&mut self) {
+        if self.current_file_index >= self.files.len() {
+            return;
+        }
+        self.files.remove(self.current_file_index);
+        self.current_file_index = self.current_file_index.min(self.files.len().saturating_sub(1));
+        // A scan still running reports against the old file indexes
+        self.scan_generation += 1;
+        self.error = None;
+        self.load_preview_for_current_file();
+    }
+    
+    /// The sheet to import and the cells to read from it. Returns whether the preview needs loading again.
+    fn sheet_settings(ui: &mut egui::Ui, sheet: &mut SheetSource) -> bool {
+        let mut changed = false;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.label(egui::RichText::new("Sheet").size(16.0).strong());
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Sheet:");
+                let previous = sheet.sheet.clone();
+                egui::ComboBox::from_id_salt("sheet_selector")
+                    .selected_text(&sheet.sheet)
+                    .show_ui(ui, |ui| {
+                        for name in &sheet.sheets {
+                            ui.selectable_value(&mut sheet.sheet, name.clone(), name);
+                        }
+                    });
+                changed |= sheet.sheet != previous;
+            });
+            ui.horizontal(|ui| {
+                ui.label("Cells:");
+                let response = ui.add(egui::TextEdit::singleline(&mut sheet.range)
+                    .hint_text("whole sheet")
+                    .desired_width(120.0))
+                    .on_hover_text("A range like B2:F100, or B2 to read from there to the end of the sheet");
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    changed = true;
+                }
+                if ui.button("Apply").clicked() {
+                    changed = true;
+                }
+            });
+            ui.label(
+                egui::RichText::new("Header rows count from the first row of the range. Formulas are read as their last calculated values.")
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(150))
+            );
+        });
+        changed
+    }
+    
+    /// Settings for a JSON file: flattening and the columns to keep, with their inferred types.
+    /// Returns whether the preview needs loading again.
+    fn json_settings(ui: &mut egui::Ui, json: &mut JsonSource, columns: &mut [ColumnConfig]) -> bool {
+        let mut changed = false;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.label(egui::RichText::new("JSON Settings").size(16.0).strong());
+            ui.add_space(5.0);
+            changed = ui.checkbox(&mut json.flatten, "Flatten nested objects")
+                .on_hover_text("Make a column per nested field, like user.address.city, instead of one struct column per object")
+                .changed();
+            ui.label(
+                egui::RichText::new("Types are inferred from every record when importing; the preview shows the first 50")
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(150))
+            );
+        });
+        
+        ui.add_space(10.0);
+        
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.label(egui::RichText::new("Column Selection").size(14.0));
+            ui.horizontal(|ui| {
+                if ui.button("Select All").clicked() {
+                    columns.iter_mut().for_each(|col| col.included = true);
+                }
+                if ui.button("Deselect All").clicked() {
+                    columns.iter_mut().for_each(|col| col.included = false);
+                }
+                ui.label(format!("{} / {} selected", columns.iter().filter(|col| col.included).count(), columns.len()));
+            });
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .id_salt("json_columns")
+                .max_height(ui.available_height())
+                .show(ui, |ui| {
+                    egui::Grid::new("json_column_grid")
+                        .striped(true)
+                        .spacing([16.0, 4.0])
+                        .show(ui, |ui| {
+                            for (col_idx, col) in columns.iter_mut().enumerate() {
+                                ui.checkbox(&mut col.included, "");
+                                ui.label(&col.name);
+                                let data_type = json.schema.as_ref()
+                                    .and_then(|schema| schema.fields().get(col_idx).map(|field| json_ingest::describe_type(field.data_type())))
+                                    .unwrap_or_default();
+                                ui.label(egui::RichText::new(data_type).monospace().size(12.0));
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+        changed
+    }
+    
+    /// Preview a JSON file's first records as rows under a header of their columns
+    fn load_json_preview(&mut self) {
+        let Some(config) = self.files.get_mut(self.current_file_index) else {
+            return;
+        };
+        let Some(json) = &mut config.json else {
+            return;
+        };
+        match json_ingest::preview(&config.path, json.flatten, 50) {
+            Ok((schema, rows)) => {
+                // Columns keep their selection across a change of flattening
+                let previous: HashMap<String, bool> = config.columns.drain(..)
+                    .map(|col| (col.name, col.included))
+                    .collect();
+                config.columns = schema.fields().iter()
+                    .map(|field| ColumnConfig {
+                        included: previous.get(field.name()).copied().unwrap_or(true),
+                        ..ColumnConfig::new(field.name().clone())
+                    })
+                    .collect();
+                let mut preview_rows = vec![config.columns.iter().map(|col| col.name.clone()).collect::<Vec<_>>()];
+                preview_rows.extend(rows);
+                config.header_row = 1;
+                config.preview_data = Some(PreviewData {
+                    original_row_numbers: (1..=preview_rows.len()).collect(),
+                    rows: preview_rows,
+                    inferred_delimiter: config.delimiter,
+                    lines: Vec::new(),
+                });
+                json.schema = Some(schema);
+            }
+            Err(e) => self.error = Some(format!("Failed to load preview: {}", e)),
+        }
     }
     
     pub fn load_preview_for_current_file(&mut self) {
+        if self.files.get(self.current_file_index).is_some_and(|config| config.json.is_some()) {
+            self.load_json_preview();
+            return;
+        }
         if let Some(config) = self.files.get_mut(self.current_file_index) {
             let path = config.path.clone();
             let delimiter = config.delimiter;
+            let encoding = config.encoding;
             let sample_size = config.sample_size;
             let header_row = config.header_row;
             
-                                // Load preview data from the beginning of the file (first 50 rows)
-                    match std::fs::read_to_string(&path) {
-                        Ok(content) => {
-                            let lines: Vec<&str> = content.lines().collect();
-                            
-                            // Take more lines to ensure we get 50 valid rows for preview
-                            let data_lines = lines.into_iter().take(200).collect::<Vec<&str>>();
-                    
-                    if data_lines.is_empty() {
-                        self.error = Some("No data found in file".to_string());
-                        return;
+            // Read the first records with the same tokenizer the import uses,
+            // so quoted fields spanning several lines show up as one row
+            let mut tokenizer = match config.open_records() {
+                Ok(tokenizer) => tokenizer,
+                Err(e) => {
+                    self.error = Some(format!("Failed to load preview: {}", e));
+                    return;
+                }
+            };
+            
+            let mut preview_rows: Vec<Vec<String>> = Vec::new();
+            let mut original_row_numbers = Vec::new();
+            let mut sample_data = Vec::new();
+            let header_idx = header_row.saturating_sub(1);
+            
+            let mut record_idx = 0usize;
+            loop {
+                let record = match tokenizer.next_record() {
+                    Ok(Some(record)) => record,
+                    Ok(None) => break,
+                    Err(e) => {
+                        self.error = Some(format!("Failed to load preview: {}", e));
+                        break;
                     }
-                    
-                    // Show raw file content for preview (first 50 lines)
-                    let mut preview_rows: Vec<Vec<String>> = Vec::new();
-                    let mut valid_data_rows = Vec::new();
-                    
-                    // Collect raw lines for preview (up to 50 lines)
-                    for (line_idx, line) in data_lines.iter().enumerate() {
-                        // Parse the line to show as individual cells
-                        let row = Self::parse_csv_line(line, delimiter);
-                        preview_rows.push(row.clone());
-                        
-                        // Also collect valid data rows for type inference (filtered)
-                        if !line.trim().starts_with('#') {
-                            let is_garbage = row.iter().all(|cell| cell.is_empty()) ||
-                                           row.iter().any(|cell| {
-                                               let cell_lower = cell.to_lowercase();
-                                               cell_lower.contains("fake line") ||
-                                               cell_lower.contains("ignore this") ||
-                                               cell_lower.contains("data starts here") ||
-                                               cell_lower.contains("realistic patterns")
-                                           });
-                            
-                            if !is_garbage && valid_data_rows.len() < sample_size {
-                                valid_data_rows.push(row);
-                            }
-                        }
-                        
-                        // Stop after 50 lines for preview
-                        if preview_rows.len() >= 50 {
-                            break;
+                };
+                
+                // Show the first 50 records in the preview
+                if preview_rows.len() < 50 {
+                    preview_rows.push(record.fields.clone());
+                    original_row_numbers.push(record.line as usize);
+                }
+                
+                // Use data rows after the header row for type inference
+                // Skip comment lines (lines starting with #)
+                if record_idx > header_idx && sample_data.len() < sample_size
+                    && !record.fields.is_empty() && !record.fields[0].starts_with('#') {
+                    sample_data.push(record.fields);
+                }
+                
+                record_idx += 1;
+                if preview_rows.len() >= 50 && sample_data.len() >= sample_size {
+                    break;
+                }
+            }
+            
+            if preview_rows.is_empty() {
+                self.error = Some("No data found in file".to_string());
+                return;
+            }
+            
+            // Get the header row from the selected header row position (1-indexed to 0-indexed)
+            if header_idx < preview_rows.len() {
+                let headers = preview_rows[header_idx].clone();
+                
+                // Update columns, keeping the date/time patterns and value words already entered
+                let mut previous: HashMap<String, ColumnConfig> = config.columns.drain(..)
+                    .map(|col| (col.name.clone(), col))
+                    .collect();
+                for header in &headers {
+                    let column = match previous.remove(header) {
+                        Some(previous) => ColumnConfig {
+                            included: true,
+                            inference: None,
+                            ..previous
+                        },
+                        None => ColumnConfig::new(header.clone()),
+                    };
+                    config.columns.push(column);
+                }
+                let datetime_patterns: Vec<Option<String>> = config.columns.iter()
+                    .map(|col| Some(col.datetime_pattern.trim().to_string()).filter(|pattern| !pattern.is_empty()))
+                    .collect();
+                let tokens: Vec<ColumnTokens> = config.columns.iter()
+                    .map(|col| col.tokens(&config.null_values))
+                    .collect();
+                
+                // Infer types using the sample data with null value, number format, pattern and boolean word awareness
+                let inferences = TypeInferrer::infer_columns_with_confidence(&headers, &sample_data, &tokens, &config.number_format, &datetime_patterns);
+                for (col, inference) in config.columns.iter_mut().zip(inferences) {
+                    col.data_type = inference.column_type.clone();
+                    col.inference = Some(inference);
+                }
+                config.scanned_whole_file = false;
+            }
+            
+            let inferred_delimiter = if config.sheet.is_some() || config.column_boundaries.is_some() {
+                delimiter
+            } else {
+                csv_tokenizer::infer_delimiter(&path, header_idx, Some(encoding)).unwrap_or(delimiter)
+            };
+            let lines = if config.column_boundaries.is_some() {
+                fixed_width::sample_lines(&path, Some(encoding), 50).unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            
+            config.preview_data = Some(PreviewData { 
+                rows: preview_rows,
+                original_row_numbers,
+                inferred_delimiter,
+                lines,
+            });
+        }
+    }
+    
+    /// Menu for a column's own null tokens and true/false words; returns whether they changed
+    fn column_values_menu(ui: &mut egui::Ui, col: &mut ColumnConfig) -> bool {
+        let mut changed = false;
+        let customised = !col.null_values.trim().is_empty() || col.booleans().is_some();
+        let mut summary = Vec::new();
+        if !col.null_values.trim().is_empty() {
+            summary.push(format!("Null: {}", col.null_values.trim()));
+        }
+        if let Some(booleans) = col.booleans() {
+            summary.push(format!("Booleans: {}", booleans.describe()));
+        }
+        let label = if customised { "⚙ custom" } else { "⚙" };
+        ui.menu_button(label, |ui| {
+            ui.label("Also null in this column:");
+            changed |= ui.add(egui::TextEdit::singleline(&mut col.null_values)
+                .hint_text("e.g. -999, n/k")
+                .desired_width(180.0))
+                .lost_focus();
+            ui.separator();
+            ui.label("Read as true / false:");
+            let default = BooleanVocabulary::default();
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::TextEdit::singleline(&mut col.true_values)
+                    .hint_text(default.true_values.join(", "))
+                    .desired_width(85.0))
+                    .lost_focus();
+                changed |= ui.add(egui::TextEdit::singleline(&mut col.false_values)
+                    .hint_text(default.false_values.join(", "))
+                    .desired_width(85.0))
+                    .lost_focus();
+            });
+            ui.horizontal(|ui| {
+                for preset in BooleanVocabulary::presets() {
+                    if ui.small_button(preset.describe()).clicked() {
+                        if preset == default {
+                            col.true_values.clear();
+                            col.false_values.clear();
+                        } else {
+                            col.true_values = preset.true_values.join(", ");
+                            col.false_values = preset.false_values.join(", ");
                         }
+                        changed = true;
                     }
-                    
-                    // Get the header row from the selected header row position (1-indexed to 0-indexed)
-                    let header_idx = header_row.saturating_sub(1);
-                    if header_idx < preview_rows.len() {
-                        let headers = preview_rows[header_idx].clone();
-                        
-                        // Use data rows after the header row for type inference (not filtered)
-                        let mut sample_data = Vec::new();
-                        for (idx, row) in preview_rows.iter().enumerate() {
-                            if idx > header_idx && sample_data.len() < sample_size {
-                                // Skip comment lines (lines starting with #)
-                                if !row.is_empty() && !row[0].starts_with('#') {
-                                    sample_data.push(row.clone());
-                                }
-                            }
-                        }
-                        
-                        // Infer types using the sample data with null value awareness
-                        let inferred_types = TypeInferrer::infer_column_types_with_nulls(&headers, &sample_data, &config.null_values);
-                        
-                        // Update columns
-                        config.columns.clear();
-                        for (header, (_name, data_type)) in headers.iter().zip(inferred_types.iter()) {
-                            config.columns.push(ColumnConfig {
-                                name: header.clone(),
-                                data_type: data_type.clone(),
-                                included: true,
-                            });
+                }
+            });
+        })
+        .response
+        .on_hover_text(if summary.is_empty() { "File null values and the default true/false words".to_string() } else { summary.join("\n") });
+        changed
+    }
+    
+    /// Confidence of the inferred type as a coloured percentage, with the reason and
+    /// the other candidate types on hover
+    fn confidence_label(ui: &mut egui::Ui, inference: &ColumnInference, chosen: &ColumnType, whole_file: bool) {
+        let color = if inference.confidence >= 0.9 {
+            egui::Color32::from_rgb(76, 175, 80)
+        } else if inference.confidence >= 0.6 {
+            egui::Color32::from_rgb(230, 170, 40)
+        } else {
+            egui::Color32::from_rgb(220, 80, 60)
+        };
+        let mut hover = format!(
+            "{}\n{} values, {} nulls ({})",
+            inference.reason,
+            inference.values,
+            inference.nulls,
+            if whole_file { "whole file" } else { "sample" }
+        );
+        if !chosen.same_kind(&inference.column_type) {
+            hover.push_str(&format!("\nInferred {}, changed by hand", inference.column_type.display_name()));
+        }
+        if !inference.candidates.is_empty() {
+            hover.push_str("\n\nValues each type can read:");
+            for (column_type, share) in &inference.candidates {
+                hover.push_str(&format!("\n  {}: {:.0}%", column_type.display_name(), share * 100.0));
+            }
+        }
+        ui.label(egui::RichText::new(format!("{:.0}%", inference.confidence * 100.0)).color(color))
+            .on_hover_text(hover);
+    }
+    
+    /// Infer the current file's column types from every row on a background thread;
+    /// the dialog stays usable and apply_scan_result() picks up the outcome
+    fn start_full_scan(&mut self) {
+        let Some(config) = self.files.get(self.current_file_index) else {
+            return;
+        };
+        let file_index = self.current_file_index;
+        let generation = self.scan_generation;
+        let path = config.path.clone();
+        let sheet = match config.sheet.as_ref().map(|sheet| sheet.selection(&config.path)).transpose() {
+            Ok(sheet) => sheet,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        let options = config.ingest_options();
+        let scan_state = self.scan_state.clone();
+        
+        if let Ok(mut state) = scan_state.lock() {
+            *state = ScanState::Running { file_index, rows: 0, fraction: 0.0 };
+        }
+        
+        std::thread::spawn(move || {
+            let progress_state = scan_state.clone();
+            let on_progress = |progress: &IngestProgress| {
+                if let Ok(mut state) = progress_state.lock() {
+                    *state = ScanState::Running { file_index, rows: progress.rows, fraction: progress.fraction() };
+                }
+            };
+            let result = match &sheet {
+                Some(sheet) => spreadsheet::infer_full_sheet(sheet, &options, on_progress),
+                None => csv_ingest::infer_full_file(&path, &options, on_progress),
+            };
+            let outcome = match result {
+                Ok(inferences) => ScanState::Done { file_index, generation, inferences },
+                Err(e) => ScanState::Failed(format!("Failed to scan {}: {}", path.display(), e)),
+            };
+            if let Ok(mut state) = scan_state.lock() {
+                *state = outcome;
+            }
+        });
+    }
+    
+    /// Apply a finished whole-file scan to its file's columns, unless the file was resampled meanwhile.
+    /// Columns whose type was picked by hand keep it.
+    fn apply_scan_result(&mut self, ctx: &Context) {
+        let Ok(mut state) = self.scan_state.lock() else {
+            return;
+        };
+        match std::mem::replace(&mut *state, ScanState::Idle) {
+            running @ ScanState::Running { .. } => {
+                *state = running;
+                ctx.request_repaint();
+            }
+            ScanState::Done { file_index, generation, inferences } => {
+                if generation != self.scan_generation {
+                    return;
+                }
+                if let Some(config) = self.files.get_mut(file_index) {
+                    for (col, inference) in config.columns.iter_mut().zip(inferences) {
+                        // The new inference is shown next to a type picked by hand, without replacing it
+                        let picked_by_hand = col.inference.as_ref().is_some_and(|previous| previous.column_type != col.data_type);
+                        if !picked_by_hand {
+                            col.data_type = inference.column_type.clone();
                         }
+                        col.inference = Some(inference);
                     }
-                    
-                    config.preview_data = Some(PreviewData { 
-                        rows: preview_rows,
-                        original_row_numbers: Vec::new(), // Not used anymore
-                    });
-                }
-                Err(e) => {
-                    self.error = Some(format!("Failed to load preview: {}", e));
+                    config.scanned_whole_file = true;
                 }
             }
+            ScanState::Failed(error) => self.error = Some(error),
+            ScanState::Idle => {}
         }
     }
     
-    fn start_database_creation(&mut self) -> Option<PathBuf> {
-        let db_path = self.database_path.clone()?;
-        let mut files = self.files.clone();
+    /// Read every file once to find values that won't parse as their column's type;
+    /// show() picks up the result and either starts the import or lists the bad values
+    fn start_value_check(&mut self) {
+        let files = self.files.clone();
+        let project_dir = self.database_path.clone();
+        let processing_state = self.processing_state.clone();
         
-        // For DataFusion, we need to create the database synchronously
-        // since it's in-memory and we need to return the actual database instance
-        match Database::open_writable(&db_path) {
-            Ok(mut db) => {
-                let total_files = files.len();
-                
-                // Create all tables first
-                // Skip pre-creating tables - let stream_insert_csv handle schema creation from CSV headers
+        if let Ok(mut state) = processing_state.lock() {
+            *state = ProcessingState::Processing("Checking values...".to_string(), 0.0);
+        }
+        
+        std::thread::spawn(move || {
+            let total_files = files.len();
+            let mut checks = Vec::new();
+            for (file_idx, config) in files.iter().enumerate() {
+                let file_name = config.file_name();
+                let progress_state = processing_state.clone();
+                let on_progress = |progress: &IngestProgress| {
+                    if let Ok(mut state) = progress_state.lock() {
+                        *state = ProcessingState::Processing(
+                            format!("Checking values in {} ({}/{}): {} rows", file_name, file_idx + 1, total_files, progress.rows),
+                            (file_idx as f32 + progress.fraction()) / total_files as f32
+                        );
+                    }
+                };
                 
-                // Import data for each file
-                for (file_idx, config) in files.iter_mut().enumerate() {
-                    // Use enhanced streaming import with custom header row
-                    match db.stream_insert_csv_with_header_row(&config.table_name, &config.path, config.delimiter, config.header_row.saturating_sub(1)) {
-                        Ok(inferred_delimiter) => {
-                            // Update the config with the inferred delimiter if it was auto-detected
-                            if config.delimiter == ',' {
-                                config.delimiter = inferred_delimiter;
-                            }
-                        }
+                // JSON has no per-value policies to check against; a value that doesn't fit stops the import
+                if config.json.is_some() {
+                    continue;
+                }
+                let options = config.ingest_options();
+                if let Some(union) = &config.union {
+                    match csv_union::check_union(&union.files, &union.options(&options), on_progress) {
+                        Ok(shards) => checks.extend(shards.into_iter().map(|shard| ValueCheck {
+                            file_index: file_idx,
+                            shard: Some(shard.path),
+                            report: shard.coercion,
+                        })),
                         Err(e) => {
-                            self.error = Some(format!("Failed to import {}: {}", config.file_name(), e));
-                            return None;
+                            if let Ok(mut state) = processing_state.lock() {
+                                *state = ProcessingState::Error(format!("Failed to read {}: {}", file_name, e));
+                            }
+                            return;
                         }
                     }
+                    continue;
                 }
-                
-                // Save all tables to persistence (Arrow IPC format) directly in the project folder
-                if let Err(e) = db.save_all_tables(&db_path) {
-                    self.error = Some(format!("Failed to save tables to persistence: {}", e));
-                    return None;
+                // Files appended to an existing table are checked against its types
+                let result = match (&config.sheet, project_dir.as_deref().and_then(|dir| config.append_target(dir))) {
+                    (Some(sheet), _) => sheet.selection(&config.path)
+                        .and_then(|selection| spreadsheet::check_coercion(&selection, &options, on_progress)),
+                    (None, Some(table_path)) => csv_ingest::check_append_coercion(&config.path, &table_path, &options, config.add_new_columns, on_progress),
+                    (None, None) => csv_ingest::check_coercion(&config.path, &options, on_progress),
+                };
+                match result {
+                    Ok(report) => checks.push(ValueCheck { file_index: file_idx, shard: None, report }),
+                    Err(e) => {
+                        if let Ok(mut state) = processing_state.lock() {
+                            *state = ProcessingState::Error(format!("Failed to read {}: {}", file_name, e));
+                        }
+                        return;
+                    }
                 }
+            }
+            
+            if let Ok(mut state) = processing_state.lock() {
+                *state = ProcessingState::Checked(checks);
+            }
+        });
+    }
+    
+    /// List the values that won't parse, per file and column, and let the user go ahead or go back
+    fn show_value_checks(&mut self, ctx: &Context) {
+        let Some(checks) = &self.value_checks else {
+            return;
+        };
+        
+        let mut go_back = false;
+        let mut import = false;
+        let blocked = checks.iter()
+            .any(|check| !check.report.is_empty() && self.files[check.file_index].coercion_policy == CoercionPolicy::Fail);
+        
+        egui::Window::new("Value Check")
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.label("Some values can't be read as the type chosen for their column:");
+                ui.add_space(8.0);
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for check in checks.iter().filter(|check| !check.report.is_empty()) {
+                            let config = &self.files[check.file_index];
+                            ui.group(|ui| {
+                                ui.set_width(ui.available_width());
+                                let file_name = match &check.shard {
+                                    Some(shard) => shard.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+                                    None => config.file_name(),
+                                };
+                                ui.label(egui::RichText::new(format!(
+                                    "{} → {}: {} bad value{}",
+                                    file_name, config.table_name, check.report.total(),
+                                    if check.report.total() == 1 { "" } else { "s" }
+                                )).strong());
+                                for column in &check.report.columns {
+                                    ui.label(format!("• {}", column.describe()));
+                                }
+                                let outcome = match config.coercion_policy {
+                                    CoercionPolicy::SetNull => "These values will be imported as null.",
+                                    CoercionPolicy::Fail => "This file is set to fail on bad values; change the type or the setting to import it.",
+                                    CoercionPolicy::FallBackToText if config.append => "The table's columns keep their types, so these values will be appended as null.",
+                                    CoercionPolicy::FallBackToText => "These columns will be imported as Text.",
+                                };
+                                ui.label(egui::RichText::new(outcome).color(egui::Color32::from_gray(160)));
+                            });
+                        }
+                    });
                 
-                // Return the path for the app to load
-                Some(db_path)
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Back").clicked() {
+                        go_back = true;
+                    }
+                    if ui.add_enabled(!blocked, egui::Button::new("Import")).clicked() {
+                        import = true;
+                    }
+                });
+            });
+        
+        if import {
+            if let Some(checks) = self.value_checks.take() {
+                self.apply_value_checks(checks);
             }
-            Err(e) => {
-                self.error = Some(format!("Failed to create database: {}", e));
-                None
+            self.start_database_creation();
+        } else if go_back {
+            self.value_checks = None;
+        }
+    }
+    
+    /// The check already found the bad columns, so switch them to Text and import with SetNull
+    /// instead of reading each file again to look for them. A folder import finds them across all its files itself.
+    fn apply_value_checks(&mut self, checks: Vec<ValueCheck>) {
+        for check in checks {
+            let config = &mut self.files[check.file_index];
+            if config.coercion_policy == CoercionPolicy::FallBackToText && config.union.is_none() {
+                for col_idx in check.report.column_indexes() {
+                    if let Some(column) = config.columns.get_mut(col_idx) {
+                        column.data_type = ColumnType::Text;
+                    }
+                }
+                config.coercion_policy = CoercionPolicy::SetNull;
             }
         }
     }
+
+    fn start_database_creation(&mut self) {
+        let Some(db_path) = self.database_path.clone() else {
+            return;
+        };
+        let files = self.files.clone();
+        let storage_format = self.storage_format;
+        let processing_state = self.processing_state.clone();
+        
+        if let Ok(mut state) = processing_state.lock() {
+            *state = ProcessingState::Processing("Starting import...".to_string(), 0.0);
+        }
+        
+        // Import on a background thread; show() picks up the progress and returns the
+        // project path once the state reaches Complete
+        std::thread::spawn(move || {
+            Self::create_database_in_thread(db_path, files, storage_format, processing_state);
+        });
+    }
     
     fn create_database_in_thread(
         db_path: PathBuf,
         mut files: Vec<FileConfig>,
+        storage_format: TableFormat,
         processing_state: Arc<Mutex<ProcessingState>>
     ) {
         // Update state to processing
@@ -961,84 +2513,125 @@ impl FileConfigDialog {
         match Database::open_writable(&db_path) {
             Ok(mut db) => {
                 let total_files = files.len();
+                let mut reports = Vec::new();
                 
-                // DataFusion is in-memory, so no need for pragmas or transactions
-                // Just proceed with table creation and data import
-                
-                // Skip pre-creating tables - let stream_insert_csv handle schema creation from CSV headers
-                
-                // Now import data for each file
+                // Stream each CSV straight into its table file in the project folder
                 for (file_idx, config) in files.iter_mut().enumerate() {
-                    // Update initial progress for this file
-                    if let Ok(mut state) = processing_state.lock() {
-                        *state = ProcessingState::Processing(
-                            format!("Importing {} ({}/{})", config.file_name(), file_idx + 1, total_files),
-                            0.2 + (0.7 * file_idx as f32 / total_files as f32)
-                        );
+                    let file_name = config.file_name();
+                    let options = config.ingest_options();
+                    // A sheet's progress is counted in rows rather than bytes
+                    let counts_bytes = config.sheet.is_none();
+                    
+                    let progress_state = processing_state.clone();
+                    let on_progress = |progress: &IngestProgress| {
+                        if let Ok(mut state) = progress_state.lock() {
+                            let overall_progress = (file_idx as f32 + progress.fraction()) / total_files as f32;
+                            let message = if counts_bytes {
+                                format!(
+                                    "Importing {} ({}/{}): {} rows, {:.1} of {:.1} MB",
+                                    file_name, file_idx + 1, total_files, progress.rows,
+                                    progress.bytes_read as f64 / 1_048_576.0,
+                                    progress.total_bytes as f64 / 1_048_576.0
+                                )
+                            } else {
+                                format!("Importing {} ({}/{}): {} rows", file_name, file_idx + 1, total_files, progress.rows)
+                            };
+                            *state = ProcessingState::Processing(message, overall_progress);
+                        }
+                    };
+                    
+                    if let Some(json) = &config.json {
+                        match db.import_json_to_file(&config.table_name, &config.path, &db_path, storage_format, &json.options(&config.columns), on_progress) {
+                            Ok(summary) => reports.push(ImportReport {
+                                file_name: file_name.clone(),
+                                table_name: config.table_name.clone(),
+                                rows: summary.rows,
+                                rejects: RejectCounts::default(),
+                                rejects_paths: Vec::new(),
+                                appended: None,
+                                missing_columns: 0,
+                            }),
+                            Err(e) => {
+                                if let Ok(mut state) = processing_state.lock() {
+                                    *state = ProcessingState::Error(format!("Failed to import {}: {}", file_name, e));
+                                }
+                                return;
+                            }
+                        }
+                        continue;
+                    }
+                    
+                    if let Some(union) = &config.union {
+                        match db.import_csv_union_to_file(&config.table_name, &union.files, &db_path, storage_format, &union.options(&options), on_progress) {
+                            Ok(summary) => reports.push(ImportReport {
+                                file_name: file_name.clone(),
+                                table_name: config.table_name.clone(),
+                                rows: summary.rows,
+                                rejects: summary.rejects,
+                                rejects_paths: summary.files.iter().filter_map(|file| file.rejects_path.clone()).collect(),
+                                appended: None,
+                                missing_columns: summary.files.iter().filter(|file| !file.missing.is_empty()).count(),
+                            }),
+                            Err(e) => {
+                                if let Ok(mut state) = processing_state.lock() {
+                                    *state = ProcessingState::Error(format!("Failed to import {}: {}", file_name, e));
+                                }
+                                return;
+                            }
+                        }
+                        continue;
                     }
                     
-                    // Use enhanced streaming import with custom header row
-                    match db.stream_insert_csv_with_header_row(&config.table_name, &config.path, config.delimiter, config.header_row.saturating_sub(1)) {
-                        Ok(inferred_delimiter) => {
+                    // Appending needs the table on disk, which may be one an earlier file in this import wrote
+                    let result = if let Some(sheet) = &config.sheet {
+                        sheet.selection(&config.path)
+                            .and_then(|selection| db.import_sheet_to_file(&config.table_name, &selection, &db_path, storage_format, &options, on_progress))
+                            .map(|summary| (summary, None))
+                    } else if config.append_target(&db_path).is_some() {
+                        db.append_csv_to_table(&config.table_name, &config.path, &db_path, &options, config.add_new_columns, on_progress)
+                            .map(|appended| (appended.ingest, Some((appended.existing_rows, appended.columns))))
+                    } else {
+                        db.import_csv_to_file(&config.table_name, &config.path, &db_path, storage_format, &options, on_progress)
+                            .map(|summary| (summary, None))
+                    };
+                    match result {
+                        Ok((summary, appended)) => {
                             // Update the config with the inferred delimiter if it was auto-detected
                             if config.delimiter == ',' {
-                                config.delimiter = inferred_delimiter;
+                                config.delimiter = summary.delimiter;
                             }
+                            reports.push(ImportReport {
+                                file_name: file_name.clone(),
+                                table_name: config.table_name.clone(),
+                                rows: summary.rows,
+                                rejects: summary.rejects,
+                                rejects_paths: summary.rejects_path.into_iter().collect(),
+                                appended,
+                                missing_columns: 0,
+                            });
                         }
                         Err(e) => {
                             if let Ok(mut state) = processing_state.lock() {
-                                *state = ProcessingState::Error(format!("Failed to import {}: {}", config.file_name(), e));
+                                *state = ProcessingState::Error(format!("Failed to import {}: {}", file_name, e));
                             }
-                            let _ = db.rollback_transaction();
                             return;
                         }
                     }
-                    
-                    // Final progress update for this file
-                    if let Ok(mut state) = processing_state.lock() {
-                        let overall_progress = 0.2 + (0.7 * (file_idx + 1) as f32 / total_files as f32);
-                        *state = ProcessingState::Processing(
-                            format!("Completed {}", config.file_name()),
-                            overall_progress
-                        );
-                    }
-                }
-                
-                // eprintln!("All files imported, creating indexes...");
-                
-                // Note: DataFusion is in-memory, so no indexes or transactions needed
-                if let Ok(mut state) = processing_state.lock() {
-                    *state = ProcessingState::Processing("Saving tables to persistence...".to_string(), 0.9);
                 }
                 
-                // Save all tables to persistence (Arrow IPC format) directly in the project folder
-                if let Err(e) = db.save_all_tables(&db_path) {
-                    eprintln!("[FileConfig] Warning: Failed to save tables to persistence: {}", e);
-                    // Don't fail the entire operation, just log the warning
-                } else {
-                    eprintln!("[FileConfig] Successfully saved {} tables to persistence", 
-                             std::fs::read_dir(&db_path).map(|entries| entries.count()).unwrap_or(0));
-                }
-                
-                // eprintln!("DataFusion context finalized successfully");
-                
                 // Explicitly drop the database connection to ensure it's closed
                 drop(db);
                 
                 if let Ok(mut state) = processing_state.lock() {
-                    *state = ProcessingState::Complete;
-                    // eprintln!("State set to Complete");
+                    *state = ProcessingState::Complete(reports);
                 }
             }
             Err(e) => {
-                // eprintln!("Failed to create database: {}", e);
                 if let Ok(mut state) = processing_state.lock() {
                     *state = ProcessingState::Error(format!("Failed to create database: {}", e));
                 }
             }
         }
-        
-        // eprintln!("create_database_in_thread finished");
     }
 
     fn validate_constraints(&self) -> Option<String> {
@@ -1068,9 +2661,9 @@ impl FileConfigDialog {
         // Check for duplicate table names
         let mut table_names = std::collections::HashSet::new();
         for config in &self.files {
-            if !table_names.insert(&config.table_name) {
+            if !table_names.insert(&config.table_name) && !config.append {
                 return Some(format!(
-                    "Duplicate table name '{}'. Each table must have a unique name.",
+                    "Duplicate table name '{}'. Each table must have a unique name, unless later files append to it.",
                     config.table_name
                 ));
             }
@@ -0,0 +1,15 @@
+use egui;
+use crate::core::TableFormat;
+
+/// Combo box for choosing the file format of a saved table
+pub fn table_format_picker(ui: &mut egui::Ui, id_salt: &str, format: &mut TableFormat) -> egui::Response {
+    ui.label("Format:");
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(format.display_name())
+        .show_ui(ui, |ui| {
+            for option in TableFormat::ALL {
+                ui.selectable_value(format, option, option.display_name());
+            }
+        })
+        .response
+}
@@ -1,5 +1,6 @@
 mod sidebar;
 mod query_window;
+mod query_library;
 mod csv_import;
 mod duplicate_detection;
 mod data_transformation;
@@ -11,9 +12,11 @@ mod home_screen;
 mod multi_csv_import;
 mod export_dialog;
 pub mod time_bin_dialog;
+mod format_picker;
 
 pub use sidebar::{Sidebar, SidebarAction};
 pub use query_window::QueryWindow;
+pub use query_library::QueryLibraryWindow;
 pub use csv_import::CsvImportDialog;
 pub use duplicate_detection::{DuplicateDetectionDialog, DuplicateResultsViewer};
 pub use data_transformation::{TransformationDialog, TransformationRequest, TransformationManager};
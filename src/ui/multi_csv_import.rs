@@ -2,6 +2,7 @@ use egui::{Context, Id, ScrollArea};
 use rfd::FileDialog;
 use std::path::PathBuf;
 use crate::core::{CsvReader, Database};
+use crate::core::csv_source;
 use crate::infer::{ColumnType, TypeInferrer};
 use indexmap::IndexMap;
 
@@ -88,7 +89,7 @@ impl MultiCsvImportDialog {
                 
                 if ui.button("➕ Add CSV Files...").clicked() {
                     if let Some(paths) = FileDialog::new()
-                        .add_filter("CSV", &["csv"])
+                        .add_filter("CSV", &csv_source::PICKER_EXTENSIONS)
                         .pick_files()
                     {
                         for path in paths {
@@ -217,16 +218,26 @@ impl MultiCsvImportDialog {
     }
     
     fn load_csv_file(&mut self, path: PathBuf) {
+        // Each CSV inside a zip archive is offered as a separate file
+        if csv_source::is_archive(&path) {
+            match csv_source::list_archive_members(&path) {
+                Ok(members) => {
+                    for member in members {
+                        self.load_csv_file(member);
+                    }
+                }
+                Err(e) => self.error = Some(format!("Failed to open archive: {}", e)),
+            }
+            return;
+        }
+        
         // Check if already loaded
         if self.csv_files.iter().any(|f| f.path == path) {
             return;
         }
         
         // Suggest table name from filename
-        let table_name = path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("table")
-            .to_string();
+        let table_name = csv_source::table_name_for(&path);
         
         match CsvReader::from_path(&path) {
             Ok(mut reader) => {
@@ -269,7 +280,14 @@ impl MultiCsvImportDialog {
         if let Ok(entries) = std::fs::read_dir(&folder) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("csv") {
+                let is_input = path.file_name()
+                    .and_then(|s| s.to_str())
+                    .map(|name| {
+                        let name = name.to_lowercase();
+                        [".csv", ".csv.gz", ".csv.zst", ".zip"].iter().any(|ext| name.ends_with(ext))
+                    })
+                    .unwrap_or(false);
+                if path.is_file() && is_input {
                     self.load_csv_file(path);
                 }
             }
@@ -0,0 +1,185 @@
+use std::time::SystemTime;
+use egui;
+use crate::core::{HistoryEntry, QueryLibrary, QueryStatus, SavedQuery};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LibraryTab {
+    History,
+    Saved,
+}
+
+/// Searchable list of a project's executed and saved queries, any of which can be opened in a new window
+pub struct QueryLibraryWindow {
+    pub visible: bool,
+    tab: LibraryTab,
+    search: String,
+    history: Vec<HistoryEntry>,
+    saved: Vec<SavedQuery>,
+    // When the lists were read, to reload them once query windows have written to the folder
+    loaded_at: Option<SystemTime>,
+    error: Option<String>,
+}
+
+impl Default for QueryLibraryWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QueryLibraryWindow {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            tab: LibraryTab::History,
+            search: String::new(),
+            history: Vec::new(),
+            saved: Vec::new(),
+            loaded_at: None,
+            error: None,
+        }
+    }
+
+    /// Show the window; returns the title and text of a query to open in a new window
+    pub fn show(&mut self, ctx: &egui::Context, library: &QueryLibrary) -> Option<(String, String)> {
+        if !self.visible {
+            return None;
+        }
+        self.reload_if_changed(library);
+
+        let mut open = true;
+        let mut to_open = None;
+        let mut to_delete = None;
+        let mut clear_history = false;
+
+        egui::Window::new("Query Library")
+            .id(egui::Id::new("query_library"))
+            .default_size([560.0, 420.0])
+            .resizable(true)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.tab, LibraryTab::History, format!("History ({})", self.history.len()));
+                    ui.selectable_value(&mut self.tab, LibraryTab::Saved, format!("Saved ({})", self.saved.len()));
+                    ui.separator();
+                    ui.add(egui::TextEdit::singleline(&mut self.search)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("Search queries"));
+                });
+                ui.label(egui::RichText::new(format!("Kept in {}", library.dir().display()))
+                    .size(11.0)
+                    .color(egui::Color32::from_gray(120)));
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("✗ {}", error));
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    match self.tab {
+                        LibraryTab::History => {
+                            // Newest first
+                            for entry in self.history.iter().rev().filter(|entry| entry.matches(&self.search)) {
+                                if Self::show_history_entry(ui, entry) {
+                                    to_open = Some((format!("History {}", entry.executed_at_local()), entry.query.clone()));
+                                }
+                            }
+                            if !self.history.is_empty() {
+                                ui.add_space(6.0);
+                                clear_history = ui.button("Clear History").clicked();
+                            }
+                        }
+                        LibraryTab::Saved => {
+                            if self.saved.is_empty() {
+                                ui.label("No saved queries yet. Use Save Query… in a query window to add one.");
+                            }
+                            for saved in self.saved.iter().filter(|saved| saved.matches(&self.search)) {
+                                ui.group(|ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.strong(&saved.name);
+                                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                            if ui.button("Delete").clicked() {
+                                                to_delete = Some(saved.name.clone());
+                                            }
+                                            if ui.button("Open").clicked() {
+                                                to_open = Some((saved.name.clone(), saved.query.clone()));
+                                            }
+                                        });
+                                    });
+                                    if !saved.description.is_empty() {
+                                        ui.label(&saved.description);
+                                    }
+                                    ui.label(egui::RichText::new(first_line(&saved.query)).monospace().color(egui::Color32::from_gray(160)))
+                                        .on_hover_text(&saved.query);
+                                });
+                            }
+                        }
+                    }
+                });
+            });
+
+        if let Some(name) = to_delete {
+            if let Err(e) = library.delete_query(&name) {
+                self.error = Some(format!("Failed to delete saved query: {}", e));
+            }
+            self.loaded_at = None;
+        }
+        if clear_history {
+            if let Err(e) = library.clear_history() {
+                self.error = Some(format!("Failed to clear query history: {}", e));
+            }
+            self.loaded_at = None;
+        }
+        self.visible = open;
+        to_open
+    }
+
+    /// One history row; returns whether Open was clicked
+    fn show_history_entry(ui: &mut egui::Ui, entry: &HistoryEntry) -> bool {
+        let mut open = false;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(entry.executed_at_local()).color(egui::Color32::from_gray(140)));
+            let color = match entry.status {
+                QueryStatus::Succeeded => egui::Color32::from_rgb(120, 200, 120),
+                QueryStatus::Failed(_) => egui::Color32::from_rgb(255, 100, 100),
+                QueryStatus::Cancelled => egui::Color32::from_rgb(220, 180, 90),
+            };
+            let status = ui.colored_label(color, entry.status.display_name());
+            if let QueryStatus::Failed(error) = &entry.status {
+                status.on_hover_text(error);
+            }
+            ui.label(format!("{:.1} s", entry.duration_ms as f64 / 1000.0));
+            if let Some(rows) = entry.rows {
+                ui.label(format!("{} rows", rows));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                open = ui.button("Open").clicked();
+                ui.add(egui::Label::new(egui::RichText::new(first_line(&entry.query)).monospace()).truncate())
+                    .on_hover_text(&entry.query);
+            });
+        });
+        open
+    }
+
+    /// Read the lists again if the history or the saved queries changed since they were read
+    fn reload_if_changed(&mut self, library: &QueryLibrary) {
+        let unchanged = self.loaded_at.is_some_and(|loaded_at| library.modified().is_none_or(|modified| modified <= loaded_at));
+        if unchanged {
+            return;
+        }
+        self.loaded_at = Some(SystemTime::now());
+        self.error = None;
+        match library.history() {
+            Ok(history) => self.history = history,
+            Err(e) => self.error = Some(format!("Failed to read query history: {}", e)),
+        }
+        match library.saved_queries() {
+            Ok(saved) => self.saved = saved,
+            Err(e) => self.error = Some(format!("Failed to read saved queries: {}", e)),
+        }
+    }
+}
+
+/// The first non-empty line of a query, for one-line lists
+fn first_line(query: &str) -> &str {
+    query.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("")
+}
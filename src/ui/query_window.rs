@@ -1,21 +1,38 @@
 use std::sync::Arc;
-use std::io::Write;
+use std::time::Duration;
 use egui;
-use crate::core::{Database, QueryResult};
+use crate::core::{Database, HistoryEntry, QueryExecutor, QueryLibrary, QueryResult, QueryStatus, RefusedStatement, RunningQuery, SavedQuery};
 
 pub struct QueryWindow {
     id: egui::Id,
     title: String,
     query: String,
+    // Every row of the last query, which the pages are sliced from
     result: Option<QueryResult>,
+    // The query running in the background, and the run to record in the history
+    running: Option<(RunningQuery, Run)>,
+    // Where executed queries are recorded and saved ones kept; `None` outside a project
+    library: Option<QueryLibrary>,
+    // Name and description being typed for saving the query
+    save_form: Option<(String, String)>,
+    // A name that is already saved, shown until replacing it is confirmed or the name changes
+    confirm_replace: Option<String>,
+    notice: Option<String>,
     error: Option<String>,
+    // The statement read-only mode refused, highlighted in the editor until the query changes
+    refused: Option<RefusedStatement>,
     page: usize,
     page_size: usize,
     export_format: ExportFormat,
-    show_export_menu: bool,
     export_mode: ExportMode,
 }
 
+/// The query text a run started with, which may be edited while it runs
+struct Run {
+    query: String,
+    executed_at: i64,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum ExportFormat {
     Csv,
@@ -35,17 +52,29 @@ impl QueryWindow {
             title,
             query: initial_query,
             result: None,
+            running: None,
+            library: None,
+            save_form: None,
+            confirm_replace: None,
+            notice: None,
             error: None,
+            refused: None,
             page: 0,
             page_size: 25,
             export_format: ExportFormat::Csv,
-            show_export_menu: false,
             export_mode: ExportMode::Page,
         }
     }
     
+    /// Record executed queries in a project's history and allow saving the query there
+    pub fn with_library(mut self, library: Option<QueryLibrary>) -> Self {
+        self.library = library;
+        self
+    }
+    
     pub fn show(&mut self, ctx: &egui::Context, db: Arc<Database>) -> bool {
         let mut open = true;
+        self.poll_running(ctx);
         
         egui::Window::new(&self.title)
             .id(self.id)
@@ -70,12 +99,22 @@ impl QueryWindow {
                     let _desired_height = text_height * line_count as f32 + 8.0;
                     let _max_height = 150.0; // Cap the maximum height
                     
+                    let refused = self.refused.as_ref().map(|refused| refused.range.clone());
+                    let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                        let mut job = highlight_refused(ui, text, refused.clone());
+                        job.wrap.max_width = wrap_width;
+                        ui.fonts(|fonts| fonts.layout_job(job))
+                    };
                     let response = egui::TextEdit::multiline(&mut self.query)
                         .font(egui::TextStyle::Monospace)
                         .desired_width(f32::INFINITY)
                         .min_size(egui::vec2(0.0, text_height + 8.0))
                         .desired_rows(line_count.min(8))
+                        .layouter(&mut layouter)
                         .show(ui);
+                    if response.response.changed() {
+                        self.refused = None;
+                    }
                     
                     // Execute on Ctrl+Enter
                     if response.response.has_focus() 
@@ -94,8 +133,69 @@ impl QueryWindow {
                         ui.ctx().request_repaint();
                     }
                     ui.label("or press Ctrl+Enter");
+                    
+                    let can_save = self.library.is_some() && !self.query.trim().is_empty();
+                    if ui.add_enabled(can_save, egui::Button::new("Save Query…")).clicked() {
+                        self.save_form = Some((self.title.clone(), String::new()));
+                        self.confirm_replace = None;
+                        self.notice = None;
+                    }
                 });
                 
+                // Name and description for the project's saved queries
+                let mut save = false;
+                let mut replace = false;
+                let mut close_form = false;
+                if let Some((name, description)) = &mut self.save_form {
+                    let confirming = self.confirm_replace.as_deref() == Some(name.trim());
+                    ui.horizontal(|ui| {
+                        ui.label("Name:");
+                        ui.add(egui::TextEdit::singleline(name).desired_width(160.0));
+                        ui.label("Description:");
+                        ui.add(egui::TextEdit::singleline(description)
+                            .desired_width(f32::INFINITY)
+                            .hint_text("What the query is for"));
+                    });
+                    ui.horizontal(|ui| {
+                        if confirming {
+                            ui.colored_label(egui::Color32::from_rgb(220, 180, 90), format!("A query named '{}' is already saved.", name.trim()));
+                            replace = ui.button("Replace").clicked();
+                        } else {
+                            save = ui.add_enabled(!name.trim().is_empty(), egui::Button::new("Save")).clicked();
+                        }
+                        close_form = ui.button("Cancel").clicked();
+                    });
+                }
+                if save || replace {
+                    self.save_query(replace);
+                } else if close_form {
+                    self.save_form = None;
+                    self.confirm_replace = None;
+                }
+                if let Some(notice) = &self.notice {
+                    ui.label(egui::RichText::new(notice).color(egui::Color32::from_gray(160)));
+                }
+                
+                // Progress of the query running in the background
+                let mut cancel = false;
+                if let Some((running, _)) = &self.running {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        let mut status = format!("Running for {:.1} s", running.elapsed().as_secs_f32());
+                        if let Some(scanned) = running.rows_scanned() {
+                            status.push_str(&format!(", {} rows scanned", scanned));
+                        }
+                        if running.rows_returned() > 0 {
+                            status.push_str(&format!(", {} rows returned", running.rows_returned()));
+                        }
+                        ui.label(status);
+                        cancel = ui.button("Cancel").clicked();
+                    });
+                }
+                if cancel {
+                    self.cancel_query();
+                }
+                
                 ui.separator();
                 
                 // Error display
@@ -104,8 +204,14 @@ impl QueryWindow {
                     ui.separator();
                 }
                 
+                // The finished result, or the rows received so far while the query runs
+                let partial = self.running.as_ref().and_then(|(running, _)| running.partial_result());
+                let shown = partial.as_ref().or(self.result.as_ref());
+                let shown_rows = shown.map_or(0, |results| results.num_rows());
+                
                 // Results section with darker background
-                if let Some(results) = &self.result {
+                if let Some(results) = shown {
+                    let page = results.page(self.page, self.page_size);
                     let available_height = ui.available_height() - 60.0; // Reserve space for controls
                     
                     ui.group(|ui| {
@@ -114,12 +220,12 @@ impl QueryWindow {
                         
                                                  // Results header
                          ui.horizontal(|ui| {
-                             let total_rows = results.total_rows.unwrap_or(results.rows.len());
-                             let actual_rows_returned = results.rows.len();
+                             let actual_rows_returned = page.num_rows();
                              
                              ui.label(format!(
-                                 "Results: {} rows (showing {}-{} of page {})",
-                                 total_rows,
+                                 "Results: {} rows{} (showing {}-{} of page {})",
+                                 shown_rows,
+                                 if partial.is_some() { " so far" } else { "" },
                                  self.page * self.page_size + 1,
                                  self.page * self.page_size + actual_rows_returned,
                                  self.page + 1
@@ -151,7 +257,7 @@ impl QueryWindow {
                              .auto_shrink([false, false])
                              .max_height(table_height)
                              .show(ui, |ui| {
-                                 self.render_results_table(ui, results);
+                                 self.render_results_table(ui, &page);
                              });
                     });
                 }
@@ -166,18 +272,10 @@ impl QueryWindow {
                                 .on_hover_text("Go to previous page")
                                 .clicked() {
                                 self.page = self.page.saturating_sub(1);
-                                self.execute_query(db.clone());
                             }
                             
-                            let total_pages = if let Some(result) = &self.result {
-                                if let Some(total_rows) = result.total_rows {
-                                    ((total_rows as f32) / (self.page_size as f32)).ceil() as usize
-                                } else {
-                                    1
-                                }
-                            } else {
-                                1
-                            };
+                            // Pages are sliced from the rows already received, so paging never re-runs the query
+                            let total_pages = shown_rows.div_ceil(self.page_size).max(1);
                             
                             ui.label(format!("Page {} of {}", self.page + 1, total_pages));
                             
@@ -189,7 +287,6 @@ impl QueryWindow {
                             
                             if next_button.clicked() {
                                 self.page += 1;
-                                self.execute_query(db.clone());
                             }
                             
                             ui.separator();
@@ -203,27 +300,34 @@ impl QueryWindow {
                                     if new_size > 0 && new_size <= 10000 {
                                         self.page_size = new_size;
                                         self.page = 0;  // Reset to first page when changing page size
-                                        // Re-execute query with new page size
-                                        self.execute_query(db.clone());
                                     }
                                 }
                              }
                         });
                         
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("Export All").clicked() {
-                                self.export_all_csv(db.clone());
-                            }
-                            
-                            if ui.button("Export Page").clicked() {
-                                self.export_page_csv();
-                            }
+                            ui.add_enabled_ui(self.result.is_some(), |ui| {
+                                ui.menu_button("Export", |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.radio_value(&mut self.export_format, ExportFormat::Csv, "CSV");
+                                        ui.radio_value(&mut self.export_format, ExportFormat::Json, "JSON");
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.radio_value(&mut self.export_mode, ExportMode::Page, "This page");
+                                        ui.radio_value(&mut self.export_mode, ExportMode::All, "All rows");
+                                    });
+                                    if ui.button("Save...").clicked() {
+                                        ui.close_menu();
+                                        self.export();
+                                    }
+                                });
+                            });
                         });
                     });
                 });
         
         // Auto-execute initial query when window opens
-        if self.page == 0 && self.result.is_none() && !self.query.is_empty() {
+        if self.page == 0 && self.result.is_none() && self.running.is_none() && self.error.is_none() && !self.query.is_empty() {
             self.execute_query(db);
         }
         
@@ -233,7 +337,7 @@ impl QueryWindow {
     fn render_results_table(&self, ui: &mut egui::Ui, result: &QueryResult) {
         let text_height = egui::TextStyle::Body.resolve(ui.style()).size;
         let available_width = ui.available_width();
-        let num_columns = result.columns.len();
+        let num_columns = result.num_columns();
         let column_width = if num_columns > 0 {
             (available_width / num_columns as f32).max(100.0)
         } else {
@@ -245,149 +349,223 @@ impl QueryWindow {
                 .striped(true)
                 .resizable(true)
                 .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                .columns(egui_extras::Column::initial(column_width).resizable(true), result.columns.len())
+                .columns(egui_extras::Column::initial(column_width).resizable(true), num_columns)
                 .header(20.0, |mut header| {
-                    for column in &result.columns {
+                    for field in result.schema().fields() {
                         header.col(|ui| {
-                            ui.strong(column);
+                            ui.strong(field.name())
+                                .on_hover_text(field.data_type().to_string());
                         });
                     }
                 })
-                .body(|mut body| {
-                    for row in &result.rows {
-                        body.row(text_height + 8.0, |mut row_ui| {
-                            for value in row {
-                                row_ui.col(|ui| {
-                                    ui.label(value);
-                                });
-                            }
-                        });
-                    }
+                .body(|body| {
+                    // Only the rows scrolled into view are formatted
+                    body.rows(text_height + 8.0, result.num_rows(), |mut row_ui| {
+                        let row = row_ui.index();
+                        for column in 0..num_columns {
+                            row_ui.col(|ui| {
+                                ui.label(result.cell(row, column));
+                            });
+                        }
+                    });
                 });
         });
     }
     
+    /// Start the query in the background; its rows can be paged through as they arrive.
+    /// A query still running is replaced, which cancels it.
     fn execute_query(&mut self, db: Arc<Database>) {
         self.error = None;
+        self.refused = None;
+        self.result = None;
         
-        match crate::core::QueryExecutor::execute_with_pagination(&db, &self.query, self.page, self.page_size) {
+        let run = Run { query: self.query.clone(), executed_at: chrono::Utc::now().timestamp() };
+        match QueryExecutor::spawn(&db, &self.query) {
+            Ok(running) => {
+                self.running = Some((running, run));
+            }
+            Err(e) => {
+                self.running = None;
+                self.error = Some(e.to_string());
+                self.result = None;
+                self.find_refused(&db);
+                self.record(run, Duration::ZERO, None, QueryStatus::Failed(e.to_string()));
+            }
+        }
+    }
+    
+    /// Take the outcome of the background query once it has finished
+    fn poll_running(&mut self, ctx: &egui::Context) {
+        let Some(outcome) = self.running.as_ref().and_then(|(running, _)| running.try_result()) else {
+            if self.running.is_some() {
+                // Keep the elapsed time, row counts and partial pages moving
+                ctx.request_repaint_after(Duration::from_millis(100));
+            }
+            return;
+        };
+        let Some((running, run)) = self.running.take() else {
+            return;
+        };
+        match outcome {
             Ok(result) => {
+                self.record(run, running.elapsed(), Some(result.num_rows()), QueryStatus::Succeeded);
                 self.result = Some(result);
             }
             Err(e) => {
+                self.record(run, running.elapsed(), None, QueryStatus::Failed(e.to_string()));
                 self.error = Some(e.to_string());
                 self.result = None;
             }
         }
     }
     
-    fn export_page_csv(&self) {
-        if let Some(result) = &self.result {
+    fn cancel_query(&mut self) {
+        if let Some((running, run)) = self.running.take() {
+            running.cancel();
+            self.error = Some(format!("Query cancelled after {:.1} s", running.elapsed().as_secs_f32()));
+            self.record(run, running.elapsed(), None, QueryStatus::Cancelled);
+        }
+    }
+    
+    /// Add a finished run to the project's query history
+    fn record(&self, run: Run, duration: Duration, rows: Option<usize>, status: QueryStatus) {
+        let Some(library) = &self.library else {
+            return;
+        };
+        let entry = HistoryEntry {
+            query: run.query,
+            executed_at: run.executed_at,
+            duration_ms: duration.as_millis() as u64,
+            rows,
+            status,
+        };
+        if let Err(e) = library.record(&entry) {
+            eprintln!("Warning: Failed to record query history: {}", e);
+        }
+    }
+    
+    /// Save the query from the form; a query already saved under the name is only replaced once confirmed
+    fn save_query(&mut self, replace: bool) {
+        let (Some(library), Some((name, description))) = (&self.library, &self.save_form) else {
+            return;
+        };
+        let saved = SavedQuery {
+            name: name.trim().to_string(),
+            description: description.trim().to_string(),
+            query: self.query.clone(),
+        };
+        if !replace && library.has_saved_query(&saved.name) {
+            self.confirm_replace = Some(saved.name);
+            return;
+        }
+        match library.save_query(&saved) {
+            Ok(_) => {
+                self.notice = Some(format!("Saved as '{}'", saved.name));
+                self.save_form = None;
+                self.confirm_replace = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to save query: {}", e)),
+        }
+    }
+    
+    /// The rows on the current page of the finished result
+    fn current_page(&self) -> Option<QueryResult> {
+        self.result.as_ref().map(|result| result.page(self.page, self.page_size))
+    }
+    
+    /// Save the current page or the whole result in the format picked in the export menu
+    fn export(&mut self) {
+        match (self.export_format, self.export_mode) {
+            (ExportFormat::Csv, ExportMode::Page) => self.export_page_csv(),
+            (ExportFormat::Json, ExportMode::Page) => self.export_page_json(),
+            (ExportFormat::Csv, ExportMode::All) => self.export_all_csv(),
+            (ExportFormat::Json, ExportMode::All) => self.export_all_json(),
+        }
+    }
+    
+    fn export_page_csv(&mut self) {
+        if let Some(result) = self.current_page() {
             if let Some(path) = rfd::FileDialog::new()
                 .add_filter("csv", &["csv"])
                 .set_file_name(&format!("{}_page_{}.csv", self.title, self.page + 1))
                 .save_file()
             {
-                if let Ok(mut writer) = crate::core::CsvWriter::from_path(&path) {
-                    // Write headers
-                    let _ = writer.write_headers(&result.columns);
-                    
-                    // Write rows from current page
-                    for row in &result.rows {
-                        let _ = writer.write_record(row);
-                    }
-                    
-                    let _ = writer.flush();
+                if let Err(e) = result.write_csv(&path) {
+                    self.error = Some(e.to_string());
                 }
             }
         }
     }
     
-    fn export_page_json(&self) {
-        if let Some(result) = &self.result {
+    fn export_page_json(&mut self) {
+        if let Some(result) = self.current_page() {
             if let Some(path) = rfd::FileDialog::new()
                 .add_filter("json", &["json"])
                 .set_file_name(&format!("{}_page_{}.json", self.title, self.page + 1))
                 .save_file()
             {
-                let json_data: Vec<serde_json::Map<String, serde_json::Value>> = result.rows
-                    .iter()
-                    .map(|row| {
-                        let mut map = serde_json::Map::new();
-                        for (i, value) in row.iter().enumerate() {
-                            if let Some(column) = result.columns.get(i) {
-                                map.insert(column.clone(), serde_json::Value::String(value.clone()));
-                            }
-                        }
-                        map
-                    })
-                    .collect();
-                
-                if let Ok(json_string) = serde_json::to_string_pretty(&json_data) {
-                    let _ = std::fs::File::create(path).and_then(|mut file| file.write_all(json_string.as_bytes()));
+                if let Err(e) = result.write_json(&path) {
+                    self.error = Some(e.to_string());
                 }
             }
         }
     }
     
-    fn export_all_csv(&self, db: Arc<Database>) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("csv", &["csv"])
-            .set_file_name(&format!("{}_all.csv", self.title))
-            .save_file()
-        {
-            // Execute query without pagination to get all results
-            match crate::core::QueryExecutor::execute(&db, &self.query) {
-                Ok(all_results) => {
-                    if let Ok(mut writer) = crate::core::CsvWriter::from_path(&path) {
-                        // Write headers
-                        let _ = writer.write_headers(&all_results.columns);
-                        
-                        // Write all rows
-                        for row in &all_results.rows {
-                            let _ = writer.write_record(row);
-                        }
-                        
-                        let _ = writer.flush();
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to export all results: {}", e);
+    fn export_all_csv(&mut self) {
+        if let Some(result) = &self.result {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("csv", &["csv"])
+                .set_file_name(&format!("{}_all.csv", self.title))
+                .save_file()
+            {
+                if let Err(e) = result.write_csv(&path) {
+                    self.error = Some(format!("Failed to export all results: {}", e));
                 }
             }
         }
     }
     
-    fn export_all_json(&self, db: Arc<Database>) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("json", &["json"])
-            .set_file_name(&format!("{}_all.json", self.title))
-            .save_file()
-        {
-            // Execute query without pagination to get all results
-            match crate::core::QueryExecutor::execute(&db, &self.query) {
-                Ok(all_results) => {
-                    let json_data: Vec<serde_json::Map<String, serde_json::Value>> = all_results.rows
-                        .iter()
-                        .map(|row| {
-                            let mut map = serde_json::Map::new();
-                            for (i, value) in row.iter().enumerate() {
-                                if let Some(column) = all_results.columns.get(i) {
-                                    map.insert(column.clone(), serde_json::Value::String(value.clone()));
-                                }
-                            }
-                            map
-                        })
-                        .collect();
-                    
-                    if let Ok(json_string) = serde_json::to_string_pretty(&json_data) {
-                        let _ = std::fs::File::create(path).and_then(|mut file| file.write_all(json_string.as_bytes()));
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to export all results: {}", e);
+    fn export_all_json(&mut self) {
+        if let Some(result) = &self.result {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("json", &["json"])
+                .set_file_name(&format!("{}_all.json", self.title))
+                .save_file()
+            {
+                if let Err(e) = result.write_json(&path) {
+                    self.error = Some(format!("Failed to export all results: {}", e));
                 }
             }
         }
     }
-} 
\ No newline at end of file
+    
+    /// Point at the statement that stopped the query from starting, if read-only mode refused one
+    fn find_refused(&mut self, db: &Database) {
+        if db.is_readonly() {
+            self.refused = QueryExecutor::refused_statement(&self.query).ok().flatten();
+        }
+    }
+}
+
+/// Lay out the query in the editor's font, marking the refused statement's text
+fn highlight_refused(ui: &egui::Ui, text: &str, refused: Option<std::ops::Range<usize>>) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let color = ui.visuals().widgets.inactive.text_color();
+    let plain = egui::TextFormat::simple(font_id.clone(), color);
+    let marked = egui::TextFormat {
+        background: egui::Color32::from_rgb(90, 30, 30),
+        underline: egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 100, 100)),
+        ..egui::TextFormat::simple(font_id, color)
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    match refused.filter(|range| text.get(range.clone()).is_some()) {
+        Some(range) => {
+            job.append(&text[..range.start], 0.0, plain.clone());
+            job.append(&text[range.clone()], 0.0, marked);
+            job.append(&text[range.end..], 0.0, plain);
+        }
+        None => job.append(text, 0.0, plain),
+    }
+    job
+}
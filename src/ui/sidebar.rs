@@ -10,6 +10,7 @@ pub enum SidebarAction {
     AddComputedColumns,
     AddTimeBinColumn,
     AddGroupIdColumns,
+    OpenQueryLibrary,
 }
 
 pub struct Sidebar {
@@ -66,6 +67,10 @@ impl Sidebar {
                 action = SidebarAction::AddGroupIdColumns;
             }
             
+            if ui.button("📚 Query Library").clicked() {
+                action = SidebarAction::OpenQueryLibrary;
+            }
+            
             ui.add_space(10.0);
             
             // Tables section
@@ -110,7 +115,10 @@ impl Sidebar {
                                     ui.visuals().text_color(),
                                 );
                                 
-                                let info_text = format!("{} rows, {} columns", table.row_count, table.columns.len());
+                                let info_text = match table.row_count {
+                                    Some(rows) => format!("{} rows, {} columns", rows, table.columns.len()),
+                                    None => format!("{} columns", table.columns.len()),
+                                };
                                 let info_pos = text_pos + egui::vec2(0.0, 20.0);
                                 ui.painter().text(
                                     info_pos,
@@ -2,6 +2,8 @@ use egui::{self, RichText, Color32};
 use std::sync::Arc;
 use crate::core::database::Database;
 use crate::core::error::Result;
+use crate::core::TableFormat;
+use crate::ui::format_picker::table_format_picker;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TimeBinStrategy {
@@ -26,6 +28,7 @@ pub struct TimeBinConfig {
     pub strategy: TimeBinStrategy,
     pub output_column_name: String,
     pub output_filename: Option<String>,
+    pub output_format: TableFormat,
 }
 
 pub struct TimeBinDialog {
@@ -37,6 +40,7 @@ pub struct TimeBinDialog {
     pub strategy: TimeBinStrategy,
     pub output_column_name: String,
     pub output_filename: String,
+    pub output_format: TableFormat,
     pub error_message: Option<String>,
     last_updated_table: Option<String>,
     pub success_message: Option<String>,
@@ -69,6 +73,7 @@ impl Default for TimeBinDialog {
             },
             output_column_name: String::new(),
             output_filename: String::new(),
+            output_format: TableFormat::default(),
             error_message: None,
             last_updated_table: None,
             success_message: None,
@@ -117,8 +122,8 @@ impl TimeBinDialog {
         
         // First pass: collect all potential time columns
         for (col, dtype) in columns.iter().zip(types.iter()) {
-            // Include actual timestamp columns
-            if matches!(dtype, DataType::Timestamp(_, _)) {
+            // Include actual timestamp and time-of-day columns
+            if matches!(dtype, DataType::Timestamp(_, _) | DataType::Time32(_) | DataType::Time64(_)) {
                 timestamp_columns.push(col.clone());
             }
             // Collect string columns that might contain time data
@@ -202,6 +207,7 @@ impl TimeBinDialog {
         let mut strategy = self.strategy.clone();
         let mut output_column_name = self.output_column_name.clone();
         let mut output_filename = self.output_filename.clone();
+        let mut output_format = self.output_format;
         let preview_data = self.preview_data.clone();
         
         let window_result = egui::Window::new("Add Time Bin Column")
@@ -434,10 +440,14 @@ impl TimeBinDialog {
                                 let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
                                 output_filename = format!("{}_timebin_{}", selected_table, timestamp);
                             }
-                            if !output_filename.is_empty() && !output_filename.ends_with(".arrow") {
-                                ui.label(egui::RichText::new("(.arrow will be added)").weak());
+                            let extension = format!(".{}", output_format.extension());
+                            if !output_filename.is_empty() && !output_filename.ends_with(&extension) {
+                                ui.label(egui::RichText::new(format!("({} will be added)", extension)).weak());
                             }
                         });
+                        ui.horizontal(|ui| {
+                            table_format_picker(ui, "time_bin_output_format", &mut output_format);
+                        });
                     });
                     
                     // Preview button and data
@@ -497,6 +507,8 @@ impl TimeBinDialog {
             self.output_filename = output_filename;
         }
         
+        self.output_format = output_format;
+        
         if should_generate_preview {
             // Generate detailed preview info
             if let Err(e) = self.generate_preview_info(&database) {
@@ -565,6 +577,7 @@ impl TimeBinDialog {
                     } else {
                         Some(self.output_filename.clone())
                     },
+                    output_format: self.output_format,
                 };
 
                 // Apply the time bin logic
@@ -747,6 +760,7 @@ impl TimeBinDialog {
             strategy: self.strategy.clone(),
             output_column_name: "preview_bin".to_string(),
             output_filename: None,
+            output_format: TableFormat::default(),
         };
         
         // Get the table data